@@ -4,6 +4,7 @@ use std::{
 };
 
 use anyhow::Result;
+use cw_core::schema::Schema;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_reader, to_writer};
 use tracing::{debug, info};
@@ -79,6 +80,8 @@ pub struct Connection {
     pub min_pool: u32,
     #[serde(default)]
     pub max_pool: u32,
+    #[serde(default)]
+    pub schema: Schema,
 }
 
 impl Default for Connection {
@@ -91,15 +94,17 @@ impl Default for Connection {
             ssl_mode: Default::default(),
             min_pool: 1,
             max_pool: 16,
+            schema: Default::default(),
         }
     }
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Clone, Copy, Debug)]
+#[derive(Deserialize, Serialize, PartialEq, Clone, Copy, Default, Debug)]
 #[repr(u8)]
 pub enum SslMode {
     Disable = 0,
     Allow,
+    #[default]
     Prefer,
     Require,
     VerifyCa,
@@ -128,12 +133,6 @@ impl SslMode {
     }
 }
 
-impl Default for SslMode {
-    fn default() -> Self {
-        Self::Prefer
-    }
-}
-
 #[derive(Deserialize, Serialize, PartialEq, Default, Debug)]
 pub struct Account {
     pub login: String,
@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use cw_core::{changes::Changes, tables::Table};
+use eframe::egui::Context;
+use tokio::{
+    runtime::Runtime,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver},
+    task::JoinHandle,
+    time::sleep,
+};
+
+use crate::utils::Pool;
+
+/// Background listener of the table changes made by other users
+///
+/// While the listener is offline, open windows are refreshed every [`LiveUpdates::REFRESH`]
+/// and the connection is retried
+#[derive(Debug)]
+pub struct LiveUpdates {
+    channel: UnboundedReceiver<LiveEvent>,
+    handle: JoinHandle<()>,
+    pub status: LiveStatus,
+}
+
+#[derive(Debug)]
+enum LiveEvent {
+    Connected,
+    Changed(Table),
+    /// Connection was lost, changes made until reconnecting are unknown
+    Missed,
+    Offline(String),
+    Refresh,
+}
+
+#[derive(Debug)]
+pub enum LiveStatus {
+    Connecting,
+    Live,
+    Offline(String),
+}
+
+/// Tables to be reloaded
+#[derive(Debug)]
+pub enum Refresh {
+    Tables(Vec<Table>),
+    All,
+}
+
+impl Refresh {
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Self::Tables(tables) if tables.is_empty())
+    }
+
+    pub fn affects(&self, tables: &[Table]) -> bool {
+        match self {
+            Self::Tables(changed) => changed.iter().any(|table| tables.contains(table)),
+            Self::All => true,
+        }
+    }
+}
+
+impl LiveUpdates {
+    /// Period of the fallback refresh while the listener is offline
+    pub const REFRESH: Duration = Duration::from_secs(60);
+
+    /// Spawns the listener, the UI is repainted on every change
+    pub fn start(runtime: &Runtime, pool: Pool, ctx: Context) -> Self {
+        let (tx, rx) = unbounded_channel();
+
+        let handle = runtime.spawn(async move {
+            let send = |event| {
+                ctx.request_repaint();
+                tx.send(event).is_ok()
+            };
+
+            loop {
+                let error = match Changes::listen(&pool).await {
+                    Ok(mut changes) => {
+                        if !send(LiveEvent::Connected) {
+                            return;
+                        }
+                        loop {
+                            let event = match changes.next().await {
+                                Ok(Some(table)) => LiveEvent::Changed(table),
+                                Ok(None) => LiveEvent::Missed,
+                                Err(err) => break err,
+                            };
+                            if !send(event) {
+                                return;
+                            }
+                        }
+                    }
+                    Err(err) => err,
+                };
+
+                if !send(LiveEvent::Offline(format!("{error:#}"))) {
+                    return;
+                }
+                sleep(Self::REFRESH).await;
+                if !send(LiveEvent::Refresh) {
+                    return;
+                }
+            }
+        });
+
+        Self {
+            channel: rx,
+            handle,
+            status: LiveStatus::Connecting,
+        }
+    }
+
+    /// Tables changed since the last poll
+    pub fn poll(&mut self) -> Refresh {
+        let mut refresh = Refresh::Tables(Vec::new());
+
+        while let Ok(event) = self.channel.try_recv() {
+            match event {
+                LiveEvent::Connected => {
+                    // Nothing was heard while offline
+                    if matches!(self.status, LiveStatus::Offline(_)) {
+                        refresh = Refresh::All;
+                    }
+                    self.status = LiveStatus::Live;
+                }
+                LiveEvent::Changed(table) => {
+                    if let Refresh::Tables(tables) = &mut refresh {
+                        if !tables.contains(&table) {
+                            tables.push(table);
+                        }
+                    }
+                }
+                LiveEvent::Missed | LiveEvent::Refresh => refresh = Refresh::All,
+                LiveEvent::Offline(msg) => self.status = LiveStatus::Offline(msg),
+            }
+        }
+
+        refresh
+    }
+}
+
+impl Drop for LiveUpdates {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -1,4 +1,4 @@
 pub mod config;
-pub mod database;
+pub mod live;
 pub mod request;
 pub mod user;
@@ -46,7 +46,7 @@ impl<T: Send + 'static, R: Send + 'static> Request<T, R> {
             Err(TryRecvError::Disconnected) => {
                 self.status = RequestStatus::Finished({
                     let inner = self.inner.take().unwrap();
-                    runtime.block_on(async move { inner.await }).unwrap()
+                    runtime.block_on(inner).unwrap()
                 })
             }
         }
@@ -1,6 +1,6 @@
 use cw_core::{
-    tables::{Account, LaborContract, Person, Staff},
-    types::{AccountRole, AccountStatus, ContractStatus, MetaTime, StaffStatus},
+    tables::{Account, Person, Staff},
+    types::{AccountRole, AccountStatus, MetaTime, StaffStatus},
     uuid::Uuid,
 };
 
@@ -8,7 +8,6 @@ use cw_core::{
 pub struct User {
     pub account: Account,
     pub staff: Staff,
-    pub labor_contract: LaborContract,
     pub person: Person,
 }
 
@@ -28,14 +27,9 @@ impl Default for User {
                 uuid: Uuid::new_v4(),
                 contract: Uuid::nil(),
                 position: Uuid::nil(),
+                branch: Uuid::nil(),
                 status: StaffStatus::Working,
-            },
-            labor_contract: LaborContract {
-                uuid: Uuid::new_v4(),
-                person: Uuid::nil(),
-                passport: String::new(),
-                status: ContractStatus::Active,
-                signed: Some(MetaTime::now()),
+                deleted_at: None,
                 meta: MetaTime::default(),
             },
             person: Person {
@@ -45,6 +39,8 @@ impl Default for User {
                 last_name: String::from("Israpilov"),
                 email: String::from("very1fake.coder@gmail.com"),
                 phone: String::from("+79955927029"),
+                anonymized: None,
+                deleted_at: None,
                 meta: MetaTime::default(),
             },
         }
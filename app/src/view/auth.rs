@@ -83,6 +83,9 @@ impl AuthView {
             match Argon2::default().verify_password(password.as_bytes(), &hash) {
                 Ok(_) => {
                     let staff = Staff::get_by_uuid(account.staff).fetch_one(&*pool).await?;
+                    if staff.deleted_at.is_some() {
+                        bail!("Staff of the account is deleted")
+                    }
                     let labor_contract = LaborContract::get_by_uuid(staff.contract)
                         .fetch_one(&*pool)
                         .await?;
@@ -92,7 +95,6 @@ impl AuthView {
                     Ok(User {
                         account,
                         staff,
-                        labor_contract,
                         person,
                     })
                 }
@@ -1,11 +1,27 @@
-use std::{mem::replace, sync::Arc};
+use std::{collections::BTreeMap, fs, mem::replace, path::PathBuf, sync::Arc};
 
+use anyhow::bail;
 use cw_core::{
-    tables::{Component, Manufacturer, Person, Phone, Position, Service, Supplier},
+    chrono::{DateTime, Utc},
+    receipt::Receipt,
+    search::Search,
+    tables::{
+        Branch, Component, Invoice, Manufacturer, Order, Payment, Person, Phone, Position, Service,
+        StockTransfer, Supplier, Supply, SupplyItem, Warehouse,
+    },
+    trash,
+    types::{
+        AccountRole, Conflict, Imei, Money, PaymentKind, PaymentMethod, SupplyStatus,
+        TransferStatus,
+    },
     uuid::Uuid,
+    views::PhoneHistory,
 };
 use eframe::{
-    egui::{Button, Context, RichText, TopBottomPanel, Window},
+    egui::{
+        Button, CollapsingHeader, ComboBox, Context, DragValue, Grid, Key, RichText, TextEdit,
+        TopBottomPanel, Window,
+    },
     emath::{Align2, Vec2},
     epaint::Color32,
 };
@@ -14,6 +30,7 @@ use tokio::runtime::Runtime;
 
 use crate::{
     model::{
+        live::{LiveStatus, LiveUpdates},
         request::{Request, RequestStatus},
         user::User,
     },
@@ -29,6 +46,20 @@ pub struct MainView {
     user: User,
     windows: WindowStorage,
     delete_prompt: DeletePrompt,
+    receipt_prompt: ReceiptPrompt,
+    payment_prompt: PaymentPrompt,
+    supply_prompt: SupplyPrompt,
+    transfer_prompt: TransferPrompt,
+    history_prompt: HistoryPrompt,
+    merge_prompt: MergePrompt,
+    restore_prompt: RestorePrompt,
+    conflict_prompt: ConflictPrompt,
+    search_text: String,
+    search_prompt: SearchPrompt,
+    /// Started on the first frame, stopped with the view
+    live: Option<LiveUpdates>,
+    /// Refreshes of the open windows after changes made by others, shown as a badge
+    changes: BTreeMap<TableWindow, usize>,
 }
 
 impl MainView {
@@ -39,23 +70,104 @@ impl MainView {
             user,
             windows,
             delete_prompt: DeletePrompt::None,
+            receipt_prompt: ReceiptPrompt::None,
+            payment_prompt: PaymentPrompt::None,
+            supply_prompt: SupplyPrompt::None,
+            transfer_prompt: TransferPrompt::None,
+            history_prompt: HistoryPrompt::None,
+            merge_prompt: MergePrompt::None,
+            restore_prompt: RestorePrompt::None,
+            conflict_prompt: ConflictPrompt::None,
+            search_text: String::new(),
+            search_prompt: SearchPrompt::None,
+            live: None,
+            changes: BTreeMap::new(),
         }
     }
 
     pub fn update(&mut self, ctx: &Context, runtime: &Runtime, pool: Pool) {
+        let branch = self.user.staff.branch;
+
+        let live = self
+            .live
+            .get_or_insert_with(|| LiveUpdates::start(runtime, Arc::clone(&pool), ctx.clone()));
+        let refresh = live.poll();
+        for (window, (open, _, state)) in &mut self.windows {
+            if *open && !refresh.is_empty() && refresh.affects(window.tables()) {
+                state.refresh(runtime, Arc::clone(&pool), *window, branch);
+                *self.changes.entry(*window).or_default() += 1;
+            }
+            state.poll_refresh(runtime);
+        }
+
         TopBottomPanel::top("main_tabs").show(ctx, |ui| {
             ui.horizontal_wrapped(|ui| {
                 for (window, (open, _, state)) in &mut self.windows {
-                    if ui.selectable_label(*open, window.as_str()).clicked() {
+                    let label = match self.changes.get(window) {
+                        Some(count) => format!("{} ({count})", window.as_str()),
+                        None => window.as_str().to_string(),
+                    };
+                    let response = ui.selectable_label(*open, label);
+                    if let WindowState::Refreshing(..) = state {
+                        ui.spinner();
+                    }
+                    if response.clicked() {
+                        self.changes.remove(window);
                         if *open {
                             *open = false;
                             *state = WindowState::None;
                         } else {
                             *open = true;
-                            *state = WindowState::load(runtime, Arc::clone(&pool), *window);
+                            *state = WindowState::load(runtime, Arc::clone(&pool), *window, branch);
                         }
                     }
                 }
+
+                ui.separator();
+                match &live.status {
+                    LiveStatus::Connecting => {
+                        ui.label("Connecting to live updates");
+                    }
+                    LiveStatus::Live => {
+                        ui.label(RichText::new("● Live").color(Color32::GREEN))
+                            .on_hover_text("Open tables are refreshed once changed");
+                    }
+                    LiveStatus::Offline(msg) => {
+                        ui.label(RichText::new("○ Offline").color(Color32::YELLOW))
+                            .on_hover_text(format!(
+                                "Open tables are refreshed every {} seconds\n{msg}",
+                                LiveUpdates::REFRESH.as_secs()
+                            ));
+                    }
+                }
+            })
+        });
+
+        TopBottomPanel::top("search_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let response = ui
+                    .add(TextEdit::singleline(&mut self.search_text).hint_text(
+                        "Client name, email, phone number, IMEI, component or supplier",
+                    ));
+                let entered = response.lost_focus() && ui.input().key_pressed(Key::Enter);
+                let text = self.search_text.trim().to_string();
+                if (ui.button("🔍 Search").clicked() || entered)
+                    && text.chars().count() >= Search::MIN_LENGTH
+                {
+                    let s_pool = Arc::clone(&pool);
+                    let s_text = text.clone();
+                    let groups: Vec<_> = self
+                        .windows
+                        .keys()
+                        .filter_map(TableWindow::search_group)
+                        .collect();
+                    self.search_prompt = SearchPrompt::Loading(
+                        text,
+                        Request::simple(runtime, move || async move {
+                            Search::run(&s_pool, &s_text, &groups, Search::LIMIT).await
+                        }),
+                    );
+                }
             })
         });
 
@@ -91,66 +203,122 @@ impl MainView {
                                                     runtime,
                                                     Arc::clone(&pool),
                                                     window,
+                                                    branch,
                                                 );
+                                            if let Some((_, _, state)) =
+                                                self.windows.get_mut(&TableWindow::Trash)
+                                            {
+                                                if state.is_visible() {
+                                                    *state = WindowState::load(
+                                                        runtime,
+                                                        Arc::clone(&pool),
+                                                        TableWindow::Trash,
+                                                        branch,
+                                                    );
+                                                }
+                                            }
                                             DeletePrompt::None
                                         }
-                                        Err(err) => DeletePrompt::Error(format!("{err}")),
+                                        Err(err) => match err.downcast_ref::<Conflict>() {
+                                            Some(conflict) => {
+                                                self.conflict_prompt = ConflictPrompt::Reload(
+                                                    window,
+                                                    conflict.to_string(),
+                                                );
+                                                DeletePrompt::None
+                                            }
+                                            None => DeletePrompt::Error(format!("{err}")),
+                                        },
                                     },
                                     _ => {
                                         ui.spinner();
                                         ui.add_space(8.0);
-                                        ui.label("Deleting row");
+                                        ui.label("Moving row to the trash");
                                         DeletePrompt::Loading((window, request))
                                     }
                                 }
                             }
-                            DeletePrompt::Confirm((uuid, window)) => {
+                            DeletePrompt::Confirm((uuid, version, window)) => {
                                 ui.label(format!(
                                     "Are you sure you want to delete '{}' from '{}' table",
                                     uuid,
                                     window.as_str()
                                 ));
+                                ui.label("The row is kept in the trash until purged");
                                 if ui.add(Button::new("Delete").fill(Color32::RED)).clicked() {
                                     let d_pool = Arc::clone(&pool);
-                                    let d_uuid = uuid;
+                                    let (d_uuid, d_version) = (uuid, version);
                                     self.delete_prompt = DeletePrompt::Loading((
                                         window,
                                         Request::simple(runtime, move || async move {
                                             match window {
                                                 TableWindow::People => {
-                                                    Person::delete_by_uuid(d_uuid)
+                                                    Person::delete_by_uuid(d_uuid, d_version)
                                                         .execute(&*d_pool)
-                                                        .await?;
+                                                        .await
+                                                        .map_err(Conflict::map)?;
                                                 }
                                                 TableWindow::Positions => {
-                                                    Position::delete_by_uuid(d_uuid)
+                                                    Position::delete_by_uuid(d_uuid, d_version)
                                                         .execute(&*d_pool)
-                                                        .await?;
+                                                        .await
+                                                        .map_err(Conflict::map)?;
                                                 }
                                                 TableWindow::Manufacturers => {
-                                                    Manufacturer::delete_by_uuid(d_uuid)
+                                                    Manufacturer::delete_by_uuid(d_uuid, d_version)
                                                         .execute(&*d_pool)
-                                                        .await?;
+                                                        .await
+                                                        .map_err(Conflict::map)?;
                                                 }
                                                 TableWindow::Services => {
-                                                    Service::delete_by_uuid(d_uuid)
+                                                    Service::delete_by_uuid(d_uuid, d_version)
                                                         .execute(&*d_pool)
-                                                        .await?;
+                                                        .await
+                                                        .map_err(Conflict::map)?;
                                                 }
                                                 TableWindow::Suppliers => {
-                                                    Supplier::delete_by_uuid(d_uuid)
+                                                    Supplier::delete_by_uuid(d_uuid, d_version)
                                                         .execute(&*d_pool)
-                                                        .await?;
+                                                        .await
+                                                        .map_err(Conflict::map)?;
                                                 }
                                                 TableWindow::Phones => {
-                                                    Phone::delete_by_uuid(d_uuid)
+                                                    Phone::delete_by_uuid(d_uuid, d_version)
                                                         .execute(&*d_pool)
-                                                        .await?;
+                                                        .await
+                                                        .map_err(Conflict::map)?;
                                                 }
                                                 TableWindow::Components => {
-                                                    Component::delete_by_uuid(d_uuid)
+                                                    Component::delete_by_uuid(d_uuid, d_version)
+                                                        .execute(&*d_pool)
+                                                        .await
+                                                        .map_err(Conflict::map)?;
+                                                }
+                                                TableWindow::Stock => {
+                                                    Warehouse::delete_by_uuid(d_uuid, d_version)
                                                         .execute(&*d_pool)
-                                                        .await?;
+                                                        .await
+                                                        .map_err(Conflict::map)?;
+                                                }
+                                                TableWindow::Orders => {
+                                                    Order::delete_by_uuid(d_uuid, d_version)
+                                                        .execute(&*d_pool)
+                                                        .await
+                                                        .map_err(Conflict::map)?;
+                                                }
+                                                TableWindow::Supplies => {
+                                                    Supply::delete_by_uuid(d_uuid, d_version)
+                                                        .execute(&*d_pool)
+                                                        .await
+                                                        .map_err(Conflict::map)?;
+                                                }
+                                                TableWindow::Transfers
+                                                | TableWindow::Duplicates
+                                                | TableWindow::Trash => {
+                                                    bail!(
+                                                        "Rows can't be deleted from '{}' table",
+                                                        window.as_str()
+                                                    )
                                                 }
                                             }
                                             Ok(())
@@ -161,7 +329,7 @@ impl MainView {
                                     self.delete_prompt = if ui.button("Cancel").clicked() {
                                         DeletePrompt::None
                                     } else {
-                                        DeletePrompt::Confirm((uuid, window))
+                                        DeletePrompt::Confirm((uuid, version, window))
                                     }
                                 }
                             }
@@ -171,151 +339,1602 @@ impl MainView {
                 });
         }
 
-        self.windows
-            .iter_mut()
-            .map(|(window, (open, access, state))| {
-                if *open != state.is_visible() && !*open {
-                    *state = WindowState::None;
-                }
-                (window, (open, access, state))
-            })
-            .for_each(|(window, (open, access, state))| {
-                Window::new(window.as_str())
-                    .open(open)
-                    .resizable(true)
-                    .enabled(self.delete_prompt.is_none())
-                    .scroll2([true; 2])
-                    .show(ctx, |ui| match state {
-                        WindowState::Loaded(window_data) => {
-                            match window_data {
-                                TableData::People { data } => Table::draw(
-                                    ui,
-                                    &[
-                                        Size::exact(ID_WIDTH),
-                                        Size::exact(UUID_WIDTH),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::exact(TIMESTAMP_WIDTH),
-                                        Size::exact(TIMESTAMP_WIDTH),
-                                        Size::exact(BUTTON_WIDTH),
-                                    ],
-                                    &[
-                                        "ID",
-                                        "UUID",
-                                        "First Name",
-                                        "Middle Name",
-                                        "Last Name",
-                                        "Email",
-                                        "Phone",
-                                        "Updated",
-                                        "Created",
-                                    ],
-                                    (data.len(), |index, mut row| match data.get(index) {
-                                        Some(person) => {
-                                            row.col(|ui| {
-                                                ui.label(index.to_string());
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(format!("{}", person.uuid));
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(person.first_name.clone());
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(if person.middle_name.is_some() {
-                                                    person.middle_name.clone().unwrap()
-                                                } else {
-                                                    String::new()
-                                                });
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(person.last_name.clone());
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(person.email.clone());
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(person.phone.clone());
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(format!("{}", person.meta.updated));
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(format!("{}", person.meta.created));
-                                            });
-                                            row.col(|ui| {
-                                                if *access >= TableAccess::Delete {
-                                                    if ui.button("🗑").clicked() {
-                                                        self.delete_prompt = DeletePrompt::Confirm(
-                                                            (person.uuid, *window),
-                                                        );
-                                                    }
+        if !self.receipt_prompt.is_none() {
+            Window::new("Receipt")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        let msg = match self.receipt_prompt.take() {
+                            ReceiptPrompt::Saving(mut request) => {
+                                match request.peek(runtime).status.take() {
+                                    RequestStatus::Finished(Ok(path)) => {
+                                        format!("Receipt saved to '{}'", path.display())
+                                    }
+                                    RequestStatus::Finished(Err(err)) => {
+                                        format!("An error occurred: {err}")
+                                    }
+                                    _ => {
+                                        ui.spinner();
+                                        ui.add_space(8.0);
+                                        ui.label("Saving receipt");
+                                        self.receipt_prompt = ReceiptPrompt::Saving(request);
+                                        return;
+                                    }
+                                }
+                            }
+                            ReceiptPrompt::Done(msg) => msg,
+                            ReceiptPrompt::None => unreachable!(),
+                        };
+                        ui.label(&msg);
+                        ui.add_space(8.0);
+                        if !ui.button("Close").clicked() {
+                            self.receipt_prompt = ReceiptPrompt::Done(msg);
+                        }
+                    })
+                });
+        }
+
+        if !self.payment_prompt.is_none() {
+            Window::new("Payments")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        let msg = match self.payment_prompt.take() {
+                            PaymentPrompt::Loading(order, tracking, mut request) => {
+                                match request.peek(runtime).status.take() {
+                                    RequestStatus::Finished(Ok(invoices)) => {
+                                        self.payment_prompt = PaymentPrompt::Edit {
+                                            order,
+                                            tracking,
+                                            selected: invoices
+                                                .last()
+                                                .map(|(invoice, _)| invoice.uuid),
+                                            invoices,
+                                            amount: String::new(),
+                                            method: PaymentMethod::Cash,
+                                        };
+                                        return;
+                                    }
+                                    RequestStatus::Finished(Err(err)) => {
+                                        format!("An error occurred: {err}")
+                                    }
+                                    _ => {
+                                        ui.spinner();
+                                        ui.add_space(8.0);
+                                        ui.label(format!("Loading invoices of '{tracking}'"));
+                                        self.payment_prompt =
+                                            PaymentPrompt::Loading(order, tracking, request);
+                                        return;
+                                    }
+                                }
+                            }
+                            PaymentPrompt::Edit {
+                                order,
+                                tracking,
+                                invoices,
+                                mut selected,
+                                mut amount,
+                                mut method,
+                            } => {
+                                ui.heading(&tracking);
+                                ui.add_space(8.0);
+                                Grid::new("order_invoices").striped(true).show(ui, |ui| {
+                                    ui.label("Invoice");
+                                    ui.label("Billed");
+                                    ui.label("Paid");
+                                    ui.end_row();
+                                    for (invoice, paid) in &invoices {
+                                        ui.radio_value(
+                                            &mut selected,
+                                            Some(invoice.uuid),
+                                            format!("{}", invoice.meta.created),
+                                        );
+                                        ui.label(invoice.amount.to_string());
+                                        ui.label(paid.to_string());
+                                        ui.end_row();
+                                    }
+                                });
+                                ui.add_space(8.0);
+                                ui.horizontal(|ui| {
+                                    ui.label("Amount");
+                                    ui.add(TextEdit::singleline(&mut amount).hint_text("12.50"));
+                                    ComboBox::from_label("Method")
+                                        .selected_text(method.as_str())
+                                        .show_ui(ui, |ui| {
+                                            for m in PaymentMethod::ALL {
+                                                ui.selectable_value(&mut method, m, m.as_str());
+                                            }
+                                        });
+                                });
+                                ui.add_space(8.0);
+                                let money = amount.trim().parse::<Money>().ok();
+                                let staff = self.user.staff.uuid;
+                                let (issue, pay, refund) = ui
+                                    .horizontal(|ui| {
+                                        (
+                                            ui.add_enabled(
+                                                money.is_some(),
+                                                Button::new("Issue invoice"),
+                                            )
+                                            .clicked(),
+                                            ui.add_enabled(
+                                                money.is_some() && selected.is_some(),
+                                                Button::new("Pay"),
+                                            )
+                                            .clicked(),
+                                            ui.add_enabled(
+                                                money.is_some() && selected.is_some(),
+                                                Button::new("Refund"),
+                                            )
+                                            .clicked(),
+                                        )
+                                    })
+                                    .inner;
+                                let p_pool = Arc::clone(&pool);
+                                match (money, selected) {
+                                    (Some(money), _) if issue => {
+                                        self.payment_prompt = PaymentPrompt::Running(
+                                            Request::simple(runtime, move || async move {
+                                                let invoice =
+                                                    Invoice::new_auto(order, money, staff);
+                                                if invoice
+                                                    .issue()
+                                                    .execute(&*p_pool)
+                                                    .await?
+                                                    .rows_affected()
+                                                    == 0
+                                                {
+                                                    bail!("Order '{tracking}' is deleted");
                                                 }
-                                            });
+                                                Ok(format!(
+                                                    "Invoice of {} issued for '{tracking}'",
+                                                    invoice.amount
+                                                ))
+                                            }),
+                                        );
+                                    }
+                                    (Some(money), Some(invoice)) if pay || refund => {
+                                        let kind = if pay {
+                                            PaymentKind::Payment
+                                        } else {
+                                            PaymentKind::Refund
+                                        };
+                                        self.payment_prompt = PaymentPrompt::Running(
+                                            Request::simple(runtime, move || async move {
+                                                let payment = Payment::new_auto(
+                                                    invoice, kind, method, money, staff,
+                                                );
+                                                payment.record().execute(&*p_pool).await?;
+                                                Ok(format!(
+                                                    "{kind:?} of {} recorded for '{tracking}'",
+                                                    payment.amount
+                                                ))
+                                            }),
+                                        );
+                                    }
+                                    _ if ui.button("Close").clicked() => (),
+                                    _ => {
+                                        self.payment_prompt = PaymentPrompt::Edit {
+                                            order,
+                                            tracking,
+                                            invoices,
+                                            selected,
+                                            amount,
+                                            method,
+                                        };
+                                    }
+                                }
+                                return;
+                            }
+                            PaymentPrompt::Running(mut request) => {
+                                match request.peek(runtime).status.take() {
+                                    RequestStatus::Finished(Ok(msg)) => {
+                                        if let Some((_, _, state)) =
+                                            self.windows.get_mut(&TableWindow::Orders)
+                                        {
+                                            if state.is_visible() {
+                                                *state = WindowState::load(
+                                                    runtime,
+                                                    Arc::clone(&pool),
+                                                    TableWindow::Orders,
+                                                    branch,
+                                                );
+                                            }
                                         }
-                                        None => {
-                                            row.col(|ui| {
-                                                ui.label("Error while indexing");
-                                            });
+                                        msg
+                                    }
+                                    RequestStatus::Finished(Err(err)) => {
+                                        format!("An error occurred: {err}")
+                                    }
+                                    _ => {
+                                        ui.spinner();
+                                        ui.add_space(8.0);
+                                        ui.label("Recording payment");
+                                        self.payment_prompt = PaymentPrompt::Running(request);
+                                        return;
+                                    }
+                                }
+                            }
+                            PaymentPrompt::Done(msg) => msg,
+                            PaymentPrompt::None => unreachable!(),
+                        };
+                        ui.label(&msg);
+                        ui.add_space(8.0);
+                        if !ui.button("Close").clicked() {
+                            self.payment_prompt = PaymentPrompt::Done(msg);
+                        }
+                    })
+                });
+        }
+
+        if !self.supply_prompt.is_none() {
+            Window::new("Supply")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        let msg = match self.supply_prompt.take() {
+                            SupplyPrompt::Loading(supply, version, mut request) => {
+                                match request.peek(runtime).status.take() {
+                                    RequestStatus::Finished(Ok(lines)) => {
+                                        self.supply_prompt = SupplyPrompt::Receive {
+                                            supply,
+                                            version,
+                                            lines: lines
+                                                .into_iter()
+                                                .map(|(line, name)| {
+                                                    let received =
+                                                        line.received.unwrap_or(line.ordered);
+                                                    (line, name, received)
+                                                })
+                                                .collect(),
+                                        };
+                                        return;
+                                    }
+                                    RequestStatus::Finished(Err(err)) => {
+                                        format!("An error occurred: {err}")
+                                    }
+                                    _ => {
+                                        ui.spinner();
+                                        ui.add_space(8.0);
+                                        ui.label("Loading supply lines");
+                                        self.supply_prompt =
+                                            SupplyPrompt::Loading(supply, version, request);
+                                        return;
+                                    }
+                                }
+                            }
+                            SupplyPrompt::Receive {
+                                supply,
+                                version,
+                                mut lines,
+                            } => {
+                                Grid::new("supply_lines").striped(true).show(ui, |ui| {
+                                    ui.label("Component");
+                                    ui.label("Ordered");
+                                    ui.label("Received");
+                                    ui.end_row();
+                                    for (line, name, received) in &mut lines {
+                                        ui.label(name.as_str());
+                                        ui.label(line.ordered.to_string());
+                                        ui.add(DragValue::new(received).clamp_range(0..=i32::MAX));
+                                        ui.end_row();
+                                    }
+                                });
+                                ui.add_space(8.0);
+                                if ui.button("Receive").clicked() {
+                                    let s_pool = Arc::clone(&pool);
+                                    self.supply_prompt = SupplyPrompt::Running(Request::simple(
+                                        runtime,
+                                        move || async move {
+                                            let mut tx = s_pool.begin().await?;
+                                            for (line, _, received) in lines {
+                                                SupplyItem::set_received(
+                                                    supply,
+                                                    line.component,
+                                                    received,
+                                                    line.meta.updated,
+                                                )
+                                                .execute(&mut tx)
+                                                .await
+                                                .map_err(Conflict::map)?;
+                                            }
+                                            Supply::receive(supply, version)
+                                                .execute(&mut tx)
+                                                .await
+                                                .map_err(Conflict::map)?;
+                                            tx.commit().await?;
+                                            Ok(format!("Supply '{supply}' delivered"))
+                                        },
+                                    ));
+                                } else if !ui.button("Cancel").clicked() {
+                                    self.supply_prompt = SupplyPrompt::Receive {
+                                        supply,
+                                        version,
+                                        lines,
+                                    };
+                                }
+                                return;
+                            }
+                            SupplyPrompt::Running(mut request) => {
+                                match request.peek(runtime).status.take() {
+                                    RequestStatus::Finished(Ok(msg)) => {
+                                        if let Some((_, _, state)) =
+                                            self.windows.get_mut(&TableWindow::Supplies)
+                                        {
+                                            *state = WindowState::load(
+                                                runtime,
+                                                Arc::clone(&pool),
+                                                TableWindow::Supplies,
+                                                branch,
+                                            );
                                         }
-                                    }),
-                                ),
-                                TableData::Positions { data } => Table::draw(
-                                    ui,
-                                    &[
-                                        Size::exact(ID_WIDTH),
-                                        Size::exact(UUID_WIDTH),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::initial(80.0),
-                                        Size::exact(TIMESTAMP_WIDTH),
-                                        Size::exact(TIMESTAMP_WIDTH),
-                                        Size::exact(BUTTON_WIDTH),
-                                    ],
-                                    &[
-                                        "ID", "UUID", "Name", "Details", "Salary", "Updated",
-                                        "Created",
-                                    ],
-                                    (data.len(), |index, mut row| match data.get(index) {
-                                        Some(position) => {
-                                            row.col(|ui| {
-                                                ui.label(index.to_string());
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(format!("{}", position.uuid));
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(position.name.clone());
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(if position.details.is_some() {
-                                                    position.details.clone().unwrap()
-                                                } else {
-                                                    String::new()
-                                                });
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(format!(
-                                                    "{}₽.",
-                                                    position.salary.to_bigdecimal(2)
-                                                ));
+                                        msg
+                                    }
+                                    RequestStatus::Finished(Err(err)) => {
+                                        if let Some(conflict) = err.downcast_ref::<Conflict>() {
+                                            self.conflict_prompt = ConflictPrompt::Reload(
+                                                TableWindow::Supplies,
+                                                conflict.to_string(),
+                                            );
+                                            return;
+                                        }
+                                        format!("An error occurred: {err}")
+                                    }
+                                    _ => {
+                                        ui.spinner();
+                                        ui.add_space(8.0);
+                                        ui.label("Updating supply");
+                                        self.supply_prompt = SupplyPrompt::Running(request);
+                                        return;
+                                    }
+                                }
+                            }
+                            SupplyPrompt::Done(msg) => msg,
+                            SupplyPrompt::None => unreachable!(),
+                        };
+                        ui.label(&msg);
+                        ui.add_space(8.0);
+                        if !ui.button("Close").clicked() {
+                            self.supply_prompt = SupplyPrompt::Done(msg);
+                        }
+                    })
+                });
+        }
+
+        if !self.transfer_prompt.is_none() {
+            Window::new("Transfer")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        let msg = match self.transfer_prompt.take() {
+                            TransferPrompt::Loading(item, version, available, mut request) => {
+                                match request.peek(runtime).status.take() {
+                                    RequestStatus::Finished(Ok(branches)) => {
+                                        self.transfer_prompt = TransferPrompt::Ship {
+                                            item,
+                                            version,
+                                            available,
+                                            amount: available,
+                                            destination: None,
+                                            branches,
+                                        };
+                                        return;
+                                    }
+                                    RequestStatus::Finished(Err(err)) => {
+                                        format!("An error occurred: {err}")
+                                    }
+                                    _ => {
+                                        ui.spinner();
+                                        ui.add_space(8.0);
+                                        ui.label("Loading branches");
+                                        self.transfer_prompt = TransferPrompt::Loading(
+                                            item, version, available, request,
+                                        );
+                                        return;
+                                    }
+                                }
+                            }
+                            TransferPrompt::Ship {
+                                item,
+                                version,
+                                available,
+                                mut amount,
+                                mut destination,
+                                branches,
+                            } => {
+                                let selected = branches
+                                    .iter()
+                                    .find(|branch| Some(branch.uuid) == destination)
+                                    .map(|branch| branch.name.clone());
+                                ComboBox::from_label("Destination")
+                                    .selected_text(selected.clone().unwrap_or_default())
+                                    .show_ui(ui, |ui| {
+                                        for branch in &branches {
+                                            ui.selectable_value(
+                                                &mut destination,
+                                                Some(branch.uuid),
+                                                &branch.name,
+                                            );
+                                        }
+                                    });
+                                ui.horizontal(|ui| {
+                                    ui.label("Amount");
+                                    ui.add(
+                                        DragValue::new(&mut amount)
+                                            .clamp_range(1..=available)
+                                            .suffix(format!(" of {available}")),
+                                    );
+                                });
+                                ui.add_space(8.0);
+                                let ship = ui
+                                    .add_enabled(destination.is_some(), Button::new("Ship"))
+                                    .clicked();
+                                match (destination, selected) {
+                                    (Some(to), Some(name)) if ship => {
+                                        let t_pool = Arc::clone(&pool);
+                                        let sender = self.user.staff.uuid;
+                                        self.transfer_prompt = TransferPrompt::Running(
+                                            TableWindow::Stock,
+                                            Request::simple(runtime, move || async move {
+                                                StockTransfer::ship(
+                                                    item, to, amount, sender, version,
+                                                )
+                                                .execute(&*t_pool)
+                                                .await
+                                                .map_err(Conflict::map)?;
+                                                Ok(format!("{amount} shipped to '{name}'"))
+                                            }),
+                                        );
+                                    }
+                                    _ if ui.button("Cancel").clicked() => (),
+                                    _ => {
+                                        self.transfer_prompt = TransferPrompt::Ship {
+                                            item,
+                                            version,
+                                            available,
+                                            amount,
+                                            destination,
+                                            branches,
+                                        };
+                                    }
+                                }
+                                return;
+                            }
+                            TransferPrompt::Running(window, mut request) => {
+                                match request.peek(runtime).status.take() {
+                                    RequestStatus::Finished(Ok(msg)) => {
+                                        for window in [TableWindow::Stock, TableWindow::Transfers] {
+                                            if let Some((_, _, state)) =
+                                                self.windows.get_mut(&window)
+                                            {
+                                                if state.is_visible() {
+                                                    *state = WindowState::load(
+                                                        runtime,
+                                                        Arc::clone(&pool),
+                                                        window,
+                                                        branch,
+                                                    );
+                                                }
+                                            }
+                                        }
+                                        msg
+                                    }
+                                    RequestStatus::Finished(Err(err)) => {
+                                        if let Some(conflict) = err.downcast_ref::<Conflict>() {
+                                            self.conflict_prompt = ConflictPrompt::Reload(
+                                                window,
+                                                conflict.to_string(),
+                                            );
+                                            return;
+                                        }
+                                        format!("An error occurred: {err}")
+                                    }
+                                    _ => {
+                                        ui.spinner();
+                                        ui.add_space(8.0);
+                                        ui.label("Updating transfer");
+                                        self.transfer_prompt =
+                                            TransferPrompt::Running(window, request);
+                                        return;
+                                    }
+                                }
+                            }
+                            TransferPrompt::Done(msg) => msg,
+                            TransferPrompt::None => unreachable!(),
+                        };
+                        ui.label(&msg);
+                        ui.add_space(8.0);
+                        if !ui.button("Close").clicked() {
+                            self.transfer_prompt = TransferPrompt::Done(msg);
+                        }
+                    })
+                });
+        }
+
+        if !self.merge_prompt.is_none() {
+            Window::new("Merge confirmation")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        let msg = match self.merge_prompt.take() {
+                            MergePrompt::Confirm((kept, kept_label), (duplicate, duplicate_label)) => {
+                                ui.label(format!(
+                                    "Are you sure you want to merge '{duplicate_label}' into '{kept_label}'"
+                                ));
+                                ui.label("Phones, orders and contracts are moved, the duplicate is deleted");
+                                ui.add_space(8.0);
+                                if ui.add(Button::new("Merge").fill(Color32::RED)).clicked() {
+                                    let m_pool = Arc::clone(&pool);
+                                    self.merge_prompt = MergePrompt::Running(Request::simple(
+                                        runtime,
+                                        move || async move {
+                                            Person::merge(kept, duplicate)
+                                                .fetch_one(&*m_pool)
+                                                .await?;
+                                            Ok(format!(
+                                                "'{duplicate_label}' merged into '{kept_label}'"
+                                            ))
+                                        },
+                                    ));
+                                } else if ui.button("Swap").clicked() {
+                                    self.merge_prompt = MergePrompt::Confirm(
+                                        (duplicate, duplicate_label),
+                                        (kept, kept_label),
+                                    );
+                                } else if !ui.button("Cancel").clicked() {
+                                    self.merge_prompt = MergePrompt::Confirm(
+                                        (kept, kept_label),
+                                        (duplicate, duplicate_label),
+                                    );
+                                }
+                                return;
+                            }
+                            MergePrompt::Running(mut request) => {
+                                match request.peek(runtime).status.take() {
+                                    RequestStatus::Finished(Ok(msg)) => {
+                                        for window in [TableWindow::Duplicates, TableWindow::People] {
+                                            if let Some((_, _, state)) = self.windows.get_mut(&window) {
+                                                if state.is_visible() {
+                                                    *state = WindowState::load(
+                                                        runtime,
+                                                        Arc::clone(&pool),
+                                                        window,
+                                                        branch,
+                                                    );
+                                                }
+                                            }
+                                        }
+                                        msg
+                                    }
+                                    RequestStatus::Finished(Err(err)) => {
+                                        format!("An error occurred: {err}")
+                                    }
+                                    _ => {
+                                        ui.spinner();
+                                        ui.add_space(8.0);
+                                        ui.label("Merging people");
+                                        self.merge_prompt = MergePrompt::Running(request);
+                                        return;
+                                    }
+                                }
+                            }
+                            MergePrompt::Done(msg) => msg,
+                            MergePrompt::None => unreachable!(),
+                        };
+                        ui.label(&msg);
+                        ui.add_space(8.0);
+                        if !ui.button("Close").clicked() {
+                            self.merge_prompt = MergePrompt::Done(msg);
+                        }
+                    })
+                });
+        }
+
+        if !self.restore_prompt.is_none() {
+            Window::new("Restore")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        let msg = match self.restore_prompt.take() {
+                            RestorePrompt::Running(restored, mut request) => {
+                                match request.peek(runtime).status.take() {
+                                    RequestStatus::Finished(Ok(msg)) => {
+                                        for window in [Some(TableWindow::Trash), restored]
+                                            .into_iter()
+                                            .flatten()
+                                        {
+                                            if let Some((_, _, state)) =
+                                                self.windows.get_mut(&window)
+                                            {
+                                                if state.is_visible() {
+                                                    *state = WindowState::load(
+                                                        runtime,
+                                                        Arc::clone(&pool),
+                                                        window,
+                                                        branch,
+                                                    );
+                                                }
+                                            }
+                                        }
+                                        msg
+                                    }
+                                    RequestStatus::Finished(Err(err)) => {
+                                        if let Some(conflict) = err.downcast_ref::<Conflict>() {
+                                            self.conflict_prompt = ConflictPrompt::Reload(
+                                                TableWindow::Trash,
+                                                conflict.to_string(),
+                                            );
+                                            return;
+                                        }
+                                        format!("An error occurred: {err}")
+                                    }
+                                    _ => {
+                                        ui.spinner();
+                                        ui.add_space(8.0);
+                                        ui.label("Restoring row");
+                                        self.restore_prompt =
+                                            RestorePrompt::Running(restored, request);
+                                        return;
+                                    }
+                                }
+                            }
+                            RestorePrompt::Done(msg) => msg,
+                            RestorePrompt::None => unreachable!(),
+                        };
+                        ui.label(&msg);
+                        ui.add_space(8.0);
+                        if !ui.button("Close").clicked() {
+                            self.restore_prompt = RestorePrompt::Done(msg);
+                        }
+                    })
+                });
+        }
+
+        if !self.conflict_prompt.is_none() {
+            Window::new("Conflict")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        if let ConflictPrompt::Reload(window, msg) = self.conflict_prompt.take() {
+                            ui.label(RichText::new(&msg).color(Color32::RED));
+                            ui.add_space(8.0);
+                            if ui.button("Reload").clicked() {
+                                if let Some((_, _, state)) = self.windows.get_mut(&window) {
+                                    *state = WindowState::load(
+                                        runtime,
+                                        Arc::clone(&pool),
+                                        window,
+                                        branch,
+                                    );
+                                }
+                            } else if !ui.button("Close").clicked() {
+                                self.conflict_prompt = ConflictPrompt::Reload(window, msg);
+                            }
+                        }
+                    })
+                });
+        }
+
+        if !self.history_prompt.is_none() {
+            let mut open = true;
+            Window::new("Device history")
+                .open(&mut open)
+                .collapsible(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| match self.history_prompt.take() {
+                    HistoryPrompt::Loading(imei, mut request) => {
+                        match request.peek(runtime).status.take() {
+                            RequestStatus::Finished(Ok(events)) => {
+                                self.history_prompt = HistoryPrompt::Loaded(imei, events);
+                            }
+                            RequestStatus::Finished(Err(err)) => {
+                                self.history_prompt =
+                                    HistoryPrompt::Error(format!("An error occurred: {err}"));
+                            }
+                            _ => {
+                                ui.vertical_centered(|ui| {
+                                    ui.spinner();
+                                    ui.add_space(8.0);
+                                    ui.label(format!("Loading history of '{imei}'"));
+                                });
+                                self.history_prompt = HistoryPrompt::Loading(imei, request);
+                            }
+                        }
+                    }
+                    HistoryPrompt::Loaded(imei, events) => {
+                        ui.heading(imei.as_str());
+                        ui.add_space(8.0);
+                        Table::draw(
+                            ui,
+                            &[
+                                Size::exact(TIMESTAMP_WIDTH),
+                                Size::initial(60.0),
+                                Size::initial(120.0),
+                                Size::initial(160.0),
+                                Size::initial(160.0),
+                                Size::initial(60.0),
+                                Size::initial(100.0),
+                            ],
+                            &[
+                                "Date",
+                                "Kind",
+                                "Order",
+                                "Person",
+                                "Description",
+                                "Amount",
+                                "Price",
+                            ],
+                            (events.len(), |index, mut row| match events.get(index) {
+                                Some(event) => {
+                                    row.col(|ui| {
+                                        ui.label(format!("{}", event.at));
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(event.kind.clone());
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(event.tracking.clone().unwrap_or_default());
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(event.person.clone());
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(event.description.clone());
+                                    });
+                                    row.col(|ui| {
+                                        if let Some(amount) = event.amount {
+                                            ui.label(amount.to_string());
+                                        }
+                                    });
+                                    row.col(|ui| {
+                                        if let Some(price) = &event.price {
+                                            ui.label(price.to_string());
+                                        }
+                                    });
+                                }
+                                None => {
+                                    row.col(|ui| {
+                                        ui.label("Error while indexing");
+                                    });
+                                }
+                            }),
+                        );
+                        self.history_prompt = HistoryPrompt::Loaded(imei, events);
+                    }
+                    HistoryPrompt::Error(msg) => {
+                        ui.vertical_centered(|ui| {
+                            ui.label(&msg);
+                        });
+                        self.history_prompt = HistoryPrompt::Error(msg);
+                    }
+                    HistoryPrompt::None => unreachable!(),
+                });
+            if !open {
+                self.history_prompt = HistoryPrompt::None;
+            }
+        }
+
+        if !self.search_prompt.is_none() {
+            let mut open = true;
+            Window::new("Search")
+                .open(&mut open)
+                .collapsible(false)
+                .scroll2([false, true])
+                .show(ctx, |ui| match self.search_prompt.take() {
+                    SearchPrompt::Loading(text, mut request) => {
+                        match request.peek(runtime).status.take() {
+                            RequestStatus::Finished(Ok(found)) => {
+                                self.search_prompt = SearchPrompt::Loaded(text, found);
+                            }
+                            RequestStatus::Finished(Err(err)) => {
+                                self.search_prompt =
+                                    SearchPrompt::Error(format!("An error occurred: {err}"));
+                            }
+                            _ => {
+                                ui.vertical_centered(|ui| {
+                                    ui.spinner();
+                                    ui.add_space(8.0);
+                                    ui.label(format!("Searching '{text}'"));
+                                });
+                                self.search_prompt = SearchPrompt::Loading(text, request);
+                            }
+                        }
+                    }
+                    SearchPrompt::Loaded(text, found) => {
+                        if found.is_empty() {
+                            ui.label(format!("Nothing found for '{text}'"));
+                        }
+                        if !found.clients.is_empty() {
+                            CollapsingHeader::new(format!("Clients ({})", found.clients.len()))
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    Grid::new("search_clients").striped(true).show(ui, |ui| {
+                                        for person in &found.clients {
+                                            ui.label(format!(
+                                                "{} {}",
+                                                person.first_name, person.last_name
+                                            ))
+                                            .on_hover_text(person.uuid.to_string());
+                                            ui.label(&person.phone);
+                                            ui.label(&person.email);
+                                            ui.end_row();
+                                        }
+                                    });
+                                });
+                        }
+                        if !found.phones.is_empty() {
+                            CollapsingHeader::new(format!("Phones ({})", found.phones.len()))
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    Grid::new("search_phones").striped(true).show(ui, |ui| {
+                                        for phone in &found.phones {
+                                            ui.label(phone.imei.as_str())
+                                                .on_hover_text(phone.uuid.to_string());
+                                            ui.label(&phone.model);
+                                            ui.label(&phone.owner);
+                                            if ui.button("🕑").clicked() {
+                                                let h_pool = Arc::clone(&pool);
+                                                let h_imei = phone.imei;
+                                                self.history_prompt = HistoryPrompt::Loading(
+                                                    phone.imei,
+                                                    Request::simple(runtime, move || async move {
+                                                        Ok(PhoneHistory::get_by_imei(h_imei)
+                                                            .fetch_all(&*h_pool)
+                                                            .await?)
+                                                    }),
+                                                );
+                                            }
+                                            ui.end_row();
+                                        }
+                                    });
+                                });
+                        }
+                        if !found.components.is_empty() {
+                            CollapsingHeader::new(format!(
+                                "Components ({})",
+                                found.components.len()
+                            ))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                Grid::new("search_components").striped(true).show(ui, |ui| {
+                                    for component in &found.components {
+                                        ui.label(&component.name)
+                                            .on_hover_text(component.uuid.to_string());
+                                        ui.label(&component.kind);
+                                        ui.label(&component.model);
+                                        ui.label(&component.manufacturer);
+                                        ui.end_row();
+                                    }
+                                });
+                            });
+                        }
+                        if !found.suppliers.is_empty() {
+                            CollapsingHeader::new(format!("Suppliers ({})", found.suppliers.len()))
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    Grid::new("search_suppliers").striped(true).show(ui, |ui| {
+                                        for supplier in &found.suppliers {
+                                            ui.label(&supplier.name)
+                                                .on_hover_text(supplier.uuid.to_string());
+                                            ui.label(supplier.country.as_str());
+                                            ui.end_row();
+                                        }
+                                    });
+                                });
+                        }
+                        self.search_prompt = SearchPrompt::Loaded(text, found);
+                    }
+                    SearchPrompt::Error(msg) => {
+                        ui.vertical_centered(|ui| {
+                            ui.label(&msg);
+                        });
+                        self.search_prompt = SearchPrompt::Error(msg);
+                    }
+                    SearchPrompt::None => unreachable!(),
+                });
+            if !open {
+                self.search_prompt = SearchPrompt::None;
+            }
+        }
+
+        let staff = self.user.staff.uuid;
+        let role = self.user.account.role;
+
+        self.windows
+            .iter_mut()
+            .map(|(window, (open, access, state))| {
+                if *open != state.is_visible() && !*open {
+                    *state = WindowState::None;
+                    self.changes.remove(window);
+                }
+                (window, (open, access, state))
+            })
+            .for_each(|(window, (open, access, state))| {
+                Window::new(window.as_str())
+                    .open(open)
+                    .resizable(true)
+                    .enabled(
+                        self.delete_prompt.is_none()
+                            && self.receipt_prompt.is_none()
+                            && self.payment_prompt.is_none()
+                            && self.supply_prompt.is_none()
+                            && self.transfer_prompt.is_none()
+                            && self.conflict_prompt.is_none(),
+                    )
+                    .scroll2([true; 2])
+                    .show(ctx, |ui| match state {
+                        WindowState::Loaded(window_data)
+                        | WindowState::Refreshing(window_data, _) => {
+                            match window_data {
+                                TableData::People { data } => Table::draw(
+                                    ui,
+                                    &[
+                                        Size::exact(ID_WIDTH),
+                                        Size::exact(UUID_WIDTH),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &[
+                                        "ID",
+                                        "UUID",
+                                        "First Name",
+                                        "Middle Name",
+                                        "Last Name",
+                                        "Email",
+                                        "Phone",
+                                        "Updated",
+                                        "Created",
+                                    ],
+                                    (data.len(), |index, mut row| match data.get(index) {
+                                        Some(person) => {
+                                            row.col(|ui| {
+                                                ui.label(index.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", person.uuid));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(person.first_name.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(if person.middle_name.is_some() {
+                                                    person.middle_name.clone().unwrap()
+                                                } else {
+                                                    String::new()
+                                                });
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(person.last_name.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(person.email.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(person.phone.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", person.meta.updated));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", person.meta.created));
+                                            });
+                                            row.col(|ui| {
+                                                if *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt = DeletePrompt::Confirm((
+                                                        person.uuid,
+                                                        person.meta.updated,
+                                                        *window,
+                                                    ));
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            row.col(|ui| {
+                                                ui.label("Error while indexing");
+                                            });
+                                        }
+                                    }),
+                                ),
+                                TableData::Positions { data } => Table::draw(
+                                    ui,
+                                    &[
+                                        Size::exact(ID_WIDTH),
+                                        Size::exact(UUID_WIDTH),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(80.0),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &[
+                                        "ID", "UUID", "Name", "Details", "Salary", "Updated",
+                                        "Created",
+                                    ],
+                                    (data.len(), |index, mut row| match data.get(index) {
+                                        Some(position) => {
+                                            row.col(|ui| {
+                                                ui.label(index.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", position.uuid));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(position.name.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(if position.details.is_some() {
+                                                    position.details.clone().unwrap()
+                                                } else {
+                                                    String::new()
+                                                });
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(position.salary.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", position.meta.updated));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", position.meta.created));
+                                            });
+                                            row.col(|ui| {
+                                                if *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt = DeletePrompt::Confirm((
+                                                        position.uuid,
+                                                        position.meta.updated,
+                                                        *window,
+                                                    ));
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            row.col(|ui| {
+                                                ui.label("Error while indexing");
+                                            });
+                                        }
+                                    }),
+                                ),
+                                TableData::Manufacturers { data } => Table::draw(
+                                    ui,
+                                    &[
+                                        Size::exact(ID_WIDTH),
+                                        Size::exact(UUID_WIDTH),
+                                        Size::initial(120.0),
+                                        Size::exact(50.0),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &["ID", "UUID", "Name", "Country"],
+                                    (data.len(), |index, mut row| match data.get(index) {
+                                        Some(manufacturer) => {
+                                            row.col(|ui| {
+                                                ui.label(index.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", manufacturer.uuid));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(manufacturer.name.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(manufacturer.country.as_str());
+                                            });
+                                            row.col(|ui| {
+                                                if *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt = DeletePrompt::Confirm((
+                                                        manufacturer.uuid,
+                                                        manufacturer.meta.updated,
+                                                        *window,
+                                                    ));
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            row.col(|ui| {
+                                                ui.label("Error while indexing");
+                                            });
+                                        }
+                                    }),
+                                ),
+                                TableData::Services { data } => Table::draw(
+                                    ui,
+                                    &[
+                                        Size::exact(ID_WIDTH),
+                                        Size::exact(UUID_WIDTH),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(60.0),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &[
+                                        "ID",
+                                        "UUID",
+                                        "Name",
+                                        "Description",
+                                        "Warranty",
+                                        "Updated",
+                                        "Created",
+                                    ],
+                                    (data.len(), |index, mut row| match data.get(index) {
+                                        Some(service) => {
+                                            row.col(|ui| {
+                                                ui.label(index.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", service.uuid));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(service.name.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(if service.description.is_some() {
+                                                    service.description.clone().unwrap()
+                                                } else {
+                                                    String::new()
+                                                });
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{} d.", service.warranty_days));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", service.meta.created));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", service.meta.created));
+                                            });
+                                            row.col(|ui| {
+                                                if *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt = DeletePrompt::Confirm((
+                                                        service.uuid,
+                                                        service.meta.updated,
+                                                        *window,
+                                                    ));
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            row.col(|ui| {
+                                                ui.label("Error while indexing");
+                                            });
+                                        }
+                                    }),
+                                ),
+                                TableData::Suppliers { data } => Table::draw(
+                                    ui,
+                                    &[
+                                        Size::exact(ID_WIDTH),
+                                        Size::exact(UUID_WIDTH),
+                                        Size::initial(120.0),
+                                        Size::exact(235.0),
+                                        Size::initial(120.0),
+                                        Size::initial(210.0),
+                                        Size::exact(COUNTRY_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &["ID", "UUID", "Name", "IBAN", "Swift", "Address", "Country"],
+                                    (data.len(), |index, mut row| match data.get(index) {
+                                        Some(supplier) => {
+                                            row.col(|ui| {
+                                                ui.label(index.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", supplier.uuid));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(supplier.name.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(supplier.iban.as_str());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(supplier.swift.as_str());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(supplier.address.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(supplier.country.as_str());
+                                            });
+                                            row.col(|ui| {
+                                                if *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt = DeletePrompt::Confirm((
+                                                        supplier.uuid,
+                                                        supplier.meta.updated,
+                                                        *window,
+                                                    ));
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            row.col(|ui| {
+                                                ui.label("Error while indexing");
+                                            });
+                                        }
+                                    }),
+                                ),
+                                TableData::Phones { data } => Table::draw(
+                                    ui,
+                                    &[
+                                        Size::exact(ID_WIDTH),
+                                        Size::exact(UUID_WIDTH),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &[
+                                        "ID",
+                                        "UUID",
+                                        "Owner",
+                                        "IMEI",
+                                        "WiFi",
+                                        "Bluetooth",
+                                        "Model",
+                                        "Color",
+                                        "Updated",
+                                        "Created",
+                                    ],
+                                    (data.len(), |index, mut row| match data.get(index) {
+                                        Some(phone) => {
+                                            row.col(|ui| {
+                                                ui.label(index.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", phone.uuid));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(phone.owner.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(phone.imei.as_str());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", phone.wifi.clone()));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", phone.bluetooth.clone()));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(phone.model.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(phone.color.as_str());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", phone.meta.updated));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", phone.meta.created));
+                                            });
+                                            row.col(|ui| {
+                                                if ui.button("🕑").clicked() {
+                                                    let h_pool = Arc::clone(&pool);
+                                                    let h_imei = phone.imei;
+                                                    self.history_prompt = HistoryPrompt::Loading(
+                                                        phone.imei,
+                                                        Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                Ok(PhoneHistory::get_by_imei(
+                                                                    h_imei,
+                                                                )
+                                                                .fetch_all(&*h_pool)
+                                                                .await?)
+                                                            },
+                                                        ),
+                                                    );
+                                                }
+                                            });
+                                            row.col(|ui| {
+                                                if *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt = DeletePrompt::Confirm((
+                                                        phone.uuid,
+                                                        phone.meta.updated,
+                                                        *window,
+                                                    ));
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            row.col(|ui| {
+                                                ui.label("Error while indexing");
+                                            });
+                                        }
+                                    }),
+                                ),
+                                TableData::Components { data } => Table::draw(
+                                    ui,
+                                    &[
+                                        Size::exact(ID_WIDTH),
+                                        Size::exact(UUID_WIDTH),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &["ID", "UUID", "Name", "Kind", "Phone Model", "Manufacturer"],
+                                    (data.len(), |index, mut row| match data.get(index) {
+                                        Some(component) => {
+                                            row.col(|ui| {
+                                                ui.label(index.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", component.uuid));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(component.name.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(component.kind.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(component.model.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(component.manufacturer.clone());
+                                            });
+                                            row.col(|ui| {
+                                                if *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt = DeletePrompt::Confirm((
+                                                        component.uuid,
+                                                        component.meta.updated,
+                                                        *window,
+                                                    ));
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            row.col(|ui| {
+                                                ui.label("Error while indexing");
+                                            });
+                                        }
+                                    }),
+                                ),
+                                TableData::Stock { data } => Table::draw(
+                                    ui,
+                                    &[
+                                        Size::exact(ID_WIDTH),
+                                        Size::exact(UUID_WIDTH),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(80.0),
+                                        Size::initial(60.0),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &[
+                                        "ID",
+                                        "UUID",
+                                        "Branch",
+                                        "Component",
+                                        "Supplier",
+                                        "Price",
+                                        "Amount",
+                                        "Updated",
+                                        "Created",
+                                    ],
+                                    (data.len(), |index, mut row| match data.get(index) {
+                                        Some(item) => {
+                                            row.col(|ui| {
+                                                ui.label(index.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", item.uuid));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(item.branch.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(item.component.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(item.supplier.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(item.price.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(item.amount.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", item.meta.updated));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", item.meta.created));
+                                            });
+                                            row.col(|ui| {
+                                                if *access >= TableAccess::Edit
+                                                    && item.amount > 0
+                                                    && ui.button("🚚").clicked()
+                                                {
+                                                    let t_pool = Arc::clone(&pool);
+                                                    self.transfer_prompt = TransferPrompt::Loading(
+                                                        item.uuid,
+                                                        item.meta.updated,
+                                                        item.amount,
+                                                        Request::simple(runtime, move || async move {
+                                                            Ok(Branch::get_all()
+                                                                .fetch_all(&*t_pool)
+                                                                .await?
+                                                                .into_iter()
+                                                                .filter(|b| b.uuid != branch)
+                                                                .collect())
+                                                        }),
+                                                    );
+                                                }
+                                            });
+                                            row.col(|ui| {
+                                                if *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt = DeletePrompt::Confirm((
+                                                        item.uuid,
+                                                        item.meta.updated,
+                                                        *window,
+                                                    ));
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            row.col(|ui| {
+                                                ui.label("Error while indexing");
+                                            });
+                                        }
+                                    }),
+                                ),
+                                TableData::Transfers { data } => Table::draw(
+                                    ui,
+                                    &[
+                                        Size::exact(ID_WIDTH),
+                                        Size::exact(UUID_WIDTH),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(120.0),
+                                        Size::initial(60.0),
+                                        Size::initial(80.0),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &[
+                                        "ID",
+                                        "UUID",
+                                        "Component",
+                                        "Supplier",
+                                        "From",
+                                        "To",
+                                        "Amount",
+                                        "Status",
+                                        "Updated",
+                                        "Created",
+                                    ],
+                                    (data.len(), |index, mut row| match data.get(index) {
+                                        Some(transfer) => {
+                                            row.col(|ui| {
+                                                ui.label(index.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", transfer.uuid));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(transfer.component.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(transfer.supplier.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(transfer.source.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(transfer.destination.clone());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(transfer.amount.to_string());
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{:?}", transfer.status));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", transfer.meta.updated));
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", position.meta.updated));
+                                                ui.label(format!("{}", transfer.meta.created));
                                             });
+                                            let in_transit = *access >= TableAccess::Edit
+                                                && matches!(
+                                                    transfer.status,
+                                                    TransferStatus::InTransit
+                                                );
+                                            let (t_uuid, t_version) =
+                                                (transfer.uuid, transfer.meta.updated);
                                             row.col(|ui| {
-                                                ui.label(format!("{}", position.meta.created));
+                                                if in_transit && ui.button("📦").clicked() {
+                                                    let t_pool = Arc::clone(&pool);
+                                                    self.transfer_prompt = TransferPrompt::Running(
+                                                        TableWindow::Transfers,
+                                                        Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                StockTransfer::receive(
+                                                                    t_uuid, staff, t_version,
+                                                                )
+                                                                .execute(&*t_pool)
+                                                                .await
+                                                                .map_err(Conflict::map)?;
+                                                                Ok(format!(
+                                                                    "Transfer '{t_uuid}' received"
+                                                                ))
+                                                            },
+                                                        ));
+                                                }
                                             });
                                             row.col(|ui| {
-                                                if *access >= TableAccess::Delete {
-                                                    if ui.button("🗑").clicked() {
-                                                        self.delete_prompt = DeletePrompt::Confirm(
-                                                            (position.uuid, *window),
-                                                        );
-                                                    }
+                                                if in_transit && ui.button("✖").clicked() {
+                                                    let t_pool = Arc::clone(&pool);
+                                                    self.transfer_prompt = TransferPrompt::Running(
+                                                        TableWindow::Transfers,
+                                                        Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                StockTransfer::cancel(
+                                                                    t_uuid, t_version,
+                                                                )
+                                                                .execute(&*t_pool)
+                                                                .await
+                                                                .map_err(Conflict::map)?;
+                                                                Ok(format!(
+                                                                    "Transfer '{t_uuid}' cancelled"
+                                                                ))
+                                                            },
+                                                        ));
                                                 }
                                             });
                                         }
@@ -326,37 +1945,117 @@ impl MainView {
                                         }
                                     }),
                                 ),
-                                TableData::Manufacturers { data } => Table::draw(
+                                TableData::Orders { data } => Table::draw(
                                     ui,
                                     &[
                                         Size::exact(ID_WIDTH),
                                         Size::exact(UUID_WIDTH),
+                                        Size::initial(80.0),
                                         Size::initial(120.0),
-                                        Size::exact(50.0),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
                                         Size::exact(BUTTON_WIDTH),
                                     ],
-                                    &["ID", "UUID", "Name", "Country"],
+                                    &["ID", "UUID", "Tracking", "Status", "Updated", "Created"],
                                     (data.len(), |index, mut row| match data.get(index) {
-                                        Some(manufacturer) => {
+                                        Some(order) => {
                                             row.col(|ui| {
                                                 ui.label(index.to_string());
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", manufacturer.uuid));
+                                                ui.label(format!("{}", order.uuid));
                                             });
                                             row.col(|ui| {
-                                                ui.label(manufacturer.name.clone());
+                                                ui.label(order.tracking.clone());
+                                            });
+                                            row.col(|ui| {
+                                                if order.is_warranty_claim() {
+                                                    ui.label(format!(
+                                                        "{} (warranty)",
+                                                        order.status.as_str()
+                                                    ));
+                                                } else {
+                                                    ui.label(order.status.as_str());
+                                                }
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", order.meta.updated));
+                                            });
+                                            row.col(|ui| {
+                                                ui.label(format!("{}", order.meta.created));
+                                            });
+                                            row.col(|ui| {
+                                                if ui.button("🧾").clicked() {
+                                                    let r_pool = Arc::clone(&pool);
+                                                    let r_uuid = order.uuid;
+                                                    self.receipt_prompt =
+                                                        ReceiptPrompt::Saving(Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                let receipt =
+                                                                    Receipt::fetch(&r_pool, r_uuid)
+                                                                        .await?;
+                                                                let path = PathBuf::from(
+                                                                    receipt.file_name(),
+                                                                );
+                                                                fs::write(
+                                                                    &path,
+                                                                    receipt.to_html(),
+                                                                )?;
+                                                                Ok(path)
+                                                            },
+                                                        ));
+                                                }
                                             });
                                             row.col(|ui| {
-                                                ui.label(manufacturer.country.clone());
+                                                if *access >= TableAccess::Create
+                                                    && ui.button("💳").clicked()
+                                                {
+                                                    let p_pool = Arc::clone(&pool);
+                                                    let p_uuid = order.uuid;
+                                                    self.payment_prompt = PaymentPrompt::Loading(
+                                                        order.uuid,
+                                                        order.tracking.clone(),
+                                                        Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                let invoices =
+                                                                    Invoice::get_by_order(p_uuid)
+                                                                        .fetch_all(&*p_pool)
+                                                                        .await?;
+                                                                let mut lines = Vec::with_capacity(
+                                                                    invoices.len(),
+                                                                );
+                                                                for invoice in invoices {
+                                                                    let payments =
+                                                                        Payment::get_by_invoice(
+                                                                            invoice.uuid,
+                                                                        )
+                                                                        .fetch_all(&*p_pool)
+                                                                        .await?;
+                                                                    let paid = Payment::net(
+                                                                        &payments,
+                                                                        invoice.amount.currency(),
+                                                                    )?;
+                                                                    lines.push((invoice, paid));
+                                                                }
+                                                                Ok(lines)
+                                                            },
+                                                        ),
+                                                    );
+                                                }
                                             });
                                             row.col(|ui| {
-                                                if *access >= TableAccess::Delete {
-                                                    if ui.button("🗑").clicked() {
-                                                        self.delete_prompt = DeletePrompt::Confirm(
-                                                            (manufacturer.uuid, *window),
-                                                        );
-                                                    }
+                                                if *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt = DeletePrompt::Confirm((
+                                                        order.uuid,
+                                                        order.meta.updated,
+                                                        *window,
+                                                    ));
                                                 }
                                             });
                                         }
@@ -367,102 +2066,278 @@ impl MainView {
                                         }
                                     }),
                                 ),
-                                TableData::Services { data } => Table::draw(
+                                TableData::Supplies { data } => Table::draw(
                                     ui,
                                     &[
                                         Size::exact(ID_WIDTH),
                                         Size::exact(UUID_WIDTH),
                                         Size::initial(120.0),
-                                        Size::initial(120.0),
+                                        Size::initial(80.0),
+                                        Size::initial(60.0),
+                                        Size::initial(60.0),
+                                        Size::initial(60.0),
+                                        Size::initial(60.0),
                                         Size::exact(TIMESTAMP_WIDTH),
                                         Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
                                         Size::exact(BUTTON_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                        Size::exact(BUTTON_WIDTH),
+                                    ],
+                                    &[
+                                        "ID",
+                                        "UUID",
+                                        "Supplier",
+                                        "Status",
+                                        "Lines",
+                                        "Ordered",
+                                        "Received",
+                                        "Mismatch",
+                                        "Due",
+                                        "Delivered",
+                                        "Created",
                                     ],
-                                    &["ID", "UUID", "Name", "Description", "Updated", "Created"],
                                     (data.len(), |index, mut row| match data.get(index) {
-                                        Some(service) => {
+                                        Some(supply) => {
                                             row.col(|ui| {
                                                 ui.label(index.to_string());
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", service.uuid));
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(service.name.clone());
+                                                ui.label(format!("{}", supply.uuid));
                                             });
                                             row.col(|ui| {
-                                                ui.label(if service.description.is_some() {
-                                                    service.description.clone().unwrap()
-                                                } else {
-                                                    String::new()
-                                                });
+                                                ui.label(supply.supplier.clone());
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", service.meta.created));
+                                                ui.label(format!("{:?}", supply.status));
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", service.meta.created));
+                                                ui.label(supply.lines.to_string());
                                             });
                                             row.col(|ui| {
-                                                if *access >= TableAccess::Delete {
-                                                    if ui.button("🗑").clicked() {
-                                                        self.delete_prompt = DeletePrompt::Confirm(
-                                                            (service.uuid, *window),
-                                                        );
-                                                    }
-                                                }
+                                                ui.label(supply.ordered.to_string());
                                             });
-                                        }
-                                        None => {
                                             row.col(|ui| {
-                                                ui.label("Error while indexing");
+                                                ui.label(
+                                                    supply
+                                                        .received
+                                                        .map(|r| r.to_string())
+                                                        .unwrap_or_default(),
+                                                );
                                             });
-                                        }
-                                    }),
-                                ),
-                                TableData::Suppliers { data } => Table::draw(
-                                    ui,
-                                    &[
-                                        Size::exact(ID_WIDTH),
-                                        Size::exact(UUID_WIDTH),
-                                        Size::initial(120.0),
-                                        Size::exact(235.0),
-                                        Size::initial(120.0),
-                                        Size::initial(210.0),
-                                        Size::exact(COUNTRY_WIDTH),
-                                        Size::exact(BUTTON_WIDTH),
-                                    ],
-                                    &["ID", "UUID", "Name", "IBAN", "Swift", "Address", "Country"],
-                                    (data.len(), |index, mut row| match data.get(index) {
-                                        Some(supplier) => {
                                             row.col(|ui| {
-                                                ui.label(index.to_string());
+                                                if supply.discrepancies > 0 {
+                                                    ui.colored_label(
+                                                        Color32::RED,
+                                                        supply.discrepancies.to_string(),
+                                                    );
+                                                } else {
+                                                    ui.label(supply.discrepancies.to_string());
+                                                }
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", supplier.uuid));
+                                                ui.label(
+                                                    supply
+                                                        .due
+                                                        .map(|d| d.to_string())
+                                                        .unwrap_or_default(),
+                                                );
                                             });
                                             row.col(|ui| {
-                                                ui.label(supplier.name.clone());
+                                                ui.label(
+                                                    supply
+                                                        .delivered
+                                                        .map(|d| d.to_string())
+                                                        .unwrap_or_default(),
+                                                );
                                             });
                                             row.col(|ui| {
-                                                ui.label(supplier.iban.clone());
+                                                ui.label(format!("{}", supply.meta.created));
                                             });
+                                            // Managers decide on supplies under review and
+                                            // track signed ones, warehouse workers receive them
+                                            let edit = *access >= TableAccess::Edit;
+                                            let manage = edit
+                                                && matches!(
+                                                    role,
+                                                    AccountRole::Admin | AccountRole::Manager
+                                                );
+                                            let stock = edit
+                                                && matches!(
+                                                    role,
+                                                    AccountRole::Admin
+                                                        | AccountRole::WarehouseWorker
+                                                );
+                                            let review = matches!(
+                                                supply.status,
+                                                SupplyStatus::Review | SupplyStatus::Negotiation
+                                            );
+                                            let signed = matches!(
+                                                supply.status,
+                                                SupplyStatus::Signed | SupplyStatus::Paid
+                                            );
+                                            let awaiting = signed
+                                                || matches!(
+                                                    supply.status,
+                                                    SupplyStatus::Dispatched
+                                                );
+                                            let s_uuid = supply.uuid;
+                                            let s_version = supply.meta.updated;
+
                                             row.col(|ui| {
-                                                ui.label(supplier.swift.clone());
+                                                let s_pool = Arc::clone(&pool);
+                                                if manage
+                                                    && review
+                                                    && ui
+                                                        .button("✔")
+                                                        .on_hover_text("Approve")
+                                                        .clicked()
+                                                {
+                                                    self.supply_prompt =
+                                                        SupplyPrompt::Running(Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                Supply::approve(
+                                                                    s_uuid, staff, s_version,
+                                                                )
+                                                                .execute(&*s_pool)
+                                                                .await
+                                                                .map_err(Conflict::map)?;
+                                                                Ok(format!(
+                                                                    "Supply '{s_uuid}' signed"
+                                                                ))
+                                                            },
+                                                        ));
+                                                } else if manage
+                                                    && signed
+                                                    && ui
+                                                        .button("🚚")
+                                                        .on_hover_text("Mark as dispatched")
+                                                        .clicked()
+                                                {
+                                                    self.supply_prompt =
+                                                        SupplyPrompt::Running(Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                Supply::dispatch(
+                                                                    s_uuid, None, s_version,
+                                                                )
+                                                                .execute(&*s_pool)
+                                                                .await
+                                                                .map_err(Conflict::map)?;
+                                                                Ok(format!(
+                                                                    "Supply '{s_uuid}' dispatched"
+                                                                ))
+                                                            },
+                                                        ));
+                                                }
                                             });
                                             row.col(|ui| {
-                                                ui.label(supplier.address.clone());
+                                                let s_pool = Arc::clone(&pool);
+                                                if manage
+                                                    && review
+                                                    && ui
+                                                        .button("✖")
+                                                        .on_hover_text("Reject")
+                                                        .clicked()
+                                                {
+                                                    self.supply_prompt =
+                                                        SupplyPrompt::Running(Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                let result = Supply::reject(
+                                                                    s_uuid, staff, s_version,
+                                                                )
+                                                                .execute(&*s_pool)
+                                                                .await
+                                                                .map_err(Conflict::map)?;
+                                                                if result.rows_affected() == 0 {
+                                                                    bail!("Supply '{s_uuid}' is not under review");
+                                                                }
+                                                                Ok(format!(
+                                                                    "Supply '{s_uuid}' rejected"
+                                                                ))
+                                                            },
+                                                        ));
+                                                } else if stock
+                                                    && awaiting
+                                                    && ui
+                                                        .button("📦")
+                                                        .on_hover_text("Receive")
+                                                        .clicked()
+                                                {
+                                                    self.supply_prompt = SupplyPrompt::Loading(
+                                                        s_uuid,
+                                                        s_version,
+                                                        Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                let lines =
+                                                                    SupplyItem::get_by_supply(
+                                                                        s_uuid,
+                                                                    )
+                                                                    .fetch_all(&*s_pool)
+                                                                    .await?;
+                                                                let mut named =
+                                                                    Vec::with_capacity(lines.len());
+                                                                for line in lines {
+                                                                    let component =
+                                                                        Component::get_by_uuid(
+                                                                            line.component,
+                                                                        )
+                                                                        .fetch_one(&*s_pool)
+                                                                        .await?;
+                                                                    named.push((
+                                                                        line,
+                                                                        component.name,
+                                                                    ));
+                                                                }
+                                                                Ok(named)
+                                                            },
+                                                        ),
+                                                    );
+                                                }
                                             });
                                             row.col(|ui| {
-                                                ui.label(supplier.country.clone());
+                                                if manage
+                                                    && awaiting
+                                                    && ui
+                                                        .button("⚠")
+                                                        .on_hover_text("Mark as failed")
+                                                        .clicked()
+                                                {
+                                                    let s_pool = Arc::clone(&pool);
+                                                    self.supply_prompt =
+                                                        SupplyPrompt::Running(Request::simple(
+                                                            runtime,
+                                                            move || async move {
+                                                                let result =
+                                                                    Supply::fail(s_uuid, s_version)
+                                                                        .execute(&*s_pool)
+                                                                        .await
+                                                                        .map_err(Conflict::map)?;
+                                                                if result.rows_affected() == 0 {
+                                                                    bail!("Supply '{s_uuid}' is not awaiting delivery");
+                                                                }
+                                                                Ok(format!(
+                                                                    "Supply '{s_uuid}' failed"
+                                                                ))
+                                                            },
+                                                        ));
+                                                }
                                             });
+                                            // Supplies awaiting delivery are failed instead
                                             row.col(|ui| {
-                                                if *access >= TableAccess::Delete {
-                                                    if ui.button("🗑").clicked() {
-                                                        self.delete_prompt = DeletePrompt::Confirm(
-                                                            (supplier.uuid, *window),
-                                                        );
-                                                    }
+                                                if manage
+                                                    && !awaiting
+                                                    && *access >= TableAccess::Delete
+                                                    && ui.button("🗑").clicked()
+                                                {
+                                                    self.delete_prompt =
+                                                        DeletePrompt::Confirm((
+                                                            s_uuid, s_version, *window,
+                                                        ));
                                                 }
                                             });
                                         }
@@ -473,72 +2348,91 @@ impl MainView {
                                         }
                                     }),
                                 ),
-                                TableData::Phones { data } => Table::draw(
+                                TableData::Duplicates { data } => Table::draw(
                                     ui,
                                     &[
                                         Size::exact(ID_WIDTH),
-                                        Size::exact(UUID_WIDTH),
-                                        Size::initial(120.0),
+                                        Size::initial(140.0),
+                                        Size::initial(180.0),
                                         Size::initial(120.0),
+                                        Size::initial(140.0),
+                                        Size::initial(180.0),
                                         Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::exact(TIMESTAMP_WIDTH),
-                                        Size::exact(TIMESTAMP_WIDTH),
+                                        Size::initial(60.0),
+                                        Size::initial(100.0),
                                         Size::exact(BUTTON_WIDTH),
                                     ],
                                     &[
                                         "ID",
-                                        "UUID",
-                                        "Owner",
-                                        "IMEI",
-                                        "WiFi",
-                                        "Bluetooth",
-                                        "Model",
-                                        "Color",
-                                        "Updated",
-                                        "Created",
+                                        "Person",
+                                        "Email",
+                                        "Phone",
+                                        "Duplicate",
+                                        "Email",
+                                        "Phone",
+                                        "Name",
+                                        "Same",
                                     ],
                                     (data.len(), |index, mut row| match data.get(index) {
-                                        Some(phone) => {
+                                        Some(pair) => {
                                             row.col(|ui| {
                                                 ui.label(index.to_string());
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", phone.uuid));
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(phone.owner.clone());
+                                                ui.label(pair.person_name.clone());
                                             });
                                             row.col(|ui| {
-                                                ui.label(phone.imei.clone());
+                                                ui.label(pair.person_email.clone());
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", phone.wifi.clone()));
+                                                ui.label(pair.person_phone.clone());
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", phone.bluetooth.clone()));
+                                                ui.label(pair.duplicate_name.clone());
                                             });
                                             row.col(|ui| {
-                                                ui.label(phone.model.clone());
+                                                ui.label(pair.duplicate_email.clone());
                                             });
                                             row.col(|ui| {
-                                                ui.label(phone.color.as_str());
+                                                ui.label(pair.duplicate_phone.clone());
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", phone.meta.updated));
+                                                ui.label(format!(
+                                                    "{:.0}%",
+                                                    pair.name_similarity * 100.0
+                                                ));
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", phone.meta.created));
+                                                let same = match (pair.same_phone, pair.same_email)
+                                                {
+                                                    (true, true) => "phone, email",
+                                                    (true, false) => "phone",
+                                                    (false, true) => "email",
+                                                    (false, false) => "",
+                                                };
+                                                ui.label(same);
                                             });
                                             row.col(|ui| {
-                                                if *access >= TableAccess::Delete {
-                                                    if ui.button("🗑").clicked() {
-                                                        self.delete_prompt = DeletePrompt::Confirm(
-                                                            (phone.uuid, *window),
-                                                        );
-                                                    }
+                                                if *access >= TableAccess::Edit
+                                                    && ui.button("🔀").clicked()
+                                                {
+                                                    self.merge_prompt = MergePrompt::Confirm(
+                                                        (
+                                                            pair.person,
+                                                            format!(
+                                                                "{} <{}>",
+                                                                pair.person_name, pair.person_email
+                                                            ),
+                                                        ),
+                                                        (
+                                                            pair.duplicate,
+                                                            format!(
+                                                                "{} <{}>",
+                                                                pair.duplicate_name,
+                                                                pair.duplicate_email
+                                                            ),
+                                                        ),
+                                                    );
                                                 }
                                             });
                                         }
@@ -549,52 +2443,57 @@ impl MainView {
                                         }
                                     }),
                                 ),
-                                TableData::Components { data } => Table::draw(
+                                TableData::Trash { data } => Table::draw(
                                     ui,
                                     &[
                                         Size::exact(ID_WIDTH),
+                                        Size::initial(110.0),
                                         Size::exact(UUID_WIDTH),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
-                                        Size::initial(120.0),
+                                        Size::initial(220.0),
+                                        Size::exact(TIMESTAMP_WIDTH),
                                         Size::exact(BUTTON_WIDTH),
                                     ],
-                                    &[
-                                        "ID",
-                                        "UUID",
-                                        "Name",
-                                        "Kind",
-                                        "Phone Model",
-                                        "Manufacturer",
-                                    ],
+                                    &["ID", "Table", "UUID", "Name", "Deleted"],
                                     (data.len(), |index, mut row| match data.get(index) {
-                                        Some(component) => {
+                                        Some(trashed) => {
                                             row.col(|ui| {
                                                 ui.label(index.to_string());
                                             });
                                             row.col(|ui| {
-                                                ui.label(format!("{}", component.uuid));
-                                            });
-                                            row.col(|ui| {
-                                                ui.label(component.name.clone());
+                                                ui.label(trashed.table.name());
                                             });
                                             row.col(|ui| {
-                                                ui.label(component.kind.clone());
+                                                ui.label(trashed.uuid.to_string());
                                             });
                                             row.col(|ui| {
-                                                ui.label(component.model.clone());
+                                                ui.label(trashed.label.clone());
                                             });
                                             row.col(|ui| {
-                                                ui.label(component.manufacturer.clone());
+                                                ui.label(format!("{}", trashed.deleted_at));
                                             });
                                             row.col(|ui| {
-                                                if *access >= TableAccess::Delete {
-                                                    if ui.button("🗑").clicked() {
-                                                        self.delete_prompt = DeletePrompt::Confirm(
-                                                            (component.uuid, *window),
-                                                        );
-                                                    }
+                                                if *access >= TableAccess::Edit
+                                                    && ui.button("♻").clicked()
+                                                {
+                                                    let r_pool = Arc::clone(&pool);
+                                                    let (table, uuid, version, label) = (
+                                                        trashed.table,
+                                                        trashed.uuid,
+                                                        trashed.updated,
+                                                        trashed.label.clone(),
+                                                    );
+                                                    self.restore_prompt = RestorePrompt::Running(
+                                                        TableWindow::of_table(table),
+                                                        Request::simple(runtime, move || async move {
+                                                            trash::restore(
+                                                                &*r_pool, table, uuid, version,
+                                                            )
+                                                            .await?;
+                                                            Ok(format!(
+                                                                "'{label}' restored to '{table}' table"
+                                                            ))
+                                                        }),
+                                                    );
                                                 }
                                             });
                                         }
@@ -647,7 +2546,8 @@ impl MainView {
 
 pub enum DeletePrompt {
     None,
-    Confirm((Uuid, TableWindow)),
+    /// Row of the given version in the window
+    Confirm((Uuid, DateTime<Utc>, TableWindow)),
     Loading((TableWindow, Request<(), ()>)),
     Error(String),
 }
@@ -661,3 +2561,182 @@ impl DeletePrompt {
         replace(self, Self::None)
     }
 }
+
+pub enum SupplyPrompt {
+    None,
+    /// Lines of the supply to be received are being read with their component names
+    Loading(Uuid, DateTime<Utc>, Request<(), Vec<(SupplyItem, String)>>),
+    /// Received amount of every line, the supply version is checked on receiving
+    Receive {
+        supply: Uuid,
+        version: DateTime<Utc>,
+        lines: Vec<(SupplyItem, String, i32)>,
+    },
+    Running(Request<(), String>),
+    Done(String),
+}
+
+impl SupplyPrompt {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn take(&mut self) -> Self {
+        replace(self, Self::None)
+    }
+}
+
+pub enum TransferPrompt {
+    None,
+    /// Branches the item of the given version can be shipped to are being read
+    Loading(Uuid, DateTime<Utc>, i32, Request<(), Vec<Branch>>),
+    Ship {
+        item: Uuid,
+        version: DateTime<Utc>,
+        available: i32,
+        amount: i32,
+        destination: Option<Uuid>,
+        branches: Vec<Branch>,
+    },
+    /// Window of the changed row to be reloaded on conflict
+    Running(TableWindow, Request<(), String>),
+    Done(String),
+}
+
+impl TransferPrompt {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn take(&mut self) -> Self {
+        replace(self, Self::None)
+    }
+}
+
+pub enum PaymentPrompt {
+    None,
+    /// Invoices of the order are being read with the amounts paid on them
+    Loading(Uuid, String, Request<(), Vec<(Invoice, Money)>>),
+    /// Typed amount is either billed with a new invoice or paid (refunded) on the selected one
+    Edit {
+        order: Uuid,
+        tracking: String,
+        invoices: Vec<(Invoice, Money)>,
+        selected: Option<Uuid>,
+        amount: String,
+        method: PaymentMethod,
+    },
+    Running(Request<(), String>),
+    Done(String),
+}
+
+impl PaymentPrompt {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn take(&mut self) -> Self {
+        replace(self, Self::None)
+    }
+}
+
+pub enum ReceiptPrompt {
+    None,
+    Saving(Request<(), PathBuf>),
+    Done(String),
+}
+
+impl ReceiptPrompt {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn take(&mut self) -> Self {
+        replace(self, Self::None)
+    }
+}
+
+pub enum HistoryPrompt {
+    None,
+    Loading(Imei, Request<(), Vec<PhoneHistory>>),
+    Loaded(Imei, Vec<PhoneHistory>),
+    Error(String),
+}
+
+impl HistoryPrompt {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn take(&mut self) -> Self {
+        replace(self, Self::None)
+    }
+}
+
+pub enum SearchPrompt {
+    None,
+    Loading(String, Request<(), Search>),
+    Loaded(String, Search),
+    Error(String),
+}
+
+impl SearchPrompt {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn take(&mut self) -> Self {
+        replace(self, Self::None)
+    }
+}
+
+pub enum MergePrompt {
+    None,
+    /// Kept and duplicate people with their labels
+    Confirm((Uuid, String), (Uuid, String)),
+    Running(Request<(), String>),
+    Done(String),
+}
+
+impl MergePrompt {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn take(&mut self) -> Self {
+        replace(self, Self::None)
+    }
+}
+
+/// Row was changed by someone else since it was read, its window is to be reloaded
+pub enum ConflictPrompt {
+    None,
+    Reload(TableWindow, String),
+}
+
+impl ConflictPrompt {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn take(&mut self) -> Self {
+        replace(self, Self::None)
+    }
+}
+
+pub enum RestorePrompt {
+    None,
+    /// Window of the restored row to be reloaded with the trash
+    Running(Option<TableWindow>, Request<(), String>),
+    Done(String),
+}
+
+impl RestorePrompt {
+    pub fn is_none(&self) -> bool {
+        matches!(self, Self::None)
+    }
+
+    pub fn take(&mut self) -> Self {
+        replace(self, Self::None)
+    }
+}
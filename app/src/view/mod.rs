@@ -13,6 +13,7 @@ pub mod main;
 pub mod setup;
 pub mod table;
 
+#[allow(clippy::large_enum_variant)]
 pub enum AppViews {
     Auth(AuthView),
     Setup(SetupView),
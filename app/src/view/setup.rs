@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use cw_core::sqlx::PgPool;
+use cw_core::{schema::Schema, sqlx::PgPool};
 use eframe::{
     egui::{ComboBox, Context, DragValue, RichText, TextEdit, TextStyle, Window},
     emath::{Align2, Vec2},
@@ -24,6 +24,7 @@ pub struct SetupView {
     user_input: String,
     password_input: String,
     database_input: String,
+    schema_input: String,
     ssl_mode: SslMode,
     min_pool: u32,
     max_pool: u32,
@@ -52,6 +53,7 @@ impl SetupView {
             this.user_input = connection.user.clone();
             this.password_input = connection.password.clone();
             this.database_input = connection.database.clone();
+            this.schema_input = connection.schema.to_string();
             this.min_pool = connection.min_pool;
             this.max_pool = connection.max_pool;
         }
@@ -71,7 +73,23 @@ impl SetupView {
         this
     }
 
+    /// Empty input stands for the `public` schema
+    fn schema(&self) -> Result<Schema, String> {
+        if self.schema_input.is_empty() {
+            Ok(Schema::default())
+        } else {
+            self.schema_input.parse()
+        }
+    }
+
     fn start_processing(&mut self, runtime: &Runtime) {
+        let schema = match self.schema() {
+            Ok(schema) => schema,
+            Err(err) => {
+                self.error = Some(err);
+                return;
+            }
+        };
         let uri = format!(
             "postgres://{}:{}@{}/{}",
             self.user_input, self.password_input, self.host_input, self.database_input
@@ -82,7 +100,7 @@ impl SetupView {
         self.processing = Some(Request::new(
             runtime.spawn(async move {
                 tx.send(String::from("Connecting")).await?;
-                let pool = open_pool(uri, ssl_mode, bound).await?;
+                let pool = open_pool(uri, ssl_mode, bound, schema).await?;
                 tx.send(String::from("Connected")).await?;
                 Ok(Arc::new(pool))
             }),
@@ -129,6 +147,7 @@ impl SetupView {
                                     ssl_mode: self.ssl_mode,
                                     min_pool: self.min_pool,
                                     max_pool: self.max_pool,
+                                    schema: self.schema().unwrap_or_default(),
                                 });
                                 response = ViewResponse::next(pool, self.is_reactive);
                             }
@@ -179,6 +198,12 @@ impl SetupView {
                     );
                     ui.add_space(16.0);
                     ui.collapsing(RichText::new("More options").heading(), |ui| {
+                        ui.heading("Schema: ");
+                        ui.add(
+                            TextEdit::singleline(&mut self.schema_input)
+                                .font(TextStyle::Heading)
+                                .hint_text(Schema::PUBLIC),
+                        );
                         ui.heading("SSL Mode: ");
                         ComboBox::from_id_source("ssl_mode")
                             .selected_text(self.ssl_mode.as_str())
@@ -225,6 +250,7 @@ impl Default for SetupView {
             user_input: Default::default(),
             password_input: Default::default(),
             database_input: Default::default(),
+            schema_input: Default::default(),
             ssl_mode: Default::default(),
             min_pool: 1,
             max_pool: 16,
@@ -1,15 +1,25 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, mem::replace};
 
 use cw_core::{
-    tables::{Manufacturer, Person, Position, Service, Supplier},
+    functions::{DuplicatePair, PersonDuplicates},
+    search::SearchGroup,
+    tables::{Manufacturer, Order, Person, Position, Service, Supplier},
+    trash::{self, Trashed},
     types::AccountRole,
-    views::{ComponentBeautified, PhoneBeautified},
+    uuid::Uuid,
+    views::{
+        ComponentBeautified, PhoneBeautified, StockTransferBeautified, SupplyBeautified,
+        WarehouseBeautified,
+    },
 };
 use eframe::egui::{TextStyle, Ui};
 use egui_extras::{Size, TableBuilder, TableRow};
 use tokio::runtime::Runtime;
 
-use crate::{model::request::Request, utils::Pool};
+use crate::{
+    model::request::{Request, RequestStatus},
+    utils::Pool,
+};
 
 pub type WindowStorage = BTreeMap<TableWindow, (bool, TableAccess, WindowState)>;
 
@@ -60,6 +70,12 @@ pub enum TableData {
     Suppliers { data: Vec<Supplier> },
     Phones { data: Vec<PhoneBeautified> },
     Components { data: Vec<ComponentBeautified> },
+    Stock { data: Vec<WarehouseBeautified> },
+    Transfers { data: Vec<StockTransferBeautified> },
+    Orders { data: Vec<Order> },
+    Supplies { data: Vec<SupplyBeautified> },
+    Duplicates { data: Vec<DuplicatePair> },
+    Trash { data: Vec<Trashed> },
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
@@ -71,6 +87,12 @@ pub enum TableWindow {
     Suppliers,
     Phones,
     Components,
+    Stock,
+    Transfers,
+    Orders,
+    Supplies,
+    Duplicates,
+    Trash,
 }
 
 impl TableWindow {
@@ -82,6 +104,12 @@ impl TableWindow {
         Self::Suppliers,
         Self::Phones,
         Self::Components,
+        Self::Stock,
+        Self::Transfers,
+        Self::Orders,
+        Self::Supplies,
+        Self::Duplicates,
+        Self::Trash,
     ];
 
     pub fn all_by_role(role: AccountRole) -> WindowStorage {
@@ -138,6 +166,35 @@ impl TableWindow {
                 (TableAccess::View, AccountRole::Serviceman),
                 (TableAccess::View, AccountRole::WarehouseWorker),
             ],
+            Self::Stock => &[
+                (TableAccess::Edit, AccountRole::Admin),
+                (TableAccess::View, AccountRole::Manager),
+                (TableAccess::View, AccountRole::Serviceman),
+                (TableAccess::Edit, AccountRole::WarehouseWorker),
+            ],
+            Self::Transfers => &[
+                (TableAccess::Edit, AccountRole::Admin),
+                (TableAccess::View, AccountRole::Manager),
+                (TableAccess::Edit, AccountRole::WarehouseWorker),
+            ],
+            Self::Orders => &[
+                (TableAccess::Full, AccountRole::Admin),
+                (TableAccess::View, AccountRole::Manager),
+                (TableAccess::Create, AccountRole::Shopman),
+            ],
+            Self::Supplies => &[
+                (TableAccess::Edit, AccountRole::Admin),
+                (TableAccess::Edit, AccountRole::Manager),
+                (TableAccess::Edit, AccountRole::WarehouseWorker),
+            ],
+            Self::Duplicates => &[
+                (TableAccess::Full, AccountRole::Admin),
+                (TableAccess::Edit, AccountRole::Accountant),
+            ],
+            Self::Trash => &[
+                (TableAccess::Full, AccountRole::Admin),
+                (TableAccess::Edit, AccountRole::Manager),
+            ],
         }
     }
 
@@ -150,6 +207,85 @@ impl TableWindow {
             Self::Suppliers => "Suppliers",
             Self::Phones => "Phones",
             Self::Components => "Components",
+            Self::Stock => "Stock",
+            Self::Transfers => "Transfers",
+            Self::Orders => "Orders",
+            Self::Supplies => "Supplies",
+            Self::Duplicates => "Duplicates",
+            Self::Trash => "Trash",
+        }
+    }
+
+    /// Window listing rows of the database table, if there is one
+    pub fn of_table(table: cw_core::tables::Table) -> Option<Self> {
+        use cw_core::tables::Table;
+
+        match table {
+            Table::Person => Some(Self::People),
+            Table::Position => Some(Self::Positions),
+            Table::Manufacturer => Some(Self::Manufacturers),
+            Table::Service => Some(Self::Services),
+            Table::Supplier => Some(Self::Suppliers),
+            Table::Phone => Some(Self::Phones),
+            Table::Component => Some(Self::Components),
+            Table::Warehouse => Some(Self::Stock),
+            Table::Order => Some(Self::Orders),
+            Table::Supply => Some(Self::Supplies),
+            _ => None,
+        }
+    }
+
+    /// Search results shown to those who can open the window
+    pub fn search_group(&self) -> Option<SearchGroup> {
+        match self {
+            Self::People => Some(SearchGroup::Clients),
+            Self::Phones => Some(SearchGroup::Phones),
+            Self::Components => Some(SearchGroup::Components),
+            Self::Suppliers => Some(SearchGroup::Suppliers),
+            _ => None,
+        }
+    }
+
+    /// Database tables the window data is read from
+    pub fn tables(&self) -> &[cw_core::tables::Table] {
+        use cw_core::tables::Table;
+
+        match self {
+            Self::People => &[Table::Person],
+            Self::Positions => &[Table::Position],
+            Self::Manufacturers => &[Table::Manufacturer],
+            Self::Services => &[Table::Service],
+            Self::Suppliers => &[Table::Supplier],
+            Self::Phones => &[Table::Phone, Table::Person, Table::PhoneModel],
+            Self::Components => &[
+                Table::Component,
+                Table::ComponentKind,
+                Table::Manufacturer,
+                Table::PhoneModel,
+            ],
+            Self::Stock => &[
+                Table::Warehouse,
+                Table::Component,
+                Table::Supplier,
+                Table::Branch,
+            ],
+            Self::Transfers => &[
+                Table::StockTransfer,
+                Table::Warehouse,
+                Table::Component,
+                Table::Supplier,
+                Table::Branch,
+            ],
+            Self::Orders => &[Table::Order],
+            Self::Supplies => &[
+                Table::Supply,
+                Table::SupplyItem,
+                Table::SupplyContract,
+                Table::Supplier,
+                Table::Staff,
+            ],
+            Self::Duplicates => &[Table::Person, Table::PersonMerge],
+            Self::Trash => &trash::TABLES,
         }
     }
 }
@@ -159,6 +295,8 @@ pub enum WindowState {
     Error(String),
     Loading(Request<(), TableData>, TableWindow),
     Loaded(TableData),
+    /// Loaded data is shown until the changed one is read
+    Refreshing(TableData, Request<(), TableData>),
 }
 
 impl WindowState {
@@ -166,35 +304,91 @@ impl WindowState {
         !matches!(self, Self::None)
     }
 
-    pub fn load(runtime: &Runtime, pool: Pool, window: TableWindow) -> Self {
-        Self::Loading(
-            Request::simple(runtime, move || async move {
-                Ok(match window {
-                    TableWindow::People => TableData::People {
-                        data: Person::get_all().fetch_all(&*pool).await?,
-                    },
-                    TableWindow::Positions => TableData::Positions {
-                        data: Position::get_all().fetch_all(&*pool).await?,
-                    },
-                    TableWindow::Manufacturers => TableData::Manufacturers {
-                        data: Manufacturer::get_all().fetch_all(&*pool).await?,
-                    },
-                    TableWindow::Services => TableData::Services {
-                        data: Service::get_all().fetch_all(&*pool).await?,
-                    },
-                    TableWindow::Suppliers => TableData::Suppliers {
-                        data: Supplier::get_all().fetch_all(&*pool).await?,
-                    },
-                    TableWindow::Phones => TableData::Phones {
-                        data: PhoneBeautified::get_all().fetch_all(&*pool).await?,
-                    },
-                    TableWindow::Components => TableData::Components {
-                        data: ComponentBeautified::get_all().fetch_all(&*pool).await?,
-                    },
-                })
-            }),
-            window,
-        )
+    /// Branch-bound tables (stock, transfers, orders, supplies) are filtered by the given branch
+    pub fn load(runtime: &Runtime, pool: Pool, window: TableWindow, branch: Uuid) -> Self {
+        Self::Loading(Self::request(runtime, pool, window, branch), window)
+    }
+
+    /// Reads the window data again keeping the loaded one on the screen
+    pub fn refresh(&mut self, runtime: &Runtime, pool: Pool, window: TableWindow, branch: Uuid) {
+        *self = match replace(self, Self::None) {
+            Self::None => Self::None,
+            Self::Loaded(data) | Self::Refreshing(data, _) => {
+                Self::Refreshing(data, Self::request(runtime, pool, window, branch))
+            }
+            Self::Error(_) | Self::Loading(..) => Self::load(runtime, pool, window, branch),
+        }
+    }
+
+    /// Swaps refreshed data in once it's read
+    pub fn poll_refresh(&mut self, runtime: &Runtime) {
+        if let Self::Refreshing(_, request) = self {
+            if let RequestStatus::Finished(result) = request.peek(runtime).status.take() {
+                *self = match result {
+                    Ok(data) => Self::Loaded(data),
+                    Err(err) => Self::Error(format!("{err}")),
+                }
+            }
+        }
+    }
+
+    fn request(
+        runtime: &Runtime,
+        pool: Pool,
+        window: TableWindow,
+        branch: Uuid,
+    ) -> Request<(), TableData> {
+        Request::simple(runtime, move || async move {
+            Ok(match window {
+                TableWindow::People => TableData::People {
+                    data: Person::get_all().fetch_all(&*pool).await?,
+                },
+                TableWindow::Positions => TableData::Positions {
+                    data: Position::get_all().fetch_all(&*pool).await?,
+                },
+                TableWindow::Manufacturers => TableData::Manufacturers {
+                    data: Manufacturer::get_all().fetch_all(&*pool).await?,
+                },
+                TableWindow::Services => TableData::Services {
+                    data: Service::get_all().fetch_all(&*pool).await?,
+                },
+                TableWindow::Suppliers => TableData::Suppliers {
+                    data: Supplier::get_all().fetch_all(&*pool).await?,
+                },
+                TableWindow::Phones => TableData::Phones {
+                    data: PhoneBeautified::get_all().fetch_all(&*pool).await?,
+                },
+                TableWindow::Components => TableData::Components {
+                    data: ComponentBeautified::get_all().fetch_all(&*pool).await?,
+                },
+                TableWindow::Stock => TableData::Stock {
+                    data: WarehouseBeautified::get_by_branch(branch)
+                        .fetch_all(&*pool)
+                        .await?,
+                },
+                TableWindow::Transfers => TableData::Transfers {
+                    data: StockTransferBeautified::get_by_branch(branch)
+                        .fetch_all(&*pool)
+                        .await?,
+                },
+                TableWindow::Orders => TableData::Orders {
+                    data: Order::get_by_branch(branch).fetch_all(&*pool).await?,
+                },
+                TableWindow::Supplies => TableData::Supplies {
+                    data: SupplyBeautified::get_by_branch(branch)
+                        .fetch_all(&*pool)
+                        .await?,
+                },
+                TableWindow::Duplicates => TableData::Duplicates {
+                    data: PersonDuplicates::report(PersonDuplicates::THRESHOLD)
+                        .fetch_all(&*pool)
+                        .await?,
+                },
+                TableWindow::Trash => TableData::Trash {
+                    data: Trashed::fetch(&*pool, None).await?,
+                },
+            })
+        })
     }
 }
 
@@ -1,181 +1,95 @@
-use std::{fs::File, io::Write, sync::Arc};
+use std::{
+    fs::{self, File},
+    io::{BufReader, Write},
+    sync::Arc,
+};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 
 use cw_core::{
+    chrono::{DateTime, Duration, Utc},
+    drift,
+    dump::{Dump, ExportFilter},
+    explain::Probe,
     extensions::Extension,
-    functions::Function,
+    functions::{
+        CashRegisterClose, Function, IndexPricesPreview, PersonDuplicates, SupplierPerformance,
+        SupplyCostForPeriod,
+    },
     generator::Config,
+    indexes::Index,
+    migrations::Migration,
+    privacy::{self, InactiveClient, PersonalData},
     procedures::Procedure,
+    receipt::Receipt,
+    schema::Schema,
+    script::Script,
+    search::{Search, SearchGroup},
     sqlx::{
         pool::PoolOptions,
         postgres::{PgConnectOptions, PgSslMode},
         query, Error, Executor, PgPool,
     },
-    tables::Table,
+    tables::{
+        Invoice, Order, Payment, Person, Phone, PriceChange, StockTransfer, Supply, SupplyContract,
+        SupplyItem, Table, Warehouse,
+    },
     traits::Insertable,
+    trash::{self, Trashed},
     triggers::Trigger,
-    types::DbType,
-    views::View,
+    types::{Conflict, Currency, DbType, Money, SupplyStatus},
+    uuid::Uuid,
+    views::{PhoneHistory, SupplyItemBeautified, View},
+    BigDecimal,
 };
 use futures::future::try_join_all;
-use serde_json::{json, to_string};
+use serde_json::{from_reader, to_string, to_string_pretty};
 
-use crate::opt::{
-    Command, DatabaseAction, DatabaseOpt, DatabaseUri, Generate, Opt, PoolSize, SslMode,
+use crate::{
+    export, import,
+    opt::{
+        Command, Data, DatabaseAction, DatabaseOpt, DatabaseUri, ExportFormat, Generate, Opt,
+        OrderCmd, OrderRef, PersonCmd, PhoneCmd, PoolSize, PriceCmd, PrivacyCmd, SearchOpt,
+        SslMode, StockCmd, SupplyCmd, TrashCmd, ViewsCmd,
+    },
 };
 
 pub async fn app(opt: Opt) -> Result<()> {
     match opt.command {
+        Command::Database(DatabaseOpt {
+            db,
+            action: DatabaseAction::Create { dry_run, json },
+        }) => {
+            run_script(
+                Script::create(db.schema.clone()),
+                db,
+                opt.pool_size,
+                dry_run,
+                json,
+            )
+            .await?
+        }
+        Command::Database(DatabaseOpt {
+            db,
+            action: DatabaseAction::Drop { dry_run, json },
+        }) => {
+            run_script(
+                Script::drop(db.schema.clone()),
+                db,
+                opt.pool_size,
+                dry_run,
+                json,
+            )
+            .await?
+        }
         Command::Database(DatabaseOpt {
             db,
             action: command,
         }) => {
+            let schema = db.schema.clone();
             let pool = open_pool(db, opt.pool_size).await?;
             match command {
-                DatabaseAction::Create => {
-                    println!("\n- Loading extensions");
-                    Extension::create_all(&pool, |(extension, p)| {
-                        if p {
-                            println!("> Extension '{extension}' has been loaded")
-                        }
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Creating types");
-                    DbType::create_all(&pool, |(db_type, result)| {
-                        match &result {
-                            Ok(_) => println!("> Type '{db_type}' has been created"),
-                            Err(_) => (),
-                        };
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Creating tables");
-                    Table::create_all(&pool, |(table, result)| {
-                        match &result {
-                            Ok(_) => println!("> Table '{table}' has been created"),
-                            Err(_) => (),
-                        };
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Creating views");
-                    View::create_all(&pool, |(view, result)| {
-                        match &result {
-                            Ok(_) => println!("> View '{view}' has been created"),
-                            Err(_) => (),
-                        };
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Creating functions");
-                    Function::create_all(&pool, |(function, result)| {
-                        match &result {
-                            Ok(_) => println!("> Function '{function}' has been created"),
-                            Err(_) => (),
-                        };
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Creating procedures");
-                    Procedure::create_all(&pool, |(procedure, result)| {
-                        match &result {
-                            Ok(_) => println!("> Procedure '{procedure}' has been created"),
-                            Err(_) => (),
-                        };
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Creating triggers");
-                    Trigger::create_all(&pool, |(trigger, table, result)| {
-                        match &result {
-                            Ok(_) => println!(
-                                ">> Trigger '{trigger}' for '{table}' table has been created"
-                            ),
-                            Err(_) => (),
-                        };
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                }
-                DatabaseAction::Drop => {
-                    print!("\n- Dropping triggers\n");
-                    Trigger::drop_all(&pool, |(trigger, table, result)| {
-                        match &result {
-                            Ok(_) => println!(
-                                ">> Trigger '{trigger}' for '{table}' table has been dropped"
-                            ),
-                            Err(_) => (),
-                        }
-                        result
-                    })
-                    .await?;
-                    print!("\n- Dropping procedures\n");
-                    Procedure::drop_all(&pool, |(procedure, result)| {
-                        match &result {
-                            Ok(_) => println!("> Procedure '{procedure}' has been dropped"),
-                            Err(_) => (),
-                        }
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Dropping functions");
-                    Function::drop_all(&pool, |(function, result)| {
-                        match &result {
-                            Ok(_) => println!("> Function '{function}' has been dropped"),
-                            Err(_) => (),
-                        }
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Dropping views");
-                    View::drop_all(&pool, |(view, result)| {
-                        match &result {
-                            Ok(_) => println!("> View '{view}' has been dropped"),
-                            Err(_) => (),
-                        }
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Dropping tables");
-                    Table::drop_all(&pool, |(table, result)| {
-                        match &result {
-                            Ok(_) => println!("> Table '{table}' has been dropped"),
-                            Err(_) => (),
-                        }
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Dropping types");
-                    DbType::drop_all(&pool, |(db_type, result)| {
-                        match &result {
-                            Ok(_) => println!("> Type '{db_type}' has been dropped"),
-                            Err(_) => (),
-                        }
-                        result
-                    })
-                    .await?;
-                    println!("- Done\n");
-                    println!("\n- Unloading extensions");
-                    Extension::drop_all(&pool, |(e, p)| {
-                        if p {
-                            println!("> Extension '{e}' has been unloaded")
-                        }
-                    })
-                    .await?;
-                    println!("- Done\n");
-                }
+                DatabaseAction::Create { .. } | DatabaseAction::Drop { .. } => unreachable!(),
                 DatabaseAction::Check { fix } => {
                     println!("\n- Checking extensions");
                     for e in Extension::ALL {
@@ -250,6 +164,31 @@ pub async fn app(opt: Opt) -> Result<()> {
                     }
                     println!("- Done\n");
 
+                    println!("\n- Checking indexes");
+                    for index in Index::ALL {
+                        print!("> Checking '{index}' index : ");
+                        if index
+                            .exists(&pool)
+                            .await
+                            .context("While checking index existence")?
+                        {
+                            println!("OK");
+                        } else {
+                            println!("Not Exists");
+
+                            if fix {
+                                print!(">> Trying to create index '{index}' : ");
+                                match pool.execute(query(&index.create())).await {
+                                    Ok(_) => println!("OK\n"),
+                                    Err(err) => {
+                                        println!("Failed\n>>\t{err}\n");
+                                    }
+                                };
+                            }
+                        }
+                    }
+                    println!("- Done\n");
+
                     println!("\n- Checking view");
                     for view in View::ALL {
                         print!("> Checking '{view}' view : ");
@@ -289,7 +228,7 @@ pub async fn app(opt: Opt) -> Result<()> {
 
                             if fix {
                                 print!(">> Trying to create function '{function}' : ");
-                                match pool.execute(query(function.create())).await {
+                                match pool.execute(query(&function.create())).await {
                                     Ok(_) => println!("OK\n"),
                                     Err(err) => {
                                         println!("Failed\n>>\t{err}\n");
@@ -314,7 +253,7 @@ pub async fn app(opt: Opt) -> Result<()> {
 
                             if fix {
                                 print!(">> Trying to create procedure '{procedure}' : ");
-                                match pool.execute(query(procedure.create())).await {
+                                match pool.execute(query(&procedure.create())).await {
                                     Ok(_) => println!("OK\n"),
                                     Err(err) => {
                                         println!("Failed\n>>\t{err}\n");
@@ -354,6 +293,94 @@ pub async fn app(opt: Opt) -> Result<()> {
                         }
                     }
                     println!("- Done\n");
+
+                    println!("\n- Checking schema drift");
+                    let drifts = drift::detect(&pool, schema.name())
+                        .await
+                        .context("While detecting schema drift")?;
+                    if drifts.is_empty() {
+                        println!("> No drift found");
+                    }
+                    for drift in drifts {
+                        println!("> {} '{}' : {}", drift.object, drift.name, drift.kind);
+                        if let Some(expected) = &drift.expected {
+                            println!(">>\texpected: {expected}");
+                        }
+                        if let Some(actual) = &drift.actual {
+                            println!(">>\tactual:   {actual}");
+                        }
+                        if drift.fix.is_empty() {
+                            println!(">> Can't be fixed automatically");
+                            continue;
+                        }
+
+                        for statement in &drift.fix {
+                            if fix {
+                                print!(">> Executing '{statement}' : ");
+                                match query(statement).execute(&pool).await {
+                                    Ok(_) => println!("OK"),
+                                    Err(err) => println!("Failed\n>>\t{err}"),
+                                };
+                            } else {
+                                println!(">> Fix: {statement}");
+                            }
+                        }
+                    }
+                    println!("- Done\n");
+                }
+                DatabaseAction::Migrate { dry_run } => {
+                    println!("\n- Checking migrations");
+                    let pending = Migration::pending(&pool).await?;
+                    for migration in Migration::ALL {
+                        if pending.iter().any(|m| m.name() == migration.name()) {
+                            println!("> Migration '{migration}' : Pending");
+                            println!(">> {}", migration.description());
+                        } else {
+                            println!("> Migration '{migration}' : Not needed");
+                        }
+                    }
+
+                    if dry_run && !pending.is_empty() {
+                        println!("\n- Migration script");
+                        for statement in Migration::script(&pending, &schema) {
+                            println!("{statement}");
+                        }
+                    } else if !pending.is_empty() {
+                        let applied = Migration::apply_pending(&pool, &schema).await?;
+                        println!("> {} migrations applied", applied.len());
+                    }
+                    println!("- Done\n");
+                }
+                DatabaseAction::Analyze { plan } => {
+                    println!("\n- Analyzing queries");
+                    let mut flagged = 0;
+                    for probe in Probe::ALL {
+                        let explained = probe.explain(&pool).await?;
+                        if explained.full_scans.is_empty() {
+                            println!("> Query '{probe}' : OK (cost {:.2})", explained.cost);
+                        } else {
+                            flagged += 1;
+                            println!(
+                                "> Query '{probe}' : {} full scans (cost {:.2})",
+                                explained.full_scans.len(),
+                                explained.cost
+                            );
+                        }
+                        for scan in &explained.full_scans {
+                            print!(">> {} on '{}'", scan.node, scan.relation);
+                            match &scan.filter {
+                                Some(filter) => println!(" filtered by {filter}"),
+                                None => println!(),
+                            }
+                        }
+                        if plan {
+                            println!("{}", to_string_pretty(&explained.json)?);
+                        }
+                    }
+                    if flagged > 0 {
+                        println!("> {flagged} queries scan whole tables, check missing indexes");
+                    }
+                    println!("- Done\n");
                 }
                 DatabaseAction::Truncate => {
                     println!("\n!!! Truncating database !!!\n");
@@ -371,34 +398,44 @@ pub async fn app(opt: Opt) -> Result<()> {
         }
         Command::Generate(command) => {
             let gen = Config::default();
+            gen.validate()?;
 
             print!("Generating sample data...");
-            let (
-                mut component_kind,
-                mut service,
-                mut position,
-                mut manufacturer,
-                mut person,
-                mut supplier,
-                mut labor_contract,
-                mut phone_model,
-                mut staff,
-                mut component,
-                mut phone,
-                mut account,
-                mut supply_contract,
-                mut order,
-                mut supply,
-                mut warehouse,
-                mut service_phone_model,
-                mut warehouse_supply,
-                mut order_service,
-                mut order_warehouse,
-            ) = gen.gen_full();
+            let pack = gen.gen_full();
             println!(" : Done");
 
             match command {
                 Generate::Push { uri } => {
+                    let (
+                        mut component_kind,
+                        mut service,
+                        mut position,
+                        mut manufacturer,
+                        mut person,
+                        mut supplier,
+                        mut branch,
+                        mut exchange_rate,
+                        mut labor_contract,
+                        mut phone_model,
+                        mut staff,
+                        mut component,
+                        mut phone,
+                        mut account,
+                        mut phone_ownership,
+                        mut supply_contract,
+                        order,
+                        mut supply,
+                        mut warehouse,
+                        mut stock_transfer,
+                        mut invoice,
+                        mut payment,
+                        mut service_phone_model,
+                        mut supply_contract_item,
+                        mut warehouse_supply,
+                        mut supply_item,
+                        mut order_service,
+                        mut order_warehouse,
+                    ) = pack;
                     let pool = Arc::new(open_pool(uri, opt.pool_size).await?);
 
                     fn mapper<T>(obj: T) -> Box<dyn Insertable + Send + Sync>
@@ -436,7 +473,9 @@ pub async fn app(opt: Opt) -> Result<()> {
                             .chain(position.drain(..).map(mapper))
                             .chain(manufacturer.drain(..).map(mapper))
                             .chain(person.drain(..).map(mapper))
-                            .chain(supplier.drain(..).map(mapper)),
+                            .chain(supplier.drain(..).map(mapper))
+                            .chain(branch.drain(..).map(mapper))
+                            .chain(exchange_rate.drain(..).map(mapper)),
                     )
                     .await?;
 
@@ -461,7 +500,20 @@ pub async fn app(opt: Opt) -> Result<()> {
                     )
                     .await?;
 
-                    insert_group(&pool, "third", account.drain(..).map(mapper)).await?;
+                    insert_group(
+                        &pool,
+                        "third",
+                        account
+                            .drain(..)
+                            .map(mapper)
+                            .chain(phone_ownership.drain(..).map(mapper)),
+                    )
+                    .await?;
+
+                    // Warranty claims reference original orders, so they go to the next group
+                    let (mut claim, mut order): (Vec<_>, Vec<_>) = order
+                        .into_iter()
+                        .partition(|order| order.is_warranty_claim());
 
                     insert_group(
                         &pool,
@@ -480,7 +532,10 @@ pub async fn app(opt: Opt) -> Result<()> {
                             .drain(..)
                             .map(mapper)
                             .chain(service_phone_model.drain(..).map(mapper))
-                            .chain(warehouse.drain(..).map(mapper)),
+                            .chain(warehouse.drain(..).map(mapper))
+                            .chain(supply_contract_item.drain(..).map(mapper))
+                            .chain(invoice.drain(..).map(mapper))
+                            .chain(claim.drain(..).map(mapper)),
                     )
                     .await?;
 
@@ -490,7 +545,10 @@ pub async fn app(opt: Opt) -> Result<()> {
                         order_service
                             .drain(..)
                             .map(mapper)
-                            .chain(warehouse_supply.drain(..).map(mapper)),
+                            .chain(warehouse_supply.drain(..).map(mapper))
+                            .chain(supply_item.drain(..).map(mapper))
+                            .chain(stock_transfer.drain(..).map(mapper))
+                            .chain(payment.drain(..).map(mapper)),
                     )
                     .await?;
 
@@ -502,30 +560,966 @@ pub async fn app(opt: Opt) -> Result<()> {
                         .write(true)
                         .truncate(true)
                         .open(path)?;
-                    let dump = json!({
-                        "component_kind": component_kind,
-                        "service": service,
-                        "position": position,
-                        "manufacturer": manufacturer,
-                        "person": person,
-                        "supplier": supplier,
-                        "labor_contract": labor_contract,
-                        "phone_model": phone_model,
-                        "staff": staff,
-                        "component": component,
-                        "phone": phone,
-                        "account": account,
-                        "warehouse": warehouse,
-                        "order": order,
-                        "service_phone_model": service_phone_model,
-                        "order_service": order_service,
-                        "order_warehouse": order_warehouse,
-                    });
-
-                    file.write_all(to_string(&dump)?.as_bytes())?;
+                    file.write_all(to_string(&Dump::from(pack))?.as_bytes())?;
                 }
             }
         }
+        Command::Data(command) => match command {
+            Data::Load { uri, path } => {
+                print!("Reading dump file...");
+                let dump: Dump = from_reader(BufReader::new(
+                    File::open(&path)
+                        .with_context(|| format!("While opening '{}'", path.display()))?,
+                ))
+                .context("While parsing dump file")?;
+                println!(" : Done");
+
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                println!("\n- Loading dump");
+                dump.load(&pool, |(table, count)| {
+                    println!("> {count} rows inserted into '{table}' table")
+                })
+                .await?;
+                println!("- Done\n");
+            }
+            Data::Export {
+                uri,
+                format,
+                path,
+                tables,
+                from,
+                to,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                print!("Reading database snapshot...");
+                let dump = Dump::fetch(&pool, &ExportFilter { tables, from, to }).await?;
+                println!(" : Done");
+
+                let tables = export::rows(&dump)?;
+                let path = path.unwrap_or_else(|| format.default_path());
+
+                println!("\n- Exporting to '{}'", path.display());
+                match format {
+                    ExportFormat::JsonLines => export::write_jsonl(&tables, &path)?,
+                    ExportFormat::Csv => export::write_csv(&tables, &path)?,
+                    ExportFormat::Archive => export::write_archive(&tables, &path)?,
+                }
+                for (table, rows) in tables.iter().filter(|(_, rows)| !rows.is_empty()) {
+                    println!("> {} rows exported from '{table}' table", rows.len());
+                }
+                println!("- Done\n");
+            }
+            Data::Rates { uri, path } => {
+                let rates = import::read_rates(&path)?;
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let mut tx = pool.begin().await?;
+                for rate in &rates {
+                    rate.upsert().execute(&mut tx).await.with_context(|| {
+                        format!("While importing {} rate for {}", rate.currency, rate.day)
+                    })?;
+                }
+                tx.commit().await?;
+
+                println!("> {} exchange rates imported", rates.len());
+            }
+        },
+        Command::Order(command) => match command {
+            OrderCmd::Receipt { uri, order, path } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let receipt = match order {
+                    OrderRef::Uuid(uuid) => Receipt::fetch(&pool, uuid).await?,
+                    OrderRef::Tracking(code) => Receipt::fetch_by_tracking(&pool, &code).await?,
+                };
+                let path = path.unwrap_or_else(|| receipt.file_name().into());
+
+                fs::write(&path, receipt.to_html())
+                    .with_context(|| format!("While writing '{}'", path.display()))?;
+                println!(
+                    "> Receipt of order '{}' written to '{}'",
+                    receipt.order.tracking,
+                    path.display()
+                );
+            }
+            OrderCmd::Invoice {
+                uri,
+                order,
+                amount,
+                issuer,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let order = match order {
+                    OrderRef::Uuid(uuid) => uuid,
+                    OrderRef::Tracking(code) => {
+                        Order::get_by_tracking(&code)
+                            .fetch_one(&pool)
+                            .await
+                            .with_context(|| format!("While reading order '{code}'"))?
+                            .uuid
+                    }
+                };
+                let invoice = Invoice::new_auto(order, amount, issuer);
+
+                if invoice
+                    .issue()
+                    .execute(&pool)
+                    .await
+                    .context("While issuing invoice")?
+                    .rows_affected()
+                    == 0
+                {
+                    bail!("Order '{order}' is missing or deleted");
+                }
+
+                println!("> Invoice '{}' of {} issued", invoice.uuid, invoice.amount);
+            }
+            OrderCmd::Pay {
+                uri,
+                invoice,
+                kind,
+                amount,
+                method,
+                cashier,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let payment = Payment::new_auto(invoice, kind, method, amount, cashier);
+
+                payment
+                    .record()
+                    .execute(&pool)
+                    .await
+                    .with_context(|| format!("While recording {kind:?} on invoice '{invoice}'"))?;
+
+                let billed = Invoice::get_by_uuid(invoice).fetch_one(&pool).await?;
+                let payments = Payment::get_by_invoice(invoice).fetch_all(&pool).await?;
+                let paid = Payment::net(&payments, billed.amount.currency())?;
+                println!(
+                    "> {kind:?} '{}' of {} recorded, {paid} of {} paid",
+                    payment.uuid, payment.amount, billed.amount
+                );
+            }
+            OrderCmd::Close { uri, date, branch } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let date = date.unwrap_or_else(|| Utc::now().date_naive());
+
+                let lines = CashRegisterClose::report(date, branch)
+                    .fetch_all(&pool)
+                    .await
+                    .with_context(|| format!("While building close report for {date}"))?;
+
+                println!("- Cash register close for {date}\n");
+                println!(
+                    "{:<16}{:>10}{:>16}{:>16}{:>16}",
+                    "Method", "Payments", "Received", "Refunded", "Collected"
+                );
+                for line in &lines {
+                    println!(
+                        "{:<16}{:>10}{:>16}{:>16}{:>16}",
+                        line.method.as_str(),
+                        line.payments,
+                        line.received.to_string(),
+                        line.refunded.to_string(),
+                        line.collected.to_string(),
+                    );
+                }
+                let total =
+                    Money::checked_sum(lines.iter().map(|line| &line.collected), Currency::BASE)?;
+                println!("\n> Total collected: {total}");
+            }
+        },
+        Command::Price(command) => match command {
+            PriceCmd::Index {
+                uri,
+                coef,
+                scope,
+                target,
+                preview,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                if preview {
+                    let lines = IndexPricesPreview::preview(coef, scope, target)
+                        .fetch_all(&pool)
+                        .await
+                        .context("While previewing price indexation")?;
+
+                    println!("{:<60}{:>16}{:>16}", "Name", "Old price", "New price");
+                    for line in &lines {
+                        println!(
+                            "{:<60}{:>16}{:>16}",
+                            line.name,
+                            line.old_price.to_string(),
+                            line.new_price.to_string(),
+                        );
+                    }
+                    println!("\n> {} prices would be changed", lines.len());
+                } else {
+                    let (change,) = PriceChange::index(coef, scope, target)
+                        .fetch_one(&pool)
+                        .await
+                        .context("While indexing prices")?;
+                    println!("> Prices indexed by price change '{change}'");
+                }
+            }
+            PriceCmd::Rollback { uri, change } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let (reverting,) = PriceChange::rollback(change)
+                    .fetch_one(&pool)
+                    .await
+                    .with_context(|| format!("While rolling back price change '{change}'"))?;
+                println!("> Price change '{change}' rolled back by '{reverting}'");
+            }
+        },
+        Command::Supply(command) => match command {
+            SupplyCmd::Cost { uri, from, to } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let to = to.unwrap_or_else(Utc::now);
+                let from = from.unwrap_or_else(|| to - Duration::days(30));
+
+                let lines = SupplyCostForPeriod::report(from, to)
+                    .fetch_all(&pool)
+                    .await
+                    .context("While building supply cost report")?;
+
+                println!(
+                    "- Supply cost from {} to {}\n",
+                    from.date_naive(),
+                    to.date_naive()
+                );
+                println!(
+                    "{:<40}{:>10}{:>20}{:>20}",
+                    "Supplier", "Supplies", "Cost", "Base cost"
+                );
+                for line in &lines {
+                    println!(
+                        "{:<40}{:>10}{:>20}{:>20}",
+                        line.name,
+                        line.supplies,
+                        line.cost.to_string(),
+                        line.base_cost.to_string(),
+                    );
+                }
+                let total =
+                    Money::checked_sum(lines.iter().map(|line| &line.base_cost), Currency::BASE)?;
+                println!("\n> Total cost: {total}");
+            }
+            SupplyCmd::Performance { uri, from, to } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let lines = SupplierPerformance::report(from, to)
+                    .fetch_all(&pool)
+                    .await
+                    .context("While building supplier performance report")?;
+
+                let rate = |rate: &Option<BigDecimal>| match rate {
+                    Some(rate) => format!("{}%", (rate * BigDecimal::from(100)).with_scale(1)),
+                    None => "-".to_string(),
+                };
+                println!("- Supplier performance\n");
+                println!(
+                    "{:<40}{:>10}{:>10}{:>10}{:>10}{:>10}{:>10}",
+                    "Supplier", "Supplies", "Delivered", "On time", "Failed", "On time%", "Failed%"
+                );
+                for line in &lines {
+                    println!(
+                        "{:<40}{:>10}{:>10}{:>10}{:>10}{:>10}{:>10}",
+                        line.name,
+                        line.supplies,
+                        line.delivered,
+                        line.on_time,
+                        line.failed,
+                        rate(&line.on_time_rate),
+                        rate(&line.failure_rate),
+                    );
+                }
+            }
+            SupplyCmd::Expire { uri } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let (expired,) = SupplyContract::expire()
+                    .fetch_one(&pool)
+                    .await
+                    .context("While expiring supply contracts")?;
+
+                println!("> {expired} supply contracts expired");
+            }
+            SupplyCmd::Order {
+                uri,
+                contract,
+                staff,
+                items,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let supply = Supply::new_auto(contract, staff, SupplyStatus::Review, None);
+
+                let mut tx = pool.begin().await?;
+                supply
+                    .insert()
+                    .execute(&mut tx)
+                    .await
+                    .context("While placing supply")?;
+                for (component, amount) in items {
+                    if SupplyItem::order(supply.uuid, component, amount)
+                        .execute(&mut tx)
+                        .await
+                        .with_context(|| format!("While ordering component '{component}'"))?
+                        .rows_affected()
+                        == 0
+                    {
+                        bail!("Contract '{contract}' has no terms for component '{component}'");
+                    }
+                }
+                tx.commit().await?;
+
+                println!("> Supply '{}' placed for review", supply.uuid);
+            }
+            SupplyCmd::Approve {
+                uri,
+                supply,
+                manager,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let version = supply_version(&pool, supply, updated).await?;
+
+                Supply::approve(supply, manager, version)
+                    .execute(&pool)
+                    .await
+                    .map_err(Conflict::map)
+                    .context("While approving supply")?;
+
+                println!("> Supply '{supply}' signed");
+            }
+            SupplyCmd::Reject {
+                uri,
+                supply,
+                manager,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let version = supply_version(&pool, supply, updated).await?;
+
+                if Supply::reject(supply, manager, version)
+                    .execute(&pool)
+                    .await
+                    .map_err(Conflict::map)
+                    .context("While rejecting supply")?
+                    .rows_affected()
+                    == 0
+                {
+                    bail!("Supply '{supply}' is not under review");
+                }
+
+                println!("> Supply '{supply}' rejected");
+            }
+            SupplyCmd::Dispatch {
+                uri,
+                supply,
+                due,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let version = supply_version(&pool, supply, updated).await?;
+
+                Supply::dispatch(supply, due, version)
+                    .execute(&pool)
+                    .await
+                    .map_err(Conflict::map)
+                    .context("While dispatching supply")?;
+
+                println!("> Supply '{supply}' dispatched");
+            }
+            SupplyCmd::Receive {
+                uri,
+                supply,
+                items,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let version = supply_version(&pool, supply, updated).await?;
+
+                let mut tx = pool.begin().await?;
+                let lines = SupplyItem::get_by_supply(supply)
+                    .fetch_all(&mut tx)
+                    .await
+                    .context("While fetching supply lines")?;
+                for (component, amount) in items {
+                    let line = lines
+                        .iter()
+                        .find(|line| line.component == component)
+                        .with_context(|| {
+                            format!("Component '{component}' was not ordered by supply '{supply}'")
+                        })?;
+                    SupplyItem::set_received(supply, component, amount, line.meta.updated)
+                        .execute(&mut tx)
+                        .await
+                        .map_err(Conflict::map)
+                        .with_context(|| format!("While receiving component '{component}'"))?;
+                }
+                Supply::receive(supply, version)
+                    .execute(&mut tx)
+                    .await
+                    .map_err(Conflict::map)
+                    .context("While receiving supply")?;
+                tx.commit().await?;
+
+                println!("> Supply '{supply}' delivered");
+            }
+            SupplyCmd::Fail {
+                uri,
+                supply,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let version = supply_version(&pool, supply, updated).await?;
+
+                if Supply::fail(supply, version)
+                    .execute(&pool)
+                    .await
+                    .map_err(Conflict::map)
+                    .context("While failing supply")?
+                    .rows_affected()
+                    == 0
+                {
+                    bail!("Supply '{supply}' is not awaiting delivery");
+                }
+
+                println!("> Supply '{supply}' failed");
+            }
+            SupplyCmd::Show { uri, supply } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let lines = SupplyItemBeautified::get_by_supply(supply)
+                    .fetch_all(&pool)
+                    .await
+                    .context("While fetching supply lines")?;
+
+                let version = supply_version(&pool, supply, None).await?;
+                println!("- Supply '{supply}' updated at {}\n", version.to_rfc3339());
+                println!(
+                    "{:<40}{:>10}{:>10}{:>12}{:>20}",
+                    "Component", "Ordered", "Received", "Difference", "Price"
+                );
+                for line in &lines {
+                    let optional = |value: Option<i32>| match value {
+                        Some(value) => value.to_string(),
+                        None => "-".to_string(),
+                    };
+                    println!(
+                        "{:<40}{:>10}{:>10}{:>12}{:>20}",
+                        line.component,
+                        line.ordered,
+                        optional(line.received),
+                        optional(line.difference),
+                        line.price.to_string(),
+                    );
+                }
+                let discrepancies = lines
+                    .iter()
+                    .filter(|line| !matches!(line.difference, None | Some(0)))
+                    .count();
+                println!("\n> {discrepancies} lines with discrepancies");
+            }
+        },
+        Command::Stock(command) => match command {
+            StockCmd::Ship {
+                uri,
+                item,
+                to,
+                amount,
+                sender,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let version = match updated {
+                    Some(updated) => updated,
+                    None => {
+                        Warehouse::get_by_uuid(item)
+                            .fetch_optional(&pool)
+                            .await
+                            .context("While fetching warehouse item")?
+                            .with_context(|| format!("Warehouse item '{item}' not found"))?
+                            .meta
+                            .updated
+                    }
+                };
+
+                StockTransfer::ship(item, to, amount, sender, version)
+                    .execute(&pool)
+                    .await
+                    .map_err(Conflict::map)
+                    .context("While shipping stock")?;
+
+                println!("> {amount} of item '{item}' shipped to branch '{to}'");
+            }
+            StockCmd::Receive {
+                uri,
+                transfer,
+                receiver,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let version = transfer_version(&pool, transfer, updated).await?;
+
+                StockTransfer::receive(transfer, receiver, version)
+                    .execute(&pool)
+                    .await
+                    .map_err(Conflict::map)
+                    .context("While receiving transfer")?;
+
+                println!("> Transfer '{transfer}' received");
+            }
+            StockCmd::Cancel {
+                uri,
+                transfer,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let version = transfer_version(&pool, transfer, updated).await?;
+
+                StockTransfer::cancel(transfer, version)
+                    .execute(&pool)
+                    .await
+                    .map_err(Conflict::map)
+                    .context("While cancelling transfer")?;
+
+                println!("> Transfer '{transfer}' cancelled");
+            }
+        },
+        Command::Phone(command) => match command {
+            PhoneCmd::History { uri, imei } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let events = PhoneHistory::get_by_imei(imei)
+                    .fetch_all(&pool)
+                    .await
+                    .context("While fetching device history")?;
+                if events.is_empty() {
+                    bail!("Phone with IMEI '{imei}' not found");
+                }
+
+                println!("- Device '{imei}'\n");
+                println!(
+                    "{:<22}{:<10}{:<14}{:<40}{:<40}{:>8}{:>20}",
+                    "Date", "Kind", "Order", "Person", "Description", "Amount", "Price"
+                );
+                for event in &events {
+                    println!(
+                        "{:<22}{:<10}{:<14}{:<40}{:<40}{:>8}{:>20}",
+                        event.at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                        event.kind,
+                        event.tracking.as_deref().unwrap_or("-"),
+                        event.person,
+                        event.description,
+                        event
+                            .amount
+                            .map_or_else(|| "-".to_string(), |amount| amount.to_string()),
+                        event
+                            .price
+                            .as_ref()
+                            .map_or_else(|| "-".to_string(), ToString::to_string),
+                    );
+                }
+                let orders = events.iter().filter(|event| event.kind == "Order").count();
+                let owners = events.iter().filter(|event| event.kind == "Owner").count();
+                println!("\n> {orders} orders, {owners} owners");
+            }
+            PhoneCmd::Transfer {
+                uri,
+                imei,
+                to,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let phone = Phone::get_by_imei(imei)
+                    .fetch_optional(&pool)
+                    .await
+                    .context("While fetching phone")?
+                    .with_context(|| format!("Phone with IMEI '{imei}' not found"))?;
+                if phone.person == to {
+                    bail!("Person '{to}' already owns phone '{imei}'");
+                }
+                Phone::transfer(phone.uuid, to, updated.unwrap_or(phone.meta.updated))
+                    .execute(&pool)
+                    .await
+                    .map_err(Conflict::map)
+                    .context("While transferring phone")?;
+
+                println!(
+                    "> Phone '{imei}' handed over from '{}' to '{to}'",
+                    phone.person
+                );
+            }
+            PhoneCmd::Duplicates { uri } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let duplicates = Phone::duplicates()
+                    .fetch_all(&pool)
+                    .await
+                    .context("While searching for duplicate IMEIs")?;
+
+                for (imei, phones) in &duplicates {
+                    println!("- {imei}");
+                    for phone in phones {
+                        println!("    {phone}");
+                    }
+                }
+                println!("> {} duplicate IMEIs found", duplicates.len());
+            }
+        },
+        Command::Person(command) => match command {
+            PersonCmd::Duplicates { uri, threshold } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let pairs = PersonDuplicates::report(threshold)
+                    .fetch_all(&pool)
+                    .await
+                    .context("While searching for duplicate people")?;
+
+                for pair in &pairs {
+                    let mut reasons = Vec::new();
+                    if pair.same_phone {
+                        reasons.push("same phone".to_string());
+                    }
+                    if pair.same_email {
+                        reasons.push("same email".to_string());
+                    }
+                    reasons.push(format!("name {:.0}%", pair.name_similarity * 100.0));
+                    println!("- {}", reasons.join(", "));
+                    println!(
+                        "    {:<38}{:<40}{:<40}{}",
+                        pair.person.to_string(),
+                        pair.person_name,
+                        pair.person_email,
+                        pair.person_phone
+                    );
+                    println!(
+                        "    {:<38}{:<40}{:<40}{}",
+                        pair.duplicate.to_string(),
+                        pair.duplicate_name,
+                        pair.duplicate_email,
+                        pair.duplicate_phone
+                    );
+                }
+                println!("> {} possible duplicates found", pairs.len());
+            }
+            PersonCmd::Merge {
+                uri,
+                kept,
+                duplicate,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let (merge,) = Person::merge(kept, duplicate)
+                    .fetch_one(&pool)
+                    .await
+                    .with_context(|| format!("While merging person '{duplicate}'"))?;
+
+                println!("> Person '{duplicate}' merged into '{kept}' by '{merge}'");
+            }
+        },
+        Command::Privacy(command) => match command {
+            PrivacyCmd::Export { uri, person, path } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let data = PersonalData::fetch(&pool, person).await?;
+                let json = to_string_pretty(&data)?;
+                match path {
+                    Some(path) => {
+                        fs::write(&path, json)
+                            .with_context(|| format!("While writing '{}'", path.display()))?;
+                        println!(
+                            "> Personal data of '{person}' written to '{}'",
+                            path.display()
+                        );
+                    }
+                    None => println!("{json}"),
+                }
+            }
+            PrivacyCmd::Anonymize { uri, person } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                Person::anonymize(person)
+                    .execute(&pool)
+                    .await
+                    .with_context(|| format!("While anonymizing person '{person}'"))?;
+
+                println!("> Person '{person}' anonymized");
+            }
+            PrivacyCmd::Retention { uri, days, dry_run } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let before = Utc::now() - Duration::days(days.into());
+
+                let clients = if dry_run {
+                    InactiveClient::get_before(before)
+                        .fetch_all(&pool)
+                        .await
+                        .context("While searching for inactive clients")?
+                } else {
+                    privacy::apply_retention(&pool, before).await?
+                };
+
+                // Names of the anonymized clients are not to be left in the output
+                for client in &clients {
+                    println!(
+                        "{:<38}{:<40}{}",
+                        client.person.to_string(),
+                        if dry_run { client.name.as_str() } else { "-" },
+                        client.last_activity.date_naive()
+                    );
+                }
+                if dry_run {
+                    println!(
+                        "> {} clients inactive since {}",
+                        clients.len(),
+                        before.date_naive()
+                    );
+                } else {
+                    println!("> {} clients anonymized", clients.len());
+                }
+            }
+        },
+        Command::Trash(command) => match command {
+            TrashCmd::List { uri } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let rows = Trashed::fetch(&pool, None).await?;
+                for row in &rows {
+                    println!(
+                        "{:<16}{:<38}{:<40}{}",
+                        row.table.name(),
+                        row.uuid.to_string(),
+                        row.label,
+                        row.deleted_at.format("%Y-%m-%d %H:%M")
+                    );
+                }
+                println!("> {} rows in the trash", rows.len());
+            }
+            TrashCmd::Restore {
+                uri,
+                table,
+                uuid,
+                updated,
+            } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let version = match updated {
+                    Some(updated) => updated,
+                    None => {
+                        Trashed::fetch(&pool, None)
+                            .await?
+                            .into_iter()
+                            .find(|row| row.table == table && row.uuid == uuid)
+                            .with_context(|| {
+                                format!("No row '{uuid}' of '{table}' table in the trash")
+                            })?
+                            .updated
+                    }
+                };
+
+                trash::restore(&pool, table, uuid, version).await?;
+
+                println!("> Row '{uuid}' restored to '{table}' table");
+            }
+            TrashCmd::Purge { uri, days, dry_run } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+                let before = Utc::now() - Duration::days(days.into());
+
+                let outcome = trash::purge(&pool, before, dry_run).await?;
+                for row in &outcome.purged {
+                    println!(
+                        "{:<16}{:<38}{}",
+                        row.table.name(),
+                        row.uuid.to_string(),
+                        row.label
+                    );
+                }
+                println!(
+                    "> {} rows {}, {} still referenced rows left in the trash",
+                    outcome.purged.len(),
+                    if dry_run { "to be purged" } else { "purged" },
+                    outcome.kept.len()
+                );
+            }
+        },
+        Command::Search(SearchOpt { uri, text, limit }) => {
+            let pool = open_pool(uri, opt.pool_size).await?;
+
+            let found = Search::run(&pool, &text, &SearchGroup::ALL, limit).await?;
+            if !found.clients.is_empty() {
+                println!("- Clients");
+                for person in &found.clients {
+                    println!(
+                        "{:<38}{:<40}{:<20}{}",
+                        person.uuid.to_string(),
+                        format!("{} {}", person.first_name, person.last_name),
+                        person.phone,
+                        person.email
+                    );
+                }
+            }
+            if !found.phones.is_empty() {
+                println!("- Phones");
+                for phone in &found.phones {
+                    println!(
+                        "{:<38}{:<18}{:<30}{}",
+                        phone.uuid.to_string(),
+                        phone.imei.to_string(),
+                        phone.model,
+                        phone.owner
+                    );
+                }
+            }
+            if !found.components.is_empty() {
+                println!("- Components");
+                for component in &found.components {
+                    println!(
+                        "{:<38}{:<30}{:<20}{}",
+                        component.uuid.to_string(),
+                        component.name,
+                        component.kind,
+                        component.model
+                    );
+                }
+            }
+            if !found.suppliers.is_empty() {
+                println!("- Suppliers");
+                for supplier in &found.suppliers {
+                    println!("{:<38}{}", supplier.uuid.to_string(), supplier.name);
+                }
+            }
+            if found.is_empty() {
+                println!("> Nothing found");
+            }
+        }
+        Command::Views(command) => match command {
+            ViewsCmd::Status { uri } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                for status in View::statuses(&pool).await? {
+                    println!(
+                        "{:<22}{:<18}{}",
+                        status.view.name(),
+                        status
+                            .refreshed
+                            .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_else(|| "Never".to_string()),
+                        if status.is_stale() { "Stale" } else { "Fresh" }
+                    );
+                }
+            }
+            ViewsCmd::Refresh { uri, views, stale } => {
+                let pool = open_pool(uri, opt.pool_size).await?;
+
+                let mut failed = 0;
+                for view in views {
+                    if stale && !view.status(&pool).await?.is_stale() {
+                        println!("> View '{view}' : Fresh");
+                        continue;
+                    }
+
+                    let started = Utc::now();
+                    match view.refresh(&pool).await {
+                        Ok(()) => println!(
+                            "> View '{view}' : Refreshed in {} ms",
+                            (Utc::now() - started).num_milliseconds()
+                        ),
+                        Err(err) => {
+                            failed += 1;
+                            println!("> View '{view}' : Failed\n>>\t{err:#}");
+                        }
+                    }
+                }
+                if failed > 0 {
+                    bail!("{failed} views failed to refresh");
+                }
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Version of the supply the status change is based on, the current one if not given
+async fn supply_version(
+    pool: &PgPool,
+    supply: Uuid,
+    updated: Option<DateTime<Utc>>,
+) -> Result<DateTime<Utc>> {
+    if let Some(updated) = updated {
+        return Ok(updated);
+    }
+
+    Ok(Supply::get_by_uuid(supply)
+        .fetch_optional(pool)
+        .await
+        .context("While fetching supply")?
+        .with_context(|| format!("Supply '{supply}' not found"))?
+        .meta
+        .updated)
+}
+
+/// Version of the transfer the status change is based on, the current one if not given
+async fn transfer_version(
+    pool: &PgPool,
+    transfer: Uuid,
+    updated: Option<DateTime<Utc>>,
+) -> Result<DateTime<Utc>> {
+    if let Some(updated) = updated {
+        return Ok(updated);
+    }
+
+    Ok(StockTransfer::get_by_uuid(transfer)
+        .fetch_optional(pool)
+        .await
+        .context("While fetching transfer")?
+        .with_context(|| format!("Transfer '{transfer}' not found"))?
+        .meta
+        .updated)
+}
+
+/// Prints or executes create/drop script
+async fn run_script(
+    script: Script,
+    db: DatabaseUri,
+    pool_size: PoolSize,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    if dry_run {
+        print!("{}", script.sql());
+        return Ok(());
+    }
+
+    // Script creates the schema, so connections can't require it yet
+    let pool = open_pool(
+        DatabaseUri {
+            schema: Schema::default(),
+            ..db
+        },
+        pool_size,
+    )
+    .await?;
+
+    if !json {
+        println!("\n- Running script ({} objects)", script.objects.len());
+    }
+    let summary = script
+        .execute(&pool, |outcome| {
+            if !json {
+                println!("> {} '{}' : {}", outcome.kind, outcome.name, outcome.status);
+            }
+        })
+        .await?;
+
+    if json {
+        println!("{}", to_string(&summary)?);
+    } else {
+        println!("- Done\n");
     }
 
     Ok(())
@@ -536,15 +1530,13 @@ async fn open_pool(uri: DatabaseUri, pool_size: PoolSize) -> Result<PgPool, Erro
     let options: PgConnectOptions = uri.inner.as_str().parse()?;
 
     // Connecting to database
-    let pool = PoolOptions::new()
-        .min_connections(pool_size.0)
-        .max_connections(pool_size.1)
-        // .after_connect(|conn: &mut PgConnection| {
-        //     Box::pin(async move {
-        //         conn.execute("SET user = 'cli-tool';").await?;
-        //         Ok(())
-        //     })
-        // })
+    let pool = uri
+        .schema
+        .apply(
+            PoolOptions::new()
+                .min_connections(pool_size.0)
+                .max_connections(pool_size.1),
+        )
         .connect_with(
             options
                 .application_name("CW-CLI")
@@ -0,0 +1,157 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use cw_core::{dump::Dump, tables::Table};
+use flate2::{write::GzEncoder, Compression};
+use serde::Serialize;
+use serde_json::{json, to_value, Value};
+use tar::{Builder, Header};
+
+/// Rows of every table in the dump (dependency order) as json values
+pub fn rows(dump: &Dump) -> Result<Vec<(Table, Vec<Value>)>> {
+    fn values<T: Serialize>(rows: &[T]) -> Result<Vec<Value>> {
+        rows.iter()
+            .map(|row| to_value(row).map_err(Into::into))
+            .collect()
+    }
+
+    Ok(vec![
+        (Table::Person, values(&dump.person)?),
+        (Table::Supplier, values(&dump.supplier)?),
+        (Table::Manufacturer, values(&dump.manufacturer)?),
+        (Table::Position, values(&dump.position)?),
+        (Table::Service, values(&dump.service)?),
+        (Table::ComponentKind, values(&dump.component_kind)?),
+        (Table::Branch, values(&dump.branch)?),
+        (Table::ExchangeRate, values(&dump.exchange_rate)?),
+        (Table::LaborContract, values(&dump.labor_contract)?),
+        (Table::Staff, values(&dump.staff)?),
+        (Table::PhoneModel, values(&dump.phone_model)?),
+        (Table::Phone, values(&dump.phone)?),
+        (Table::SupplyContract, values(&dump.supply_contract)?),
+        (Table::Supply, values(&dump.supply)?),
+        (Table::Account, values(&dump.account)?),
+        (Table::PhoneOwnership, values(&dump.phone_ownership)?),
+        (Table::PersonMerge, values(&dump.person_merge)?),
+        (Table::Component, values(&dump.component)?),
+        (Table::Warehouse, values(&dump.warehouse)?),
+        (Table::Order, values(&dump.order)?),
+        (Table::StockTransfer, values(&dump.stock_transfer)?),
+        (Table::Invoice, values(&dump.invoice)?),
+        (Table::Payment, values(&dump.payment)?),
+        (Table::PriceChange, values(&dump.price_change)?),
+        (Table::WarehousePrice, values(&dump.warehouse_price)?),
+        (Table::ServicePhoneModel, values(&dump.service_phone_model)?),
+        (Table::ServicePrice, values(&dump.service_price)?),
+        (
+            Table::SupplyContractItem,
+            values(&dump.supply_contract_item)?,
+        ),
+        (Table::WarehouseSupply, values(&dump.warehouse_supply)?),
+        (Table::SupplyItem, values(&dump.supply_item)?),
+        (Table::OrderService, values(&dump.order_service)?),
+        (Table::OrderWarehouse, values(&dump.order_warehouse)?),
+    ])
+}
+
+/// Writes every row as separate `{"table": ..., "row": ...}` line
+pub fn write_jsonl(tables: &[(Table, Vec<Value>)], path: &Path) -> Result<()> {
+    let mut file = BufWriter::new(
+        File::create(path).with_context(|| format!("While creating '{}'", path.display()))?,
+    );
+
+    for (table, rows) in tables {
+        for row in rows {
+            serde_json::to_writer(&mut file, &json!({ "table": table.name(), "row": row }))?;
+            file.write_all(b"\n")?;
+        }
+    }
+
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Writes `<Table>.csv` file per non-empty table into the directory
+pub fn write_csv(tables: &[(Table, Vec<Value>)], dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("While creating '{}'", dir.display()))?;
+
+    for (table, rows) in tables.iter().filter(|(_, rows)| !rows.is_empty()) {
+        fs::write(dir.join(format!("{table}.csv")), to_csv(rows)?)
+            .with_context(|| format!("While writing '{table}' table"))?;
+    }
+
+    Ok(())
+}
+
+/// Writes gzipped tar with `<Table>.csv` entry per non-empty table
+pub fn write_archive(tables: &[(Table, Vec<Value>)], path: &Path) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("While creating '{}'", path.display()))?;
+    let mut archive = Builder::new(GzEncoder::new(file, Compression::default()));
+    let mtime = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    for (table, rows) in tables.iter().filter(|(_, rows)| !rows.is_empty()) {
+        let data = to_csv(rows)?;
+
+        let mut header = Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        header.set_cksum();
+
+        archive
+            .append_data(&mut header, format!("{table}.csv"), data.as_slice())
+            .with_context(|| format!("While archiving '{table}' table"))?;
+    }
+
+    archive.into_inner()?.finish()?;
+
+    Ok(())
+}
+
+/// Serializes rows to csv, nested objects (e.g. `meta`) become dotted columns
+fn to_csv(rows: &[Value]) -> Result<Vec<u8>> {
+    let rows = rows
+        .iter()
+        .map(|row| {
+            let mut fields = Vec::new();
+            flatten(String::new(), row, &mut fields);
+            fields
+        })
+        .collect::<Vec<_>>();
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+
+    if let Some(first) = rows.first() {
+        writer.write_record(first.iter().map(|(column, _)| column))?;
+    }
+    for row in &rows {
+        writer.write_record(row.iter().map(|(_, value)| value))?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+fn flatten(prefix: String, value: &Value, fields: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let column = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(column, value, fields);
+            }
+        }
+        Value::Null => fields.push((prefix, String::new())),
+        Value::String(s) => fields.push((prefix, s.clone())),
+        other => fields.push((prefix, other.to_string())),
+    }
+}
@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use cw_core::{chrono::NaiveDate, tables::ExchangeRate, types::Currency, BigDecimal};
+use serde::Deserialize;
+
+/// Row of the exchange rates file (e.g. `USD,2022-01-31,77.5`)
+#[derive(Deserialize)]
+struct RateRecord {
+    currency: Currency,
+    #[serde(alias = "date")]
+    day: NaiveDate,
+    rate: BigDecimal,
+}
+
+/// Reads `currency,day,rate` csv file with header, rate is the price of one unit
+/// of the currency in the base one
+pub fn read_rates(path: &Path) -> Result<Vec<ExchangeRate>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .with_context(|| format!("While opening '{}'", path.display()))?;
+
+    reader
+        .deserialize()
+        .enumerate()
+        .map(|(i, record)| {
+            let RateRecord {
+                currency,
+                day,
+                rate,
+            } = record.with_context(|| format!("While reading rate at line {}", i + 2))?;
+
+            Ok(ExchangeRate::new_auto(currency, day, rate))
+        })
+        .collect()
+}
@@ -3,6 +3,8 @@ use app::app;
 use opt::parse;
 
 mod app;
+mod export;
+mod import;
 mod opt;
 
 #[tokio::main(flavor = "current_thread")]
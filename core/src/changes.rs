@@ -0,0 +1,59 @@
+//! Live notifications about changed tables
+//!
+//! Every table has the `notify_change` statement trigger sending `schema.table`
+//! to [`Changes::CHANNEL`] once the transaction commits
+
+use anyhow::{Context, Result};
+use sqlx::{postgres::PgListener, query_as, PgPool};
+
+use crate::tables::Table;
+
+/// Listener of the table changes of the pool schema
+pub struct Changes {
+    listener: PgListener,
+    schema: String,
+}
+
+impl Changes {
+    pub const CHANNEL: &'static str = "table_changes";
+
+    /// Starts listening on a connection taken from the pool for the listener lifetime
+    pub async fn listen(pool: &PgPool) -> Result<Self> {
+        let (schema,): (String,) = query_as("SELECT current_schema();")
+            .fetch_one(pool)
+            .await
+            .context("While reading the current schema")?;
+
+        let mut listener = PgListener::connect_with(pool)
+            .await
+            .context("While connecting the change listener")?;
+        listener
+            .listen(Self::CHANNEL)
+            .await
+            .with_context(|| format!("While listening on '{}' channel", Self::CHANNEL))?;
+
+        Ok(Self { listener, schema })
+    }
+
+    /// Waits for the next changed table
+    ///
+    /// Returns `None` if the connection was lost, changes made until the listener reconnects
+    /// on the next call are missed
+    pub async fn next(&mut self) -> Result<Option<Table>> {
+        loop {
+            let notification = match self.listener.try_recv().await? {
+                Some(notification) => notification,
+                None => return Ok(None),
+            };
+
+            // Other schemas of the database are other shops
+            if let Some((schema, table)) = notification.payload().split_once('.') {
+                if schema == self.schema {
+                    if let Ok(table) = table.parse() {
+                        return Ok(Some(table));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,790 @@
+//! Schema drift detection
+//!
+//! Expected schema is created from scratch in a temporary schema within a transaction
+//! which gets rolled back, then both schemas are introspected through `pg_catalog` and compared
+
+use core::fmt;
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use sqlx::{query, query_as, PgPool, Postgres, Transaction};
+
+use crate::{
+    functions::Function, indexes::Index, procedures::Procedure, tables::Table, triggers::Trigger,
+    types::DbType, views::View,
+};
+
+/// Temporary schema the expected objects are created in
+const EXPECTED_SCHEMA: &str = "cw_drift_expected";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ObjectKind {
+    Type,
+    Table,
+    Column,
+    Constraint,
+    Index,
+    View,
+    Function,
+    Trigger,
+}
+
+impl fmt::Display for ObjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Type => "Type",
+            Self::Table => "Table",
+            Self::Column => "Column",
+            Self::Constraint => "Constraint",
+            Self::Index => "Index",
+            Self::View => "View",
+            Self::Function => "Function",
+            Self::Trigger => "Trigger",
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DriftKind {
+    /// Object is expected but absent
+    Missing,
+    /// Object is present but not expected
+    Unexpected,
+    /// Object definition differs from the expected one
+    Changed,
+}
+
+impl fmt::Display for DriftKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Missing => "Missing",
+            Self::Unexpected => "Unexpected",
+            Self::Changed => "Changed",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Drift {
+    pub object: ObjectKind,
+    /// Object name, columns and constraints are prefixed with the table (e.g. `Phone.imei`)
+    pub name: String,
+    pub kind: DriftKind,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    /// Statements bringing the object to the expected state,
+    /// empty if it can't be done without losing data
+    pub fix: Vec<String>,
+}
+
+impl Drift {
+    fn new(object: ObjectKind, name: impl Into<String>, kind: DriftKind) -> Self {
+        Self {
+            object,
+            name: name.into(),
+            kind,
+            expected: None,
+            actual: None,
+            fix: Vec::new(),
+        }
+    }
+
+    fn expected(mut self, expected: impl Into<String>) -> Self {
+        self.expected = Some(expected.into());
+        self
+    }
+
+    fn actual(mut self, actual: impl Into<String>) -> Self {
+        self.actual = Some(actual.into());
+        self
+    }
+
+    fn fix(mut self, statement: impl Into<String>) -> Self {
+        self.fix.push(statement.into());
+        self
+    }
+}
+
+/// Compares objects of the `schema` with the ones expected by the application
+pub async fn detect(pool: &PgPool, schema: &str) -> Result<Vec<Drift>> {
+    let mut tx = pool.begin().await?;
+
+    query(&format!("CREATE SCHEMA {EXPECTED_SCHEMA};"))
+        .execute(&mut tx)
+        .await?;
+    // Extensions are resolved from the public schema
+    query(&format!(
+        "SET LOCAL search_path TO {EXPECTED_SCHEMA}, public;"
+    ))
+    .execute(&mut tx)
+    .await?;
+
+    create_expected(&mut tx).await?;
+
+    let expected = Snapshot::read(&mut tx, EXPECTED_SCHEMA)
+        .await
+        .context("While reading expected schema")?;
+    let actual = Snapshot::read(&mut tx, schema)
+        .await
+        .with_context(|| format!("While reading '{schema}' schema"))?;
+
+    tx.rollback().await?;
+
+    Ok(diff(
+        &expected.normalized(EXPECTED_SCHEMA),
+        &actual.normalized(schema),
+    ))
+}
+
+async fn create_expected(tx: &mut Transaction<'static, Postgres>) -> Result<()> {
+    for db_type in DbType::ALL {
+        query(db_type.create())
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("While creating expected '{db_type}' type"))?;
+    }
+    for function in Function::ALL
+        .into_iter()
+        .filter(Function::is_table_dependency)
+    {
+        query(&function.create())
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("While creating expected '{function}' function"))?;
+    }
+    for table in Table::ALL {
+        query(table.create())
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("While creating expected '{table}' table"))?;
+    }
+    for index in Index::ALL {
+        query(&index.create())
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("While creating expected '{index}' index"))?;
+    }
+    for view in View::ALL {
+        query(view.create())
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("While creating expected '{view}' view"))?;
+    }
+    for function in Function::ALL
+        .into_iter()
+        .filter(|function| !function.is_table_dependency())
+    {
+        query(&function.create())
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("While creating expected '{function}' function"))?;
+    }
+    for procedure in Procedure::ALL {
+        query(&procedure.create())
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("While creating expected '{procedure}' procedure"))?;
+    }
+    for trigger in Trigger::ALL {
+        for table in trigger.tables() {
+            query(&trigger.create(*table))
+                .execute(&mut *tx)
+                .await
+                .with_context(|| {
+                    format!("While creating expected '{trigger}' trigger for '{table}' table")
+                })?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct Column {
+    data_type: String,
+    not_null: bool,
+    default: Option<String>,
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.data_type)?;
+        if self.not_null {
+            f.write_str(" NOT NULL")?;
+        }
+        if let Some(default) = &self.default {
+            write!(f, " DEFAULT {default}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default, Debug)]
+struct Snapshot {
+    /// Tables (`r`) and composite types (`c`) by name
+    relations: BTreeMap<String, String>,
+    columns: BTreeMap<(String, String), Column>,
+    constraints: BTreeMap<(String, String), String>,
+    /// Indexes not backing constraints
+    indexes: BTreeMap<(String, String), String>,
+    enums: BTreeMap<String, Vec<String>>,
+    views: BTreeMap<String, String>,
+    functions: BTreeMap<String, String>,
+    triggers: BTreeMap<(String, String), String>,
+}
+
+impl Snapshot {
+    async fn read(tx: &mut Transaction<'static, Postgres>, schema: &str) -> Result<Self> {
+        let mut snapshot = Self::default();
+
+        for (name, kind) in query_as::<_, (String, String)>(
+            r#"SELECT c.relname::text, c.relkind::text FROM pg_catalog.pg_class c
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = $1 AND c.relkind IN ('r', 'c')
+    AND NOT EXISTS (
+        SELECT 1 FROM pg_catalog.pg_depend d
+        WHERE d.objid IN (c.oid, c.reltype) AND d.deptype = 'e'
+    );"#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            snapshot.relations.insert(name, kind);
+        }
+
+        for (relation, name, data_type, not_null, default) in
+            query_as::<_, (String, String, String, bool, Option<String>)>(
+                r#"SELECT c.relname::text, a.attname::text,
+    format_type(a.atttypid, a.atttypmod), a.attnotnull, pg_get_expr(d.adbin, d.adrelid)
+FROM pg_catalog.pg_attribute a
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+    LEFT JOIN pg_catalog.pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+WHERE n.nspname = $1 AND c.relkind IN ('r', 'c') AND a.attnum > 0 AND NOT a.attisdropped;"#,
+            )
+            .bind(schema)
+            .fetch_all(&mut *tx)
+            .await?
+        {
+            snapshot.columns.insert(
+                (relation, name),
+                Column {
+                    data_type,
+                    not_null,
+                    default,
+                },
+            );
+        }
+
+        for (relation, name, definition) in query_as::<_, (String, String, String)>(
+            r#"SELECT c.relname::text, con.conname::text, pg_get_constraintdef(con.oid)
+FROM pg_catalog.pg_constraint con
+    JOIN pg_catalog.pg_class c ON c.oid = con.conrelid
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = $1;"#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            snapshot.constraints.insert((relation, name), definition);
+        }
+
+        for (relation, name, definition) in query_as::<_, (String, String, String)>(
+            r#"SELECT c.relname::text, ic.relname::text, pg_get_indexdef(i.indexrelid)
+FROM pg_catalog.pg_index i
+    JOIN pg_catalog.pg_class c ON c.oid = i.indrelid
+    JOIN pg_catalog.pg_class ic ON ic.oid = i.indexrelid
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = $1
+    AND NOT EXISTS (SELECT 1 FROM pg_catalog.pg_constraint con WHERE con.conindid = i.indexrelid);"#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            snapshot.indexes.insert((relation, name), definition);
+        }
+
+        for (name, labels) in query_as::<_, (String, Vec<String>)>(
+            r#"SELECT t.typname::text, array_agg(e.enumlabel::text ORDER BY e.enumsortorder)
+FROM pg_catalog.pg_enum e
+    JOIN pg_catalog.pg_type t ON t.oid = e.enumtypid
+    JOIN pg_catalog.pg_namespace n ON n.oid = t.typnamespace
+WHERE n.nspname = $1
+GROUP BY t.typname;"#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            snapshot.enums.insert(name, labels);
+        }
+
+        for (name, definition) in query_as::<_, (String, String)>(
+            r#"SELECT c.relname::text, pg_get_viewdef(c.oid) FROM pg_catalog.pg_class c
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = $1 AND c.relkind IN ('v', 'm');"#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            snapshot.views.insert(name, definition);
+        }
+
+        for (name, definition) in query_as::<_, (String, String)>(
+            r#"SELECT p.proname::text, pg_get_functiondef(p.oid) FROM pg_catalog.pg_proc p
+    JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+WHERE n.nspname = $1 AND p.prokind IN ('f', 'p')
+    AND NOT EXISTS (
+        SELECT 1 FROM pg_catalog.pg_depend d WHERE d.objid = p.oid AND d.deptype = 'e'
+    );"#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            snapshot.functions.insert(name, definition);
+        }
+
+        for (relation, name, definition) in query_as::<_, (String, String, String)>(
+            r#"SELECT c.relname::text, t.tgname::text, pg_get_triggerdef(t.oid)
+FROM pg_catalog.pg_trigger t
+    JOIN pg_catalog.pg_class c ON c.oid = t.tgrelid
+    JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+WHERE n.nspname = $1 AND NOT t.tgisinternal;"#,
+        )
+        .bind(schema)
+        .fetch_all(&mut *tx)
+        .await?
+        {
+            snapshot.triggers.insert((relation, name), definition);
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Strips schema qualification, so definitions from different schemas become comparable
+    fn normalized(mut self, schema: &str) -> Self {
+        let strip = |s: &mut String| {
+            *s = s
+                .replace(&format!(r#""{schema}"."#), "")
+                .replace(&format!("{schema}."), "")
+                // Pinned `search_path` of the routines
+                .replace(&format!("'{schema}', "), "")
+                .trim()
+                .to_string()
+        };
+
+        for column in self.columns.values_mut() {
+            strip(&mut column.data_type);
+            if let Some(default) = &mut column.default {
+                strip(default);
+            }
+        }
+        self.constraints.values_mut().for_each(strip);
+        self.indexes.values_mut().for_each(strip);
+        self.views.values_mut().for_each(strip);
+        self.functions.values_mut().for_each(strip);
+        self.triggers.values_mut().for_each(strip);
+
+        self
+    }
+}
+
+fn diff(expected: &Snapshot, actual: &Snapshot) -> Vec<Drift> {
+    let mut drifts = Vec::new();
+
+    // Enums
+    for (name, labels) in &expected.enums {
+        match actual.enums.get(name) {
+            Some(actual_labels) if actual_labels != labels => {
+                drifts.push(enum_drift(name, labels, actual_labels))
+            }
+            Some(_) => (),
+            None => drifts.push(missing(ObjectKind::Type, name)),
+        }
+    }
+
+    // Tables and composite types
+    for (name, kind) in &expected.relations {
+        if !actual.relations.contains_key(name) {
+            drifts.push(missing(
+                if kind == "c" {
+                    ObjectKind::Type
+                } else {
+                    ObjectKind::Table
+                },
+                name,
+            ));
+        }
+    }
+    for (name, kind) in &actual.relations {
+        if kind == "r" && !expected.relations.contains_key(name) {
+            drifts.push(Drift::new(ObjectKind::Table, name, DriftKind::Unexpected));
+        }
+    }
+
+    // Columns (attributes) of the relations present in both schemas
+    let shared = |relation: &String| {
+        expected.relations.contains_key(relation) && actual.relations.contains_key(relation)
+    };
+    for ((relation, name), column) in expected.columns.iter().filter(|((r, _), _)| shared(r)) {
+        let composite = expected.relations[relation] == "c";
+        let full_name = format!("{relation}.{name}");
+
+        match actual.columns.get(&(relation.clone(), name.clone())) {
+            None => drifts.push(
+                Drift::new(ObjectKind::Column, full_name, DriftKind::Missing)
+                    .expected(column.to_string())
+                    .fix(if composite {
+                        format!(
+                            r#"ALTER TYPE "{relation}" ADD ATTRIBUTE "{name}" {};"#,
+                            column.data_type
+                        )
+                    } else {
+                        format!(r#"ALTER TABLE "{relation}" ADD COLUMN "{name}" {column};"#)
+                    }),
+            ),
+            Some(actual_column) if actual_column.to_string() != column.to_string() => drifts.push(
+                column_drift(relation, name, composite, column, actual_column),
+            ),
+            Some(_) => (),
+        }
+    }
+    for ((relation, name), column) in actual.columns.iter().filter(|((r, _), _)| shared(r)) {
+        if !expected
+            .columns
+            .contains_key(&(relation.clone(), name.clone()))
+        {
+            // Left intact, the column may contain data
+            drifts.push(
+                Drift::new(
+                    ObjectKind::Column,
+                    format!("{relation}.{name}"),
+                    DriftKind::Unexpected,
+                )
+                .actual(column.to_string()),
+            );
+        }
+    }
+
+    // Constraints
+    for ((relation, name), definition) in
+        expected.constraints.iter().filter(|((r, _), _)| shared(r))
+    {
+        let full_name = format!("{relation}.{name}");
+        let add = format!(r#"ALTER TABLE "{relation}" ADD CONSTRAINT "{name}" {definition};"#);
+
+        match actual.constraints.get(&(relation.clone(), name.clone())) {
+            None => drifts.push(
+                Drift::new(ObjectKind::Constraint, full_name, DriftKind::Missing)
+                    .expected(definition)
+                    .fix(add),
+            ),
+            Some(actual_definition) if actual_definition != definition => drifts.push(
+                Drift::new(ObjectKind::Constraint, full_name, DriftKind::Changed)
+                    .expected(definition)
+                    .actual(actual_definition)
+                    .fix(format!(
+                        r#"ALTER TABLE "{relation}" DROP CONSTRAINT "{name}";"#
+                    ))
+                    .fix(add),
+            ),
+            Some(_) => (),
+        }
+    }
+    for ((relation, name), definition) in actual.constraints.iter().filter(|((r, _), _)| shared(r))
+    {
+        if !expected
+            .constraints
+            .contains_key(&(relation.clone(), name.clone()))
+        {
+            drifts.push(
+                Drift::new(
+                    ObjectKind::Constraint,
+                    format!("{relation}.{name}"),
+                    DriftKind::Unexpected,
+                )
+                .actual(definition)
+                .fix(format!(
+                    r#"ALTER TABLE "{relation}" DROP CONSTRAINT "{name}";"#
+                )),
+            );
+        }
+    }
+
+    // Indexes
+    for ((relation, name), definition) in expected.indexes.iter().filter(|((r, _), _)| shared(r)) {
+        let index = Index::ALL.into_iter().find(|i| i.name() == *name);
+        let full_name = format!("{relation}.{name}");
+
+        match actual.indexes.get(&(relation.clone(), name.clone())) {
+            None => {
+                let mut drift = Drift::new(ObjectKind::Index, full_name, DriftKind::Missing)
+                    .expected(definition);
+                if let Some(index) = index {
+                    drift = drift.fix(index.create());
+                }
+                drifts.push(drift);
+            }
+            Some(actual_definition) if actual_definition != definition => {
+                let mut drift = Drift::new(ObjectKind::Index, full_name, DriftKind::Changed)
+                    .expected(definition)
+                    .actual(actual_definition);
+                if let Some(index) = index {
+                    drift = drift.fix(index.drop()).fix(index.create());
+                }
+                drifts.push(drift);
+            }
+            Some(_) => (),
+        }
+    }
+    for ((relation, name), definition) in actual.indexes.iter().filter(|((r, _), _)| shared(r)) {
+        if !expected
+            .indexes
+            .contains_key(&(relation.clone(), name.clone()))
+        {
+            // Left intact, it may be added by the administrator on purpose
+            drifts.push(
+                Drift::new(
+                    ObjectKind::Index,
+                    format!("{relation}.{name}"),
+                    DriftKind::Unexpected,
+                )
+                .actual(definition),
+            );
+        }
+    }
+
+    // Views
+    for (name, definition) in &expected.views {
+        match actual.views.get(name) {
+            None => drifts.push(missing(ObjectKind::View, name)),
+            Some(actual_definition) if actual_definition != definition => {
+                let mut drift = Drift::new(ObjectKind::View, name, DriftKind::Changed)
+                    .expected(definition)
+                    .actual(actual_definition);
+                if let Some(view) = View::ALL.into_iter().find(|v| v.name() == name) {
+                    drift = drift.fix(view.drop()).fix(view.create());
+                }
+                drifts.push(drift);
+            }
+            Some(_) => (),
+        }
+    }
+
+    // Functions and procedures
+    for (name, definition) in &expected.functions {
+        match actual.functions.get(name) {
+            None => drifts.push(missing(ObjectKind::Function, name)),
+            Some(actual_definition) if actual_definition != definition => {
+                let mut drift = Drift::new(ObjectKind::Function, name, DriftKind::Changed)
+                    .expected(definition)
+                    .actual(actual_definition);
+                if let Some(create) = create_statement(ObjectKind::Function, name) {
+                    drift = drift.fix(or_replace(&create));
+                }
+                drifts.push(drift);
+            }
+            Some(_) => (),
+        }
+    }
+
+    // Triggers
+    for ((relation, name), definition) in &expected.triggers {
+        let trigger = Trigger::ALL.into_iter().find(|t| t.name() == name);
+        let table = relation.parse::<Table>().ok();
+        let full_name = format!("{relation}.{name}");
+
+        match actual.triggers.get(&(relation.clone(), name.clone())) {
+            None => {
+                let mut drift = Drift::new(ObjectKind::Trigger, full_name, DriftKind::Missing)
+                    .expected(definition);
+                if let (Some(trigger), Some(table)) = (trigger, table) {
+                    drift = drift.fix(trigger.create(table));
+                }
+                drifts.push(drift);
+            }
+            Some(actual_definition) if actual_definition != definition => {
+                let mut drift = Drift::new(ObjectKind::Trigger, full_name, DriftKind::Changed)
+                    .expected(definition)
+                    .actual(actual_definition);
+                if let (Some(trigger), Some(table)) = (trigger, table) {
+                    drift = drift.fix(trigger.drop(table)).fix(trigger.create(table));
+                }
+                drifts.push(drift);
+            }
+            Some(_) => (),
+        }
+    }
+    for ((relation, name), definition) in &actual.triggers {
+        if shared(relation)
+            && !expected
+                .triggers
+                .contains_key(&(relation.clone(), name.clone()))
+        {
+            drifts.push(
+                Drift::new(
+                    ObjectKind::Trigger,
+                    format!("{relation}.{name}"),
+                    DriftKind::Unexpected,
+                )
+                .actual(definition),
+            );
+        }
+    }
+
+    drifts
+}
+
+fn missing(object: ObjectKind, name: &str) -> Drift {
+    let drift = Drift::new(object, name, DriftKind::Missing);
+
+    match create_statement(object, name) {
+        Some(create) => drift.fix(create),
+        None => drift,
+    }
+}
+
+fn column_drift(
+    relation: &str,
+    name: &str,
+    composite: bool,
+    expected: &Column,
+    actual: &Column,
+) -> Drift {
+    let mut drift = Drift::new(
+        ObjectKind::Column,
+        format!("{relation}.{name}"),
+        DriftKind::Changed,
+    )
+    .expected(expected.to_string())
+    .actual(actual.to_string());
+
+    if composite {
+        if expected.data_type != actual.data_type {
+            drift = drift.fix(format!(
+                r#"ALTER TYPE "{relation}" ALTER ATTRIBUTE "{name}" TYPE {};"#,
+                expected.data_type
+            ));
+        }
+
+        return drift;
+    }
+
+    let alter = format!(r#"ALTER TABLE "{relation}" ALTER COLUMN "{name}""#);
+    if expected.data_type != actual.data_type {
+        drift = drift.fix(format!(
+            r#"{alter} TYPE {data_type} USING "{name}"::{data_type};"#,
+            data_type = expected.data_type
+        ));
+    }
+    if expected.default != actual.default {
+        drift = drift.fix(match &expected.default {
+            Some(default) => format!("{alter} SET DEFAULT {default};"),
+            None => format!("{alter} DROP DEFAULT;"),
+        });
+    }
+    if expected.not_null != actual.not_null {
+        drift = drift.fix(if expected.not_null {
+            format!("{alter} SET NOT NULL;")
+        } else {
+            format!("{alter} DROP NOT NULL;")
+        });
+    }
+
+    drift
+}
+
+/// Enum labels can only be added or renamed, removing them requires manual intervention
+fn enum_drift(name: &str, expected: &[String], actual: &[String]) -> Drift {
+    let list = |labels: &[String]| {
+        labels
+            .iter()
+            .map(|label| format!("'{label}'"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut drift = Drift::new(ObjectKind::Type, name, DriftKind::Changed)
+        .expected(list(expected))
+        .actual(list(actual));
+
+    let renamed = expected.len() == actual.len()
+        && expected
+            .iter()
+            .zip(actual)
+            .all(|(e, a)| e == a || (!actual.contains(e) && !expected.contains(a)));
+    if renamed {
+        for (e, a) in expected.iter().zip(actual).filter(|(e, a)| e != a) {
+            drift = drift.fix(format!(
+                r#"ALTER TYPE "{name}" RENAME VALUE '{a}' TO '{e}';"#
+            ));
+        }
+
+        return drift;
+    }
+
+    if actual.iter().any(|label| !expected.contains(label)) {
+        // Unexpected labels can't be dropped without recreating the type
+        return drift;
+    }
+
+    for (i, label) in expected.iter().enumerate() {
+        if actual.contains(label) {
+            continue;
+        }
+
+        drift = drift.fix(match (i.checked_sub(1), actual.first()) {
+            (Some(prev), _) => format!(
+                r#"ALTER TYPE "{name}" ADD VALUE '{label}' AFTER '{}';"#,
+                expected[prev]
+            ),
+            (None, Some(first)) => {
+                format!(r#"ALTER TYPE "{name}" ADD VALUE '{label}' BEFORE '{first}';"#)
+            }
+            (None, None) => format!(r#"ALTER TYPE "{name}" ADD VALUE '{label}';"#),
+        });
+    }
+
+    drift
+}
+
+/// Application definition of the object
+fn create_statement(object: ObjectKind, name: &str) -> Option<String> {
+    match object {
+        ObjectKind::Type => DbType::ALL
+            .into_iter()
+            .find(|t| t.name() == name)
+            .map(|t| t.create().to_string()),
+        ObjectKind::Table => name.parse::<Table>().ok().map(|t| t.create().to_string()),
+        ObjectKind::View => View::ALL
+            .into_iter()
+            .find(|v| v.name() == name)
+            .map(|v| v.create().to_string()),
+        ObjectKind::Function => Function::ALL
+            .into_iter()
+            .find(|f| f.name() == name)
+            .map(|f| f.create())
+            .or_else(|| {
+                Procedure::ALL
+                    .into_iter()
+                    .find(|p| p.name() == name)
+                    .map(|p| p.create())
+            }),
+        ObjectKind::Column | ObjectKind::Constraint | ObjectKind::Index | ObjectKind::Trigger => {
+            None
+        }
+    }
+}
+
+pub(crate) fn or_replace(create: &str) -> String {
+    if create.starts_with("CREATE OR REPLACE ") {
+        create.to_string()
+    } else {
+        create.replacen("CREATE ", "CREATE OR REPLACE ", 1)
+    }
+}
@@ -0,0 +1,730 @@
+use core::fmt::Display;
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgRow, query, query_as, FromRow, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    generator::FullPack,
+    tables::{
+        Account, Branch, Component, ComponentKind, ExchangeRate, Invoice, LaborContract,
+        Manufacturer, Order, OrderService, OrderWarehouse, Payment, Person, PersonMerge, Phone,
+        PhoneModel, PhoneOwnership, Position, PriceChange, Service, ServicePhoneModel,
+        ServicePrice, Staff, StockTransfer, Supplier, Supply, SupplyContract, SupplyContractItem,
+        SupplyItem, Table, Warehouse, WarehousePrice, WarehouseSupply,
+    },
+    traits::Insertable,
+    types::Currency,
+};
+
+/// Portable snapshot of all application tables (except [`AuditLog`](crate::tables::AuditLog))
+///
+/// Fields are declared in dependency order, the same order is used while loading
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Dump {
+    pub version: u32,
+    pub person: Vec<Person>,
+    pub supplier: Vec<Supplier>,
+    pub manufacturer: Vec<Manufacturer>,
+    pub position: Vec<Position>,
+    pub service: Vec<Service>,
+    pub component_kind: Vec<ComponentKind>,
+    pub branch: Vec<Branch>,
+    pub exchange_rate: Vec<ExchangeRate>,
+    pub labor_contract: Vec<LaborContract>,
+    pub staff: Vec<Staff>,
+    pub phone_model: Vec<PhoneModel>,
+    pub phone: Vec<Phone>,
+    pub supply_contract: Vec<SupplyContract>,
+    pub supply: Vec<Supply>,
+    pub account: Vec<Account>,
+    pub phone_ownership: Vec<PhoneOwnership>,
+    pub person_merge: Vec<PersonMerge>,
+    pub component: Vec<Component>,
+    pub warehouse: Vec<Warehouse>,
+    pub order: Vec<Order>,
+    pub stock_transfer: Vec<StockTransfer>,
+    pub invoice: Vec<Invoice>,
+    pub payment: Vec<Payment>,
+    pub price_change: Vec<PriceChange>,
+    pub warehouse_price: Vec<WarehousePrice>,
+    pub service_phone_model: Vec<ServicePhoneModel>,
+    pub service_price: Vec<ServicePrice>,
+    pub supply_contract_item: Vec<SupplyContractItem>,
+    pub warehouse_supply: Vec<WarehouseSupply>,
+    pub supply_item: Vec<SupplyItem>,
+    pub order_service: Vec<OrderService>,
+    pub order_warehouse: Vec<OrderWarehouse>,
+}
+
+impl Dump {
+    /// Current version of the dump format
+    pub const VERSION: u32 = 15;
+
+    /// Rows of every table grouped in insertion order
+    pub fn groups(&self) -> [(Table, Vec<&(dyn Insertable + Sync)>); 32] {
+        fn rows<T: Insertable + Sync>(items: &[T]) -> Vec<&(dyn Insertable + Sync)> {
+            items
+                .iter()
+                .map(|item| item as &(dyn Insertable + Sync))
+                .collect()
+        }
+
+        [
+            (Table::Person, rows(&self.person)),
+            (Table::Supplier, rows(&self.supplier)),
+            (Table::Manufacturer, rows(&self.manufacturer)),
+            (Table::Position, rows(&self.position)),
+            (Table::Service, rows(&self.service)),
+            (Table::ComponentKind, rows(&self.component_kind)),
+            (Table::Branch, rows(&self.branch)),
+            (Table::ExchangeRate, rows(&self.exchange_rate)),
+            (Table::LaborContract, rows(&self.labor_contract)),
+            (Table::Staff, rows(&self.staff)),
+            (Table::PhoneModel, rows(&self.phone_model)),
+            (Table::Phone, rows(&self.phone)),
+            (Table::SupplyContract, rows(&self.supply_contract)),
+            (Table::Supply, rows(&self.supply)),
+            (Table::Account, rows(&self.account)),
+            (Table::PhoneOwnership, rows(&self.phone_ownership)),
+            (Table::PersonMerge, rows(&self.person_merge)),
+            (Table::Component, rows(&self.component)),
+            (Table::Warehouse, rows(&self.warehouse)),
+            // Warranty claims reference original orders, so they are inserted last
+            (
+                Table::Order,
+                self.order
+                    .iter()
+                    .filter(|order| !order.is_warranty_claim())
+                    .chain(self.order.iter().filter(|order| order.is_warranty_claim()))
+                    .map(|order| order as &(dyn Insertable + Sync))
+                    .collect(),
+            ),
+            (Table::StockTransfer, rows(&self.stock_transfer)),
+            (Table::Invoice, rows(&self.invoice)),
+            (Table::Payment, rows(&self.payment)),
+            // Rollbacks reference reverted changes, so they are inserted last
+            (
+                Table::PriceChange,
+                self.price_change
+                    .iter()
+                    .filter(|change| change.reverts.is_none())
+                    .chain(
+                        self.price_change
+                            .iter()
+                            .filter(|change| change.reverts.is_some()),
+                    )
+                    .map(|change| change as &(dyn Insertable + Sync))
+                    .collect(),
+            ),
+            (Table::WarehousePrice, rows(&self.warehouse_price)),
+            (Table::ServicePhoneModel, rows(&self.service_phone_model)),
+            (Table::ServicePrice, rows(&self.service_price)),
+            (Table::SupplyContractItem, rows(&self.supply_contract_item)),
+            (Table::WarehouseSupply, rows(&self.warehouse_supply)),
+            (Table::SupplyItem, rows(&self.supply_item)),
+            (Table::OrderService, rows(&self.order_service)),
+            (Table::OrderWarehouse, rows(&self.order_warehouse)),
+        ]
+    }
+
+    /// Checks dump version, primary key uniqueness and that every foreign key points to a row
+    /// present in the dump
+    pub fn validate(&self) -> Result<()> {
+        if self.version != Self::VERSION {
+            bail!(
+                "Unsupported dump version {} (expected {})",
+                self.version,
+                Self::VERSION
+            );
+        }
+
+        let person = keys(Table::Person, self.person.iter().map(|r| r.uuid))?;
+        let supplier = keys(Table::Supplier, self.supplier.iter().map(|r| r.uuid))?;
+        let manufacturer = keys(
+            Table::Manufacturer,
+            self.manufacturer.iter().map(|r| r.uuid),
+        )?;
+        let position = keys(Table::Position, self.position.iter().map(|r| r.uuid))?;
+        let service = keys(Table::Service, self.service.iter().map(|r| r.uuid))?;
+        let component_kind = keys(
+            Table::ComponentKind,
+            self.component_kind.iter().map(|r| r.uuid),
+        )?;
+        let branch = keys(Table::Branch, self.branch.iter().map(|r| r.uuid))?;
+        let labor_contract = keys(
+            Table::LaborContract,
+            self.labor_contract.iter().map(|r| r.uuid),
+        )?;
+        let staff = keys(Table::Staff, self.staff.iter().map(|r| r.uuid))?;
+        let phone_model = keys(Table::PhoneModel, self.phone_model.iter().map(|r| r.uuid))?;
+        let phone = keys(Table::Phone, self.phone.iter().map(|r| r.uuid))?;
+        let supply_contract = keys(
+            Table::SupplyContract,
+            self.supply_contract.iter().map(|r| r.uuid),
+        )?;
+        let supply = keys(Table::Supply, self.supply.iter().map(|r| r.uuid))?;
+        keys(Table::Account, self.account.iter().map(|r| r.uuid))?;
+        let component = keys(Table::Component, self.component.iter().map(|r| r.uuid))?;
+        let warehouse = keys(Table::Warehouse, self.warehouse.iter().map(|r| r.uuid))?;
+        let order = keys(Table::Order, self.order.iter().map(|r| r.uuid))?;
+        keys(
+            Table::StockTransfer,
+            self.stock_transfer.iter().map(|r| r.uuid),
+        )?;
+        let invoice = keys(Table::Invoice, self.invoice.iter().map(|r| r.uuid))?;
+        keys(Table::Payment, self.payment.iter().map(|r| r.uuid))?;
+        let price_change = keys(Table::PriceChange, self.price_change.iter().map(|r| r.uuid))?;
+        keys(
+            Table::WarehousePrice,
+            self.warehouse_price.iter().map(|r| r.uuid),
+        )?;
+        keys(
+            Table::ServicePrice,
+            self.service_price.iter().map(|r| r.uuid),
+        )?;
+        let service_phone_model: HashSet<(Uuid, Uuid)> = self
+            .service_phone_model
+            .iter()
+            .map(|r| (r.service, r.phone_model))
+            .collect();
+
+        for r in &self.labor_contract {
+            reference(Table::LaborContract, r.uuid, "person", &person, r.person)?;
+        }
+        for r in &self.staff {
+            reference(
+                Table::Staff,
+                r.uuid,
+                "contract",
+                &labor_contract,
+                r.contract,
+            )?;
+            reference(Table::Staff, r.uuid, "position", &position, r.position)?;
+            reference(Table::Staff, r.uuid, "branch", &branch, r.branch)?;
+        }
+        for r in &self.phone_model {
+            reference(
+                Table::PhoneModel,
+                r.uuid,
+                "manufacturer",
+                &manufacturer,
+                r.manufacturer,
+            )?;
+        }
+        for r in &self.phone {
+            reference(Table::Phone, r.uuid, "person", &person, r.person)?;
+            reference(Table::Phone, r.uuid, "model", &phone_model, r.model)?;
+        }
+        for r in &self.supply_contract {
+            reference(
+                Table::SupplyContract,
+                r.uuid,
+                "supplier",
+                &supplier,
+                r.supplier,
+            )?;
+            reference(Table::SupplyContract, r.uuid, "manager", &staff, r.manager)?;
+        }
+        for r in &self.supply {
+            reference(
+                Table::Supply,
+                r.uuid,
+                "contract",
+                &supply_contract,
+                r.contract,
+            )?;
+            reference(Table::Supply, r.uuid, "staff", &staff, r.staff)?;
+            if let Some(approver) = r.approver {
+                reference(Table::Supply, r.uuid, "approver", &staff, approver)?;
+            }
+        }
+        for r in &self.account {
+            reference(Table::Account, r.uuid, "staff", &staff, r.staff)?;
+        }
+        let mut ownership = HashSet::new();
+        for r in &self.phone_ownership {
+            let row = format!("({}, {})", r.phone, r.since);
+            if !ownership.insert((r.phone, r.since)) {
+                bail!(
+                    "Duplicate primary key '{row}' in '{}' table",
+                    Table::PhoneOwnership
+                );
+            }
+            reference(Table::PhoneOwnership, &row, "phone", &phone, r.phone)?;
+            reference(Table::PhoneOwnership, &row, "person", &person, r.person)?;
+        }
+        keys(Table::PersonMerge, self.person_merge.iter().map(|r| r.uuid))?;
+        for r in &self.person_merge {
+            reference(Table::PersonMerge, r.uuid, "survivor", &person, r.survivor)?;
+        }
+        for r in &self.component {
+            reference(Table::Component, r.uuid, "kind", &component_kind, r.kind)?;
+            reference(
+                Table::Component,
+                r.uuid,
+                "phone_model",
+                &phone_model,
+                r.phone_model,
+            )?;
+            reference(
+                Table::Component,
+                r.uuid,
+                "manufacturer",
+                &manufacturer,
+                r.manufacturer,
+            )?;
+        }
+        for r in &self.warehouse {
+            reference(
+                Table::Warehouse,
+                r.uuid,
+                "component",
+                &component,
+                r.component,
+            )?;
+            reference(Table::Warehouse, r.uuid, "supplier", &supplier, r.supplier)?;
+            reference(Table::Warehouse, r.uuid, "branch", &branch, r.branch)?;
+        }
+        for r in &self.order {
+            reference(Table::Order, r.uuid, "client", &person, r.client)?;
+            reference(Table::Order, r.uuid, "phone", &phone, r.phone)?;
+            reference(Table::Order, r.uuid, "serviceman", &staff, r.serviceman)?;
+            reference(Table::Order, r.uuid, "shopman", &staff, r.shopman)?;
+            reference(Table::Order, r.uuid, "branch", &branch, r.branch)?;
+            if let Some(original) = r.warranty_of {
+                reference(Table::Order, r.uuid, "warranty_of", &order, original)?;
+            }
+        }
+        for r in &self.stock_transfer {
+            reference(Table::StockTransfer, r.uuid, "item", &warehouse, r.item)?;
+            reference(
+                Table::StockTransfer,
+                r.uuid,
+                "destination",
+                &branch,
+                r.destination,
+            )?;
+            reference(Table::StockTransfer, r.uuid, "sender", &staff, r.sender)?;
+            if let Some(receiver) = r.receiver {
+                reference(Table::StockTransfer, r.uuid, "receiver", &staff, receiver)?;
+            }
+        }
+        for r in &self.invoice {
+            reference(Table::Invoice, r.uuid, "order", &order, r.order)?;
+            reference(Table::Invoice, r.uuid, "issuer", &staff, r.issuer)?;
+        }
+        for r in &self.payment {
+            reference(Table::Payment, r.uuid, "invoice", &invoice, r.invoice)?;
+            reference(Table::Payment, r.uuid, "cashier", &staff, r.cashier)?;
+        }
+        for r in &self.price_change {
+            if let Some(reverts) = r.reverts {
+                reference(
+                    Table::PriceChange,
+                    r.uuid,
+                    "reverts",
+                    &price_change,
+                    reverts,
+                )?;
+            }
+        }
+        for r in &self.warehouse_price {
+            reference(Table::WarehousePrice, r.uuid, "item", &warehouse, r.item)?;
+            if let Some(change) = r.change {
+                reference(
+                    Table::WarehousePrice,
+                    r.uuid,
+                    "change",
+                    &price_change,
+                    change,
+                )?;
+            }
+        }
+        for r in &self.service_price {
+            if !service_phone_model.contains(&(r.service, r.phone_model)) {
+                bail!(
+                    "Row '{}' of '{}' table references missing '({}, {})' in 'service, phone_model' fields",
+                    r.uuid,
+                    Table::ServicePrice,
+                    r.service,
+                    r.phone_model,
+                );
+            }
+            if let Some(change) = r.change {
+                reference(Table::ServicePrice, r.uuid, "change", &price_change, change)?;
+            }
+        }
+        for r in &self.service_phone_model {
+            let row = format!("({}, {})", r.service, r.phone_model);
+            reference(
+                Table::ServicePhoneModel,
+                &row,
+                "service",
+                &service,
+                r.service,
+            )?;
+            reference(
+                Table::ServicePhoneModel,
+                &row,
+                "phone_model",
+                &phone_model,
+                r.phone_model,
+            )?;
+        }
+        let mut rates = HashSet::new();
+        for r in &self.exchange_rate {
+            if !rates.insert((r.currency, r.day)) {
+                bail!(
+                    "Duplicate primary key '({}, {})' in '{}' table",
+                    r.currency,
+                    r.day,
+                    Table::ExchangeRate
+                );
+            }
+        }
+        let contract_currency: HashMap<Uuid, Currency> = self
+            .supply_contract
+            .iter()
+            .map(|c| (c.uuid, c.currency))
+            .collect();
+        for r in &self.supply_contract_item {
+            let row = format!("({}, {})", r.contract, r.component);
+            reference(
+                Table::SupplyContractItem,
+                &row,
+                "contract",
+                &supply_contract,
+                r.contract,
+            )?;
+            reference(
+                Table::SupplyContractItem,
+                &row,
+                "component",
+                &component,
+                r.component,
+            )?;
+            if contract_currency.get(&r.contract) != Some(&r.price.currency()) {
+                bail!(
+                    "Row '{row}' of '{}' table has price in currency other than its contract",
+                    Table::SupplyContractItem
+                );
+            }
+        }
+        let supply_currency: HashMap<Uuid, Currency> = self
+            .supply
+            .iter()
+            .filter_map(|s| contract_currency.get(&s.contract).map(|c| (s.uuid, *c)))
+            .collect();
+        for r in &self.warehouse_supply {
+            let row = format!("({}, {})", r.item, r.supply);
+            reference(Table::WarehouseSupply, &row, "item", &warehouse, r.item)?;
+            reference(Table::WarehouseSupply, &row, "supply", &supply, r.supply)?;
+            if supply_currency.get(&r.supply) != Some(&r.price.currency()) {
+                bail!(
+                    "Row '{row}' of '{}' table has price in currency other than its contract",
+                    Table::WarehouseSupply
+                );
+            }
+        }
+        for r in &self.supply_item {
+            let row = format!("({}, {})", r.supply, r.component);
+            reference(Table::SupplyItem, &row, "supply", &supply, r.supply)?;
+            reference(
+                Table::SupplyItem,
+                &row,
+                "component",
+                &component,
+                r.component,
+            )?;
+            if supply_currency.get(&r.supply) != Some(&r.price.currency()) {
+                bail!(
+                    "Row '{row}' of '{}' table has price in currency other than its contract",
+                    Table::SupplyItem
+                );
+            }
+        }
+        for r in &self.order_service {
+            let row = format!("({}, {})", r.order, r.service);
+            reference(Table::OrderService, &row, "order", &order, r.order)?;
+            reference(Table::OrderService, &row, "service", &service, r.service)?;
+        }
+        for r in &self.order_warehouse {
+            let row = format!("({}, {})", r.order, r.item);
+            reference(Table::OrderWarehouse, &row, "order", &order, r.order)?;
+            reference(Table::OrderWarehouse, &row, "item", &warehouse, r.item)?;
+        }
+
+        Ok(())
+    }
+
+    /// Validates dump and inserts all rows in dependency order within a single transaction
+    pub async fn load(&self, pool: &PgPool, printer: impl Fn((Table, usize))) -> Result<()> {
+        self.validate().context("While validating dump")?;
+
+        let mut tx = pool.begin().await?;
+
+        for (table, rows) in self.groups() {
+            for row in &rows {
+                row.insert()
+                    .execute(&mut tx)
+                    .await
+                    .with_context(|| format!("While inserting into '{table}' table"))?;
+            }
+            printer((table, rows.len()));
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+impl Dump {
+    /// Reads a consistent snapshot of the database within a single repeatable read transaction
+    ///
+    /// Tables excluded by `filter` are left empty, so a filtered dump may fail [`Dump::validate`]
+    pub async fn fetch(pool: &PgPool, filter: &ExportFilter) -> Result<Self> {
+        let mut tx = pool.begin().await?;
+        query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY;")
+            .execute(&mut tx)
+            .await?;
+
+        let dump = Self {
+            version: Self::VERSION,
+            person: fetch(&mut tx, filter, Table::Person).await?,
+            supplier: fetch(&mut tx, filter, Table::Supplier).await?,
+            manufacturer: fetch(&mut tx, filter, Table::Manufacturer).await?,
+            position: fetch(&mut tx, filter, Table::Position).await?,
+            service: fetch(&mut tx, filter, Table::Service).await?,
+            component_kind: fetch(&mut tx, filter, Table::ComponentKind).await?,
+            branch: fetch(&mut tx, filter, Table::Branch).await?,
+            exchange_rate: fetch(&mut tx, filter, Table::ExchangeRate).await?,
+            labor_contract: fetch(&mut tx, filter, Table::LaborContract).await?,
+            staff: fetch(&mut tx, filter, Table::Staff).await?,
+            phone_model: fetch(&mut tx, filter, Table::PhoneModel).await?,
+            phone: fetch(&mut tx, filter, Table::Phone).await?,
+            supply_contract: fetch(&mut tx, filter, Table::SupplyContract).await?,
+            supply: fetch(&mut tx, filter, Table::Supply).await?,
+            account: fetch(&mut tx, filter, Table::Account).await?,
+            phone_ownership: fetch(&mut tx, filter, Table::PhoneOwnership).await?,
+            person_merge: fetch(&mut tx, filter, Table::PersonMerge).await?,
+            component: fetch(&mut tx, filter, Table::Component).await?,
+            warehouse: fetch(&mut tx, filter, Table::Warehouse).await?,
+            order: fetch(&mut tx, filter, Table::Order).await?,
+            stock_transfer: fetch(&mut tx, filter, Table::StockTransfer).await?,
+            invoice: fetch(&mut tx, filter, Table::Invoice).await?,
+            payment: fetch(&mut tx, filter, Table::Payment).await?,
+            price_change: fetch(&mut tx, filter, Table::PriceChange).await?,
+            warehouse_price: fetch(&mut tx, filter, Table::WarehousePrice).await?,
+            service_phone_model: fetch(&mut tx, filter, Table::ServicePhoneModel).await?,
+            service_price: fetch(&mut tx, filter, Table::ServicePrice).await?,
+            supply_contract_item: fetch(&mut tx, filter, Table::SupplyContractItem).await?,
+            warehouse_supply: fetch(&mut tx, filter, Table::WarehouseSupply).await?,
+            supply_item: fetch(&mut tx, filter, Table::SupplyItem).await?,
+            order_service: fetch(&mut tx, filter, Table::OrderService).await?,
+            order_warehouse: fetch(&mut tx, filter, Table::OrderWarehouse).await?,
+        };
+
+        tx.commit().await?;
+
+        Ok(dump)
+    }
+}
+
+/// Selects which tables and which period get exported
+#[derive(Default, Debug)]
+pub struct ExportFilter {
+    /// Tables to export, all tables if empty
+    pub tables: Vec<Table>,
+    /// Lower bound (inclusive) for row creation time
+    pub from: Option<DateTime<Utc>>,
+    /// Upper bound (exclusive) for row creation time
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl ExportFilter {
+    pub fn includes(&self, table: Table) -> bool {
+        self.tables.is_empty() || self.tables.iter().any(|t| t.name() == table.name())
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.from.is_some() || self.to.is_some()
+    }
+}
+
+impl From<FullPack> for Dump {
+    fn from(
+        (
+            component_kind,
+            service,
+            position,
+            manufacturer,
+            person,
+            supplier,
+            branch,
+            exchange_rate,
+            labor_contract,
+            phone_model,
+            staff,
+            component,
+            phone,
+            account,
+            phone_ownership,
+            supply_contract,
+            order,
+            supply,
+            warehouse,
+            stock_transfer,
+            invoice,
+            payment,
+            service_phone_model,
+            supply_contract_item,
+            warehouse_supply,
+            supply_item,
+            order_service,
+            order_warehouse,
+        ): FullPack,
+    ) -> Self {
+        Self {
+            version: Self::VERSION,
+            person,
+            supplier,
+            manufacturer,
+            position,
+            service,
+            component_kind,
+            branch,
+            exchange_rate,
+            labor_contract,
+            staff,
+            phone_model,
+            phone,
+            supply_contract,
+            supply,
+            account,
+            phone_ownership,
+            person_merge: Vec::new(),
+            component,
+            warehouse,
+            order,
+            stock_transfer,
+            invoice,
+            payment,
+            price_change: Vec::new(),
+            warehouse_price: Vec::new(),
+            service_phone_model,
+            service_price: Vec::new(),
+            supply_contract_item,
+            warehouse_supply,
+            supply_item,
+            order_service,
+            order_warehouse,
+        }
+    }
+}
+
+/// Timestamp expression used for period filtering,
+/// tables without creation time are exported as a whole
+fn created_column(table: Table) -> Option<&'static str> {
+    match table {
+        Table::Person
+        | Table::Position
+        | Table::Service
+        | Table::Branch
+        | Table::LaborContract
+        | Table::Phone
+        | Table::SupplyContract
+        | Table::Supply
+        | Table::AuditLog
+        | Table::PersonMerge
+        | Table::Account
+        | Table::Warehouse
+        | Table::Order
+        | Table::StockTransfer
+        | Table::Invoice
+        | Table::Payment
+        | Table::PriceChange
+        | Table::ServicePhoneModel
+        | Table::SupplyContractItem
+        | Table::SupplyItem => Some("(meta).created"),
+        Table::PhoneOwnership => Some("since"),
+        Table::WarehouseSupply => Some("created"),
+        Table::WarehousePrice | Table::ServicePrice => Some("effective"),
+        Table::ExchangeRate => Some("day"),
+        Table::OrderService | Table::OrderWarehouse => {
+            Some(r#"(SELECT (o.meta).created FROM "Order" o WHERE o.uuid = "order")"#)
+        }
+        Table::Supplier
+        | Table::Manufacturer
+        | Table::ComponentKind
+        | Table::Staff
+        | Table::PhoneModel
+        | Table::Component => None,
+    }
+}
+
+async fn fetch<T>(
+    tx: &mut Transaction<'static, Postgres>,
+    filter: &ExportFilter,
+    table: Table,
+) -> Result<Vec<T>>
+where
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    if !filter.includes(table) {
+        return Ok(Vec::new());
+    }
+
+    let rows = match created_column(table) {
+        Some(column) if filter.is_bounded() => {
+            let sql = format!(
+                r#"SELECT * FROM "{table}"
+WHERE ($1::timestamptz IS NULL OR {column} >= $1)
+    AND ($2::timestamptz IS NULL OR {column} < $2);"#
+            );
+            query_as(&sql)
+                .bind(filter.from)
+                .bind(filter.to)
+                .fetch_all(&mut *tx)
+                .await
+        }
+        _ => {
+            let sql = format!(r#"SELECT * FROM "{table}";"#);
+            query_as(&sql).fetch_all(&mut *tx).await
+        }
+    };
+
+    rows.with_context(|| format!("While exporting '{table}' table"))
+}
+
+/// Collects primary keys of the table, fails on duplicates
+fn keys(table: Table, uuids: impl Iterator<Item = Uuid>) -> Result<HashSet<Uuid>> {
+    let mut set = HashSet::new();
+
+    for uuid in uuids {
+        if !set.insert(uuid) {
+            bail!("Duplicate primary key '{uuid}' in '{table}' table");
+        }
+    }
+
+    Ok(set)
+}
+
+/// Fails if `target` is not present in referenced table keys
+fn reference(
+    table: Table,
+    row: impl Display,
+    field: &str,
+    keys: &HashSet<Uuid>,
+    target: Uuid,
+) -> Result<()> {
+    if keys.contains(&target) {
+        Ok(())
+    } else {
+        bail!("Row '{row}' of '{table}' table references missing '{target}' in '{field}' field")
+    }
+}
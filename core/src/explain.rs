@@ -0,0 +1,227 @@
+//! Query plans of the queries the application runs most
+//!
+//! Plans are built with `enable_seqscan` off, so the planner picks an index whenever there
+//! is one even on the small tables. Scans still reading a whole relation to filter it show
+//! a missing [`Index`](crate::indexes::Index)
+
+use core::fmt;
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+use sqlx::{query, query_as, types::Json, PgPool};
+
+use crate::{
+    functions::{
+        CashRegisterClose, RevenueForPeriod, SupplierPerformance, SupplyCostForPeriod,
+        WarrantyForPeriod,
+    },
+    receipt::Receipt,
+    tables::{Order, Phone},
+    traits::Recreatable,
+    views::{PhoneHistory, StockTransferBeautified, SupplyBeautified, WarehouseBeautified},
+};
+
+/// Arguments taken from the existing rows
+const BRANCH: &str = r#"(SELECT uuid FROM "Branch" ORDER BY uuid LIMIT 1)"#;
+const PERSON: &str = r#"(SELECT uuid FROM "Person" ORDER BY uuid LIMIT 1)"#;
+const ORDER: &str = r#"(SELECT uuid FROM "Order" ORDER BY uuid LIMIT 1)"#;
+const IMEI: &str = r#"(SELECT imei FROM "Phone" ORDER BY uuid LIMIT 1)"#;
+const MONTH_AGO: &str = "(now() - interval '1 month')";
+const NOW: &str = "now()";
+const TODAY: &str = "current_date";
+
+#[derive(Clone, Copy, Debug)]
+pub enum Probe {
+    StockByBranch,
+    TransfersByBranch,
+    SuppliesByBranch,
+    OrdersByBranch,
+    OrdersByClient,
+    OrderServices,
+    OrderParts,
+    PhonesByPerson,
+    PhoneHistory,
+    Revenue,
+    CashRegister,
+    Warranty,
+    SupplyCost,
+    SupplierPerformance,
+}
+
+impl Probe {
+    pub const ALL: [Self; 14] = [
+        Self::StockByBranch,
+        Self::TransfersByBranch,
+        Self::SuppliesByBranch,
+        Self::OrdersByBranch,
+        Self::OrdersByClient,
+        Self::OrderServices,
+        Self::OrderParts,
+        Self::PhonesByPerson,
+        Self::PhoneHistory,
+        Self::Revenue,
+        Self::CashRegister,
+        Self::Warranty,
+        Self::SupplyCost,
+        Self::SupplierPerformance,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::StockByBranch => "stock_by_branch",
+            Self::TransfersByBranch => "transfers_by_branch",
+            Self::SuppliesByBranch => "supplies_by_branch",
+            Self::OrdersByBranch => "orders_by_branch",
+            Self::OrdersByClient => "orders_by_client",
+            Self::OrderServices => "order_services",
+            Self::OrderParts => "order_parts",
+            Self::PhonesByPerson => "phones_by_person",
+            Self::PhoneHistory => "phone_history",
+            Self::Revenue => "revenue",
+            Self::CashRegister => "cash_register",
+            Self::Warranty => "warranty",
+            Self::SupplyCost => "supply_cost",
+            Self::SupplierPerformance => "supplier_performance",
+        }
+    }
+
+    /// Query the application runs, with its arguments taken from the existing rows. Report
+    /// functions pin their `search_path`, so they are never inlined and `EXPLAIN` of a call
+    /// hides their plan; their bodies are explained instead, taken from the definitions
+    pub fn sql(&self) -> String {
+        match self {
+            Self::StockByBranch => WarehouseBeautified::BY_BRANCH.replace("$1", BRANCH),
+            Self::TransfersByBranch => StockTransferBeautified::BY_BRANCH.replace("$1", BRANCH),
+            Self::SuppliesByBranch => SupplyBeautified::BY_BRANCH.replace("$1", BRANCH),
+            Self::OrdersByBranch => Order::BY_BRANCH.replace("$1", BRANCH),
+            Self::OrdersByClient => Order::BY_CLIENT.replace("$1", PERSON),
+            Self::OrderServices => Receipt::SERVICES.replace("$1", ORDER),
+            Self::OrderParts => Receipt::PARTS.replace("$1", ORDER),
+            Self::PhonesByPerson => Phone::BY_PERSON.replace("$1", PERSON),
+            Self::PhoneHistory => PhoneHistory::BY_IMEI.replace("$1", IMEI),
+            Self::Revenue => body::<RevenueForPeriod>(&[
+                ("from_date", MONTH_AGO),
+                ("to_date", NOW),
+                ("for_branch", BRANCH),
+            ]),
+            Self::CashRegister => {
+                body::<CashRegisterClose>(&[("for_day", TODAY), ("for_branch", BRANCH)])
+            }
+            Self::Warranty => body::<WarrantyForPeriod>(&[
+                ("from_date", MONTH_AGO),
+                ("to_date", NOW),
+                ("for_branch", BRANCH),
+            ]),
+            Self::SupplyCost => {
+                body::<SupplyCostForPeriod>(&[("from_date", MONTH_AGO), ("to_date", NOW)])
+            }
+            Self::SupplierPerformance => {
+                body::<SupplierPerformance>(&[("from_date", MONTH_AGO), ("to_date", NOW)])
+            }
+        }
+    }
+
+    pub async fn explain(&self, pool: &PgPool) -> Result<Plan> {
+        let mut tx = pool.begin().await?;
+        query("SET LOCAL enable_seqscan = off;")
+            .execute(&mut tx)
+            .await?;
+
+        let (Json(plan),): (Json<JsonValue>,) =
+            query_as(&format!("EXPLAIN (FORMAT JSON) {}", self.sql()))
+                .fetch_one(&mut tx)
+                .await
+                .with_context(|| format!("While explaining '{self}' query"))?;
+        tx.rollback().await?;
+
+        let root = &plan[0]["Plan"];
+        let mut full_scans = Vec::new();
+        FullScan::collect(root, &mut full_scans);
+
+        Ok(Plan {
+            probe: *self,
+            cost: root["Total Cost"].as_f64().unwrap_or_default(),
+            full_scans,
+            json: plan,
+        })
+    }
+}
+
+/// Query of a `LANGUAGE SQL` function, its parameters replaced by the arguments
+fn body<F: Recreatable>(args: &[(&str, &str)]) -> String {
+    let body = F::CREATE.split("$$").nth(1).unwrap_or_default();
+    let mut sql = String::with_capacity(body.len());
+    let mut rest = body.trim().trim_end_matches(';');
+
+    // Whole words only, a parameter name may be a part of a column one
+    while let Some(start) = rest.find(|c: char| c.is_alphanumeric() || c == '_') {
+        sql.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(end);
+        match args.iter().find(|(param, _)| *param == word) {
+            Some((_, arg)) => sql.push_str(arg),
+            None => sql.push_str(word),
+        }
+        rest = tail;
+    }
+    sql.push_str(rest);
+
+    sql
+}
+
+impl fmt::Display for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug)]
+pub struct Plan {
+    pub probe: Probe,
+    /// Planner estimate, inflated by the disabled sequential scans if there are any
+    pub cost: f64,
+    pub full_scans: Vec<FullScan>,
+    /// `EXPLAIN (FORMAT JSON)` output
+    pub json: JsonValue,
+}
+
+/// Scan reading every row of the relation to filter them
+#[derive(Debug)]
+pub struct FullScan {
+    pub relation: String,
+    /// `Seq Scan` or an index scan without index condition
+    pub node: String,
+    pub filter: Option<String>,
+}
+
+impl FullScan {
+    fn collect(node: &JsonValue, scans: &mut Vec<Self>) {
+        let node_type = node["Node Type"].as_str().unwrap_or_default();
+        let filter = node["Filter"].as_str();
+        let full = match node_type {
+            "Seq Scan" => true,
+            "Index Scan" | "Index Only Scan" => node["Index Cond"].is_null() && filter.is_some(),
+            _ => false,
+        };
+
+        if full {
+            scans.push(Self {
+                relation: node["Relation Name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                node: node_type.to_string(),
+                filter: filter.map(str::to_string),
+            });
+        }
+
+        if let Some(children) = node["Plans"].as_array() {
+            for child in children {
+                Self::collect(child, scans);
+            }
+        }
+    }
+}
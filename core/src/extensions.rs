@@ -1,30 +1,35 @@
 use core::fmt;
 
-use anyhow::{Context, Result};
-use sqlx::{postgres::PgDatabaseError, query, query_as, Error, PgPool, Postgres};
+use sqlx::{query_as, Error, Executor, Postgres};
 
+pub use pg_trgm::PgTrgm;
 pub use uuid_ossp::UuidOssp;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Extension {
     UuidOssp,
+    PgTrgm,
 }
 
 impl Extension {
-    pub const ALL: [Self; 1] = [Self::UuidOssp];
+    pub const ALL: [Self; 2] = [Self::UuidOssp, Self::PgTrgm];
 
     pub fn name(&self) -> &str {
         match self {
             Extension::UuidOssp => UuidOssp::NAME,
+            Extension::PgTrgm => PgTrgm::NAME,
         }
     }
 
-    pub async fn exists(&self, pool: &PgPool) -> Result<bool, Error> {
+    pub async fn exists<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
         match query_as::<Postgres, (bool,)>(
             "SELECT true FROM pg_catalog.pg_extension WHERE extname = $1",
         )
         .bind(self.name())
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
         {
             Ok(_) => Ok(true),
@@ -41,59 +46,16 @@ impl Extension {
     pub fn create(&self) -> &str {
         match self {
             Extension::UuidOssp => UuidOssp::CREATE,
+            Extension::PgTrgm => PgTrgm::CREATE,
         }
     }
 
     pub fn drop(&self) -> &str {
         match self {
             Extension::UuidOssp => UuidOssp::DROP,
+            Extension::PgTrgm => PgTrgm::DROP,
         }
     }
-
-    /// Loads all postgresql extensions necessary for application
-    pub async fn create_all(pool: &PgPool, printer: impl Fn((Extension, bool))) -> Result<()> {
-        for extension in Self::ALL {
-            match query(extension.create()).execute(pool).await {
-                Ok(_) => printer((extension, true)),
-                Err(err) => {
-                    if let Error::Database(err) = &err {
-                        if err.downcast_ref::<PgDatabaseError>().code() == "42710" {
-                            printer((extension, true)); // TODO: Explicit alert
-                            continue;
-                        }
-                    }
-                    printer((extension, false));
-                    return Err(err)
-                        .with_context(|| format!("While loading '{extension}' extension"));
-                }
-            }
-        }
-
-        Ok(())
-    }
-
-    /// Unloads all postgresql extensions
-    pub async fn drop_all(pool: &PgPool, printer: impl Fn((Extension, bool))) -> Result<()> {
-        for extension in Self::ALL {
-            match query(extension.drop()).execute(pool).await {
-                Ok(_) => printer((extension, true)),
-                Err(err) => {
-                    if let Error::Database(err) = &err {
-                        if err.downcast_ref::<PgDatabaseError>().code() == "42501" {
-                            printer((extension, true)); // TODO: Explicit alert
-                            continue;
-                        }
-                    }
-
-                    printer((extension, false));
-                    return Err(err)
-                        .with_context(|| format!("While unloading '{extension}' extension"));
-                }
-            }
-        }
-
-        Ok(())
-    }
 }
 
 impl fmt::Display for Extension {
@@ -110,7 +72,20 @@ mod uuid_ossp {
 
     impl UuidOssp {
         pub const NAME: &'static str = "uuid-ossp";
-        pub const CREATE: &'static str = r#"CREATE EXTENSION "uuid-ossp";"#;
+        pub const CREATE: &'static str = r#"CREATE EXTENSION "uuid-ossp" WITH SCHEMA public;"#;
         pub const DROP: &'static str = r#"DROP EXTENSION "uuid-ossp";"#;
     }
 }
+
+// -------------------------------------------------------------------------------------------------
+
+mod pg_trgm {
+
+    pub struct PgTrgm;
+
+    impl PgTrgm {
+        pub const NAME: &'static str = "pg_trgm";
+        pub const CREATE: &'static str = r#"CREATE EXTENSION pg_trgm WITH SCHEMA public;"#;
+        pub const DROP: &'static str = r#"DROP EXTENSION pg_trgm;"#;
+    }
+}
@@ -0,0 +1,14 @@
+use crate::traits::Recreatable;
+
+/// Currency code of the shop, same as [`Currency::BASE`](crate::types::Currency::BASE)
+pub struct BaseCurrency;
+
+impl Recreatable for BaseCurrency {
+    const NAME: &'static str = "base_currency";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "base_currency" () RETURNS text AS $$
+    SELECT 'RUB';
+$$ LANGUAGE SQL IMMUTABLE;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "base_currency";"#;
+}
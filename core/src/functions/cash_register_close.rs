@@ -0,0 +1,52 @@
+use chrono::NaiveDate;
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
+
+use crate::{
+    traits::Recreatable,
+    types::{Money, PaymentMethod},
+    PgQueryAs,
+};
+
+/// Daily close report of the cash register, one row per payment method
+pub struct CashRegisterClose;
+
+impl Recreatable for CashRegisterClose {
+    const NAME: &'static str = "cash_register_close";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "cash_register_close" (for_day date, for_branch uuid DEFAULT NULL) RETURNS TABLE(method "PaymentMethod", payments bigint, received monetary, refunded monetary, collected monetary) AS $$
+    SELECT p.method,
+        count(*) FILTER (WHERE p.kind = 'Payment') as payments,
+        to_monetary(coalesce(sum((p.amount).amount) FILTER (WHERE p.kind = 'Payment'), 0)) as received,
+        to_monetary(coalesce(sum((p.amount).amount) FILTER (WHERE p.kind = 'Refund'), 0)) as refunded,
+        to_monetary(coalesce(sum(CASE WHEN p.kind = 'Refund' THEN -(p.amount).amount ELSE (p.amount).amount END), 0)) as collected
+    FROM "Payment" p
+        JOIN "Invoice" i ON p.invoice = i.uuid
+        JOIN "Order" o ON i."order" = o.uuid
+    WHERE (p.meta).created >= for_day AND (p.meta).created < for_day + 1
+        AND (for_branch IS NULL OR o.branch = for_branch)
+    GROUP BY p.method
+    ORDER BY p.method;
+$$ LANGUAGE SQL;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "cash_register_close";"#;
+}
+
+/// Single row of the [`CashRegisterClose`] report
+#[derive(FromRow, Clone, Debug)]
+pub struct CloseLine {
+    pub method: PaymentMethod,
+    pub payments: i64,
+    pub received: Money,
+    pub refunded: Money,
+    pub collected: Money,
+}
+
+impl CashRegisterClose {
+    /// Report for the day, all branches are included if `branch` is not set
+    pub fn report(day: NaiveDate, branch: Option<Uuid>) -> PgQueryAs<CloseLine> {
+        query_as(r#"SELECT * FROM "cash_register_close"($1, $2)"#)
+            .bind(day)
+            .bind(branch)
+    }
+}
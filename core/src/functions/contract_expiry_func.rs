@@ -0,0 +1,21 @@
+use crate::traits::Recreatable;
+
+/// Turns active contracts past their validity into expired ones
+pub struct ContractExpiryFunc;
+
+impl Recreatable for ContractExpiryFunc {
+    const NAME: &'static str = "contract_expiry_func";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION contract_expiry_func() RETURNS trigger AS
+$$
+BEGIN
+    IF NEW.status = 'Active' AND NEW.valid_to < current_date THEN
+        NEW.status := 'Expired';
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE PLPGSQL;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "contract_expiry_func";"#;
+}
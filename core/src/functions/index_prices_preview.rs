@@ -0,0 +1,66 @@
+use sqlx::{query_as, types::BigDecimal, FromRow};
+use uuid::Uuid;
+
+use crate::{
+    traits::Recreatable,
+    types::{Money, PriceScope},
+    PgQueryAs,
+};
+
+/// Prices affected by [`IndexPrices`](crate::procedures::IndexPrices) with the same arguments,
+/// nothing is changed
+pub struct IndexPricesPreview;
+
+impl Recreatable for IndexPricesPreview {
+    const NAME: &'static str = "index_prices_preview";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "index_prices_preview" (coef numeric, price_scope "PriceScope" DEFAULT 'All', scope_target uuid DEFAULT NULL) RETURNS TABLE(item uuid, service uuid, phone_model uuid, name text, old_price monetary, new_price monetary) AS $$
+    SELECT w.uuid, NULL::uuid, c.phone_model, c.name, w.price,
+        (round((w.price).amount * coef, 2), (w.price).currency)::monetary
+    FROM "Warehouse" w
+        JOIN "Component" c ON w.component = c.uuid
+    WHERE price_scope = 'All'
+        OR (price_scope = 'Manufacturer' AND c.manufacturer = scope_target)
+        OR (price_scope = 'ComponentKind' AND c.kind = scope_target)
+        OR (price_scope = 'PhoneModel' AND c.phone_model = scope_target)
+    UNION ALL
+    SELECT NULL::uuid, spm.service, spm.phone_model, s.name || ' (' || pm.name || ')', spm.price,
+        (round((spm.price).amount * coef, 2), (spm.price).currency)::monetary
+    FROM "ServicePhoneModel" spm
+        JOIN "Service" s ON spm.service = s.uuid
+        JOIN "PhoneModel" pm ON spm.phone_model = pm.uuid
+    WHERE spm.price IS NOT NULL
+        AND (price_scope = 'All'
+            OR (price_scope = 'Manufacturer' AND pm.manufacturer = scope_target)
+            OR (price_scope = 'Service' AND spm.service = scope_target)
+            OR (price_scope = 'PhoneModel' AND spm.phone_model = scope_target))
+    ORDER BY 4;
+$$ LANGUAGE SQL STABLE;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "index_prices_preview";"#;
+}
+
+/// Single price of the [`IndexPricesPreview`], either `item` or `service` is set
+#[derive(FromRow, Clone, Debug)]
+pub struct PreviewLine {
+    /// Warehouse item uuid
+    pub item: Option<Uuid>,
+    pub service: Option<Uuid>,
+    pub phone_model: Uuid,
+    pub name: String,
+    pub old_price: Money,
+    pub new_price: Money,
+}
+
+impl IndexPricesPreview {
+    pub fn preview(
+        coef: BigDecimal,
+        scope: PriceScope,
+        target: Option<Uuid>,
+    ) -> PgQueryAs<PreviewLine> {
+        query_as(r#"SELECT * FROM "index_prices_preview"($1, $2, $3)"#)
+            .bind(coef)
+            .bind(scope)
+            .bind(target)
+    }
+}
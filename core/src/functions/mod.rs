@@ -1,66 +1,199 @@
 use core::fmt;
 
-use anyhow::{Context, Result};
-use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool, Postgres};
+use sqlx::{query_as, Error, Executor, Postgres};
 
-use crate::traits::Recreatable;
+use crate::{schema::Schema, traits::Recreatable};
 
 pub mod audit_log_func;
+pub mod base_currency;
+pub mod cash_register_close;
+pub mod contract_expiry_func;
+pub mod index_prices_preview;
+pub mod notify_change_func;
+pub mod person_duplicates;
+pub mod phone_ownership_func;
 pub mod revenue_for_period;
+pub mod service_price_at;
+pub mod settle_order_func;
+pub mod supplier_performance;
+pub mod supply_cost_for_period;
+pub mod supply_delivery_func;
+pub mod to_base_currency;
+pub mod to_monetary;
 pub mod update_time_func;
+pub mod warehouse_price_at;
+pub mod warranty_for_period;
+pub mod warranty_price_func;
 
 pub use audit_log_func::AuditLogFunc;
+pub use base_currency::BaseCurrency;
+pub use cash_register_close::CashRegisterClose;
+pub use contract_expiry_func::ContractExpiryFunc;
+pub use index_prices_preview::IndexPricesPreview;
+pub use notify_change_func::NotifyChangeFunc;
+pub use person_duplicates::{DuplicatePair, PersonDuplicates};
+pub use phone_ownership_func::PhoneOwnershipFunc;
 pub use revenue_for_period::RevenueForPeriod;
+pub use service_price_at::ServicePriceAt;
+pub use settle_order_func::SettleOrderFunc;
+pub use supplier_performance::SupplierPerformance;
+pub use supply_cost_for_period::SupplyCostForPeriod;
+pub use supply_delivery_func::SupplyDeliveryFunc;
+pub use to_base_currency::ToBaseCurrency;
+pub use to_monetary::ToMonetary;
 pub use update_time_func::UpdateTimeFunc;
+pub use warehouse_price_at::WarehousePriceAt;
+pub use warranty_for_period::WarrantyForPeriod;
+pub use warranty_price_func::WarrantyPriceFunc;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Function {
     UpdateTimeFunc,
     AuditLogFunc,
+    BaseCurrency,
+    ToMonetary,
     RevenueForPeriod,
+    SettleOrderFunc,
+    CashRegisterClose,
+    WarrantyPriceFunc,
+    IndexPricesPreview,
+    WarehousePriceAt,
+    ServicePriceAt,
+    WarrantyForPeriod,
+    ToBaseCurrency,
+    SupplyCostForPeriod,
+    ContractExpiryFunc,
+    SupplyDeliveryFunc,
+    SupplierPerformance,
+    PhoneOwnershipFunc,
+    PersonDuplicates,
+    NotifyChangeFunc,
 }
 
 impl Function {
-    pub const ALL: [Self; 3] = [
+    pub const ALL: [Self; 20] = [
         Self::UpdateTimeFunc,
         Self::AuditLogFunc,
+        Self::BaseCurrency,
+        Self::ToMonetary,
         Self::RevenueForPeriod,
+        Self::SettleOrderFunc,
+        Self::CashRegisterClose,
+        Self::WarrantyPriceFunc,
+        Self::IndexPricesPreview,
+        Self::WarehousePriceAt,
+        Self::ServicePriceAt,
+        Self::ToBaseCurrency,
+        Self::WarrantyForPeriod,
+        Self::SupplyCostForPeriod,
+        Self::ContractExpiryFunc,
+        Self::SupplyDeliveryFunc,
+        Self::SupplierPerformance,
+        Self::PhoneOwnershipFunc,
+        Self::PersonDuplicates,
+        Self::NotifyChangeFunc,
     ];
 
+    /// Function referenced by table defaults or checks, so it is created before the tables
+    /// and replaced instead of being dropped while migrating
+    pub fn is_table_dependency(&self) -> bool {
+        matches!(self, Self::BaseCurrency)
+    }
+
     pub fn name(&self) -> &str {
         match self {
             Self::UpdateTimeFunc => UpdateTimeFunc::NAME,
             Self::AuditLogFunc => AuditLogFunc::NAME,
+            Self::BaseCurrency => BaseCurrency::NAME,
+            Self::ToMonetary => ToMonetary::NAME,
             Self::RevenueForPeriod => RevenueForPeriod::NAME,
+            Self::SettleOrderFunc => SettleOrderFunc::NAME,
+            Self::CashRegisterClose => CashRegisterClose::NAME,
+            Self::WarrantyPriceFunc => WarrantyPriceFunc::NAME,
+            Self::IndexPricesPreview => IndexPricesPreview::NAME,
+            Self::WarehousePriceAt => WarehousePriceAt::NAME,
+            Self::ServicePriceAt => ServicePriceAt::NAME,
+            Self::WarrantyForPeriod => WarrantyForPeriod::NAME,
+            Self::ToBaseCurrency => ToBaseCurrency::NAME,
+            Self::SupplyCostForPeriod => SupplyCostForPeriod::NAME,
+            Self::ContractExpiryFunc => ContractExpiryFunc::NAME,
+            Self::SupplyDeliveryFunc => SupplyDeliveryFunc::NAME,
+            Self::SupplierPerformance => SupplierPerformance::NAME,
+            Self::PhoneOwnershipFunc => PhoneOwnershipFunc::NAME,
+            Self::PersonDuplicates => PersonDuplicates::NAME,
+            Self::NotifyChangeFunc => NotifyChangeFunc::NAME,
         }
     }
 
-    pub fn create(&self) -> &str {
-        match self {
+    pub fn create(&self) -> String {
+        pin_search_path(match self {
             Self::UpdateTimeFunc => UpdateTimeFunc::CREATE,
             Self::AuditLogFunc => AuditLogFunc::CREATE,
+            Self::BaseCurrency => BaseCurrency::CREATE,
+            Self::ToMonetary => ToMonetary::CREATE,
             Self::RevenueForPeriod => RevenueForPeriod::CREATE,
-        }
+            Self::SettleOrderFunc => SettleOrderFunc::CREATE,
+            Self::CashRegisterClose => CashRegisterClose::CREATE,
+            Self::WarrantyPriceFunc => WarrantyPriceFunc::CREATE,
+            Self::IndexPricesPreview => IndexPricesPreview::CREATE,
+            Self::WarehousePriceAt => WarehousePriceAt::CREATE,
+            Self::ServicePriceAt => ServicePriceAt::CREATE,
+            Self::WarrantyForPeriod => WarrantyForPeriod::CREATE,
+            Self::ToBaseCurrency => ToBaseCurrency::CREATE,
+            Self::SupplyCostForPeriod => SupplyCostForPeriod::CREATE,
+            Self::ContractExpiryFunc => ContractExpiryFunc::CREATE,
+            Self::SupplyDeliveryFunc => SupplyDeliveryFunc::CREATE,
+            Self::SupplierPerformance => SupplierPerformance::CREATE,
+            Self::PhoneOwnershipFunc => PhoneOwnershipFunc::CREATE,
+            Self::PersonDuplicates => PersonDuplicates::CREATE,
+            Self::NotifyChangeFunc => NotifyChangeFunc::CREATE,
+        })
     }
 
     pub fn drop(&self) -> &str {
         match self {
             Self::UpdateTimeFunc => UpdateTimeFunc::DROP,
             Self::AuditLogFunc => AuditLogFunc::DROP,
+            Self::BaseCurrency => BaseCurrency::DROP,
+            Self::ToMonetary => ToMonetary::DROP,
             Self::RevenueForPeriod => RevenueForPeriod::DROP,
+            Self::SettleOrderFunc => SettleOrderFunc::DROP,
+            Self::CashRegisterClose => CashRegisterClose::DROP,
+            Self::WarrantyPriceFunc => WarrantyPriceFunc::DROP,
+            Self::IndexPricesPreview => IndexPricesPreview::DROP,
+            Self::WarehousePriceAt => WarehousePriceAt::DROP,
+            Self::ServicePriceAt => ServicePriceAt::DROP,
+            Self::WarrantyForPeriod => WarrantyForPeriod::DROP,
+            Self::ToBaseCurrency => ToBaseCurrency::DROP,
+            Self::SupplyCostForPeriod => SupplyCostForPeriod::DROP,
+            Self::ContractExpiryFunc => ContractExpiryFunc::DROP,
+            Self::SupplyDeliveryFunc => SupplyDeliveryFunc::DROP,
+            Self::SupplierPerformance => SupplierPerformance::DROP,
+            Self::PhoneOwnershipFunc => PhoneOwnershipFunc::DROP,
+            Self::PersonDuplicates => PersonDuplicates::DROP,
+            Self::NotifyChangeFunc => NotifyChangeFunc::DROP,
         }
     }
 
-    pub async fn exists(&self, pool: &PgPool) -> Result<bool, Error> {
+    /// Drop statement qualified with the schema, as the unqualified one is ambiguous while
+    /// another schema in the path has the same routine over its own types
+    pub fn drop_from(&self, schema: &Schema) -> String {
+        format!(r#"DROP FUNCTION "{schema}"."{self}";"#)
+    }
+
+    pub async fn exists<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
         match query_as::<Postgres, (bool,)>(
             r#"SELECT true
 FROM pg_catalog.pg_proc
     JOIN pg_namespace ON pg_catalog.pg_proc.pronamespace = pg_namespace.oid
 WHERE proname = $1
-    AND nspname = 'public';"#,
+    AND nspname = current_schema();"#,
         )
         .bind(self.name())
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
         {
             Ok(_) => Ok(true),
@@ -73,32 +206,15 @@ WHERE proname = $1
             }
         }
     }
+}
 
-    /// Create all procedures necessary for application
-    pub async fn create_all(
-        pool: &PgPool,
-        handler: impl Fn((Function, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for function in Self::ALL {
-            handler((function, query(function.create()).execute(pool).await))
-                .with_context(|| format!("While creating '{function}' function"))?;
-        }
-
-        Ok(())
-    }
-
-    /// Drop all application procedures
-    pub async fn drop_all(
-        pool: &PgPool,
-        handler: impl Fn((Function, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for function in Self::ALL {
-            handler((function, query(function.drop()).execute(pool).await))
-                .with_context(|| format!("While dropping '{function}' function"))?;
-        }
-
-        Ok(())
-    }
+/// Pins `search_path` of the routine to the one it is created with, so the objects referenced
+/// in its body are resolved within the same schema whatever path the caller has
+pub(crate) fn pin_search_path(create: &str) -> String {
+    format!(
+        "{} SET search_path FROM CURRENT;",
+        create.trim_end().trim_end_matches(';')
+    )
 }
 
 impl fmt::Display for Function {
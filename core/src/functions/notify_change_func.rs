@@ -0,0 +1,20 @@
+use crate::traits::Recreatable;
+
+/// Notifies listeners of [`Changes::CHANNEL`](crate::changes::Changes::CHANNEL) about
+/// the changed table as `schema.table`, notifications of one transaction are folded by Postgres
+pub struct NotifyChangeFunc;
+
+impl Recreatable for NotifyChangeFunc {
+    const NAME: &'static str = "notify_change_func";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION notify_change_func() RETURNS trigger AS
+$$
+BEGIN
+    PERFORM pg_notify('table_changes', TG_TABLE_SCHEMA || '.' || TG_TABLE_NAME);
+
+    RETURN NULL;
+END;
+$$ LANGUAGE PLPGSQL;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "notify_change_func";"#;
+}
@@ -0,0 +1,64 @@
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
+
+use crate::{traits::Recreatable, PgQueryAs};
+
+/// Pairs of people looking like the same customer entered twice: with the same phone number
+/// (digits only, without country code), the same email ignoring case or similar names,
+/// the one registered earlier comes first, anonymized and deleted people are left out
+pub struct PersonDuplicates;
+
+impl Recreatable for PersonDuplicates {
+    const NAME: &'static str = "person_duplicates";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "person_duplicates" (threshold real DEFAULT 0.6) RETURNS TABLE(person uuid, person_name text, person_email text, person_phone text, duplicate uuid, duplicate_name text, duplicate_email text, duplicate_phone text, name_similarity real, same_phone boolean, same_email boolean) AS $$
+    WITH p AS (
+        SELECT uuid, email, phone, (meta).created,
+            CONCAT_WS(' ', first_name, middle_name, last_name) as name,
+            right(regexp_replace(phone, '\D', '', 'g'), 10) as digits
+        FROM "Person"
+        WHERE anonymized IS NULL AND deleted_at IS NULL
+    )
+    SELECT a.uuid, a.name, a.email, a.phone,
+        b.uuid, b.name, b.email, b.phone,
+        similarity(a.name, b.name),
+        a.digits <> '' AND a.digits = b.digits,
+        lower(a.email) = lower(b.email)
+    FROM p a
+        JOIN p b ON a.created < b.created OR (a.created = b.created AND a.uuid < b.uuid)
+    WHERE (a.digits <> '' AND a.digits = b.digits)
+        OR lower(a.email) = lower(b.email)
+        OR similarity(a.name, b.name) >= threshold
+    ORDER BY 10 DESC, 11 DESC, 9 DESC, a.name;
+$$ LANGUAGE SQL STABLE;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "person_duplicates";"#;
+}
+
+/// Single pair of the [`PersonDuplicates`] report
+#[derive(FromRow, Clone, Debug)]
+pub struct DuplicatePair {
+    /// Registered earlier, proposed to survive the merge
+    pub person: Uuid,
+    pub person_name: String,
+    pub person_email: String,
+    pub person_phone: String,
+    pub duplicate: Uuid,
+    pub duplicate_name: String,
+    pub duplicate_email: String,
+    pub duplicate_phone: String,
+    /// Trigram similarity of full names from 0 to 1
+    pub name_similarity: f32,
+    pub same_phone: bool,
+    pub same_email: bool,
+}
+
+impl PersonDuplicates {
+    /// Default of the SQL function
+    pub const THRESHOLD: f32 = 0.6;
+
+    /// Names are similar enough starting from `threshold` (0 to 1)
+    pub fn report(threshold: f32) -> PgQueryAs<DuplicatePair> {
+        query_as(r#"SELECT * FROM "person_duplicates"($1)"#).bind(threshold)
+    }
+}
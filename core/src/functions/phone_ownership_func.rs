@@ -0,0 +1,31 @@
+use crate::traits::Recreatable;
+
+/// Keeps the previous owner of the phone in `PhoneOwnership`, the ownership started
+/// when the one before ended or when the phone was registered
+pub struct PhoneOwnershipFunc;
+
+impl Recreatable for PhoneOwnershipFunc {
+    const NAME: &'static str = "phone_ownership_func";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION phone_ownership_func() RETURNS trigger AS
+$$
+BEGIN
+    INSERT INTO "PhoneOwnership" (phone, person, since, until)
+    VALUES (
+        OLD.uuid,
+        OLD.person,
+        coalesce(
+            (SELECT max(until) FROM "PhoneOwnership" WHERE phone = OLD.uuid),
+            (OLD.meta).created
+        ),
+        now()
+    )
+    -- Owners changed again within the same transaction are skipped
+    ON CONFLICT DO NOTHING;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE PLPGSQL;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "phone_ownership_func";"#;
+}
@@ -1,31 +1,21 @@
 use crate::traits::Recreatable;
 
+/// Revenue actually collected through payments, refunds are subtracted
 pub struct RevenueForPeriod;
 
 impl Recreatable for RevenueForPeriod {
     const NAME: &'static str = "revenue_for_period";
 
-    const CREATE: &'static str = r#"CREATE FUNCTION "revenue_for_period" (from_date timestamptz, to_date timestamptz) RETURNS TABLE(services money, components money, summary money) AS $$
-DECLARE 
-    from_services money;
-    from_components money;
-BEGIN
-    SELECT sum(price) INTO from_services
-    FROM "OrderService" os
-        join "Order" o ON os."order" = o.uuid
-    WHERE o.status IN ('Active', 'Complete')
-        AND (o.meta).created BETWEEN from_date and to_date;
-
-    SELECT sum(price) INTO from_components
-    FROM "OrderWarehouse" ow
-        join "Order" o ON ow."order" = o.uuid
-    WHERE o.status IN ('Active', 'Complete')
-        AND (o.meta).created BETWEEN from_date and to_date;
-
-    RETURN QUERY
-        SELECT from_services, from_components, (from_services + from_components) as summary;
-END;
-$$ LANGUAGE 'plpgsql';"#;
+    const CREATE: &'static str = r#"CREATE FUNCTION "revenue_for_period" (from_date timestamptz, to_date timestamptz, for_branch uuid DEFAULT NULL) RETURNS TABLE(received monetary, refunded monetary, summary monetary) AS $$
+    SELECT to_monetary(coalesce(sum((p.amount).amount) FILTER (WHERE p.kind = 'Payment'), 0)) as received,
+        to_monetary(coalesce(sum((p.amount).amount) FILTER (WHERE p.kind = 'Refund'), 0)) as refunded,
+        to_monetary(coalesce(sum(CASE WHEN p.kind = 'Refund' THEN -(p.amount).amount ELSE (p.amount).amount END), 0)) as summary
+    FROM "Payment" p
+        JOIN "Invoice" i ON p.invoice = i.uuid
+        JOIN "Order" o ON i."order" = o.uuid
+    WHERE (p.meta).created BETWEEN from_date and to_date
+        AND (for_branch IS NULL OR o.branch = for_branch);
+$$ LANGUAGE SQL;"#;
 
     const DROP: &'static str = r#"DROP FUNCTION "revenue_for_period";"#;
 }
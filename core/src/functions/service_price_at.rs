@@ -0,0 +1,24 @@
+use crate::traits::Recreatable;
+
+/// Price of the service for the phone model at the given moment according to
+/// [`ServicePrice`](crate::tables::ServicePrice) history,
+/// the current price if it was never indexed
+pub struct ServicePriceAt;
+
+impl Recreatable for ServicePriceAt {
+    const NAME: &'static str = "service_price_at";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "service_price_at" (for_service uuid, for_phone_model uuid, at timestamptz) RETURNS monetary AS $$
+    SELECT coalesce(
+        (SELECT price FROM "ServicePrice"
+            WHERE service = for_service AND phone_model = for_phone_model AND effective <= at
+            ORDER BY effective DESC LIMIT 1),
+        (SELECT price FROM "ServicePrice"
+            WHERE service = for_service AND phone_model = for_phone_model
+            ORDER BY effective LIMIT 1),
+        (SELECT price FROM "ServicePhoneModel" WHERE service = for_service AND phone_model = for_phone_model)
+    );
+$$ LANGUAGE SQL STABLE;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "service_price_at";"#;
+}
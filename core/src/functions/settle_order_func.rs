@@ -0,0 +1,38 @@
+use crate::traits::Recreatable;
+
+/// Activates order pending payment once the paid amount covers all its invoices
+pub struct SettleOrderFunc;
+
+impl Recreatable for SettleOrderFunc {
+    const NAME: &'static str = "settle_order_func";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION settle_order_func() RETURNS trigger AS
+$$
+DECLARE
+    target uuid;
+    billed numeric;
+    paid numeric;
+BEGIN
+    SELECT "order" INTO target FROM "Invoice" WHERE uuid = NEW.invoice;
+
+    SELECT coalesce(sum((amount).amount), 0) INTO billed
+    FROM "Invoice"
+    WHERE "order" = target AND deleted_at IS NULL;
+
+    SELECT coalesce(sum(CASE WHEN p.kind = 'Refund' THEN -(p.amount).amount ELSE (p.amount).amount END), 0) INTO paid
+    FROM "Payment" p
+        JOIN "Invoice" i ON p.invoice = i.uuid
+    WHERE i."order" = target AND i.deleted_at IS NULL;
+
+    IF paid >= billed THEN
+        UPDATE "Order"
+        SET status = 'Active'
+        WHERE uuid = target AND status = 'PendingPayment';
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE PLPGSQL;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "settle_order_func";"#;
+}
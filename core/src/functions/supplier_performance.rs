@@ -0,0 +1,68 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
+
+use crate::{traits::Recreatable, PgQueryAs};
+
+/// Delivery statistics of the suppliers over the supplies created within the period,
+/// only delivered supplies with known due time count towards on-time rate
+pub struct SupplierPerformance;
+
+impl Recreatable for SupplierPerformance {
+    const NAME: &'static str = "supplier_performance";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "supplier_performance" (from_date timestamptz DEFAULT NULL, to_date timestamptz DEFAULT NULL) RETURNS TABLE(supplier uuid, name text, supplies bigint, delivered bigint, on_time bigint, failed bigint, on_time_rate numeric, failure_rate numeric) AS $$
+    SELECT s.uuid, s.name,
+        count(*) as supplies,
+        count(*) FILTER (WHERE sp.status = 'Delivered') as delivered,
+        count(*) FILTER (WHERE sp.status = 'Delivered' AND sp.delivered <= sp.due) as on_time,
+        count(*) FILTER (WHERE sp.status = 'Failed') as failed,
+        round(
+            count(*) FILTER (WHERE sp.status = 'Delivered' AND sp.delivered <= sp.due)::numeric
+                / nullif(count(*) FILTER (WHERE sp.status = 'Delivered' AND sp.due IS NOT NULL), 0),
+            4
+        ) as on_time_rate,
+        round(
+            count(*) FILTER (WHERE sp.status = 'Failed')::numeric
+                / nullif(count(*) FILTER (WHERE sp.status IN ('Delivered', 'Failed')), 0),
+            4
+        ) as failure_rate
+    FROM "Supplier" s
+        JOIN "SupplyContract" c ON c.supplier = s.uuid
+        JOIN "Supply" sp ON sp.contract = c.uuid
+    WHERE (from_date IS NULL OR (sp.meta).created >= from_date)
+        AND (to_date IS NULL OR (sp.meta).created < to_date)
+    GROUP BY s.uuid, s.name
+    ORDER BY on_time_rate DESC NULLS LAST, s.name;
+$$ LANGUAGE SQL STABLE;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "supplier_performance";"#;
+}
+
+/// Single row of the [`SupplierPerformance`] report
+#[derive(FromRow, Clone, Debug)]
+pub struct PerformanceLine {
+    pub supplier: Uuid,
+    pub name: String,
+    pub supplies: i64,
+    pub delivered: i64,
+    pub on_time: i64,
+    pub failed: i64,
+    /// Share of delivered supplies (with due time) delivered on time
+    pub on_time_rate: Option<BigDecimal>,
+    /// Share of failed supplies among finished ones
+    pub failure_rate: Option<BigDecimal>,
+}
+
+impl SupplierPerformance {
+    /// Report over all supplies if the period is not set
+    pub fn report(
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> PgQueryAs<PerformanceLine> {
+        query_as(r#"SELECT * FROM "supplier_performance"($1, $2)"#)
+            .bind(from)
+            .bind(to)
+    }
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
+
+use crate::{
+    traits::Recreatable,
+    types::{Currency, Money},
+    PgQueryAs,
+};
+
+/// Cost of the supplied items per supplier and purchase price currency, also converted
+/// to the base currency at the rate of the supply day
+pub struct SupplyCostForPeriod;
+
+impl Recreatable for SupplyCostForPeriod {
+    const NAME: &'static str = "supply_cost_for_period";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "supply_cost_for_period" (from_date timestamptz, to_date timestamptz) RETURNS TABLE(supplier uuid, name text, currency text, supplies bigint, cost monetary, base_cost monetary) AS $$
+    SELECT s.uuid, s.name, (ws.price).currency,
+        count(DISTINCT ws.supply) as supplies,
+        (round(sum((ws.price).amount * ws.amount), 2), (ws.price).currency)::monetary as cost,
+        to_monetary(sum((to_base_currency(ws.price, ws.created::date)).amount * ws.amount)) as base_cost
+    FROM "WarehouseSupply" ws
+        JOIN "Supply" sp ON ws.supply = sp.uuid
+        JOIN "SupplyContract" c ON sp.contract = c.uuid
+        JOIN "Supplier" s ON c.supplier = s.uuid
+    WHERE ws.created >= from_date AND ws.created < to_date
+    GROUP BY s.uuid, s.name, (ws.price).currency
+    ORDER BY base_cost DESC;
+$$ LANGUAGE SQL STABLE;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "supply_cost_for_period";"#;
+}
+
+/// Single row of the [`SupplyCostForPeriod`] report
+#[derive(FromRow, Clone, Debug)]
+pub struct SupplyCostLine {
+    pub supplier: Uuid,
+    pub name: String,
+    pub currency: Currency,
+    pub supplies: i64,
+    /// Cost in the currency of the purchase prices
+    pub cost: Money,
+    pub base_cost: Money,
+}
+
+impl SupplyCostForPeriod {
+    /// Report for the supplies received within `[from, to)`
+    pub fn report(from: DateTime<Utc>, to: DateTime<Utc>) -> PgQueryAs<SupplyCostLine> {
+        query_as(r#"SELECT * FROM "supply_cost_for_period"($1, $2)"#)
+            .bind(from)
+            .bind(to)
+    }
+}
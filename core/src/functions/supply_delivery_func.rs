@@ -0,0 +1,28 @@
+use crate::traits::Recreatable;
+
+/// Sets due time of the signed supply from the longest lead time of the contract items
+/// and the delivery time once the supply is delivered
+pub struct SupplyDeliveryFunc;
+
+impl Recreatable for SupplyDeliveryFunc {
+    const NAME: &'static str = "supply_delivery_func";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION supply_delivery_func() RETURNS trigger AS
+$$
+BEGIN
+    IF NEW.signed IS NOT NULL AND NEW.due IS NULL THEN
+        NEW.due := NEW.signed + make_interval(days => (
+            SELECT max(lead_days) FROM "SupplyContractItem" WHERE contract = NEW.contract
+        ));
+    END IF;
+
+    IF NEW.status = 'Delivered' AND NEW.delivered IS NULL THEN
+        NEW.delivered := now();
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE PLPGSQL;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "supply_delivery_func";"#;
+}
@@ -0,0 +1,32 @@
+use crate::traits::Recreatable;
+
+/// Converts the amount to the base currency by the
+/// [`ExchangeRate`](crate::tables::ExchangeRate) effective on the given day,
+/// fails if there is no rate set on or before the day
+pub struct ToBaseCurrency;
+
+impl Recreatable for ToBaseCurrency {
+    const NAME: &'static str = "to_base_currency";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "to_base_currency" (value monetary, at date) RETURNS monetary AS $$
+DECLARE
+    effective numeric;
+BEGIN
+    IF value IS NULL OR (value).currency = base_currency() THEN
+        RETURN value;
+    END IF;
+
+    SELECT r.rate INTO effective FROM "ExchangeRate" r
+    WHERE r.currency = (value).currency AND r.day <= at
+    ORDER BY r.day DESC LIMIT 1;
+
+    IF effective IS NULL THEN
+        RAISE EXCEPTION 'No exchange rate for % on %', (value).currency, at;
+    END IF;
+
+    RETURN to_monetary((value).amount * effective);
+END;
+$$ LANGUAGE PLPGSQL STABLE;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "to_base_currency";"#;
+}
@@ -0,0 +1,14 @@
+use crate::traits::Recreatable;
+
+/// Amount in the base currency rounded to cents the same way as [`Money`](crate::types::Money)
+pub struct ToMonetary;
+
+impl Recreatable for ToMonetary {
+    const NAME: &'static str = "to_monetary";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "to_monetary" (amount numeric) RETURNS monetary AS $$
+    SELECT (round(amount, 2), base_currency())::monetary;
+$$ LANGUAGE SQL IMMUTABLE;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "to_monetary";"#;
+}
@@ -1,5 +1,10 @@
 use crate::traits::Recreatable;
 
+/// Bumps `updated` of the row, so it serves as the row version for optimistic locking
+///
+/// Versioned updates set `updated` to the version they are based on, the update fails with
+/// [`Conflict::CODE`](crate::types::Conflict::CODE) if the row changed since then.
+/// The version always grows, even for several updates within the same transaction
 pub struct UpdateTimeFunc;
 
 impl Recreatable for UpdateTimeFunc {
@@ -7,12 +12,18 @@ impl Recreatable for UpdateTimeFunc {
 
     const CREATE: &'static str = r#"CREATE FUNCTION update_time_func() RETURNS trigger AS
 $$
-DECLARE
-    tbl text := quote_ident(TG_TABLE_NAME);
 BEGIN
-    EXECUTE 'UPDATE ' || tbl || '
-    SET meta.updated = now()
-    WHERE uuid = ''' || OLD.uuid || ''';';
+    IF (NEW.meta).updated IS DISTINCT FROM (OLD.meta).updated THEN
+        RAISE EXCEPTION 'Row of "%" was changed at %', TG_TABLE_NAME, (OLD.meta).updated
+            USING ERRCODE = 'CW409',
+                TABLE = TG_TABLE_NAME,
+                DETAIL = (extract(epoch FROM (OLD.meta).updated) * 1000000)::bigint;
+    END IF;
+
+    NEW.meta := (
+        greatest(now(), (OLD.meta).updated + interval '1 microsecond'),
+        (OLD.meta).created
+    )::metatime;
 
     RETURN NEW;
 END;
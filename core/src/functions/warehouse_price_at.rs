@@ -0,0 +1,20 @@
+use crate::traits::Recreatable;
+
+/// Price of the warehouse item at the given moment according to
+/// [`WarehousePrice`](crate::tables::WarehousePrice) history,
+/// the current price if the item was never indexed
+pub struct WarehousePriceAt;
+
+impl Recreatable for WarehousePriceAt {
+    const NAME: &'static str = "warehouse_price_at";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "warehouse_price_at" (for_item uuid, at timestamptz) RETURNS monetary AS $$
+    SELECT coalesce(
+        (SELECT price FROM "WarehousePrice" WHERE item = for_item AND effective <= at ORDER BY effective DESC LIMIT 1),
+        (SELECT price FROM "WarehousePrice" WHERE item = for_item ORDER BY effective LIMIT 1),
+        (SELECT price FROM "Warehouse" WHERE uuid = for_item)
+    );
+$$ LANGUAGE SQL STABLE;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "warehouse_price_at";"#;
+}
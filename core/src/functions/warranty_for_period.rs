@@ -0,0 +1,23 @@
+use crate::traits::Recreatable;
+
+/// Warranty claims opened within the period and the cost of parts spent on them,
+/// in the base currency, claims bring no revenue so they are reported apart from
+/// [`RevenueForPeriod`](super::RevenueForPeriod)
+pub struct WarrantyForPeriod;
+
+impl Recreatable for WarrantyForPeriod {
+    const NAME: &'static str = "warranty_for_period";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION "warranty_for_period" (from_date timestamptz, to_date timestamptz, for_branch uuid DEFAULT NULL) RETURNS TABLE(claims bigint, parts bigint, parts_cost monetary) AS $$
+    SELECT count(DISTINCT o.uuid) as claims,
+        coalesce(sum(ow.amount), 0) as parts,
+        to_monetary(coalesce(sum((to_base_currency(warehouse_price_at(ow.item, (o.meta).created), (o.meta).created::date)).amount * ow.amount), 0)) as parts_cost
+    FROM "Order" o
+        LEFT JOIN "OrderWarehouse" ow ON ow."order" = o.uuid
+    WHERE o.warranty_of IS NOT NULL
+        AND (o.meta).created BETWEEN from_date and to_date
+        AND (for_branch IS NULL OR o.branch = for_branch);
+$$ LANGUAGE SQL;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "warranty_for_period";"#;
+}
@@ -0,0 +1,59 @@
+use crate::traits::Recreatable;
+
+/// Zeroes price of services and parts of warranty claims
+///
+/// Claims are rejected unless the original order is complete, is for the same phone and client
+/// and the claim was opened within the warranty. Only services of the original order are
+/// covered, each by its own warranty, other services are charged. Parts are covered by the
+/// longest warranty of the original order services
+pub struct WarrantyPriceFunc;
+
+impl Recreatable for WarrantyPriceFunc {
+    const NAME: &'static str = "warranty_price_func";
+
+    const CREATE: &'static str = r#"CREATE FUNCTION warranty_price_func() RETURNS trigger AS
+$$
+DECLARE
+    claim "Order";
+    original "Order";
+    warranty int;
+BEGIN
+    SELECT * INTO claim FROM "Order" WHERE uuid = NEW."order";
+    IF claim.warranty_of IS NULL THEN
+        RETURN NEW;
+    END IF;
+
+    SELECT * INTO original FROM "Order" WHERE uuid = claim.warranty_of;
+    IF original.status <> 'Complete' OR original.completed IS NULL THEN
+        RAISE EXCEPTION 'Order % of warranty claim % is not complete', original.uuid, claim.uuid;
+    END IF;
+    IF original.phone <> claim.phone OR original.client <> claim.client THEN
+        RAISE EXCEPTION 'Warranty claim % is for another phone or client than order %',
+            claim.uuid, original.uuid;
+    END IF;
+
+    IF TG_TABLE_NAME = 'OrderService' THEN
+        IF NOT EXISTS (
+            SELECT 1 FROM "OrderService" WHERE "order" = original.uuid AND service = NEW.service
+        ) THEN
+            RETURN NEW;
+        END IF;
+        SELECT warranty_days INTO warranty FROM "Service" WHERE uuid = NEW.service;
+    ELSE
+        SELECT max(s.warranty_days) INTO warranty
+        FROM "OrderService" os
+            JOIN "Service" s ON os.service = s.uuid
+        WHERE os."order" = original.uuid;
+    END IF;
+    IF (claim.meta).created > original.completed + coalesce(warranty, 0) * interval '1 day' THEN
+        RAISE EXCEPTION 'Warranty of order % expired before claim %', original.uuid, claim.uuid;
+    END IF;
+
+    NEW.price := to_monetary(0);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE PLPGSQL;"#;
+
+    const DROP: &'static str = r#"DROP FUNCTION "warranty_price_func";"#;
+}
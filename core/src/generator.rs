@@ -1,5 +1,7 @@
 use std::ops::RangeInclusive;
 
+use anyhow::{bail, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
 use fake::{
     faker::{
         address::raw::*,
@@ -18,16 +20,19 @@ use rand::{
     thread_rng, Rng,
 };
 use sqlx::types::BigDecimal;
+use uuid::Uuid;
 
 use crate::{
     tables::{
-        Account, Component, ComponentKind, LaborContract, Manufacturer, Order, OrderService,
-        OrderWarehouse, Person, Phone, PhoneModel, Position, Service, ServicePhoneModel, Staff,
-        Supplier, Supply, SupplyContract, Warehouse, WarehouseSupply,
+        Account, Branch, Component, ComponentKind, ExchangeRate, Invoice, LaborContract,
+        Manufacturer, Order, OrderService, OrderWarehouse, Payment, Person, Phone, PhoneModel,
+        PhoneOwnership, Position, Service, ServicePhoneModel, Staff, StockTransfer, Supplier,
+        SupplierDetails, Supply, SupplyContract, SupplyContractItem, SupplyItem, Warehouse,
+        WarehouseSupply,
     },
     types::{
-        AccountRole, AccountStatus, Color, ContractStatus, MetaTime, OrderStatus, StaffStatus,
-        SupplyStatus,
+        AccountRole, AccountStatus, Color, ContractStatus, Country, Currency, Iban, Imei, MetaTime,
+        Money, OrderStatus, PaymentKind, PaymentMethod, StaffStatus, SupplyStatus, TransferStatus,
     },
     utils::password_hash,
 };
@@ -39,18 +44,26 @@ pub type FullPack = (
     Vec<Manufacturer>,
     Vec<Person>,
     Vec<Supplier>,
+    Vec<Branch>,
+    Vec<ExchangeRate>,
     Vec<LaborContract>,
     Vec<PhoneModel>,
     Vec<Staff>,
     Vec<Component>,
     Vec<Phone>,
     Vec<Account>,
+    Vec<PhoneOwnership>,
     Vec<SupplyContract>,
     Vec<Order>,
     Vec<Supply>,
     Vec<Warehouse>,
+    Vec<StockTransfer>,
+    Vec<Invoice>,
+    Vec<Payment>,
     Vec<ServicePhoneModel>,
+    Vec<SupplyContractItem>,
     Vec<WarehouseSupply>,
+    Vec<SupplyItem>,
     Vec<OrderService>,
     Vec<OrderWarehouse>,
 );
@@ -61,9 +74,21 @@ const IBAN_ID_RANGE: RangeInclusive<i128> = 0..=9999999999999999999999999999;
 #[derive(Debug)]
 pub struct Config {
     pub person_count: usize,
+    /// Chance of the person to be entered once more with a typo in the name
+    /// and the phone number in another format
+    pub person_duplicate_chance: f64,
     pub supplier_count: usize,
     pub position_salary_scatter: f32,
 
+    // Branch
+    pub branch_count: usize,
+
+    // Exchange Rate
+    /// Days between consecutive rates of the currency
+    pub exchange_rate_period: i64,
+    /// Maximum relative change between consecutive rates
+    pub exchange_rate_scatter: f64,
+
     // LaborContract
     pub labor_contract_count: usize,
     pub labor_contract_weights: [u8; 5],
@@ -80,32 +105,156 @@ pub struct Config {
 
     // Phone
     pub phone_count: Vec<u8>,
+    /// Chance of the phone to have had previous owners
+    pub phone_resale_chance: f64,
 
     // Warehouse
+    /// Purchase price relative to the selling price of the item
+    pub warehouse_purchase_ratio: (f64, f64),
     pub warehouse_variations: u32,
     pub warehouse_stock: (u16, u16),
     pub warehouse_item_price_scatter: f64,
 
+    // Stock Transfer
+    pub stock_transfer_chance: f64,
+    pub stock_transfer_weights: [u8; 3],
+
     // Order
     pub order_count: usize,
     pub order_not_owner_chance: f64,
+    /// Chance of the completed order to get warranty claim
+    pub warranty_claim_chance: f64,
+
+    // Payment
+    pub payment_method_weights: [u8; 3],
+    /// Chance of the invoice being paid in two parts
+    pub payment_split_chance: f64,
+    /// Chance of the order pending payment to be partially paid
+    pub payment_partial_chance: f64,
+    /// Chance of the paid order to get partial refund
+    pub payment_refund_chance: f64,
 
     // Supply
     pub supply_count: usize,
+    /// Chance of the delivered supply line to come short of the ordered amount
+    pub supply_discrepancy_chance: f64,
+
+    // History
+    /// End of the simulated history
+    pub history_end: DateTime<Utc>,
+    /// Length of the simulated history in days
+    pub history_days: i64,
+    /// Days from the history start till the first order, staff gets hired during this period
+    pub opening_days: i64,
+    /// Relative order volume for every month (starting from January)
+    pub order_seasonality: [u8; 12],
 }
 
 impl Config {
+    pub fn history_start(&self) -> DateTime<Utc> {
+        self.history_end - Duration::days(self.history_days)
+    }
+
+    /// Time when the first order can be placed
+    pub fn opening(&self) -> DateTime<Utc> {
+        self.history_start() + Duration::days(self.opening_days)
+    }
+
+    /// Fails on weights that can't be sampled, all of them being zero
+    pub fn validate(&self) -> Result<()> {
+        let weights: [(&str, &[u8]); 6] = [
+            ("labor_contract_weights", &self.labor_contract_weights),
+            ("account_status_weights", &self.account_status_weights),
+            ("phone_count", &self.phone_count),
+            ("stock_transfer_weights", &self.stock_transfer_weights),
+            ("payment_method_weights", &self.payment_method_weights),
+            ("order_seasonality", &self.order_seasonality),
+        ];
+
+        for (name, weights) in weights {
+            if weights.iter().all(|weight| *weight == 0) {
+                bail!("Generator config '{name}' has no non-zero weight");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Random time in `[from, to)` following [`Config::order_seasonality`], uniform when
+    /// none of the months in the window has weight
+    pub fn seasonal_time(
+        &self,
+        rng: &mut ThreadRng,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        // Window split at month starts, every part weighted by its length and month
+        let mut parts = Vec::new();
+        let mut start = from;
+        while start < to {
+            let (year, month) = match start.month() {
+                12 => (start.year() + 1, 1),
+                month => (start.year(), month + 1),
+            };
+            let next = NaiveDate::from_ymd_opt(year, month, 1)
+                .expect("valid month start")
+                .and_time(NaiveTime::MIN);
+            let end = Utc.from_utc_datetime(&next).min(to);
+            parts.push((start, end));
+            start = end;
+        }
+
+        let weights = parts.iter().map(|(start, end)| {
+            (*end - *start).num_seconds().max(0) as u64
+                * self.order_seasonality[start.month0() as usize] as u64
+        });
+        match WeightedIndex::new(weights) {
+            Ok(index) => {
+                let (start, end) = parts[index.sample(rng)];
+                rand_time(rng, start, end)
+            }
+            Err(_) => rand_time(rng, from, to),
+        }
+    }
+
     pub fn gen_person(&self) -> Vec<Person> {
         let mut store = Vec::with_capacity(self.person_count);
+        let mut rng = thread_rng();
+        let duplicate_chance = Bernoulli::new(self.person_duplicate_chance).unwrap();
 
         for _ in 0..self.person_count {
-            store.push(Person::new_auto(
-                FirstName(EN).fake(),
-                None,
-                LastName(EN).fake(),
-                FreeEmail(EN).fake(),
-                CellNumber(EN).fake(),
-            ))
+            let person = Person {
+                meta: MetaTime::at(rand_time(&mut rng, self.history_start(), self.history_end)),
+                ..Person::new_auto(
+                    FirstName(EN).fake(),
+                    None,
+                    LastName(EN).fake(),
+                    FreeEmail(EN).fake(),
+                    CellNumber(EN).fake(),
+                )
+            };
+
+            if duplicate_chance.sample(&mut rng) {
+                let mut last_name: Vec<char> = person.last_name.chars().collect();
+                if last_name.len() > 2 {
+                    let at = rng.gen_range(1..last_name.len() - 1);
+                    last_name.swap(at, at + 1);
+                }
+                let digits: String = person.phone.chars().filter(char::is_ascii_digit).collect();
+                let digits = &digits[digits.len().saturating_sub(10)..];
+
+                store.push(Person {
+                    meta: MetaTime::at(rand_time(&mut rng, person.meta.created, self.history_end)),
+                    ..Person::new_auto(
+                        person.first_name.clone(),
+                        None,
+                        last_name.into_iter().collect(),
+                        FreeEmail(EN).fake(),
+                        format!("+1 {digits}"),
+                    )
+                });
+            }
+            store.push(person);
         }
 
         store
@@ -118,11 +267,17 @@ impl Config {
             .map(|(name, salary, ..)| {
                 let salary = *salary as f32;
                 let scatter = salary * self.position_salary_scatter;
-                Position::new_auto(
-                    name.to_string(),
-                    None,
-                    BigDecimal::from(rng.gen_range((salary - scatter)..(salary + scatter)) as i64),
-                )
+                Position {
+                    meta: MetaTime::at(self.history_start()),
+                    ..Position::new_auto(
+                        name.to_string(),
+                        None,
+                        Money::base(BigDecimal::from(
+                            rng.gen_range((salary - scatter)..(salary + scatter)) as i64,
+                        ))
+                        .unwrap(),
+                    )
+                }
             })
             .collect()
     }
@@ -130,7 +285,12 @@ impl Config {
     pub fn gen_manufacturer(&self) -> Vec<Manufacturer> {
         Manufacturer::SAMPLES
             .iter()
-            .map(|(name, country)| Manufacturer::new_auto(name.to_string(), country.to_string()))
+            .map(|(name, country)| {
+                Manufacturer::new_auto(
+                    name.to_string(),
+                    country.parse().expect("valid country code"),
+                )
+            })
             .collect()
     }
 
@@ -146,8 +306,13 @@ impl Config {
     pub fn gen_service(&self) -> Vec<Service> {
         Service::SAMPLES
             .iter()
-            .map(|(name, desc, ..)| {
-                Service::new_auto(name.to_string(), desc.map(|val| val.to_string()))
+            .map(|(name, desc, .., warranty_days)| Service {
+                meta: MetaTime::at(self.history_start()),
+                ..Service::new_auto(
+                    name.to_string(),
+                    desc.map(|val| val.to_string()),
+                    *warranty_days,
+                )
             })
             .collect()
     }
@@ -167,19 +332,16 @@ impl Config {
                 2 => CompanyName(ZH_TW).fake(),
                 _ => unreachable!(),
             };
-            // Invalid Iban
             let iban = {
                 let id = rng.gen_range(IBAN_ID_RANGE);
-                format!("{}{:030}", country_match(country), id)
+                Iban::from_bban(country_match(country), &format!("{:030}", id)).expect("valid bban")
             };
-            let swift = match country {
-                // EN
-                0 => Bic(EN).fake(),
-                // ZH_CN
-                1 => Bic(ZH_CN).fake(),
-                // ZH_TW
-                2 => Bic(ZH_TW).fake(),
-                _ => unreachable!(),
+            // Bank code and location of a fake one, country of the supplier
+            let swift = {
+                let bic: String = Bic(EN).fake();
+                format!("{}{}{}", &bic[..4], country_match(country), &bic[6..])
+                    .parse()
+                    .expect("valid bic")
             };
             let (code, zip, state, city, street, secondary): (
                 &str,
@@ -223,19 +385,97 @@ impl Config {
                 code, zip, state, city, street, secondary
             );
 
-            store.push(Supplier::new_auto(
-                name,
-                iban,
-                swift,
-                address,
-                country_match(country).to_string(),
-            ))
+            let details = SupplierDetails {
+                contact: Some(Name(EN).fake()),
+                email: Some(FreeEmail(EN).fake()),
+                phone: Some(PhoneNumber(EN).fake()),
+                payment_days: Some([0, 15, 30, 60][rng.gen_range(0..4)]),
+                ..Default::default()
+            };
+
+            store.push(Supplier {
+                meta: MetaTime::at(self.history_start()),
+                ..Supplier::new_auto(
+                    name,
+                    iban,
+                    swift,
+                    address,
+                    country_match(country),
+                    Some(details),
+                )
+            })
         }
 
         store
     }
 
-    /// Generate labor contracts from given list of persons
+    pub fn gen_branch(&self) -> Vec<Branch> {
+        let mut store: Vec<Branch> = Vec::with_capacity(self.branch_count);
+
+        while store.len() < self.branch_count {
+            let name = format!("{} branch", CityName(EN).fake::<String>());
+            if store.iter().any(|b| b.name == name) {
+                continue;
+            }
+
+            store.push(Branch {
+                meta: MetaTime::at(self.history_start()),
+                ..Branch::new_auto(
+                    name,
+                    format!(
+                        "{} {}, {}",
+                        BuildingNumber(EN).fake::<String>(),
+                        StreetName(EN).fake::<String>(),
+                        SecondaryAddress(EN).fake::<String>()
+                    ),
+                    CellNumber(EN).fake(),
+                )
+            })
+        }
+
+        store
+    }
+
+    /// Generate rates for the currencies of the supplier countries through the whole history
+    pub fn gen_exchange_rate(&self) -> Vec<ExchangeRate> {
+        let mut rng = thread_rng();
+        let start = self.history_start().date_naive();
+        let days = self.history_days / self.exchange_rate_period + 1;
+
+        (0..3)
+            .flat_map(|country| {
+                let currency = country_currency(country_match(country));
+                let mut rate: f64 = match country {
+                    0 => 75.0,
+                    1 => 11.0,
+                    _ => 2.5,
+                };
+
+                (0..days)
+                    .map(|i| {
+                        let day = start + Duration::days(i * self.exchange_rate_period);
+                        rate *= 1.0
+                            + rng
+                                .gen_range(-self.exchange_rate_scatter..self.exchange_rate_scatter);
+
+                        ExchangeRate {
+                            meta: MetaTime::at(
+                                Utc.from_utc_datetime(&day.and_time(NaiveTime::MIN)),
+                            ),
+                            ..ExchangeRate::new_auto(
+                                currency,
+                                day,
+                                BigDecimal::from((rate * 10_000.0).round() as i64) / 10_000,
+                            )
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Generate labor contracts from given list of persons,
+    /// contracts are drawn up and signed before the [opening](Config::opening)
     /// # Panics
     /// This function will panic if [person_store] length is less than [labor_contract_count]
     pub fn gen_labor_contract(&self, people: &[Person]) -> Vec<LaborContract> {
@@ -256,18 +496,23 @@ impl Config {
                     .any(|c: &LaborContract| c.person == p.uuid)
                 {
                     let status = ContractStatus::ALL[status_weights.sample(&mut rng)];
-                    labor_contract_store.push(LaborContract::new_auto(
-                        p.uuid,
-                        rand_string(&mut rng, 10),
-                        status,
-                        match status {
-                            ContractStatus::Review | ContractStatus::Negotiation => None,
-                            ContractStatus::Active
-                            | ContractStatus::Expired
-                            | ContractStatus::Void
-                            | ContractStatus::Rejected => Some(MetaTime::now()),
+                    let created = rand_time(&mut rng, self.history_start(), self.opening());
+                    let signed = match status {
+                        ContractStatus::Review | ContractStatus::Negotiation => None,
+                        ContractStatus::Active
+                        | ContractStatus::Expired
+                        | ContractStatus::Void
+                        | ContractStatus::Rejected => {
+                            Some(rand_time(&mut rng, created, self.opening()))
+                        }
+                    };
+                    labor_contract_store.push(LaborContract {
+                        meta: MetaTime {
+                            updated: signed.unwrap_or(created),
+                            created,
                         },
-                    ));
+                        ..LaborContract::new_auto(p.uuid, rand_string(&mut rng, 10), status, signed)
+                    });
                     break;
                 }
             }
@@ -306,6 +551,7 @@ impl Config {
         &self,
         labor_contracts: &[LaborContract],
         positions: &[Position],
+        branches: &[Branch],
     ) -> Vec<Staff> {
         let mut rng = thread_rng();
         let vacation_weights = Bernoulli::new(self.staff_vacation_chance).unwrap();
@@ -329,6 +575,7 @@ impl Config {
                 Staff::new_auto(
                     lc.uuid,
                     positions[position_weights.sample(&mut rng)].uuid,
+                    branches[rng.gen_range(0..branches.len())].uuid,
                     match lc.status {
                         ContractStatus::Active => {
                             if vacation_weights.sample(&mut rng) {
@@ -402,14 +649,18 @@ impl Config {
                 let mut phones = Vec::with_capacity(count);
 
                 for _ in 0..=count {
-                    phones.push(Phone::new_auto(
-                        p.uuid,
-                        rand_string(&mut rng, 17),
-                        MACAddress(EN).fake::<String>().parse().unwrap(),
-                        MACAddress(EN).fake::<String>().parse().unwrap(),
-                        phone_models[rng.gen_range(0..phone_models.len())].uuid,
-                        Color::ALL[rng.gen_range(0..Color::ALL.len())],
-                    ));
+                    phones.push(Phone {
+                        meta: MetaTime::at(rand_time(&mut rng, p.meta.created, self.history_end)),
+                        ..Phone::new_auto(
+                            p.uuid,
+                            Imei::with_check_digit(&rand_string(&mut rng, 14))
+                                .expect("valid imei body"),
+                            MACAddress(EN).fake::<String>().parse().unwrap(),
+                            MACAddress(EN).fake::<String>().parse().unwrap(),
+                            phone_models[rng.gen_range(0..phone_models.len())].uuid,
+                            Color::ALL[rng.gen_range(0..Color::ALL.len())],
+                        )
+                    });
                 }
 
                 phones
@@ -417,42 +668,89 @@ impl Config {
             .collect()
     }
 
-    pub fn gen_account(&self, staff: &[Staff], positions: &[Position]) -> Vec<Account> {
+    /// Previous owners of resold phones, the phone changes hands at most twice before
+    /// reaching the current owner
+    pub fn gen_phone_ownership(&self, people: &[Person], phones: &[Phone]) -> Vec<PhoneOwnership> {
+        let mut rng = thread_rng();
+        let resale_chance = Bernoulli::new(self.phone_resale_chance).unwrap();
+        let mut store = Vec::new();
+
+        for phone in phones {
+            if !resale_chance.sample(&mut rng) {
+                continue;
+            }
+
+            let mut changes = (0..rng.gen_range(1..=2))
+                .map(|_| rand_time(&mut rng, phone.meta.created, self.history_end))
+                .collect::<Vec<_>>();
+            changes.sort();
+
+            let mut since = phone.meta.created;
+            for until in changes {
+                let owner = loop {
+                    let person = &people[rng.gen_range(0..people.len())];
+                    if person.uuid != phone.person {
+                        break person;
+                    }
+                };
+                store.push(PhoneOwnership::new(phone.uuid, owner.uuid, since, until));
+                since = until;
+            }
+        }
+
+        store
+    }
+
+    /// Generate accounts for the staff, account is created when the labor contract gets signed
+    pub fn gen_account(
+        &self,
+        staff: &[Staff],
+        positions: &[Position],
+        labor_contracts: &[LaborContract],
+    ) -> Vec<Account> {
         let account_weights = WeightedIndex::new(self.account_status_weights).unwrap();
         let mut rng = thread_rng();
 
         staff
             .iter()
             .map(|s| {
-                Account::new_auto(
-                    s.uuid,
-                    Username(EN).fake(),
-                    password_hash(Password(EN, 8..33).fake::<String>().as_bytes()).unwrap(),
-                    positions
-                        .iter()
-                        .find_map(|p| {
-                            if p.uuid == s.position {
-                                Some(
-                                    Position::SAMPLES
-                                        .into_iter()
-                                        .find_map(
-                                            |(n, .., r)| {
-                                                if p.name == n {
-                                                    Some(r)
-                                                } else {
-                                                    None
-                                                }
-                                            },
-                                        )
-                                        .unwrap(),
-                                )
-                            } else {
-                                None
-                            }
-                        })
-                        .unwrap(),
-                    AccountStatus::ALL[account_weights.sample(&mut rng)],
-                )
+                let contract = labor_contracts
+                    .iter()
+                    .find(|contract| contract.uuid == s.contract)
+                    .unwrap();
+
+                Account {
+                    meta: MetaTime::at(contract.signed.unwrap_or(contract.meta.created)),
+                    ..Account::new_auto(
+                        s.uuid,
+                        Username(EN).fake(),
+                        password_hash(Password(EN, 8..33).fake::<String>().as_bytes()).unwrap(),
+                        positions
+                            .iter()
+                            .find_map(|p| {
+                                if p.uuid == s.position {
+                                    Some(
+                                        Position::SAMPLES
+                                            .into_iter()
+                                            .find_map(
+                                                |(n, .., r)| {
+                                                    if p.name == n {
+                                                        Some(r)
+                                                    } else {
+                                                        None
+                                                    }
+                                                },
+                                            )
+                                            .unwrap(),
+                                    )
+                                } else {
+                                    None
+                                }
+                            })
+                            .unwrap(),
+                        AccountStatus::ALL[account_weights.sample(&mut rng)],
+                    )
+                }
             })
             .collect()
     }
@@ -502,6 +800,8 @@ impl Config {
                 if rng.gen_bool(self.supply_contract_chance) {
                     let count = rng.gen_range(0..=self.supply_contract_count);
                     let mut contracts = Vec::with_capacity(count);
+                    // Contracts with the supplier follow each other through the history
+                    let period = Duration::days(self.history_days) / (count.max(1) as i32);
 
                     for i in 0..count {
                         let status = if i + 1 == count {
@@ -509,19 +809,47 @@ impl Config {
                         } else {
                             old_statuses.sample(&mut rng)
                         };
-                        contracts.push(SupplyContract::new_auto(
-                            supplier.uuid,
-                            managers[rng.gen_range(0..managers.len())],
-                            *status,
-                            match status {
-                                ContractStatus::Review
-                                | ContractStatus::Negotiation
-                                | ContractStatus::Rejected => None,
-                                ContractStatus::Active
-                                | ContractStatus::Expired
-                                | ContractStatus::Void => Some(MetaTime::now()),
+                        let from = self.history_start() + period * i as i32;
+                        let created = rand_time(&mut rng, from, from + period / 2);
+                        let signed = match status {
+                            ContractStatus::Review
+                            | ContractStatus::Negotiation
+                            | ContractStatus::Rejected => None,
+                            ContractStatus::Active
+                            | ContractStatus::Expired
+                            | ContractStatus::Void => {
+                                Some(rand_time(&mut rng, created, from + period / 2))
+                            }
+                        };
+                        let valid_from = signed.unwrap_or(created).date_naive();
+                        // Finished contracts are valid until the next one, active ones for
+                        // another year, the rest are not agreed yet
+                        let valid_to = match status {
+                            ContractStatus::Expired | ContractStatus::Void => {
+                                Some((from + period).date_naive().max(valid_from))
+                            }
+                            ContractStatus::Active => {
+                                Some((self.history_end + Duration::days(365)).date_naive())
+                            }
+                            ContractStatus::Review
+                            | ContractStatus::Negotiation
+                            | ContractStatus::Rejected => None,
+                        };
+                        contracts.push(SupplyContract {
+                            meta: MetaTime {
+                                updated: signed.unwrap_or(created),
+                                created,
                             },
-                        ));
+                            ..SupplyContract::new_auto(
+                                supplier.uuid,
+                                managers[rng.gen_range(0..managers.len())],
+                                *status,
+                                signed,
+                                valid_from,
+                                valid_to,
+                                country_currency(supplier.country),
+                            )
+                        });
                     }
 
                     contracts
@@ -535,6 +863,7 @@ impl Config {
     pub fn gen_supply(
         &self,
         supply_contracts: &[SupplyContract],
+        supply_contract_items: &[SupplyContractItem],
         staff: &[Staff],
         accounts: &[Account],
     ) -> Vec<Supply> {
@@ -558,6 +887,21 @@ impl Config {
             panic!("There is no warehouse workers on staff");
         }
 
+        let managers = &staff
+            .iter()
+            .filter_map(|staff| {
+                match accounts
+                    .iter()
+                    .find(|account| account.staff == staff.uuid)
+                    .unwrap()
+                    .role
+                {
+                    AccountRole::Manager => Some(staff.uuid),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
         let old_statuses = Slice::new(&[
             SupplyStatus::Delivered,
             SupplyStatus::Failed,
@@ -584,6 +928,25 @@ impl Config {
             .flat_map(|supply_contract| {
                 let count = rng.gen_range(0..=self.supply_count);
                 let mut supplies = Vec::with_capacity(count);
+                // Supplies go after the contract is signed and until the next contract
+                let from = supply_contract
+                    .signed
+                    .unwrap_or(supply_contract.meta.created);
+                let to = supply_contracts
+                    .iter()
+                    .filter(|contract| {
+                        contract.supplier == supply_contract.supplier
+                            && contract.meta.created > supply_contract.meta.created
+                    })
+                    .map(|contract| contract.meta.created)
+                    .min()
+                    .unwrap_or(self.history_end);
+                let period = (to - from) / (count.max(1) as i32);
+                let lead_days = supply_contract_items
+                    .iter()
+                    .filter(|item| item.contract == supply_contract.uuid)
+                    .map(|item| item.lead_days)
+                    .max();
 
                 for i in 0..count {
                     let status = if i + 1 == count {
@@ -591,20 +954,58 @@ impl Config {
                     } else {
                         old_statuses.sample(&mut rng)
                     };
-                    supplies.push(Supply::new_auto(
+                    let created = rand_time(
+                        &mut rng,
+                        from + period * i as i32,
+                        from + period * (i as i32 + 1),
+                    );
+                    let signed = match status {
+                        SupplyStatus::Review
+                        | SupplyStatus::Negotiation
+                        | SupplyStatus::Rejected => None,
+                        SupplyStatus::Signed
+                        | SupplyStatus::Paid
+                        | SupplyStatus::Dispatched
+                        | SupplyStatus::Delivered
+                        | SupplyStatus::Failed => Some(rand_time(
+                            &mut rng,
+                            created,
+                            (created + Duration::days(7)).min(self.history_end),
+                        )),
+                    };
+                    // Time of the last status change (e.g. delivery)
+                    let updated = match signed {
+                        Some(signed) => rand_time(
+                            &mut rng,
+                            signed,
+                            (signed + Duration::days(21)).min(self.history_end),
+                        ),
+                        None => created,
+                    };
+                    let due = signed
+                        .zip(lead_days)
+                        .map(|(signed, days)| signed + Duration::days(days as i64));
+                    let delivered = match status {
+                        SupplyStatus::Delivered => Some(updated),
+                        _ => None,
+                    };
+                    // Manager either signs or rejects the supply
+                    let approver = match status {
+                        SupplyStatus::Review | SupplyStatus::Negotiation => None,
+                        _ if managers.is_empty() => None,
+                        _ => Some(managers[rng.gen_range(0..managers.len())]),
+                    };
+                    supplies.push(Supply::new(
+                        Uuid::new_v4(),
                         supply_contract.uuid,
                         workers[rng.gen_range(0..workers.len())],
+                        approver,
                         *status,
-                        match status {
-                            SupplyStatus::Review
-                            | SupplyStatus::Negotiation
-                            | SupplyStatus::Rejected => None,
-                            SupplyStatus::Signed
-                            | SupplyStatus::Paid
-                            | SupplyStatus::Dispatched
-                            | SupplyStatus::Delivered
-                            | SupplyStatus::Failed => Some(MetaTime::now()),
-                        },
+                        signed,
+                        due,
+                        delivered,
+                        None,
+                        MetaTime { updated, created },
                     ))
                 }
 
@@ -618,6 +1019,7 @@ impl Config {
         components: &[Component],
         component_kinds: &[ComponentKind],
         supply_contracts: &[SupplyContract],
+        branches: &[Branch],
     ) -> Vec<Warehouse> {
         let mut rng = thread_rng();
         let mut suppliers = supply_contracts
@@ -646,39 +1048,113 @@ impl Config {
                         continue;
                     }
 
-                    items.push(Warehouse::new_auto(
-                        c.uuid,
-                        supp,
-                        component_kinds
-                            .iter()
-                            .find_map(|k| {
-                                if k.uuid == c.kind {
-                                    Some(
-                                        ComponentKind::SAMPLES
-                                            .into_iter()
-                                            .find_map(|(n, _, p)| {
-                                                if n == k.name {
-                                                    Some(BigDecimal::from(
-                                                        rng.gen_range(
-                                                            (p - self.warehouse_item_price_scatter)
-                                                                ..(p + self
-                                                                    .warehouse_item_price_scatter),
+                    // Item appears with the first signed contract of the supplier
+                    let created = supply_contracts
+                        .iter()
+                        .filter(|contract| contract.supplier == supp)
+                        .filter_map(|contract| contract.signed)
+                        .min()
+                        .unwrap_or_else(|| self.history_start());
+
+                    items.push(Warehouse {
+                        meta: MetaTime::at(created),
+                        ..Warehouse::new_auto(
+                            branches[rng.gen_range(0..branches.len())].uuid,
+                            c.uuid,
+                            supp,
+                            component_kinds
+                                .iter()
+                                .find_map(|k| {
+                                    if k.uuid == c.kind {
+                                        Some(
+                                            ComponentKind::SAMPLES
+                                                .into_iter()
+                                                .find_map(|(n, _, p)| {
+                                                    if n == k.name {
+                                                        Some(
+                                                            Money::base(BigDecimal::from(
+                                                                rng.gen_range(
+                                                                    (p - self
+                                                                        .warehouse_item_price_scatter)
+                                                                        ..(p + self
+                                                                            .warehouse_item_price_scatter),
+                                                                )
+                                                                    as i64,
+                                                            ))
+                                                            .unwrap(),
                                                         )
-                                                            as i64,
-                                                    ))
-                                                } else {
-                                                    None
-                                                }
-                                            })
-                                            .unwrap(),
-                                    )
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap(),
-                        rng.gen_range(self.warehouse_stock.0..self.warehouse_stock.1) as i32,
-                    ));
+                                                    } else {
+                                                        None
+                                                    }
+                                                })
+                                                .unwrap(),
+                                        )
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .unwrap(),
+                            rng.gen_range(self.warehouse_stock.0..self.warehouse_stock.1) as i32,
+                        )
+                    });
+                }
+
+                items
+            })
+            .collect()
+    }
+
+    /// Generate terms of the signed contracts for every component the supplier has in warehouse,
+    /// agreed price follows the selling price of the item at the signing day exchange rate
+    pub fn gen_supply_contract_item(
+        &self,
+        supply_contracts: &[SupplyContract],
+        warehouse: &[Warehouse],
+        exchange_rates: &[ExchangeRate],
+    ) -> Vec<SupplyContractItem> {
+        let mut rng = thread_rng();
+
+        supply_contracts
+            .iter()
+            .filter(|contract| {
+                matches!(
+                    contract.status,
+                    ContractStatus::Active | ContractStatus::Expired | ContractStatus::Void
+                )
+            })
+            .flat_map(|contract| {
+                let rate = rate_at(exchange_rates, contract.currency, contract.valid_from);
+                let mut items: Vec<SupplyContractItem> = Vec::new();
+
+                for item in warehouse
+                    .iter()
+                    .filter(|item| item.supplier == contract.supplier)
+                {
+                    if items.iter().any(|i| i.component == item.component) {
+                        continue;
+                    }
+
+                    let ratio = rng.gen_range(
+                        self.warehouse_purchase_ratio.0..self.warehouse_purchase_ratio.1,
+                    );
+                    let price = Money::new(
+                        item.price.amount() * BigDecimal::from((ratio * 100.0) as i64)
+                            / 100
+                            / &rate,
+                        contract.currency,
+                    )
+                    .unwrap();
+
+                    items.push(SupplyContractItem {
+                        meta: contract.meta.clone(),
+                        ..SupplyContractItem::new_auto(
+                            contract.uuid,
+                            item.component,
+                            price,
+                            rng.gen_range(5..=20),
+                            rng.gen_range(1..10),
+                        )
+                    });
                 }
 
                 items
@@ -696,20 +1172,26 @@ impl Config {
             .flat_map(|s| {
                 phone_models
                     .iter()
-                    .map(|pm| {
-                        ServicePhoneModel::new_auto(
+                    .map(|pm| ServicePhoneModel {
+                        meta: MetaTime::at(self.history_start()),
+                        ..ServicePhoneModel::new_auto(
                             s.uuid,
                             pm.uuid,
                             Service::SAMPLES
                                 .into_iter()
-                                .find_map(|(n, _, p, _)| {
+                                .find_map(|(n, _, p, ..)| {
                                     if n == s.name {
                                         Some(
                                             PhoneModel::SAMPLES
                                                 .into_iter()
                                                 .find_map(|(pm_n, _, _, coef)| {
                                                     if pm_n == pm.name {
-                                                        Some(BigDecimal::from((p * coef) as i64))
+                                                        Some(
+                                                            Money::base(BigDecimal::from(
+                                                                (p * coef) as i64,
+                                                            ))
+                                                            .unwrap(),
+                                                        )
                                                     } else {
                                                         None
                                                     }
@@ -733,15 +1215,24 @@ impl Config {
         warehouse: &[Warehouse],
         supplies: &[Supply],
         supply_contracts: &[SupplyContract],
+        supply_contract_items: &[SupplyContractItem],
     ) -> Vec<WarehouseSupply> {
         supplies
             .iter()
             .flat_map(|supply| {
-                let supplier = supply_contracts
+                let contract = supply_contracts
                     .iter()
                     .find(|contract| contract.uuid == supply.contract)
-                    .unwrap()
-                    .supplier;
+                    .unwrap();
+                let supplier = contract.supplier;
+                // Items are bought at the price agreed by the contract
+                let purchase = |component: &Uuid| {
+                    supply_contract_items
+                        .iter()
+                        .find(|item| item.contract == contract.uuid && item.component == *component)
+                        .map(|item| item.price.clone())
+                        .expect("contract has terms for every supplier item")
+                };
                 // Supply contracts from the supplier
                 let contracts = supply_contracts
                     .iter()
@@ -769,7 +1260,7 @@ impl Config {
                     .iter()
                     .filter_map(|item| {
                         if item.supplier == supplier {
-                            Some((item.uuid, item.amount))
+                            Some((item.uuid, item.amount, &item.component))
                         } else {
                             None
                         }
@@ -790,22 +1281,24 @@ impl Config {
                 let mut items = items.chunks(step).skip(pos).peekable();
 
                 if let Some(chunk) = items.next() {
-                    for (item, amount) in chunk {
+                    for (item, amount, component) in chunk {
                         supply_items.push(WarehouseSupply::new(
                             *item,
                             supply.uuid,
                             *amount,
+                            purchase(component),
                             supply.meta.updated,
                         ));
                     }
 
                     match items.peek() {
                         Some(chunk) if chunk.len() != step => {
-                            for (item, amount) in items.next().expect("while peeking") {
+                            for (item, amount, component) in items.next().expect("while peeking") {
                                 supply_items.push(WarehouseSupply::new(
                                     *item,
                                     supply.uuid,
                                     *amount,
+                                    purchase(component),
                                     supply.meta.updated,
                                 ));
                             }
@@ -819,26 +1312,182 @@ impl Config {
             .collect()
     }
 
+    /// Generate purchase order lines of the supplies from the supplied items,
+    /// some of the delivered supplies come short of the ordered amount
+    pub fn gen_supply_item(
+        &self,
+        supplies: &[Supply],
+        warehouse_supply: &[WarehouseSupply],
+        warehouse: &[Warehouse],
+    ) -> Vec<SupplyItem> {
+        let mut rng = thread_rng();
+        let mut store: Vec<SupplyItem> = Vec::with_capacity(warehouse_supply.len());
+
+        for supplied in warehouse_supply {
+            let supply = supplies.iter().find(|s| s.uuid == supplied.supply).unwrap();
+            let component = warehouse
+                .iter()
+                .find(|item| item.uuid == supplied.item)
+                .unwrap()
+                .component;
+            let received = matches!(supply.status, SupplyStatus::Delivered);
+
+            if let Some(line) = store
+                .iter_mut()
+                .find(|line| line.supply == supply.uuid && line.component == component)
+            {
+                line.ordered += supplied.amount;
+                line.received = line.received.map(|r| r + supplied.amount);
+                continue;
+            }
+
+            let shortage = if received && rng.gen_bool(self.supply_discrepancy_chance) {
+                rng.gen_range(1..=3)
+            } else {
+                0
+            };
+            store.push(SupplyItem::new(
+                supply.uuid,
+                component,
+                supplied.amount + shortage,
+                received.then_some(supplied.amount),
+                supplied.price.clone(),
+                supply.meta.clone(),
+            ));
+        }
+
+        store
+    }
+
+    /// Generate transfers of warehouse items between branches,
+    /// received transfers add items to the destination branch warehouse
+    pub fn gen_stock_transfer(
+        &self,
+        warehouse: &mut Vec<Warehouse>,
+        branches: &[Branch],
+        staff: &[Staff],
+    ) -> Vec<StockTransfer> {
+        if branches.len() < 2 {
+            return Vec::new();
+        }
+
+        let mut rng = thread_rng();
+        let transfer_chance = Bernoulli::new(self.stock_transfer_chance).unwrap();
+        let status_weights = WeightedIndex::new(self.stock_transfer_weights).unwrap();
+        let branch_staff = |branch: Uuid, rng: &mut ThreadRng| {
+            let local = staff
+                .iter()
+                .filter(|staff| staff.branch == branch)
+                .collect::<Vec<_>>();
+            if local.is_empty() {
+                staff[rng.gen_range(0..staff.len())].uuid
+            } else {
+                local[rng.gen_range(0..local.len())].uuid
+            }
+        };
+
+        let mut transfers = Vec::new();
+
+        // Received stock is appended, so only the rows existing beforehand are shipped
+        for i in 0..warehouse.len() {
+            let item = &warehouse[i];
+            if item.amount <= 0 || !transfer_chance.sample(&mut rng) {
+                continue;
+            }
+
+            let destinations = branches
+                .iter()
+                .filter(|branch| branch.uuid != item.branch)
+                .collect::<Vec<_>>();
+            let destination = destinations[rng.gen_range(0..destinations.len())].uuid;
+            let status = TransferStatus::ALL[status_weights.sample(&mut rng)];
+            // Transfers in transit are the recent ones
+            let from = match status {
+                TransferStatus::InTransit => {
+                    item.meta.created.max(self.history_end - Duration::days(7))
+                }
+                TransferStatus::Received | TransferStatus::Cancelled => item.meta.created,
+            };
+            let created = rand_time(&mut rng, from, self.history_end);
+            let updated = match status {
+                TransferStatus::InTransit => created,
+                TransferStatus::Received | TransferStatus::Cancelled => rand_time(
+                    &mut rng,
+                    created,
+                    (created + Duration::days(7)).min(self.history_end),
+                ),
+            };
+            let amount = rng.gen_range(1..=item.amount);
+
+            transfers.push(StockTransfer {
+                status,
+                receiver: match status {
+                    TransferStatus::Received => Some(branch_staff(destination, &mut rng)),
+                    TransferStatus::InTransit | TransferStatus::Cancelled => None,
+                },
+                meta: MetaTime { updated, created },
+                ..StockTransfer::new_auto(
+                    item.uuid,
+                    destination,
+                    amount,
+                    branch_staff(item.branch, &mut rng),
+                )
+            });
+
+            // Shipped stock leaves the source, received one joins the destination row
+            // of the same component and supplier the same way `ReceiveTransfer` does
+            let (component, supplier, price) = (item.component, item.supplier, item.price.clone());
+            match status {
+                TransferStatus::InTransit => warehouse[i].amount -= amount,
+                TransferStatus::Received => {
+                    warehouse[i].amount -= amount;
+                    match warehouse.iter_mut().find(|w| {
+                        w.branch == destination
+                            && w.component == component
+                            && w.supplier == supplier
+                    }) {
+                        Some(existing) => existing.amount += amount,
+                        None => warehouse.push(Warehouse {
+                            meta: MetaTime::at(updated),
+                            ..Warehouse::new(
+                                Uuid::new_v4(),
+                                destination,
+                                component,
+                                supplier,
+                                price,
+                                amount,
+                                None,
+                                MetaTime::default(),
+                            )
+                        }),
+                    }
+                }
+                TransferStatus::Cancelled => (),
+            }
+        }
+
+        transfers
+    }
+
     pub fn gen_order(
         &self,
         people: &[Person],
         staff: &[Staff],
         accounts: &[Account],
         phones: &[Phone],
+        ownership: &[PhoneOwnership],
     ) -> Vec<Order> {
         let mut rng = thread_rng();
+        let not_owner_chance = Bernoulli::new(self.order_not_owner_chance).unwrap();
         let servicemen = &staff
             .iter()
-            .filter_map(|staff| {
-                match accounts
+            .filter(|staff| {
+                accounts
                     .iter()
                     .find(|account| account.staff == staff.uuid)
                     .unwrap()
                     .role
-                {
-                    AccountRole::Serviceman => Some(staff.uuid),
-                    _ => None,
-                }
+                    == AccountRole::Serviceman
             })
             .collect::<Vec<_>>();
 
@@ -848,16 +1497,13 @@ impl Config {
 
         let shopmen = &staff
             .iter()
-            .filter_map(|staff| {
-                match accounts
+            .filter(|staff| {
+                accounts
                     .iter()
                     .find(|account| account.staff == staff.uuid)
                     .unwrap()
                     .role
-                {
-                    AccountRole::Shopman => Some(staff.uuid),
-                    _ => None,
-                }
+                    == AccountRole::Shopman
             })
             .collect::<Vec<_>>();
 
@@ -872,13 +1518,60 @@ impl Config {
                 let mut orders = Vec::with_capacity(count as usize);
 
                 for _ in 0..count {
-                    orders.push(Order::new_auto(
-                        people.iter().find(|c| c.uuid == phone.person).unwrap().uuid,
-                        phone.uuid,
-                        servicemen[rng.gen_range(0..servicemen.len())],
-                        shopmen[rng.gen_range(0..shopmen.len())],
-                        OrderStatus::ALL[rng.gen_range(0..OrderStatus::ALL.len())],
-                    ))
+                    let status = OrderStatus::ALL[rng.gen_range(0..OrderStatus::ALL.len())];
+                    let from = phone.meta.created.max(self.opening());
+                    // Unfinished orders are the recent ones
+                    let from = match status {
+                        OrderStatus::Processing
+                        | OrderStatus::PendingPayment
+                        | OrderStatus::Active => from.max(self.history_end - Duration::days(14)),
+                        OrderStatus::Complete | OrderStatus::Rejected => from,
+                    };
+                    let created = self.seasonal_time(&mut rng, from, self.history_end);
+                    // Order belongs to the shopman branch, servicemen of the branch are preferred
+                    let shopman = shopmen[rng.gen_range(0..shopmen.len())];
+                    let local = servicemen
+                        .iter()
+                        .filter(|serviceman| serviceman.branch == shopman.branch)
+                        .collect::<Vec<_>>();
+                    let serviceman = if local.is_empty() {
+                        servicemen[rng.gen_range(0..servicemen.len())]
+                    } else {
+                        local[rng.gen_range(0..local.len())]
+                    };
+
+                    let updated = rand_time(
+                        &mut rng,
+                        created,
+                        (created + Duration::days(14)).min(self.history_end),
+                    );
+                    // Phone is brought by its owner at the time, rarely by someone else
+                    let client = if not_owner_chance.sample(&mut rng) {
+                        people[rng.gen_range(0..people.len())].uuid
+                    } else {
+                        ownership
+                            .iter()
+                            .find(|o| {
+                                o.phone == phone.uuid && o.since <= created && created < o.until
+                            })
+                            .map_or(phone.person, |o| o.person)
+                    };
+
+                    orders.push(Order {
+                        completed: match status {
+                            OrderStatus::Complete => Some(updated),
+                            _ => None,
+                        },
+                        meta: MetaTime { updated, created },
+                        ..Order::new_auto(
+                            shopman.branch,
+                            client,
+                            phone.uuid,
+                            serviceman.uuid,
+                            shopman.uuid,
+                            status,
+                        )
+                    })
                 }
 
                 orders
@@ -886,6 +1579,74 @@ impl Config {
             .collect()
     }
 
+    /// Generate warranty claims on services of completed orders within their warranty period,
+    /// claims repeat the original services free of charge
+    pub fn gen_warranty_claim(
+        &self,
+        orders: &[Order],
+        order_service: &[OrderService],
+        services: &[Service],
+    ) -> (Vec<Order>, Vec<OrderService>) {
+        let mut rng = thread_rng();
+        let claim_chance = Bernoulli::new(self.warranty_claim_chance).unwrap();
+        let mut claims = Vec::new();
+        let mut claim_services = Vec::new();
+
+        for order in orders {
+            let completed = match order.completed {
+                Some(completed) if claim_chance.sample(&mut rng) => completed,
+                _ => continue,
+            };
+            let covered = order_service
+                .iter()
+                .filter(|os| os.order == order.uuid)
+                .filter_map(|os| {
+                    let service = services.iter().find(|s| s.uuid == os.service).unwrap();
+                    let expires = completed + Duration::days(service.warranty_days as i64);
+                    (expires > completed && completed < self.history_end)
+                        .then(|| (service.uuid, expires.min(self.history_end)))
+                })
+                .collect::<Vec<_>>();
+            if covered.is_empty() {
+                continue;
+            }
+
+            let (service, expires) = covered[rng.gen_range(0..covered.len())];
+            let created = rand_time(&mut rng, completed, expires);
+            let status = [
+                OrderStatus::Processing,
+                OrderStatus::Active,
+                OrderStatus::Complete,
+            ][rng.gen_range(0..3)];
+            let updated = match status {
+                OrderStatus::Processing => created,
+                _ => rand_time(
+                    &mut rng,
+                    created,
+                    (created + Duration::days(7)).min(self.history_end),
+                ),
+            };
+            let claim = Order {
+                status,
+                completed: match status {
+                    OrderStatus::Complete => Some(updated),
+                    _ => None,
+                },
+                meta: MetaTime { updated, created },
+                ..Order::new_warranty_claim(order, order.serviceman, order.shopman)
+            };
+
+            claim_services.push(OrderService::new(
+                claim.uuid,
+                service,
+                Money::zero(Currency::BASE),
+            ));
+            claims.push(claim);
+        }
+
+        (claims, claim_services)
+    }
+
     pub fn gen_order_service(
         &self,
         orders: &[Order],
@@ -918,12 +1679,13 @@ impl Config {
                 } else {
                     let spm = spm_list[rng.gen_range(0..spm_list.len())];
 
-                    OrderService::new(order.uuid, spm.service, spm.price)
+                    OrderService::new(order.uuid, spm.service, spm.price.clone())
                 }
             })
             .collect()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn gen_order_warehouse(
         &self,
         orders: &[Order],
@@ -975,28 +1737,156 @@ impl Config {
                     .iter()
                     .filter(|item| item.component == component.uuid)
                     .collect();
+                // Components from the order branch are preferred
+                let local: Vec<&Warehouse> = items
+                    .iter()
+                    .copied()
+                    .filter(|item| item.branch == order.branch)
+                    .collect();
+                let items = if local.is_empty() { items } else { local };
                 let item = items[rng.gen_range(0..items.len())]; // TODO: Decrease if amount is positive
 
-                OrderWarehouse::new(order.uuid, item.uuid, 1, item.price)
+                let price = if order.is_warranty_claim() {
+                    Money::zero(Currency::BASE)
+                } else {
+                    item.price.clone()
+                };
+
+                OrderWarehouse::new(order.uuid, item.uuid, 1, price)
             })
             .collect()
     }
 
+    /// Generate single invoice for every order which left processing,
+    /// billed amount is the sum of order services and parts
+    pub fn gen_invoice(
+        &self,
+        orders: &[Order],
+        order_service: &[OrderService],
+        order_warehouse: &[OrderWarehouse],
+    ) -> Vec<Invoice> {
+        let mut rng = thread_rng();
+
+        orders
+            .iter()
+            .filter(|order| {
+                !matches!(order.status, OrderStatus::Processing) && !order.is_warranty_claim()
+            })
+            .map(|order| {
+                let services = order_service
+                    .iter()
+                    .filter(|os| os.order == order.uuid)
+                    .map(|os| os.price.to_minor())
+                    .sum::<i64>();
+                let parts = order_warehouse
+                    .iter()
+                    .filter(|ow| ow.order == order.uuid)
+                    .map(|ow| ow.price.to_minor() * ow.amount as i64)
+                    .sum::<i64>();
+
+                Invoice {
+                    meta: MetaTime::at(rand_time(&mut rng, order.meta.created, order.meta.updated)),
+                    ..Invoice::new_auto(
+                        order.uuid,
+                        Money::from_minor(services + parts, Currency::BASE).unwrap(),
+                        order.shopman,
+                    )
+                }
+            })
+            .collect()
+    }
+
+    /// Generate payments of the invoices, orders pending payment are paid partially (if at all)
+    pub fn gen_payment(&self, invoices: &[Invoice], orders: &[Order]) -> Vec<Payment> {
+        let mut rng = thread_rng();
+        let method_weights = WeightedIndex::new(self.payment_method_weights).unwrap();
+        let split_chance = Bernoulli::new(self.payment_split_chance).unwrap();
+        let partial_chance = Bernoulli::new(self.payment_partial_chance).unwrap();
+        let refund_chance = Bernoulli::new(self.payment_refund_chance).unwrap();
+        let mut payments = Vec::new();
+
+        for invoice in invoices {
+            let order = orders.iter().find(|o| o.uuid == invoice.order).unwrap();
+            let total = invoice.amount.to_minor();
+            let parts = match order.status {
+                OrderStatus::Processing | OrderStatus::Rejected => continue,
+                OrderStatus::PendingPayment => {
+                    if total < 2 || !partial_chance.sample(&mut rng) {
+                        continue;
+                    }
+                    vec![rng.gen_range(1..total)]
+                }
+                OrderStatus::Active | OrderStatus::Complete => {
+                    if total >= 2 && split_chance.sample(&mut rng) {
+                        let first = rng.gen_range(1..total);
+                        vec![first, total - first]
+                    } else if total > 0 {
+                        vec![total]
+                    } else {
+                        vec![]
+                    }
+                }
+            };
+
+            let mut paid = invoice.meta.created;
+            for amount in parts {
+                paid = rand_time(&mut rng, paid, order.meta.updated.max(paid));
+                payments.push(Payment {
+                    meta: MetaTime::at(paid),
+                    ..Payment::new_auto(
+                        invoice.uuid,
+                        PaymentKind::Payment,
+                        PaymentMethod::ALL[method_weights.sample(&mut rng)],
+                        Money::from_minor(amount, Currency::BASE).unwrap(),
+                        order.shopman,
+                    )
+                });
+            }
+
+            if let OrderStatus::Complete = order.status {
+                if total >= 2 && refund_chance.sample(&mut rng) {
+                    payments.push(Payment {
+                        meta: MetaTime::at(rand_time(&mut rng, paid, self.history_end)),
+                        ..Payment::new_auto(
+                            invoice.uuid,
+                            PaymentKind::Refund,
+                            PaymentMethod::ALL[method_weights.sample(&mut rng)],
+                            Money::from_minor(rng.gen_range(1..total), Currency::BASE).unwrap(),
+                            order.shopman,
+                        )
+                    });
+                }
+            }
+        }
+
+        payments
+    }
+
     pub fn gen_full(&self) -> FullPack {
         // Low-level
         let component_kind_store = self.gen_component_kind();
         let service_store = self.gen_service();
         let position_store = self.gen_positions();
         let manufacturer_store = self.gen_manufacturer();
-        let person_store = self.gen_person();
+        let mut person_store = self.gen_person();
         let supplier_store = self.gen_supplier();
+        let branch_store = self.gen_branch();
+        let exchange_rate_store = self.gen_exchange_rate();
 
         // First group
         let labor_contract_store = self.gen_labor_contract(&person_store);
+        // Employees get registered not later than their labor contract
+        for contract in &labor_contract_store {
+            if let Some(person) = person_store.iter_mut().find(|p| p.uuid == contract.person) {
+                if person.meta.created > contract.meta.created {
+                    person.meta = MetaTime::at(contract.meta.created);
+                }
+            }
+        }
         let phone_model_store = self.gen_phone_model(&manufacturer_store);
 
         // Second group
-        let staff_store = self.gen_staff(&labor_contract_store, &position_store);
+        let staff_store = self.gen_staff(&labor_contract_store, &position_store, &branch_store);
         let component_store = self.gen_component(
             &manufacturer_store,
             &component_kind_store,
@@ -1005,32 +1895,62 @@ impl Config {
         let phone_store = self.gen_phone(&person_store, &phone_model_store);
 
         // Third group
-        let account_store = self.gen_account(&staff_store, &position_store);
+        let account_store = self.gen_account(&staff_store, &position_store, &labor_contract_store);
+        let phone_ownership_store = self.gen_phone_ownership(&person_store, &phone_store);
 
         // Fourth group
-        let order_store = self.gen_order(&person_store, &staff_store, &account_store, &phone_store);
+        let mut order_store = self.gen_order(
+            &person_store,
+            &staff_store,
+            &account_store,
+            &phone_store,
+            &phone_ownership_store,
+        );
         let supply_contract_store =
             self.gen_supply_contract(&supplier_store, &staff_store, &account_store);
 
         // Fifth group
-        let supply_store = self.gen_supply(&supply_contract_store, &staff_store, &account_store);
         let service_phone_model_store =
             self.gen_service_phone_model(&service_store, &phone_model_store);
-        let warehouse_store = self.gen_warehouse(
+        let mut warehouse_store = self.gen_warehouse(
             &component_store,
             &component_kind_store,
             &supply_contract_store,
+            &branch_store,
+        );
+        let supply_contract_item_store = self.gen_supply_contract_item(
+            &supply_contract_store,
+            &warehouse_store,
+            &exchange_rate_store,
+        );
+        let supply_store = self.gen_supply(
+            &supply_contract_store,
+            &supply_contract_item_store,
+            &staff_store,
+            &account_store,
         );
 
         // Sixth group
-        let order_service_store = self.gen_order_service(
+        let mut order_service_store = self.gen_order_service(
             &order_store,
             &phone_store,
             &phone_model_store,
             &service_phone_model_store,
         );
-        let warehouse_supply_store =
-            self.gen_warehouse_supply(&warehouse_store, &supply_store, &supply_contract_store);
+        let (claim_store, claim_service_store) =
+            self.gen_warranty_claim(&order_store, &order_service_store, &service_store);
+        order_store.extend(claim_store);
+        order_service_store.extend(claim_service_store);
+        let warehouse_supply_store = self.gen_warehouse_supply(
+            &warehouse_store,
+            &supply_store,
+            &supply_contract_store,
+            &supply_contract_item_store,
+        );
+        let supply_item_store =
+            self.gen_supply_item(&supply_store, &warehouse_supply_store, &warehouse_store);
+        let stock_transfer_store =
+            self.gen_stock_transfer(&mut warehouse_store, &branch_store, &staff_store);
 
         // Seventh group
         let order_warehouse_store = self.gen_order_warehouse(
@@ -1044,6 +1964,11 @@ impl Config {
             &warehouse_store,
         );
 
+        // Eighth group
+        let invoice_store =
+            self.gen_invoice(&order_store, &order_service_store, &order_warehouse_store);
+        let payment_store = self.gen_payment(&invoice_store, &order_store);
+
         (
             component_kind_store,
             service_store,
@@ -1051,18 +1976,26 @@ impl Config {
             manufacturer_store,
             person_store,
             supplier_store,
+            branch_store,
+            exchange_rate_store,
             labor_contract_store,
             phone_model_store,
             staff_store,
             component_store,
             phone_store,
             account_store,
+            phone_ownership_store,
             supply_contract_store,
             order_store,
             supply_store,
             warehouse_store,
+            stock_transfer_store,
+            invoice_store,
+            payment_store,
             service_phone_model_store,
+            supply_contract_item_store,
             warehouse_supply_store,
+            supply_item_store,
             order_service_store,
             order_warehouse_store,
         )
@@ -1073,8 +2006,12 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             person_count: 250,
+            person_duplicate_chance: 0.02,
             supplier_count: 50,
             position_salary_scatter: 0.005,
+            branch_count: 3,
+            exchange_rate_period: 7,
+            exchange_rate_scatter: 0.02,
             labor_contract_count: 25,
             labor_contract_weights: [2, 2, 14, 1, 1],
             staff_vacation_chance: 0.15,
@@ -1082,23 +2019,78 @@ impl Default for Config {
             supply_contract_chance: 0.8,
             supply_contract_count: 3,
             phone_count: vec![10, 1],
+            phone_resale_chance: 0.05,
+            warehouse_purchase_ratio: (0.5, 0.8),
             warehouse_variations: 5,
             warehouse_stock: (1, 3),
             warehouse_item_price_scatter: 0.5,
+            stock_transfer_chance: 0.1,
+            stock_transfer_weights: [1, 8, 1],
             order_count: 3,
             order_not_owner_chance: 0.01,
+            warranty_claim_chance: 0.1,
+            payment_method_weights: [5, 4, 1],
+            payment_split_chance: 0.2,
+            payment_partial_chance: 0.3,
+            payment_refund_chance: 0.05,
             supply_count: 5,
+            supply_discrepancy_chance: 0.1,
+            history_end: MetaTime::now(),
+            history_days: 730,
+            opening_days: 60,
+            order_seasonality: [7, 6, 7, 8, 8, 9, 10, 10, 11, 9, 10, 14],
         }
     }
 }
 
 #[inline]
-pub fn country_match(id: usize) -> &'static str {
-    match id {
+pub fn country_match(id: usize) -> Country {
+    let code = match id {
         0 => "US",
         1 => "CN",
         2 => "TW",
         _ => unreachable!(),
+    };
+
+    code.parse().expect("valid country code")
+}
+
+/// Currency of the contracts with suppliers from the country
+pub fn country_currency(country: Country) -> Currency {
+    let code = match country.as_str() {
+        "US" => "USD",
+        "CN" => "CNY",
+        "TW" => "TWD",
+        _ => return Currency::BASE,
+    };
+
+    code.parse().expect("valid currency code")
+}
+
+/// Rate of the currency effective on the day (the earliest one if there is none yet)
+pub fn rate_at(rates: &[ExchangeRate], currency: Currency, day: NaiveDate) -> BigDecimal {
+    if currency == Currency::BASE {
+        return BigDecimal::from(1);
+    }
+
+    let rates = rates.iter().filter(|rate| rate.currency == currency);
+    rates
+        .clone()
+        .filter(|rate| rate.day <= day)
+        .max_by_key(|rate| rate.day)
+        .or_else(|| rates.min_by_key(|rate| rate.day))
+        .map(|rate| rate.rate.clone())
+        .expect("rates are generated for every currency")
+}
+
+/// Uniformly distributed time in `[from, to)`, `from` if the range is empty
+pub fn rand_time(rng: &mut ThreadRng, from: DateTime<Utc>, to: DateTime<Utc>) -> DateTime<Utc> {
+    let seconds = (to - from).num_seconds();
+
+    if seconds > 0 {
+        from + Duration::seconds(rng.gen_range(0..seconds))
+    } else {
+        from
     }
 }
 
@@ -0,0 +1,204 @@
+use core::fmt;
+
+use sqlx::{query_as, Error, Executor, Postgres};
+
+use crate::{search, tables::Table};
+
+/// Secondary indexes, primary keys and unique constraints get their own with the tables
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Index {
+    PersonNameSearch,
+    PersonNameText,
+    PersonEmailSearch,
+    PersonPhoneSearch,
+    PhoneImeiSearch,
+    PhoneModelNameSearch,
+    ComponentNameSearch,
+    SupplierNameSearch,
+    /// Referencing column, serves joins and the reference checks of deleted rows
+    ForeignKey(Table, &'static str),
+    /// `(meta).created` of the rows reported by period
+    Created(Table),
+    /// Other column the rows are filtered by
+    Column(Table, &'static str),
+    /// Column unique among the rows out of the [`trash`](crate::trash), so a deleted row
+    /// doesn't block a new one with the same value
+    Unique(Table, &'static str),
+}
+
+impl Index {
+    pub const ALL: [Self; 62] = [
+        Self::PersonNameSearch,
+        Self::PersonNameText,
+        Self::PersonEmailSearch,
+        Self::PersonPhoneSearch,
+        Self::PhoneImeiSearch,
+        Self::PhoneModelNameSearch,
+        Self::ComponentNameSearch,
+        Self::SupplierNameSearch,
+        // Foreign keys not leading any primary key or unique constraint
+        Self::ForeignKey(Table::LaborContract, "person"),
+        Self::ForeignKey(Table::Staff, "contract"),
+        Self::ForeignKey(Table::Staff, "position"),
+        Self::ForeignKey(Table::Staff, "branch"),
+        Self::ForeignKey(Table::PhoneModel, "manufacturer"),
+        Self::ForeignKey(Table::Phone, "person"),
+        Self::ForeignKey(Table::Phone, "model"),
+        Self::ForeignKey(Table::SupplyContract, "supplier"),
+        Self::ForeignKey(Table::SupplyContract, "manager"),
+        Self::ForeignKey(Table::Supply, "contract"),
+        Self::ForeignKey(Table::Supply, "staff"),
+        Self::ForeignKey(Table::Supply, "approver"),
+        Self::ForeignKey(Table::PhoneOwnership, "person"),
+        Self::ForeignKey(Table::PersonMerge, "survivor"),
+        Self::ForeignKey(Table::Account, "staff"),
+        Self::ForeignKey(Table::Component, "kind"),
+        Self::ForeignKey(Table::Component, "phone_model"),
+        Self::ForeignKey(Table::Component, "manufacturer"),
+        Self::ForeignKey(Table::Warehouse, "component"),
+        Self::ForeignKey(Table::Warehouse, "supplier"),
+        Self::ForeignKey(Table::Order, "branch"),
+        Self::ForeignKey(Table::Order, "client"),
+        Self::ForeignKey(Table::Order, "phone"),
+        Self::ForeignKey(Table::Order, "serviceman"),
+        Self::ForeignKey(Table::Order, "shopman"),
+        Self::ForeignKey(Table::Order, "warranty_of"),
+        Self::ForeignKey(Table::StockTransfer, "item"),
+        Self::ForeignKey(Table::StockTransfer, "destination"),
+        Self::ForeignKey(Table::StockTransfer, "sender"),
+        Self::ForeignKey(Table::StockTransfer, "receiver"),
+        Self::ForeignKey(Table::Invoice, "order"),
+        Self::ForeignKey(Table::Invoice, "issuer"),
+        Self::ForeignKey(Table::Payment, "invoice"),
+        Self::ForeignKey(Table::Payment, "cashier"),
+        Self::ForeignKey(Table::PriceChange, "reverts"),
+        Self::ForeignKey(Table::WarehousePrice, "item"),
+        Self::ForeignKey(Table::WarehousePrice, "change"),
+        Self::ForeignKey(Table::ServicePhoneModel, "phone_model"),
+        Self::ForeignKey(Table::ServicePrice, "service"),
+        Self::ForeignKey(Table::ServicePrice, "change"),
+        Self::ForeignKey(Table::SupplyContractItem, "component"),
+        Self::ForeignKey(Table::WarehouseSupply, "supply"),
+        Self::ForeignKey(Table::SupplyItem, "component"),
+        Self::ForeignKey(Table::OrderService, "order"),
+        Self::ForeignKey(Table::OrderService, "service"),
+        Self::ForeignKey(Table::OrderWarehouse, "order"),
+        Self::ForeignKey(Table::OrderWarehouse, "item"),
+        Self::Created(Table::Order),
+        Self::Created(Table::Payment),
+        Self::Created(Table::Supply),
+        Self::Column(Table::WarehouseSupply, "created"),
+        Self::Unique(Table::Person, "email"),
+        Self::Unique(Table::Person, "phone"),
+        Self::Unique(Table::Phone, "imei"),
+    ];
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::PersonNameSearch => "Person_name_trgm".to_string(),
+            Self::PersonNameText => "Person_name_tsv".to_string(),
+            Self::PersonEmailSearch => "Person_email_trgm".to_string(),
+            Self::PersonPhoneSearch => "Person_phone_trgm".to_string(),
+            Self::PhoneImeiSearch => "Phone_imei_trgm".to_string(),
+            Self::PhoneModelNameSearch => "PhoneModel_name_trgm".to_string(),
+            Self::ComponentNameSearch => "Component_name_trgm".to_string(),
+            Self::SupplierNameSearch => "Supplier_name_trgm".to_string(),
+            Self::ForeignKey(table, column) | Self::Column(table, column) => {
+                format!("{table}_{column}_idx")
+            }
+            Self::Created(table) => format!("{table}_created_idx"),
+            // Same as the unique constraints they replace
+            Self::Unique(table, column) => format!("{table}_{column}_key"),
+        }
+    }
+
+    pub fn table(&self) -> Table {
+        match self {
+            Self::PersonNameSearch
+            | Self::PersonNameText
+            | Self::PersonEmailSearch
+            | Self::PersonPhoneSearch => Table::Person,
+            Self::PhoneImeiSearch => Table::Phone,
+            Self::PhoneModelNameSearch => Table::PhoneModel,
+            Self::ComponentNameSearch => Table::Component,
+            Self::SupplierNameSearch => Table::Supplier,
+            Self::ForeignKey(table, _)
+            | Self::Created(table)
+            | Self::Column(table, _)
+            | Self::Unique(table, _) => *table,
+        }
+    }
+
+    /// Indexes serving [`search`], added by [`Migration::Search`](crate::migrations::Migration::Search)
+    pub fn is_search(&self) -> bool {
+        !matches!(
+            self,
+            Self::ForeignKey(..) | Self::Created(_) | Self::Column(..) | Self::Unique(..)
+        )
+    }
+
+    /// Method and indexed expressions, the search queries use the same expressions
+    fn definition(&self) -> String {
+        match self {
+            Self::PersonNameSearch => format!("gin (({}) gin_trgm_ops)", search::PERSON_NAME),
+            Self::PersonNameText => format!("gin ({})", search::PERSON_NAME_TEXT),
+            Self::PersonEmailSearch => "gin (email gin_trgm_ops)".to_string(),
+            Self::PersonPhoneSearch => format!("gin (({}) gin_trgm_ops)", search::PERSON_PHONE),
+            Self::PhoneImeiSearch => "gin ((imei::text) gin_trgm_ops)".to_string(),
+            Self::PhoneModelNameSearch | Self::ComponentNameSearch | Self::SupplierNameSearch => {
+                "gin (name gin_trgm_ops)".to_string()
+            }
+            Self::ForeignKey(_, column) | Self::Column(_, column) => {
+                format!(r#"btree ("{column}")"#)
+            }
+            Self::Unique(_, column) => format!(r#"btree ("{column}") WHERE deleted_at IS NULL"#),
+            Self::Created(_) => "btree (((meta).created))".to_string(),
+        }
+    }
+
+    pub fn create(&self) -> String {
+        format!(
+            r#"CREATE {}INDEX "{}" ON "{}" USING {};"#,
+            match self {
+                Self::Unique(..) => "UNIQUE ",
+                _ => "",
+            },
+            self.name(),
+            self.table(),
+            self.definition()
+        )
+    }
+
+    pub fn drop(&self) -> String {
+        format!(r#"DROP INDEX "{}";"#, self.name())
+    }
+
+    pub async fn exists<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
+        match query_as::<Postgres, (bool,)>(
+            r#"SELECT true FROM pg_catalog.pg_class
+WHERE relkind = 'i' AND relname = $1 AND relnamespace = current_schema()::regnamespace;"#,
+        )
+        .bind(self.name())
+        .fetch_one(executor)
+        .await
+        {
+            Ok(_) => Ok(true),
+            Err(err) => {
+                if let Error::RowNotFound = err {
+                    Ok(false)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Index {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
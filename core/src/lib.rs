@@ -6,12 +6,24 @@ pub use uuid;
 
 pub use utils::{PgQuery, PgQueryAs};
 
+pub mod changes;
+pub mod drift;
+pub mod dump;
+pub mod explain;
 pub mod extensions;
 pub mod functions;
 pub mod generator;
+pub mod indexes;
+pub mod migrations;
+pub mod privacy;
 pub mod procedures;
+pub mod receipt;
+pub mod schema;
+pub mod script;
+pub mod search;
 pub mod tables;
 pub mod traits;
+pub mod trash;
 pub mod triggers;
 pub mod types;
 pub mod utils;
@@ -19,7 +31,6 @@ pub mod views;
 
 // TODO: Dataset generator
 // TODO: Batch insert
-// TODO: Datetime scattering
 // FIX: Replace unwrap() in generators with proper handler
 // TODO: Statuses for OrderService and OrderWarehouse
 // FIX: Remove no action
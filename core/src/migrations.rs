@@ -0,0 +1,925 @@
+//! Migrations of databases created by previous releases
+//!
+//! Unlike drift fixes they rewrite existing data, every migration detects on its own
+//! whether the database still needs it, so running them twice is harmless
+
+use core::fmt;
+
+use anyhow::{Context, Result};
+use sqlx::{query, query_as, Error, Executor, PgPool, Postgres};
+
+use crate::{
+    drift::or_replace,
+    functions::Function,
+    indexes::Index,
+    procedures::Procedure,
+    schema::Schema,
+    tables::{
+        ExchangeRate, Order, PersonMerge, PhoneOwnership, SupplyContractItem, SupplyItem, Table,
+    },
+    traits::Recreatable,
+    trash,
+    triggers::Trigger,
+    types::{
+        Bic, Country, Currency, Iban, Imei, Money, PaymentKind, PaymentMethod, PriceChangeStatus,
+        PriceScope, TransferStatus,
+    },
+    views::View,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub enum Migration {
+    /// Branches get their staff, stock and orders, stock is transferred between branches
+    Branches,
+    /// Orders get tracking codes
+    OrderTracking,
+    /// Orders get invoices and payments
+    Invoices,
+    /// Services get warranty period, orders get completion time and the order under warranty
+    Warranty,
+    /// Prices get history of changes and indexation
+    PriceHistory,
+    /// `money` columns become [`Money`] (`monetary`), price coefficients become `numeric`
+    MonetaryPrices,
+    /// Supply contracts get currency, supplied items get purchase price, exchange rates are added
+    SupplyCurrencies,
+    /// Supply contracts get validity period and items, supplies get due and delivery time
+    SupplierTerms,
+    /// Supplies get purchase order lines and approving manager
+    PurchaseOrders,
+    /// Bank details, IMEIs and country codes are checked by domains
+    ValidatedIdentifiers,
+    /// Phones get ownership history, IMEIs become unique
+    DeviceHistory,
+    /// Duplicate people get merged with their contacts kept, `pg_trgm` compares names
+    PersonMerges,
+    /// People get anonymization time
+    Anonymization,
+    /// Business entities get deletion time, deleted rows are kept until purged
+    SoftDelete,
+    /// Updates check the row version, supply procedures take it as an argument
+    RowVersions,
+    /// Every table notifies the app listeners about its changes
+    LiveUpdates,
+    /// Names, emails, phone numbers and IMEIs get search indexes
+    Search,
+    /// Foreign keys and creation time of the reported rows get indexes
+    Indexes,
+    /// Reports get materialized views refreshed on schedule
+    ReportViews,
+    /// Routines resolve the objects of their own schema whatever path the caller has
+    PinnedRoutines,
+    /// Tracking codes are generated by the application only
+    TrackingCodes,
+    /// Contract currency default and exchange rate check use `base_currency()`
+    BaseCurrencyDefaults,
+    /// Rest of the business entities get deletion time, emails, phone numbers and IMEIs
+    /// are unique among the rows out of the trash
+    TrashedEntities,
+    /// Components, manufacturers, phone models and staff get row versions, deletes, restores
+    /// and stock transfers check the versions
+    VersionedEntities,
+    /// Invoices and payments are in the base currency only
+    BaseCurrencyPayments,
+}
+
+impl Migration {
+    pub const ALL: [Self; 25] = [
+        Self::Branches,
+        Self::OrderTracking,
+        Self::Invoices,
+        Self::Warranty,
+        Self::PriceHistory,
+        Self::MonetaryPrices,
+        Self::SupplyCurrencies,
+        Self::SupplierTerms,
+        Self::PurchaseOrders,
+        Self::ValidatedIdentifiers,
+        Self::DeviceHistory,
+        Self::PersonMerges,
+        Self::Anonymization,
+        Self::SoftDelete,
+        Self::RowVersions,
+        Self::LiveUpdates,
+        Self::Search,
+        Self::Indexes,
+        Self::ReportViews,
+        Self::PinnedRoutines,
+        Self::TrackingCodes,
+        Self::BaseCurrencyDefaults,
+        Self::TrashedEntities,
+        Self::VersionedEntities,
+        Self::BaseCurrencyPayments,
+    ];
+
+    /// Tables of [`Migration::Branches`] as they were created then, later migrations change them
+    const BRANCH_TABLES: [&'static str; 2] = [
+        r#"CREATE TABLE "Branch" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name text UNIQUE NOT NULL,
+    address text NOT NULL,
+    phone text NOT NULL CHECK (length(phone) <= 18),
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#,
+        r#"CREATE TABLE "StockTransfer" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    item uuid NOT NULL REFERENCES "Warehouse" ON DELETE restrict ON UPDATE cascade,
+    destination uuid NOT NULL REFERENCES "Branch" ON DELETE restrict ON UPDATE cascade,
+    amount int NOT NULL CHECK (amount > 0),
+    status "TransferStatus" NOT NULL DEFAULT 'InTransit',
+    sender uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
+    receiver uuid REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#,
+    ];
+
+    /// Tables of [`Migration::Invoices`] as they were created then
+    const INVOICE_TABLES: [&'static str; 2] = [
+        r#"CREATE TABLE "Invoice" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    "order" uuid NOT NULL REFERENCES "Order" ON DELETE restrict ON UPDATE cascade,
+    amount money NOT NULL CHECK (amount >= 0::money),
+    issuer uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#,
+        r#"CREATE TABLE "Payment" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    invoice uuid NOT NULL REFERENCES "Invoice" ON DELETE restrict ON UPDATE cascade,
+    kind "PaymentKind" NOT NULL DEFAULT 'Payment',
+    method "PaymentMethod" NOT NULL,
+    amount money NOT NULL CHECK (amount > 0::money),
+    cashier uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#,
+    ];
+
+    /// Tables of [`Migration::PriceHistory`] as they were created then
+    const PRICE_HISTORY_TABLES: [&'static str; 3] = [
+        r#"CREATE TABLE "PriceChange" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    coef real NOT NULL CHECK (coef > 0),
+    scope "PriceScope" NOT NULL DEFAULT 'All',
+    target uuid CHECK ((scope = 'All') = (target IS NULL)),
+    status "PriceChangeStatus" NOT NULL DEFAULT 'Applied',
+    reverts uuid REFERENCES "PriceChange" ON DELETE restrict ON UPDATE cascade,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#,
+        r#"CREATE TABLE "WarehousePrice" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    item uuid NOT NULL REFERENCES "Warehouse" ON DELETE cascade ON UPDATE cascade,
+    price money NOT NULL,
+    effective timestamptz NOT NULL DEFAULT now(),
+    change uuid REFERENCES "PriceChange" ON DELETE restrict ON UPDATE cascade
+);"#,
+        r#"CREATE TABLE "ServicePrice" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    service uuid NOT NULL,
+    phone_model uuid NOT NULL,
+    price money NOT NULL,
+    effective timestamptz NOT NULL DEFAULT now(),
+    change uuid REFERENCES "PriceChange" ON DELETE restrict ON UPDATE cascade,
+    FOREIGN KEY (service, phone_model) REFERENCES "ServicePhoneModel" ON DELETE cascade ON UPDATE cascade
+);"#,
+    ];
+
+    /// Tables of the [`trash`] as of [`Migration::SoftDelete`]
+    const SOFT_DELETE_TABLES: [Table; 9] = [
+        Table::Invoice,
+        Table::Phone,
+        Table::Component,
+        Table::Person,
+        Table::Branch,
+        Table::Position,
+        Table::Service,
+        Table::Supplier,
+        Table::Manufacturer,
+    ];
+
+    /// Identifier columns of `text` type before [`Migration::ValidatedIdentifiers`]
+    /// with their value in the format of the domain, separators and letter case are
+    /// the only things normalized
+    const IDENTIFIER_COLUMNS: [(Table, &'static str, &'static str, &'static str); 5] = [
+        (
+            Table::Supplier,
+            "iban",
+            "upper(replace(iban, ' ', ''))",
+            Iban::NAME,
+        ),
+        (Table::Supplier, "swift", "upper(swift)", Bic::NAME),
+        (Table::Supplier, "country", "upper(country)", Country::NAME),
+        (
+            Table::Manufacturer,
+            "country",
+            "upper(country)",
+            Country::NAME,
+        ),
+        (
+            Table::Phone,
+            "imei",
+            "regexp_replace(imei, '[^0-9]', '', 'g')",
+            Imei::NAME,
+        ),
+    ];
+
+    /// Columns of the `money` type before [`Migration::MonetaryPrices`]
+    const MONEY_COLUMNS: [(Table, &'static str); 9] = [
+        (Table::Position, "salary"),
+        (Table::Warehouse, "price"),
+        (Table::Invoice, "amount"),
+        (Table::Payment, "amount"),
+        (Table::WarehousePrice, "price"),
+        (Table::ServicePhoneModel, "price"),
+        (Table::ServicePrice, "price"),
+        (Table::OrderService, "price"),
+        (Table::OrderWarehouse, "price"),
+    ];
+
+    /// Phones sharing the IMEI: the earliest one is kept (`keep`), every phone is owned
+    /// by its person until the next one is registered (`until`), the last person (`owner`)
+    /// is the current owner
+    const PHONE_DUPLICATES: &'static str = r#"WITH d AS (
+    SELECT uuid, person, (meta).created as since,
+        first_value(uuid) OVER w as keep,
+        lead((meta).created) OVER w as until,
+        last_value(person) OVER w as owner
+    FROM "Phone"
+    WINDOW w AS (
+        PARTITION BY imei ORDER BY (meta).created, uuid
+        ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING
+    )
+)"#;
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Branches => "branches",
+            Self::OrderTracking => "order_tracking",
+            Self::Invoices => "invoices",
+            Self::Warranty => "warranty",
+            Self::PriceHistory => "price_history",
+            Self::MonetaryPrices => "monetary_prices",
+            Self::SupplyCurrencies => "supply_currencies",
+            Self::SupplierTerms => "supplier_terms",
+            Self::PurchaseOrders => "purchase_orders",
+            Self::ValidatedIdentifiers => "validated_identifiers",
+            Self::DeviceHistory => "device_history",
+            Self::PersonMerges => "person_merges",
+            Self::Anonymization => "anonymization",
+            Self::SoftDelete => "soft_delete",
+            Self::RowVersions => "row_versions",
+            Self::LiveUpdates => "live_updates",
+            Self::Search => "search",
+            Self::Indexes => "indexes",
+            Self::ReportViews => "report_views",
+            Self::PinnedRoutines => "pinned_routines",
+            Self::TrackingCodes => "tracking_codes",
+            Self::BaseCurrencyDefaults => "base_currency_defaults",
+            Self::TrashedEntities => "trashed_entities",
+            Self::VersionedEntities => "versioned_entities",
+            Self::BaseCurrencyPayments => "base_currency_payments",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            Self::Branches => {
+                "Add branches and stock transfers, past staff, stock and orders are assigned to the new 'Main' branch"
+            }
+            Self::OrderTracking => "Add order tracking codes, past orders get random ones",
+            Self::Invoices => "Add invoices and payments, past orders have none",
+            Self::Warranty => {
+                "Add service warranty periods and warranty orders, past completed orders get their last update as completion time"
+            }
+            Self::PriceHistory => {
+                "Add price history and indexation, past prices stay effective until the first change"
+            }
+            Self::MonetaryPrices => "Store prices as numeric amount with currency code",
+            Self::SupplyCurrencies => {
+                "Add exchange rates and contract currencies, past supplies get the item price of the supply day as purchase price"
+            }
+            Self::SupplierTerms => {
+                "Add contract items and validity periods, past contracts are valid since signing and delivered supplies get their last update as delivery time"
+            }
+            Self::PurchaseOrders => {
+                "Add supply lines and approvers, past supplies get lines from their supplied items"
+            }
+            Self::ValidatedIdentifiers => {
+                "Check IBAN, BIC, IMEI and country codes, nothing is changed while any past identifier fails the checks, the failing rows are listed to be fixed by hand"
+            }
+            Self::DeviceHistory => {
+                "Add phone ownership history and unique IMEIs, phones sharing the IMEI are merged into the earliest one with the others' owners as previous ones"
+            }
+            Self::PersonMerges => "Add search of duplicate people and merges of them",
+            Self::Anonymization => "Add anonymization of people, nobody is anonymized yet",
+            Self::SoftDelete => "Add trash of deleted rows, nothing is deleted yet",
+            Self::RowVersions => {
+                "Reject updates of rows changed since they were read, routines are recreated with row version arguments"
+            }
+            Self::LiveUpdates => "Notify listeners about changed tables",
+            Self::Search => "Add search indexes of clients, phones, components and suppliers",
+            Self::Indexes => "Add indexes of foreign keys and creation time of orders, payments and supplies",
+            Self::ReportViews => {
+                "Add materialized views of daily revenue, stock valuation and serviceman workload"
+            }
+            Self::PinnedRoutines => {
+                "Pin search path of functions and procedures, they are recreated with the schema of the database"
+            }
+            Self::TrackingCodes => {
+                "Drop the database default of order tracking codes, existing codes are kept"
+            }
+            Self::BaseCurrencyDefaults => {
+                "Take the default contract currency and the currency excluded from exchange rates from base_currency()"
+            }
+            Self::TrashedEntities => {
+                "Add trash of orders, stock, phone models, supply contracts, supplies and staff, emails, phone numbers and IMEIs of deleted rows may be taken again"
+            }
+            Self::VersionedEntities => {
+                "Add row versions of components, manufacturers, phone models and staff, stock transfer routines are recreated with row version arguments"
+            }
+            Self::BaseCurrencyPayments => {
+                "Reject invoices and payments in other currencies than base_currency(), nothing is changed while any past one is in another currency"
+            }
+        }
+    }
+
+    pub async fn is_pending<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
+        match self {
+            Self::Branches => Table::Branch.exists(executor).await.map(|e| !e),
+            Self::OrderTracking => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_attribute a
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'Order' AND a.attname = 'tracking' AND NOT a.attisdropped;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_none()),
+            Self::Invoices => Table::Invoice.exists(executor).await.map(|e| !e),
+            Self::Warranty => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_attribute a
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'Service' AND a.attname = 'warranty_days' AND NOT a.attisdropped;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_none()),
+            Self::PriceHistory => Table::PriceChange.exists(executor).await.map(|e| !e),
+            Self::MonetaryPrices => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_attribute a
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'Warehouse' AND a.attname = 'price'
+    AND a.atttypid = 'money'::regtype;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_some()),
+            Self::SupplyCurrencies => Table::ExchangeRate.exists(executor).await.map(|e| !e),
+            Self::SupplierTerms => Table::SupplyContractItem.exists(executor).await.map(|e| !e),
+            Self::PurchaseOrders => Table::SupplyItem.exists(executor).await.map(|e| !e),
+            Self::ValidatedIdentifiers => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_attribute a
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'Phone' AND a.attname = 'imei'
+    AND a.atttypid = 'text'::regtype;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_some()),
+            Self::DeviceHistory => Table::PhoneOwnership.exists(executor).await.map(|e| !e),
+            Self::PersonMerges => Table::PersonMerge.exists(executor).await.map(|e| !e),
+            Self::Anonymization => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_attribute a
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'Person' AND a.attname = 'anonymized' AND NOT a.attisdropped;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_none()),
+            Self::SoftDelete => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_attribute a
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'Person' AND a.attname = 'deleted_at' AND NOT a.attisdropped;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_none()),
+            // Procedures of older releases don't take the version
+            Self::RowVersions => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_proc
+WHERE pronamespace = current_schema()::regnamespace
+    AND proname = 'ApproveSupply' AND pronargs = 2;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_some()),
+            Self::LiveUpdates => Function::NotifyChangeFunc
+                .exists(executor)
+                .await
+                .map(|e| !e),
+            Self::Search => Index::PersonNameSearch.exists(executor).await.map(|e| !e),
+            Self::Indexes => Index::Created(Table::Order)
+                .exists(executor)
+                .await
+                .map(|e| !e),
+            Self::ReportViews => View::DailyRevenue.exists(executor).await.map(|e| !e),
+            // Routines of older releases use the `search_path` of the caller
+            Self::PinnedRoutines => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_proc
+WHERE pronamespace = current_schema()::regnamespace
+    AND proname = 'update_time_func' AND proconfig IS NULL;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_some()),
+            // Older releases generated the code from md5 hex digits when it wasn't given
+            Self::TrackingCodes => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_attrdef d
+    JOIN pg_catalog.pg_attribute a ON a.attrelid = d.adrelid AND a.attnum = d.adnum
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'Order' AND a.attname = 'tracking';"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_some()),
+            Self::BaseCurrencyDefaults => query_as::<_, (bool,)>(
+                r#"SELECT NOT EXISTS (SELECT 1 FROM pg_catalog.pg_attrdef d
+    JOIN pg_catalog.pg_attribute a ON a.attrelid = d.adrelid AND a.attnum = d.adnum
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'SupplyContract' AND a.attname = 'currency'
+    AND pg_catalog.pg_get_expr(d.adbin, d.adrelid) LIKE '%base_currency()%');"#,
+            )
+            .fetch_one(executor)
+            .await
+            .map(|(pending,)| pending),
+            Self::TrashedEntities => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_attribute a
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'Order' AND a.attname = 'deleted_at' AND NOT a.attisdropped;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_none()),
+            Self::VersionedEntities => query_as::<_, (bool,)>(
+                r#"SELECT true FROM pg_catalog.pg_attribute a
+    JOIN pg_catalog.pg_class c ON c.oid = a.attrelid
+WHERE c.relnamespace = current_schema()::regnamespace
+    AND c.relname = 'Staff' AND a.attname = 'meta' AND NOT a.attisdropped;"#,
+            )
+            .fetch_optional(executor)
+            .await
+            .map(|row| row.is_none()),
+            Self::BaseCurrencyPayments => query_as::<_, (bool,)>(
+                r#"SELECT NOT EXISTS (SELECT 1 FROM pg_catalog.pg_constraint
+WHERE connamespace = current_schema()::regnamespace
+    AND conname = 'Payment_amount_check'
+    AND pg_catalog.pg_get_constraintdef(oid) LIKE '%base_currency()%');"#,
+            )
+            .fetch_one(executor)
+            .await
+            .map(|(pending,)| pending),
+        }
+    }
+
+    /// Changes of types, tables and data in execution order
+    ///
+    /// Views, functions, procedures and triggers are recreated around the pending migrations
+    /// as a whole by [`Migration::script`]
+    pub fn statements(&self) -> Vec<String> {
+        match self {
+            Self::Branches => {
+                let mut statements = vec![
+                    Self::create_type(TransferStatus::CREATE),
+                    Self::BRANCH_TABLES[0].to_string(),
+                    r#"INSERT INTO "Branch" (name, address, phone) VALUES ('Main', '', '');"#
+                        .to_string(),
+                ];
+                for table in [Table::Staff, Table::Warehouse, Table::Order] {
+                    statements.extend([
+                        format!(
+                            r#"ALTER TABLE "{table}"
+    ADD COLUMN branch uuid REFERENCES "Branch" ON DELETE restrict ON UPDATE cascade;"#
+                        ),
+                        format!(r#"UPDATE "{table}" SET branch = (SELECT uuid FROM "Branch");"#),
+                        format!(r#"ALTER TABLE "{table}" ALTER COLUMN branch SET NOT NULL;"#),
+                    ]);
+                }
+                statements.extend([
+                    r#"ALTER TABLE "Warehouse"
+    DROP CONSTRAINT "Warehouse_component_supplier_key",
+    ADD CONSTRAINT "Warehouse_branch_component_supplier_key" UNIQUE (branch, component, supplier);"#
+                        .to_string(),
+                    Self::BRANCH_TABLES[1].to_string(),
+                ]);
+
+                statements
+            }
+            // Same symbols as `Order::gen_tracking`, codes taken twice are drawn again
+            Self::OrderTracking => {
+                let alphabet: String = Order::TRACKING_ALPHABET.iter().collect();
+                let code = vec![
+                    format!(
+                        "substr('{alphabet}', 1 + floor(random() * {})::int, 1)",
+                        alphabet.len()
+                    );
+                    8
+                ]
+                .join(" || ");
+
+                vec![
+                    r#"ALTER TABLE "Order" ADD COLUMN tracking text;"#.to_string(),
+                    format!(
+                        r#"DO $$
+BEGIN
+    UPDATE "Order" SET tracking = {code};
+    LOOP
+        UPDATE "Order" SET tracking = {code}
+        WHERE uuid IN (
+            SELECT uuid FROM (
+                SELECT uuid, row_number() OVER (PARTITION BY tracking ORDER BY uuid) as n FROM "Order"
+            ) d
+            WHERE n > 1
+        );
+        EXIT WHEN NOT FOUND;
+    END LOOP;
+END $$;"#
+                    ),
+                    r#"ALTER TABLE "Order"
+    ALTER COLUMN tracking SET NOT NULL,
+    ADD CONSTRAINT "Order_tracking_key" UNIQUE (tracking),
+    ADD CONSTRAINT "Order_tracking_check" CHECK (length(tracking) = 8);"#
+                        .to_string(),
+                ]
+            }
+            Self::Invoices => {
+                let mut statements = vec![
+                    Self::create_type(PaymentKind::CREATE),
+                    Self::create_type(PaymentMethod::CREATE),
+                ];
+                statements.extend(Self::INVOICE_TABLES.map(String::from));
+
+                statements
+            }
+            Self::Warranty => [
+                r#"ALTER TABLE "Service"
+    ADD COLUMN warranty_days int NOT NULL DEFAULT 0 CHECK (warranty_days >= 0);"#,
+                r#"ALTER TABLE "Order"
+    ADD COLUMN completed timestamptz CHECK (completed IS NULL OR status = 'Complete'),
+    ADD COLUMN warranty_of uuid REFERENCES "Order" ON DELETE restrict ON UPDATE cascade CHECK (warranty_of <> uuid);"#,
+                r#"UPDATE "Order" SET completed = (meta).updated WHERE status = 'Complete';"#,
+            ]
+            .map(String::from)
+            .to_vec(),
+            Self::PriceHistory => {
+                let mut statements = vec![
+                    Self::create_type(PriceScope::CREATE),
+                    Self::create_type(PriceChangeStatus::CREATE),
+                ];
+                statements.extend(Self::PRICE_HISTORY_TABLES.map(String::from));
+
+                statements
+            }
+            Self::MonetaryPrices => {
+                let mut statements = vec![Self::create_type(Money::CREATE)];
+
+                for table in [Table::Invoice, Table::Payment] {
+                    statements.push(format!(
+                        r#"ALTER TABLE "{table}" DROP CONSTRAINT IF EXISTS "{table}_amount_check";"#
+                    ));
+                }
+                for (table, column) in Self::MONEY_COLUMNS {
+                    statements.push(format!(
+                        r#"ALTER TABLE "{table}" ALTER COLUMN {column} TYPE monetary
+    USING CASE WHEN {column} IS NULL THEN NULL ELSE ({column}::numeric, '{}')::monetary END;"#,
+                        Currency::BASE
+                    ));
+                }
+                statements.extend([
+                    r#"ALTER TABLE "Invoice" ADD CONSTRAINT "Invoice_amount_check" CHECK ((amount).amount >= 0);"#,
+                    r#"ALTER TABLE "Payment" ADD CONSTRAINT "Payment_amount_check" CHECK ((amount).amount > 0);"#,
+                    r#"ALTER TABLE "PriceChange" DROP CONSTRAINT IF EXISTS "PriceChange_coef_check";"#,
+                    r#"ALTER TABLE "PriceChange" ALTER COLUMN coef TYPE numeric USING coef::numeric;"#,
+                    r#"ALTER TABLE "PriceChange" ADD CONSTRAINT "PriceChange_coef_check" CHECK (coef > 0);"#,
+                ].map(String::from));
+
+                statements
+            }
+            Self::SupplyCurrencies => vec![
+                ExchangeRate::CREATE.to_string(),
+                r#"ALTER TABLE "SupplyContract"
+    ADD COLUMN currency text NOT NULL DEFAULT base_currency() CHECK (currency ~ '^[A-Z]{3}$');"#
+                    .to_string(),
+                r#"ALTER TABLE "WarehouseSupply" ADD COLUMN price monetary CHECK ((price).amount >= 0);"#
+                    .to_string(),
+                // Same as `warehouse_price_at`, which is dropped while migrating
+                r#"UPDATE "WarehouseSupply" ws SET price = coalesce(
+    (SELECT p.price FROM "WarehousePrice" p WHERE p.item = ws.item AND p.effective <= ws.created ORDER BY p.effective DESC LIMIT 1),
+    (SELECT p.price FROM "WarehousePrice" p WHERE p.item = ws.item ORDER BY p.effective LIMIT 1),
+    (SELECT w.price FROM "Warehouse" w WHERE w.uuid = ws.item)
+);"#
+                .to_string(),
+                r#"ALTER TABLE "WarehouseSupply" ALTER COLUMN price SET NOT NULL;"#.to_string(),
+            ],
+            Self::SupplierTerms => [
+                SupplyContractItem::CREATE,
+                r#"ALTER TABLE "SupplyContract" ADD COLUMN valid_from date, ADD COLUMN valid_to date;"#,
+                r#"UPDATE "SupplyContract" SET valid_from = coalesce(signed, (meta).created)::date;"#,
+                r#"ALTER TABLE "SupplyContract"
+    ALTER COLUMN valid_from SET NOT NULL,
+    ALTER COLUMN valid_from SET DEFAULT current_date,
+    ADD CONSTRAINT "SupplyContract_check" CHECK (valid_to IS NULL OR valid_to >= valid_from);"#,
+                r#"ALTER TABLE "Supply" ADD COLUMN due timestamptz, ADD COLUMN delivered timestamptz;"#,
+                r#"UPDATE "Supply" SET delivered = (meta).updated WHERE status = 'Delivered';"#,
+            ]
+            .map(String::from)
+            .to_vec(),
+            Self::PurchaseOrders => vec![
+                SupplyItem::CREATE.to_string(),
+                r#"ALTER TABLE "Supply"
+    ADD COLUMN approver uuid REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade;"#
+                    .to_string(),
+                // Supplied items of the same component in several branches make a single line
+                r#"INSERT INTO "SupplyItem" (supply, component, ordered, received, price, meta)
+SELECT supply, component, ordered, CASE WHEN status = 'Delivered' THEN ordered END, price, meta
+FROM (
+    SELECT DISTINCT ON (ws.supply, w.component) ws.supply, w.component, s.status, ws.price,
+        sum(ws.amount) OVER (PARTITION BY ws.supply, w.component) as ordered,
+        (ws.created, ws.created)::metatime as meta
+    FROM "WarehouseSupply" ws
+        JOIN "Warehouse" w ON ws.item = w.uuid
+        JOIN "Supply" s ON ws.supply = s.uuid
+    ORDER BY ws.supply, w.component, ws.created
+) lines
+WHERE ordered > 0;"#
+                    .to_string(),
+            ],
+            Self::ValidatedIdentifiers => {
+                let mut statements: Vec<String> =
+                    [Country::CREATE, Iban::CREATE, Bic::CREATE, Imei::CREATE]
+                        .map(Self::create_type)
+                        .to_vec();
+
+                statements.extend([
+                    r#"ALTER TABLE "Supplier"
+    DROP CONSTRAINT IF EXISTS "Supplier_iban_check",
+    DROP CONSTRAINT IF EXISTS "Supplier_swift_check";"#,
+                    r#"ALTER TABLE "Phone" DROP CONSTRAINT IF EXISTS "Phone_imei_check";"#,
+                ].map(String::from));
+
+                // Check digits are never recomputed, a wrong one means a mistyped number
+                let mut checks = String::new();
+                for (table, column, value, domain) in Self::IDENTIFIER_COLUMNS {
+                    checks.push_str(&format!(
+                        r#"
+    FOR r IN SELECT uuid, {column}::text AS original, {value} AS value FROM "{table}" LOOP
+        BEGIN
+            IF r.value::{domain}::text <> r.value THEN
+                RAISE SQLSTATE '23514';
+            END IF;
+        EXCEPTION WHEN check_violation THEN
+            invalid := invalid || format('{table} %s: {column} %L', r.uuid, r.original);
+        END;
+    END LOOP;"#
+                    ));
+                }
+                statements.push(format!(
+                    r#"DO $$
+DECLARE
+    r record;
+    invalid text[] := '{{}}';
+BEGIN{checks}
+    IF cardinality(invalid) > 0 THEN
+        RAISE EXCEPTION 'Identifiers failing the checks, fix them and migrate again:%',
+            E'\n' || array_to_string(invalid, E'\n');
+    END IF;
+END $$;"#
+                ));
+
+                for (table, column, value, domain) in Self::IDENTIFIER_COLUMNS {
+                    statements.push(format!(
+                        r#"ALTER TABLE "{table}" ALTER COLUMN {column} TYPE {domain} USING {value};"#
+                    ));
+                }
+
+                statements
+            }
+            Self::DeviceHistory => vec![
+                PhoneOwnership::CREATE.to_string(),
+                format!(
+                    r#"{} INSERT INTO "PhoneOwnership" (phone, person, since, until)
+SELECT keep, person, since, until FROM d WHERE until IS NOT NULL
+ON CONFLICT DO NOTHING;"#,
+                    Self::PHONE_DUPLICATES
+                ),
+                format!(
+                    r#"{} UPDATE "Order" o SET phone = d.keep FROM d WHERE o.phone = d.uuid AND d.uuid <> d.keep;"#,
+                    Self::PHONE_DUPLICATES
+                ),
+                format!(
+                    r#"{} UPDATE "Phone" p SET person = d.owner FROM d WHERE p.uuid = d.keep AND d.uuid = d.keep AND p.person <> d.owner;"#,
+                    Self::PHONE_DUPLICATES
+                ),
+                format!(
+                    r#"{} DELETE FROM "Phone" p USING d WHERE p.uuid = d.uuid AND d.uuid <> d.keep;"#,
+                    Self::PHONE_DUPLICATES
+                ),
+                r#"ALTER TABLE "Phone" ADD CONSTRAINT "Phone_imei_key" UNIQUE (imei);"#.to_string(),
+            ],
+            Self::PersonMerges => vec![
+                r#"CREATE EXTENSION IF NOT EXISTS pg_trgm WITH SCHEMA public;"#.to_string(),
+                PersonMerge::CREATE.to_string(),
+            ],
+            Self::Anonymization => vec![
+                r#"ALTER TABLE "Person" ADD COLUMN anonymized timestamptz;"#.to_string(),
+            ],
+            Self::SoftDelete => Self::SOFT_DELETE_TABLES
+                .iter()
+                .map(|table| format!(r#"ALTER TABLE "{table}" ADD COLUMN deleted_at timestamptz;"#))
+                .collect(),
+            // Only routines change, they are recreated by the script
+            Self::RowVersions => Vec::new(),
+            // Only the function and triggers are added, they are created by the script
+            Self::LiveUpdates => Vec::new(),
+            Self::Search => Index::ALL
+                .iter()
+                .filter(|index| index.is_search())
+                .map(Index::create)
+                .collect(),
+            Self::Indexes => Index::ALL
+                .iter()
+                .filter(|index| !index.is_search() && !matches!(index, Index::Unique(..)))
+                .map(Index::create)
+                .collect(),
+            // Only the views are added, they are created by the script
+            Self::ReportViews => Vec::new(),
+            // Routines are recreated by the script
+            Self::PinnedRoutines => Vec::new(),
+            Self::TrackingCodes => {
+                vec![r#"ALTER TABLE "Order" ALTER COLUMN tracking DROP DEFAULT;"#.to_string()]
+            }
+            Self::BaseCurrencyDefaults => [
+                r#"ALTER TABLE "SupplyContract" ALTER COLUMN currency SET DEFAULT base_currency();"#,
+                r#"ALTER TABLE "ExchangeRate" DROP CONSTRAINT "ExchangeRate_currency_check";"#,
+                r#"ALTER TABLE "ExchangeRate" ADD CONSTRAINT "ExchangeRate_currency_check"
+    CHECK (currency ~ '^[A-Z]{3}$' AND currency <> base_currency());"#,
+            ]
+            .map(String::from)
+            .to_vec(),
+            Self::TrashedEntities => {
+                let mut statements: Vec<String> = trash::TABLES
+                    .iter()
+                    .filter(|table| !Self::SOFT_DELETE_TABLES.contains(table))
+                    .map(|table| format!(r#"ALTER TABLE "{table}" ADD COLUMN deleted_at timestamptz;"#))
+                    .collect();
+                for index in Index::ALL {
+                    if let Index::Unique(table, _) = index {
+                        // Indexes take the names of the constraints
+                        statements.push(format!(
+                            r#"ALTER TABLE "{table}" DROP CONSTRAINT IF EXISTS "{index}";"#
+                        ));
+                        statements.push(index.create());
+                    }
+                }
+
+                statements
+            }
+            // Only the columns are added, the triggers and routines are created by the script
+            Self::VersionedEntities => [
+                Table::Manufacturer,
+                Table::PhoneModel,
+                Table::Staff,
+                Table::Component,
+            ]
+            .iter()
+            .map(|table| {
+                format!(
+                    r#"ALTER TABLE "{table}" ADD COLUMN meta metatime NOT NULL DEFAULT (now(), now());"#
+                )
+            })
+            .collect(),
+            Self::BaseCurrencyPayments => [
+                r#"ALTER TABLE "Invoice" DROP CONSTRAINT "Invoice_amount_check";"#,
+                r#"ALTER TABLE "Invoice" ADD CONSTRAINT "Invoice_amount_check"
+    CHECK ((amount).amount >= 0 AND (amount).currency = base_currency());"#,
+                r#"ALTER TABLE "Payment" DROP CONSTRAINT "Payment_amount_check";"#,
+                r#"ALTER TABLE "Payment" ADD CONSTRAINT "Payment_amount_check"
+    CHECK ((amount).amount > 0 AND (amount).currency = base_currency());"#,
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+
+    /// Creates the type or domain unless it's left by a newer release, older ones don't know to drop it
+    fn create_type(create: &str) -> String {
+        format!("DO $$ BEGIN {create} EXCEPTION WHEN duplicate_object THEN NULL; END $$;")
+    }
+
+    /// Statements applying the migrations in the given order
+    ///
+    /// Views and routines depend on the column types, so all of them are dropped beforehand
+    /// and created from scratch afterwards, except the functions the tables themselves depend on,
+    /// which are replaced before the migrations instead. Expects the schema of one of the previous releases,
+    /// the ones before [`Migration::Branches`] included
+    pub fn script(migrations: &[Self], schema: &Schema) -> Vec<String> {
+        let mut statements = Vec::new();
+
+        for trigger in Trigger::ALL {
+            for table in trigger.tables() {
+                statements.push(format!(r#"DROP TRIGGER IF EXISTS {trigger} ON "{table}";"#));
+            }
+        }
+        for view in View::ALL.iter().rev() {
+            statements.push(format!(r#"DROP {} IF EXISTS "{view}";"#, view.kind()));
+        }
+        for procedure in Procedure::ALL.iter().rev() {
+            statements.push(format!(
+                r#"DROP PROCEDURE IF EXISTS "{schema}"."{procedure}";"#
+            ));
+        }
+        for function in Function::ALL.iter().rev() {
+            if !function.is_table_dependency() {
+                statements.push(format!(
+                    r#"DROP FUNCTION IF EXISTS "{schema}"."{function}";"#
+                ));
+            }
+        }
+        statements.extend(
+            Function::ALL
+                .iter()
+                .filter(|function| function.is_table_dependency())
+                .map(|function| or_replace(&function.create())),
+        );
+
+        for migration in migrations {
+            statements.extend(migration.statements());
+        }
+
+        statements.extend(View::ALL.iter().map(|view| view.create().to_string()));
+        statements.extend(
+            Function::ALL
+                .iter()
+                .filter(|function| !function.is_table_dependency())
+                .map(Function::create),
+        );
+        statements.extend(Procedure::ALL.iter().map(Procedure::create));
+        for trigger in Trigger::ALL {
+            statements.extend(trigger.tables().iter().map(|table| trigger.create(*table)));
+        }
+
+        statements
+    }
+
+    /// Migrations the database still needs
+    pub async fn pending(pool: &PgPool) -> Result<Vec<Self>> {
+        let mut pending = Vec::new();
+
+        for migration in Self::ALL {
+            if migration
+                .is_pending(pool)
+                .await
+                .with_context(|| format!("While checking '{migration}' migration"))?
+            {
+                pending.push(migration);
+            }
+        }
+
+        Ok(pending)
+    }
+
+    /// Applies pending migrations within a single transaction and returns them
+    ///
+    /// Pool connections are expected to have `search_path` set by [`Schema::apply`]
+    pub async fn apply_pending(pool: &PgPool, schema: &Schema) -> Result<Vec<Self>> {
+        let pending = Self::pending(pool).await?;
+        if pending.is_empty() {
+            return Ok(pending);
+        }
+
+        let mut tx = pool.begin().await?;
+        for statement in Self::script(&pending, schema) {
+            query(&statement)
+                .execute(&mut tx)
+                .await
+                .with_context(|| format!("While migrating: {statement}"))?;
+        }
+        tx.commit().await?;
+
+        Ok(pending)
+    }
+}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
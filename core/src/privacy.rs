@@ -0,0 +1,182 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{query, query_as, FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    tables::{
+        Invoice, LaborContract, Order, Payment, Person, PersonMerge, Phone, PhoneOwnership, Staff,
+    },
+    types::{AccountRole, AccountStatus, MetaTime},
+    PgQueryAs,
+};
+
+/// Staff account of the person, the password hash is left out
+#[derive(FromRow, Serialize, Clone, Debug)]
+pub struct AccountData {
+    pub uuid: Uuid,
+    pub staff: Uuid,
+    pub login: String,
+    pub role: AccountRole,
+    pub status: AccountStatus,
+    pub meta: MetaTime,
+}
+
+/// Everything held about the person, handed over on their request
+#[derive(Serialize, Clone, Debug)]
+pub struct PersonalData {
+    pub exported: DateTime<Utc>,
+    pub person: Person,
+    /// Contacts of the duplicates merged into the person
+    pub merged: Vec<PersonMerge>,
+    pub phones: Vec<Phone>,
+    /// Phones the person owned before
+    pub ownership: Vec<PhoneOwnership>,
+    pub orders: Vec<Order>,
+    pub invoices: Vec<Invoice>,
+    pub payments: Vec<Payment>,
+    pub labor_contracts: Vec<LaborContract>,
+    pub staff: Vec<Staff>,
+    pub accounts: Vec<AccountData>,
+}
+
+impl PersonalData {
+    /// Reads the data within a single repeatable read transaction
+    pub async fn fetch(pool: &PgPool, person: Uuid) -> Result<Self> {
+        let mut tx = pool.begin().await?;
+        query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY;")
+            .execute(&mut tx)
+            .await?;
+
+        let data = Self {
+            exported: Utc::now(),
+            person: Person::get_by_uuid(person)
+                .fetch_one(&mut tx)
+                .await
+                .with_context(|| format!("While fetching person '{person}'"))?,
+            merged: PersonMerge::get_by_survivor(person)
+                .fetch_all(&mut tx)
+                .await
+                .context("While fetching merged duplicates")?,
+            phones: Phone::get_by_person(person)
+                .fetch_all(&mut tx)
+                .await
+                .context("While fetching phones")?,
+            ownership: PhoneOwnership::get_by_person(person)
+                .fetch_all(&mut tx)
+                .await
+                .context("While fetching phone ownership")?,
+            orders: Order::get_by_client(person)
+                .fetch_all(&mut tx)
+                .await
+                .context("While fetching orders")?,
+            invoices: query_as(
+                r#"SELECT i.* FROM "Invoice" i
+    JOIN "Order" o ON i."order" = o.uuid
+WHERE o.client = $1
+ORDER BY (i.meta).created"#,
+            )
+            .bind(person)
+            .fetch_all(&mut tx)
+            .await
+            .context("While fetching invoices")?,
+            payments: query_as(
+                r#"SELECT p.* FROM "Payment" p
+    JOIN "Invoice" i ON p.invoice = i.uuid
+    JOIN "Order" o ON i."order" = o.uuid
+WHERE o.client = $1
+ORDER BY (p.meta).created"#,
+            )
+            .bind(person)
+            .fetch_all(&mut tx)
+            .await
+            .context("While fetching payments")?,
+            labor_contracts: LaborContract::get_by_person(person)
+                .fetch_all(&mut tx)
+                .await
+                .context("While fetching labor contracts")?,
+            staff: query_as(
+                r#"SELECT s.* FROM "Staff" s
+    JOIN "LaborContract" lc ON s.contract = lc.uuid
+WHERE lc.person = $1"#,
+            )
+            .bind(person)
+            .fetch_all(&mut tx)
+            .await
+            .context("While fetching staff")?,
+            accounts: query_as(
+                r#"SELECT a.uuid, a.staff, a.login, a.role, a.status, a.meta FROM "Account" a
+    JOIN "Staff" s ON a.staff = s.uuid
+    JOIN "LaborContract" lc ON s.contract = lc.uuid
+WHERE lc.person = $1"#,
+            )
+            .bind(person)
+            .fetch_all(&mut tx)
+            .await
+            .context("While fetching accounts")?,
+        };
+
+        tx.commit().await?;
+
+        Ok(data)
+    }
+}
+
+/// Client without any activity since the retention period started
+#[derive(FromRow, Clone, Debug)]
+pub struct InactiveClient {
+    pub person: Uuid,
+    pub name: String,
+    /// Latest update of the person, their phones and orders or the end of the phone ownership
+    pub last_activity: DateTime<Utc>,
+}
+
+impl InactiveClient {
+    /// Clients inactive since `before`, people with labor contracts and orders in progress
+    /// are kept for staff records and ongoing repairs
+    pub fn get_before(before: DateTime<Utc>) -> PgQueryAs<Self> {
+        query_as(
+            r#"SELECT * FROM (
+    SELECT p.uuid as person,
+        CONCAT_WS(' ', p.first_name, p.middle_name, p.last_name) as name,
+        greatest(
+            (p.meta).updated,
+            (SELECT max((o.meta).updated) FROM "Order" o WHERE o.client = p.uuid),
+            (SELECT max((ph.meta).updated) FROM "Phone" ph WHERE ph.person = p.uuid),
+            (SELECT max(po.until) FROM "PhoneOwnership" po WHERE po.person = p.uuid)
+        ) as last_activity
+    FROM "Person" p
+    WHERE p.anonymized IS NULL
+        AND NOT EXISTS (SELECT 1 FROM "LaborContract" lc WHERE lc.person = p.uuid)
+        AND NOT EXISTS (
+            SELECT 1 FROM "Order" o
+            WHERE o.client = p.uuid AND o.status IN ('Processing', 'PendingPayment', 'Active')
+        )
+) c
+WHERE last_activity < $1
+ORDER BY last_activity"#,
+        )
+        .bind(before)
+    }
+}
+
+/// Anonymizes every client inactive since `before` within a single transaction
+pub async fn apply_retention(pool: &PgPool, before: DateTime<Utc>) -> Result<Vec<InactiveClient>> {
+    let mut tx = pool.begin().await?;
+
+    let clients = InactiveClient::get_before(before)
+        .fetch_all(&mut tx)
+        .await
+        .context("While searching for inactive clients")?;
+    for client in &clients {
+        Person::anonymize(client.person)
+            .execute(&mut tx)
+            .await
+            .with_context(|| format!("While anonymizing person '{}'", client.person))?;
+    }
+
+    tx.commit().await?;
+
+    Ok(clients)
+}
@@ -0,0 +1,52 @@
+use crate::traits::Recreatable;
+
+/// Replaces names and contacts of the person (and of the duplicates merged into them)
+/// with placeholders, passports and logins of ended labor contracts are scrubbed as well
+pub struct AnonymizePerson;
+
+impl Recreatable for AnonymizePerson {
+    const NAME: &'static str = "AnonymizePerson";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "AnonymizePerson" (target uuid) AS $$
+BEGIN
+    PERFORM 1 FROM "Person" WHERE uuid = target AND anonymized IS NULL FOR UPDATE;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Person % not found or already anonymized', target;
+    END IF;
+    IF EXISTS (
+        SELECT 1 FROM "LaborContract"
+        WHERE person = target AND status IN ('Review', 'Negotiation', 'Active')
+    ) THEN
+        RAISE EXCEPTION 'Person % has labor contract in force', target;
+    END IF;
+    IF EXISTS (
+        SELECT 1 FROM "Order"
+        WHERE client = target AND status IN ('Processing', 'PendingPayment', 'Active')
+    ) THEN
+        RAISE EXCEPTION 'Person % has orders in progress', target;
+    END IF;
+
+    UPDATE "Person"
+    SET first_name = 'Anonymized',
+        middle_name = NULL,
+        last_name = left(target::text, 8),
+        email = target::text || '@anonymized.invalid',
+        phone = 'anon-' || left(md5(target::text), 13),
+        anonymized = now()
+    WHERE uuid = target;
+    UPDATE "PersonMerge"
+    SET first_name = 'Anonymized',
+        middle_name = NULL,
+        last_name = left(merged::text, 8),
+        email = merged::text || '@anonymized.invalid',
+        phone = 'anon-' || left(md5(merged::text), 13)
+    WHERE survivor = target;
+    UPDATE "LaborContract" SET passport = left(md5(uuid::text), 10) WHERE person = target;
+    UPDATE "Account" a
+    SET login = 'anon-' || left(md5(a.uuid::text), 19), status = 'Inactive'
+    FROM "Staff" s
+        JOIN "LaborContract" lc ON s.contract = lc.uuid
+    WHERE a.staff = s.uuid AND lc.person = target;
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "AnonymizePerson";"#;
+}
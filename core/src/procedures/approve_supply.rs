@@ -0,0 +1,54 @@
+use crate::traits::Recreatable;
+
+/// Signs the supply under review on behalf of the manager,
+/// every item has to be covered by the terms of the active contract valid today
+///
+/// The supply version is checked by `update_time_func` unless it's `NULL`
+pub struct ApproveSupply;
+
+impl Recreatable for ApproveSupply {
+    const NAME: &'static str = "ApproveSupply";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "ApproveSupply" (approved_supply uuid, manager uuid, version timestamptz DEFAULT NULL) AS $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM "Account"
+        WHERE staff = manager AND role IN ('Manager', 'Admin') AND status = 'Active'
+    ) THEN
+        RAISE EXCEPTION 'Staff % is not allowed to approve supplies', manager;
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM "Supply" s
+            JOIN "SupplyContract" c ON s.contract = c.uuid
+        WHERE s.uuid = approved_supply AND c.status = 'Active'
+            AND c.valid_from <= current_date AND (c.valid_to IS NULL OR c.valid_to >= current_date)
+    ) THEN
+        RAISE EXCEPTION 'Contract of supply % is not active or not valid today', approved_supply;
+    END IF;
+
+    IF NOT EXISTS (SELECT 1 FROM "SupplyItem" WHERE supply = approved_supply) THEN
+        RAISE EXCEPTION 'Supply % has no items', approved_supply;
+    END IF;
+
+    IF EXISTS (
+        SELECT 1 FROM "SupplyItem" i
+            JOIN "Supply" s ON i.supply = s.uuid
+            LEFT JOIN "SupplyContractItem" ci
+                ON ci.contract = s.contract AND ci.component = i.component
+        WHERE i.supply = approved_supply
+            AND (ci.component IS NULL OR i.ordered < ci.min_order)
+    ) THEN
+        RAISE EXCEPTION 'Items of supply % do not match the contract terms', approved_supply;
+    END IF;
+
+    UPDATE "Supply"
+    SET status = 'Signed', signed = now(), approver = manager,
+        meta.updated = coalesce(version, (meta).updated)
+    WHERE uuid = approved_supply AND status IN ('Review', 'Negotiation');
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Supply % is not under review', approved_supply;
+    END IF;
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "ApproveSupply";"#;
+}
@@ -0,0 +1,32 @@
+use crate::traits::Recreatable;
+
+/// Returns stock in transit to the source warehouse item
+///
+/// Stock isn't returned to a deleted item, it has to be restored first. The transfer version
+/// is checked by `update_time_func` unless it's `NULL`
+pub struct CancelTransfer;
+
+impl Recreatable for CancelTransfer {
+    const NAME: &'static str = "CancelTransfer";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "CancelTransfer" (transfer uuid, version timestamptz DEFAULT NULL) AS $$
+DECLARE
+    moved "StockTransfer";
+BEGIN
+    UPDATE "StockTransfer"
+    SET status = 'Cancelled', meta.updated = coalesce(version, (meta).updated)
+    WHERE uuid = transfer AND status = 'InTransit'
+    RETURNING * INTO moved;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Transfer % is not in transit', transfer;
+    END IF;
+
+    UPDATE "Warehouse"
+    SET amount = amount + moved.amount
+    WHERE uuid = moved.item AND deleted_at IS NULL;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Item % of transfer % is deleted, restore it first', moved.item, transfer;
+    END IF;
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "CancelTransfer";"#;
+}
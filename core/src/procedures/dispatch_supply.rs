@@ -0,0 +1,22 @@
+use crate::traits::Recreatable;
+
+/// Marks the signed supply as shipped by the supplier,
+/// expected delivery replaces the one derived from the contract lead time,
+/// the supply version is checked unless it's `NULL`
+pub struct DispatchSupply;
+
+impl Recreatable for DispatchSupply {
+    const NAME: &'static str = "DispatchSupply";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "DispatchSupply" (dispatched_supply uuid, expected timestamptz, version timestamptz DEFAULT NULL) AS $$
+BEGIN
+    UPDATE "Supply"
+    SET status = 'Dispatched', due = coalesce(expected, due),
+        meta.updated = coalesce(version, (meta).updated)
+    WHERE uuid = dispatched_supply AND status IN ('Signed', 'Paid');
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Supply % is not signed', dispatched_supply;
+    END IF;
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "DispatchSupply";"#;
+}
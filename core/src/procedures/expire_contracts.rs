@@ -0,0 +1,19 @@
+use crate::traits::Recreatable;
+
+/// Marks active supply contracts past their validity as expired,
+/// count of them is returned in `expired`
+pub struct ExpireContracts;
+
+impl Recreatable for ExpireContracts {
+    const NAME: &'static str = "ExpireContracts";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "ExpireContracts" (INOUT expired int) AS $$
+BEGIN
+    UPDATE "SupplyContract"
+    SET status = 'Expired'
+    WHERE status = 'Active' AND valid_to < current_date;
+
+    GET DIAGNOSTICS expired = ROW_COUNT;
+END;
+$$ LANGUAGE plpgsql;"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "ExpireContracts";"#;
+}
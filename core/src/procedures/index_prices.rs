@@ -1,14 +1,53 @@
 use crate::traits::Recreatable;
 
+/// Multiplies prices in scope by `coef` keeping the previous prices in the history,
+/// uuid of the new [`PriceChange`](crate::tables::PriceChange) is returned in `price_change`
 pub struct IndexPrices;
 
 impl Recreatable for IndexPrices {
     const NAME: &'static str = "IndexPrices";
-    const CREATE: &'static str = r#"CREATE PROCEDURE "IndexPrices" (coef real) AS $$
-UPDATE "Warehouse"
-SET price = price * coef;
-UPDATE "ServicePhoneModel"
-SET price = price * coef;
-$$ LANGUAGE SQL;"#;
+    const CREATE: &'static str = r#"CREATE PROCEDURE "IndexPrices" (coef numeric, price_scope "PriceScope", scope_target uuid, INOUT price_change uuid) AS $$
+BEGIN
+    IF (price_scope = 'All') <> (scope_target IS NULL) THEN
+        RAISE EXCEPTION 'Target must be set for every scope except All';
+    END IF;
+
+    -- Prices the items had before the first indexation
+    INSERT INTO "WarehousePrice" (item, price, effective)
+    SELECT p.item, p.old_price, (w.meta).created
+    FROM index_prices_preview(coef, price_scope, scope_target) p
+        JOIN "Warehouse" w ON p.item = w.uuid
+    WHERE NOT EXISTS (SELECT 1 FROM "WarehousePrice" wp WHERE wp.item = p.item);
+    INSERT INTO "ServicePrice" (service, phone_model, price, effective)
+    SELECT p.service, p.phone_model, p.old_price, (spm.meta).created
+    FROM index_prices_preview(coef, price_scope, scope_target) p
+        JOIN "ServicePhoneModel" spm ON p.service = spm.service AND p.phone_model = spm.phone_model
+    WHERE NOT EXISTS (
+        SELECT 1 FROM "ServicePrice" sp WHERE sp.service = p.service AND sp.phone_model = p.phone_model
+    );
+
+    INSERT INTO "PriceChange" (coef, scope, target)
+    VALUES (coef, price_scope, scope_target)
+    RETURNING uuid INTO price_change;
+
+    INSERT INTO "WarehousePrice" (item, price, change)
+    SELECT p.item, p.new_price, price_change
+    FROM index_prices_preview(coef, price_scope, scope_target) p
+    WHERE p.item IS NOT NULL;
+    INSERT INTO "ServicePrice" (service, phone_model, price, change)
+    SELECT p.service, p.phone_model, p.new_price, price_change
+    FROM index_prices_preview(coef, price_scope, scope_target) p
+    WHERE p.service IS NOT NULL;
+
+    UPDATE "Warehouse" w
+    SET price = wp.price
+    FROM "WarehousePrice" wp
+    WHERE wp.change = price_change AND wp.item = w.uuid;
+    UPDATE "ServicePhoneModel" spm
+    SET price = sp.price
+    FROM "ServicePrice" sp
+    WHERE sp.change = price_change AND sp.service = spm.service AND sp.phone_model = spm.phone_model;
+END;
+$$ LANGUAGE 'plpgsql';"#;
     const DROP: &'static str = r#"DROP PROCEDURE "IndexPrices";"#;
 }
@@ -0,0 +1,43 @@
+use crate::traits::Recreatable;
+
+/// Moves phones, orders and labor contracts of the `duplicate` person to the `kept` one
+/// and deletes the duplicate, its contacts are kept in the
+/// [`PersonMerge`](crate::tables::PersonMerge) returned in `person_merge`
+pub struct MergePeople;
+
+impl Recreatable for MergePeople {
+    const NAME: &'static str = "MergePeople";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "MergePeople" (kept uuid, duplicate uuid, INOUT person_merge uuid) AS $$
+DECLARE
+    removed "Person";
+BEGIN
+    IF kept = duplicate THEN
+        RAISE EXCEPTION 'Person % cannot be merged into itself', kept;
+    END IF;
+    PERFORM 1 FROM "Person" WHERE uuid = kept AND anonymized IS NULL FOR UPDATE;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Person % not found or anonymized', kept;
+    END IF;
+    SELECT * INTO removed FROM "Person" WHERE uuid = duplicate AND anonymized IS NULL FOR UPDATE;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Person % not found or anonymized', duplicate;
+    END IF;
+
+    INSERT INTO "PersonMerge" (survivor, merged, first_name, middle_name, last_name, email, phone)
+    VALUES (kept, duplicate, removed.first_name, removed.middle_name, removed.last_name, removed.email, removed.phone)
+    RETURNING uuid INTO person_merge;
+    UPDATE "PersonMerge" SET survivor = kept WHERE survivor = duplicate;
+
+    UPDATE "PhoneOwnership" SET person = kept WHERE person = duplicate;
+    UPDATE "Phone" SET person = kept WHERE person = duplicate;
+    -- Phones did not change hands, so owners recorded by the phone_ownership trigger are dropped
+    DELETE FROM "PhoneOwnership" WHERE person = duplicate;
+    UPDATE "Order" SET client = kept WHERE client = duplicate;
+    UPDATE "LaborContract" SET person = kept WHERE person = duplicate;
+
+    UPDATE "Person" SET middle_name = coalesce(middle_name, removed.middle_name) WHERE uuid = kept;
+    DELETE FROM "Person" WHERE uuid = duplicate;
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "MergePeople";"#;
+}
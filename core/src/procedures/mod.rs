@@ -1,50 +1,137 @@
 use core::fmt;
 
-use anyhow::{Context, Result};
-use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool, Postgres};
+use sqlx::{query_as, Error, Executor, Postgres};
 
-use crate::traits::Recreatable;
+use crate::{functions::pin_search_path, schema::Schema, traits::Recreatable};
 
+pub mod anonymize_person;
+pub mod approve_supply;
+pub mod cancel_transfer;
+pub mod dispatch_supply;
+pub mod expire_contracts;
 pub mod index_prices;
+pub mod merge_people;
+pub mod receive_supply;
+pub mod receive_transfer;
+pub mod record_payment;
+pub mod rollback_prices;
+pub mod transfer_stock;
 
+pub use anonymize_person::AnonymizePerson;
+pub use approve_supply::ApproveSupply;
+pub use cancel_transfer::CancelTransfer;
+pub use dispatch_supply::DispatchSupply;
+pub use expire_contracts::ExpireContracts;
 pub use index_prices::IndexPrices;
+pub use merge_people::MergePeople;
+pub use receive_supply::ReceiveSupply;
+pub use receive_transfer::ReceiveTransfer;
+pub use record_payment::RecordPayment;
+pub use rollback_prices::RollbackPrices;
+pub use transfer_stock::TransferStock;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Procedure {
     IndexPrices,
+    TransferStock,
+    ReceiveTransfer,
+    CancelTransfer,
+    RollbackPrices,
+    ExpireContracts,
+    ApproveSupply,
+    DispatchSupply,
+    ReceiveSupply,
+    MergePeople,
+    AnonymizePerson,
+    RecordPayment,
 }
 
 impl Procedure {
-    pub const ALL: [Self; 1] = [Self::IndexPrices];
+    pub const ALL: [Self; 12] = [
+        Self::IndexPrices,
+        Self::TransferStock,
+        Self::ReceiveTransfer,
+        Self::CancelTransfer,
+        Self::RollbackPrices,
+        Self::ExpireContracts,
+        Self::ApproveSupply,
+        Self::DispatchSupply,
+        Self::ReceiveSupply,
+        Self::MergePeople,
+        Self::AnonymizePerson,
+        Self::RecordPayment,
+    ];
 
     pub fn name(&self) -> &str {
         match self {
             Self::IndexPrices => IndexPrices::NAME,
+            Self::TransferStock => TransferStock::NAME,
+            Self::ReceiveTransfer => ReceiveTransfer::NAME,
+            Self::CancelTransfer => CancelTransfer::NAME,
+            Self::RollbackPrices => RollbackPrices::NAME,
+            Self::ExpireContracts => ExpireContracts::NAME,
+            Self::ApproveSupply => ApproveSupply::NAME,
+            Self::DispatchSupply => DispatchSupply::NAME,
+            Self::ReceiveSupply => ReceiveSupply::NAME,
+            Self::MergePeople => MergePeople::NAME,
+            Self::AnonymizePerson => AnonymizePerson::NAME,
+            Self::RecordPayment => RecordPayment::NAME,
         }
     }
 
-    pub fn create(&self) -> &str {
-        match self {
+    pub fn create(&self) -> String {
+        pin_search_path(match self {
             Self::IndexPrices => IndexPrices::CREATE,
-        }
+            Self::TransferStock => TransferStock::CREATE,
+            Self::ReceiveTransfer => ReceiveTransfer::CREATE,
+            Self::CancelTransfer => CancelTransfer::CREATE,
+            Self::RollbackPrices => RollbackPrices::CREATE,
+            Self::ExpireContracts => ExpireContracts::CREATE,
+            Self::ApproveSupply => ApproveSupply::CREATE,
+            Self::DispatchSupply => DispatchSupply::CREATE,
+            Self::ReceiveSupply => ReceiveSupply::CREATE,
+            Self::MergePeople => MergePeople::CREATE,
+            Self::AnonymizePerson => AnonymizePerson::CREATE,
+            Self::RecordPayment => RecordPayment::CREATE,
+        })
     }
 
     pub fn drop(&self) -> &str {
         match self {
             Self::IndexPrices => IndexPrices::DROP,
+            Self::TransferStock => TransferStock::DROP,
+            Self::ReceiveTransfer => ReceiveTransfer::DROP,
+            Self::CancelTransfer => CancelTransfer::DROP,
+            Self::RollbackPrices => RollbackPrices::DROP,
+            Self::ExpireContracts => ExpireContracts::DROP,
+            Self::ApproveSupply => ApproveSupply::DROP,
+            Self::DispatchSupply => DispatchSupply::DROP,
+            Self::ReceiveSupply => ReceiveSupply::DROP,
+            Self::MergePeople => MergePeople::DROP,
+            Self::AnonymizePerson => AnonymizePerson::DROP,
+            Self::RecordPayment => RecordPayment::DROP,
         }
     }
 
-    pub async fn exists(&self, pool: &PgPool) -> Result<bool, Error> {
+    /// Drop statement qualified with the schema, as the unqualified one is ambiguous while
+    /// another schema in the path has the same routine over its own types
+    pub fn drop_from(&self, schema: &Schema) -> String {
+        format!(r#"DROP PROCEDURE "{schema}"."{self}";"#)
+    }
+
+    pub async fn exists<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
         match query_as::<Postgres, (bool,)>(
             r#"SELECT true
 FROM pg_catalog.pg_proc
     JOIN pg_namespace ON pg_catalog.pg_proc.pronamespace = pg_namespace.oid
 WHERE proname = $1
-    AND nspname = 'public';"#,
+    AND nspname = current_schema();"#,
         )
         .bind(self.name())
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
         {
             Ok(_) => Ok(true),
@@ -57,32 +144,6 @@ WHERE proname = $1
             }
         }
     }
-
-    /// Create all procedures necessary for application
-    pub async fn create_all(
-        pool: &PgPool,
-        handler: impl Fn((Procedure, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for procedure in Self::ALL {
-            handler((procedure, query(procedure.create()).execute(pool).await))
-                .with_context(|| format!("While creating '{procedure}' procedure"))?;
-        }
-
-        Ok(())
-    }
-
-    /// Drop all application procedures
-    pub async fn drop_all(
-        pool: &PgPool,
-        handler: impl Fn((Procedure, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for procedure in Self::ALL {
-            handler((procedure, query(procedure.drop()).execute(pool).await))
-                .with_context(|| format!("While dropping '{procedure}' procedure"))?;
-        }
-
-        Ok(())
-    }
 }
 
 impl fmt::Display for Procedure {
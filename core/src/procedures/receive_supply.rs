@@ -0,0 +1,60 @@
+use crate::traits::Recreatable;
+
+/// Delivers the supply to the branch of the staff who placed it
+///
+/// Lines without received amount are received as ordered, received stock is added to
+/// the branch warehouse and recorded in `WarehouseSupply`. New warehouse items take the
+/// selling price of the same component from the supplier in other branches, or the purchase
+/// price in the base currency. The supply version is checked unless it's `NULL`
+pub struct ReceiveSupply;
+
+impl Recreatable for ReceiveSupply {
+    const NAME: &'static str = "ReceiveSupply";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "ReceiveSupply" (received_supply uuid, version timestamptz DEFAULT NULL) AS $$
+DECLARE
+    delivered "Supply";
+    supply_supplier uuid;
+    supply_branch uuid;
+    line "SupplyItem";
+    stock uuid;
+BEGIN
+    UPDATE "Supply"
+    SET status = 'Delivered', meta.updated = coalesce(version, (meta).updated)
+    WHERE uuid = received_supply AND status IN ('Signed', 'Paid', 'Dispatched')
+    RETURNING * INTO delivered;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Supply % is not awaiting delivery', received_supply;
+    END IF;
+
+    SELECT supplier INTO supply_supplier FROM "SupplyContract" WHERE uuid = delivered.contract;
+    SELECT branch INTO supply_branch FROM "Staff" WHERE uuid = delivered.staff;
+
+    UPDATE "SupplyItem"
+    SET received = ordered
+    WHERE supply = received_supply AND received IS NULL;
+
+    FOR line IN SELECT * FROM "SupplyItem" WHERE supply = received_supply AND received > 0 LOOP
+        INSERT INTO "Warehouse" (branch, component, supplier, price, amount)
+        VALUES (
+            supply_branch,
+            line.component,
+            supply_supplier,
+            coalesce(
+                (SELECT w.price FROM "Warehouse" w
+                WHERE w.component = line.component AND w.supplier = supply_supplier
+                LIMIT 1),
+                to_base_currency(line.price, current_date)
+            ),
+            line.received
+        )
+        ON CONFLICT (branch, component, supplier)
+        DO UPDATE SET amount = "Warehouse".amount + EXCLUDED.amount, deleted_at = NULL
+        RETURNING uuid INTO stock;
+
+        INSERT INTO "WarehouseSupply" (item, supply, amount, price)
+        VALUES (stock, received_supply, line.received, line.price);
+    END LOOP;
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "ReceiveSupply";"#;
+}
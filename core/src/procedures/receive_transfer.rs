@@ -0,0 +1,33 @@
+use crate::traits::Recreatable;
+
+/// Adds stock in transit to the destination branch warehouse
+///
+/// The transfer version is checked by `update_time_func` unless it's `NULL`
+pub struct ReceiveTransfer;
+
+impl Recreatable for ReceiveTransfer {
+    const NAME: &'static str = "ReceiveTransfer";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "ReceiveTransfer" (transfer uuid, transfer_receiver uuid, version timestamptz DEFAULT NULL) AS $$
+DECLARE
+    moved "StockTransfer";
+    source "Warehouse";
+BEGIN
+    UPDATE "StockTransfer"
+    SET status = 'Received', receiver = transfer_receiver,
+        meta.updated = coalesce(version, (meta).updated)
+    WHERE uuid = transfer AND status = 'InTransit'
+    RETURNING * INTO moved;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Transfer % is not in transit', transfer;
+    END IF;
+
+    SELECT * INTO source FROM "Warehouse" WHERE uuid = moved.item;
+
+    INSERT INTO "Warehouse" (branch, component, supplier, price, amount)
+    VALUES (moved.destination, source.component, source.supplier, source.price, moved.amount)
+    ON CONFLICT (branch, component, supplier)
+    DO UPDATE SET amount = "Warehouse".amount + EXCLUDED.amount, deleted_at = NULL;
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "ReceiveTransfer";"#;
+}
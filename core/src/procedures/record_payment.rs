@@ -0,0 +1,42 @@
+use crate::traits::Recreatable;
+
+/// Takes the payment or records the refund on the invoice not in the trash,
+/// refund can't exceed the amount paid on the invoice with former refunds subtracted
+///
+/// The invoice row is locked, so concurrent refunds can't overdraw it together
+pub struct RecordPayment;
+
+impl Recreatable for RecordPayment {
+    const NAME: &'static str = "RecordPayment";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "RecordPayment" (
+    payment uuid,
+    paid_invoice uuid,
+    payment_kind "PaymentKind",
+    payment_method "PaymentMethod",
+    payment_amount monetary,
+    payment_cashier uuid
+) AS $$
+DECLARE
+    net numeric;
+BEGIN
+    PERFORM FROM "Invoice" WHERE uuid = paid_invoice AND deleted_at IS NULL FOR UPDATE;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Invoice % is missing or deleted', paid_invoice;
+    END IF;
+
+    IF payment_kind = 'Refund' THEN
+        SELECT coalesce(sum(CASE WHEN kind = 'Refund' THEN -(amount).amount ELSE (amount).amount END), 0) INTO net
+        FROM "Payment"
+        WHERE invoice = paid_invoice;
+
+        IF (payment_amount).amount > net THEN
+            RAISE EXCEPTION 'Refund of % exceeds % paid on invoice %', (payment_amount).amount, net, paid_invoice;
+        END IF;
+    END IF;
+
+    INSERT INTO "Payment" (uuid, invoice, kind, method, amount, cashier)
+    VALUES (payment, paid_invoice, payment_kind, payment_method, payment_amount, payment_cashier);
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "RecordPayment";"#;
+}
@@ -0,0 +1,74 @@
+use crate::traits::Recreatable;
+
+/// Restores prices preceding the change unless some of them were changed again,
+/// restoring is recorded as a new [`PriceChange`](crate::tables::PriceChange) returned in `price_change`
+pub struct RollbackPrices;
+
+impl Recreatable for RollbackPrices {
+    const NAME: &'static str = "RollbackPrices";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "RollbackPrices" (reverted uuid, INOUT price_change uuid) AS $$
+DECLARE
+    rolled_back "PriceChange";
+BEGIN
+    UPDATE "PriceChange"
+    SET status = 'RolledBack'
+    WHERE uuid = reverted AND status = 'Applied' AND reverts IS NULL
+    RETURNING * INTO rolled_back;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Price change % is not applied or is a rollback itself', reverted;
+    END IF;
+
+    IF EXISTS (
+        SELECT 1
+        FROM "WarehousePrice" wp
+            JOIN "WarehousePrice" later ON later.item = wp.item
+                AND later.uuid <> wp.uuid AND later.effective >= wp.effective
+        WHERE wp.change = reverted
+    ) OR EXISTS (
+        SELECT 1
+        FROM "ServicePrice" sp
+            JOIN "ServicePrice" later ON later.service = sp.service AND later.phone_model = sp.phone_model
+                AND later.uuid <> sp.uuid AND later.effective >= sp.effective
+        WHERE sp.change = reverted
+    ) THEN
+        RAISE EXCEPTION 'Prices of change % were changed since', reverted;
+    END IF;
+
+    INSERT INTO "PriceChange" (coef, scope, target, reverts)
+    VALUES (1 / rolled_back.coef, rolled_back.scope, rolled_back.target, reverted)
+    RETURNING uuid INTO price_change;
+
+    INSERT INTO "WarehousePrice" (item, price, change)
+    SELECT wp.item, (
+        SELECT prev.price
+        FROM "WarehousePrice" prev
+        WHERE prev.item = wp.item AND prev.effective < wp.effective
+        ORDER BY prev.effective DESC
+        LIMIT 1
+    ), price_change
+    FROM "WarehousePrice" wp
+    WHERE wp.change = reverted;
+    INSERT INTO "ServicePrice" (service, phone_model, price, change)
+    SELECT sp.service, sp.phone_model, (
+        SELECT prev.price
+        FROM "ServicePrice" prev
+        WHERE prev.service = sp.service AND prev.phone_model = sp.phone_model
+            AND prev.effective < sp.effective
+        ORDER BY prev.effective DESC
+        LIMIT 1
+    ), price_change
+    FROM "ServicePrice" sp
+    WHERE sp.change = reverted;
+
+    UPDATE "Warehouse" w
+    SET price = wp.price
+    FROM "WarehousePrice" wp
+    WHERE wp.change = price_change AND wp.item = w.uuid;
+    UPDATE "ServicePhoneModel" spm
+    SET price = sp.price
+    FROM "ServicePrice" sp
+    WHERE sp.change = price_change AND sp.service = spm.service AND sp.phone_model = spm.phone_model;
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "RollbackPrices";"#;
+}
@@ -0,0 +1,39 @@
+use crate::traits::Recreatable;
+
+/// Ships warehouse stock to another branch, stock stays in transit until received
+///
+/// Deleted items can't be shipped, the item version is checked by `update_time_func`
+/// unless it's `NULL`
+pub struct TransferStock;
+
+impl Recreatable for TransferStock {
+    const NAME: &'static str = "TransferStock";
+    const CREATE: &'static str = r#"CREATE PROCEDURE "TransferStock" (
+    transfer_item uuid,
+    transfer_destination uuid,
+    transfer_amount int,
+    transfer_sender uuid,
+    version timestamptz DEFAULT NULL
+) AS $$
+BEGIN
+    IF NOT EXISTS (SELECT FROM "Warehouse" WHERE uuid = transfer_item AND deleted_at IS NULL) THEN
+        RAISE EXCEPTION 'Item % is missing or deleted', transfer_item;
+    END IF;
+
+    IF transfer_destination = (SELECT branch FROM "Warehouse" WHERE uuid = transfer_item) THEN
+        RAISE EXCEPTION 'Item % is already in the destination branch', transfer_item;
+    END IF;
+
+    UPDATE "Warehouse"
+    SET amount = amount - transfer_amount, meta.updated = coalesce(version, (meta).updated)
+    WHERE uuid = transfer_item AND deleted_at IS NULL AND amount >= transfer_amount;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Not enough stock of item % to transfer', transfer_item;
+    END IF;
+
+    INSERT INTO "StockTransfer" (item, destination, amount, sender)
+    VALUES (transfer_item, transfer_destination, transfer_amount, transfer_sender);
+END;
+$$ LANGUAGE 'plpgsql';"#;
+    const DROP: &'static str = r#"DROP PROCEDURE "TransferStock";"#;
+}
@@ -0,0 +1,284 @@
+use core::fmt::Write;
+
+use anyhow::{Context, Result};
+use sqlx::{query_as, FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    tables::{Branch, Order, Person, Phone, PhoneModel},
+    types::{Currency, Money},
+    views::OrderWarranty,
+};
+
+/// Service or part listed on the receipt
+#[derive(FromRow, Clone, Debug)]
+pub struct ReceiptLine {
+    pub name: String,
+    pub amount: i32,
+    /// Price for a single unit
+    pub price: Money,
+    /// Price for all units
+    pub total: Money,
+}
+
+/// Printable work order handed to the client
+#[derive(Clone, Debug)]
+pub struct Receipt {
+    pub order: Order,
+    pub branch: Branch,
+    pub client: Person,
+    pub phone: Phone,
+    pub model: PhoneModel,
+    pub services: Vec<ReceiptLine>,
+    pub parts: Vec<ReceiptLine>,
+    /// Original order if this one is a warranty claim
+    pub original: Option<Order>,
+    /// Warranty granted on the services, empty unless the order is complete
+    pub warranties: Vec<OrderWarranty>,
+    pub services_total: Money,
+    pub parts_total: Money,
+    pub total: Money,
+}
+
+impl Receipt {
+    /// Services of the order, also explained by [`Probe`](crate::explain::Probe)
+    pub const SERVICES: &'static str = r#"SELECT s.name, 1 as amount, os.price, os.price as total
+FROM "OrderService" os
+    JOIN "Service" s ON os.service = s.uuid
+WHERE os."order" = $1
+ORDER BY s.name"#;
+
+    /// Parts spent on the order, also explained by [`Probe`](crate::explain::Probe)
+    pub const PARTS: &'static str = r#"SELECT c.name, ow.amount, ow.price,
+    (round((ow.price).amount * ow.amount, 2), (ow.price).currency)::monetary as total
+FROM "OrderWarehouse" ow
+    JOIN "Warehouse" w ON ow.item = w.uuid
+    JOIN "Component" c ON w.component = c.uuid
+WHERE ow."order" = $1
+ORDER BY c.name"#;
+
+    pub async fn fetch(pool: &PgPool, order: Uuid) -> Result<Self> {
+        let order = Order::get_by_uuid(order)
+            .fetch_one(pool)
+            .await
+            .with_context(|| format!("While fetching order '{order}'"))?;
+
+        Self::fetch_for(pool, order).await
+    }
+
+    pub async fn fetch_by_tracking(pool: &PgPool, tracking: &str) -> Result<Self> {
+        let order = Order::get_by_tracking(tracking)
+            .fetch_one(pool)
+            .await
+            .with_context(|| format!("While fetching order with tracking code '{tracking}'"))?;
+
+        Self::fetch_for(pool, order).await
+    }
+
+    async fn fetch_for(pool: &PgPool, order: Order) -> Result<Self> {
+        let branch = Branch::get_by_uuid(order.branch).fetch_one(pool).await?;
+        let client = Person::get_by_uuid(order.client).fetch_one(pool).await?;
+        let phone = Phone::get_by_uuid(order.phone).fetch_one(pool).await?;
+        let model = PhoneModel::get_by_uuid(phone.model).fetch_one(pool).await?;
+        let services = query_as(Self::SERVICES)
+            .bind(order.uuid)
+            .fetch_all(pool)
+            .await
+            .context("While fetching order services")?;
+        let parts = query_as(Self::PARTS)
+            .bind(order.uuid)
+            .fetch_all(pool)
+            .await
+            .context("While fetching order parts")?;
+        let original = match order.warranty_of {
+            Some(original) => Some(Order::get_by_uuid(original).fetch_one(pool).await?),
+            None => None,
+        };
+        let warranties = OrderWarranty::get_by_order(order.uuid)
+            .fetch_all(pool)
+            .await
+            .context("While fetching order warranty")?;
+        let services_total = lines_total(&services)?;
+        let parts_total = lines_total(&parts)?;
+        let total = services_total.checked_add(&parts_total)?;
+
+        Ok(Self {
+            order,
+            branch,
+            client,
+            phone,
+            model,
+            services,
+            parts,
+            original,
+            warranties,
+            services_total,
+            parts_total,
+            total,
+        })
+    }
+
+    /// Default file name for the rendered receipt
+    pub fn file_name(&self) -> String {
+        format!("receipt-{}.html", self.order.tracking)
+    }
+
+    /// Renders self-contained html page ready for printing
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let client = match &self.client.middle_name {
+            Some(middle_name) => format!(
+                "{} {} {}",
+                self.client.first_name, middle_name, self.client.last_name
+            ),
+            None => format!("{} {}", self.client.first_name, self.client.last_name),
+        };
+
+        html.push_str(HEAD);
+        let _ = write!(
+            html,
+            r#"<header>
+<h1>{branch}</h1>
+<p>{address}<br>{branch_phone}</p>
+</header>
+<h2>Work order <span class="tracking">{tracking}</span></h2>
+<table class="details">
+<tr><th>Date</th><td>{created}</td></tr>
+<tr><th>Status</th><td>{status}</td></tr>
+<tr><th>Client</th><td>{client}</td></tr>
+<tr><th>Contacts</th><td>{phone}, {email}</td></tr>
+<tr><th>Device</th><td>{model} ({color})</td></tr>
+<tr><th>IMEI</th><td>{imei}</td></tr>
+{claim}</table>
+"#,
+            branch = escape(&self.branch.name),
+            address = escape(&self.branch.address),
+            branch_phone = escape(&self.branch.phone),
+            tracking = escape(&self.order.tracking),
+            created = self.order.meta.created.format("%Y-%m-%d %H:%M"),
+            status = self.order.status.as_str(),
+            client = escape(&client),
+            phone = escape(&self.client.phone),
+            email = escape(&self.client.email),
+            model = escape(&self.model.name),
+            color = self.phone.color.as_str(),
+            imei = self.phone.imei,
+            claim = match &self.original {
+                Some(original) => format!(
+                    "<tr><th>Warranty claim</th><td>Repair of order {}, free of charge</td></tr>\n",
+                    escape(&original.tracking)
+                ),
+                None => String::new(),
+            },
+        );
+        lines(&mut html, "Services", &self.services, &self.services_total);
+        lines(&mut html, "Parts", &self.parts, &self.parts_total);
+        if !self.warranties.is_empty() {
+            html.push_str(
+                "<h3>Warranty</h3>\n<table>\n<tr><th>Service</th><th class=\"number\">Valid until</th></tr>\n",
+            );
+            for warranty in &self.warranties {
+                let _ = writeln!(
+                    html,
+                    r#"<tr><td>{}</td><td class="number">{}</td></tr>"#,
+                    escape(&warranty.service),
+                    warranty.expires.format("%Y-%m-%d"),
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        let _ = write!(
+            html,
+            r#"<p class="total">Total: {}</p>
+<p>Quote code <b>{}</b> at the counter to check the order status.</p>
+<footer>
+<span>Shopman ____________</span>
+<span>Client ____________</span>
+</footer>
+</body>
+</html>
+"#,
+            self.total,
+            escape(&self.order.tracking),
+        );
+
+        html
+    }
+}
+
+const HEAD: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Work order</title>
+<style>
+body { font-family: sans-serif; max-width: 720px; margin: 24px auto; }
+header { border-bottom: 2px solid black; }
+table { width: 100%; border-collapse: collapse; margin-bottom: 16px; }
+th, td { text-align: left; padding: 4px; border-bottom: 1px solid #ccc; }
+.details th { width: 120px; }
+.tracking { font-family: monospace; letter-spacing: 2px; }
+.number { text-align: right; }
+.total { font-size: 1.3em; font-weight: bold; text-align: right; }
+footer { display: flex; justify-content: space-between; margin-top: 48px; }
+</style>
+</head>
+<body>
+"#;
+
+/// Writes table of receipt lines, nothing if there are no lines
+fn lines(html: &mut String, title: &str, lines: &[ReceiptLine], total: &Money) {
+    if lines.is_empty() {
+        return;
+    }
+
+    let _ = write!(
+        html,
+        r#"<h3>{title}</h3>
+<table>
+<tr><th>Name</th><th class="number">Amount</th><th class="number">Price</th><th class="number">Sum</th></tr>
+"#
+    );
+    for line in lines {
+        let _ = writeln!(
+            html,
+            r#"<tr><td>{}</td><td class="number">{}</td><td class="number">{}</td><td class="number">{}</td></tr>"#,
+            escape(&line.name),
+            line.amount,
+            line.price,
+            line.total,
+        );
+    }
+    let _ = write!(
+        html,
+        r#"<tr><th colspan="3">Subtotal</th><th class="number">{}</th></tr>
+</table>
+"#,
+        total
+    );
+}
+
+/// Lines are priced in the base currency, mixing currencies is an error
+fn lines_total(lines: &[ReceiptLine]) -> Result<Money> {
+    Ok(Money::checked_sum(
+        lines.iter().map(|line| &line.total),
+        Currency::BASE,
+    )?)
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,145 @@
+use core::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{pool::PoolOptions, query_as, Error, Executor, Postgres};
+
+/// Postgres schema all application objects live in, allows to keep several shops in one database
+///
+/// Objects are resolved through the connection `search_path`,
+/// `public` always stays in the path as extensions are loaded there
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub struct Schema(String);
+
+impl Schema {
+    pub const PUBLIC: &'static str = "public";
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.0 == Self::PUBLIC
+    }
+
+    pub fn create(&self) -> String {
+        format!(r#"CREATE SCHEMA IF NOT EXISTS "{}";"#, self.0)
+    }
+
+    pub fn drop(&self) -> String {
+        format!(r#"DROP SCHEMA IF EXISTS "{}";"#, self.0)
+    }
+
+    pub fn search_path(&self) -> String {
+        if self.is_public() {
+            format!("SET search_path TO {};", Self::PUBLIC)
+        } else {
+            format!(r#"SET search_path TO "{}", {};"#, self.0, Self::PUBLIC)
+        }
+    }
+
+    pub async fn exists<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
+        match query_as::<Postgres, (bool,)>(
+            "SELECT true FROM pg_catalog.pg_namespace WHERE nspname = $1",
+        )
+        .bind(self.name())
+        .fetch_one(executor)
+        .await
+        {
+            Ok(_) => Ok(true),
+            Err(err) => {
+                if let Error::RowNotFound = err {
+                    Ok(false)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    /// Fails unless objects get resolved in this schema, `current_schema()` silently falls back
+    /// to the next schema of the `search_path` when this one doesn't exist
+    pub async fn ensure_current<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<(), Error> {
+        let (current,): (Option<String>,) = query_as("SELECT current_schema();")
+            .fetch_one(executor)
+            .await?;
+
+        if current.as_deref() == Some(self.name()) {
+            Ok(())
+        } else {
+            Err(Error::Configuration(
+                format!(
+                    "Schema '{self}' doesn't exist, objects would be resolved in '{}'",
+                    current.unwrap_or_default()
+                )
+                .into(),
+            ))
+        }
+    }
+
+    /// Sets `search_path` for every new connection of the pool, the schema has to exist
+    pub fn apply(&self, options: PoolOptions<Postgres>) -> PoolOptions<Postgres> {
+        let schema = self.clone();
+
+        options.after_connect(move |conn| {
+            let schema = schema.clone();
+            Box::pin(async move {
+                conn.execute(schema.search_path().as_str()).await?;
+                schema.ensure_current(conn).await
+            })
+        })
+    }
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Self(Self::PUBLIC.to_string())
+    }
+}
+
+impl FromStr for Schema {
+    type Err = String;
+
+    /// Accepts only lowercase unquoted identifiers, so names never need escaping
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let valid = s.len() <= 63
+            && matches!(chars.next(), Some('a'..='z' | '_'))
+            && chars.all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_'))
+            && !s.starts_with("pg_");
+
+        if valid {
+            Ok(Self(s.to_string()))
+        } else {
+            Err(format!(
+                "Invalid schema name '{s}' (lowercase letters, digits and underscores expected)"
+            ))
+        }
+    }
+}
+
+impl TryFrom<String> for Schema {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Schema> for String {
+    fn from(schema: Schema) -> Self {
+        schema.0
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
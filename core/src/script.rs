@@ -0,0 +1,408 @@
+use core::fmt;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use sqlx::{postgres::PgDatabaseError, query, query_as, Error, Executor, PgPool, Postgres};
+
+use crate::{
+    extensions::Extension, functions::Function, indexes::Index, procedures::Procedure,
+    schema::Schema, tables::Table, triggers::Trigger, types::DbType, views::View,
+};
+
+/// Any database object managed by the application
+#[derive(Clone, Copy, Debug)]
+pub enum Object {
+    Extension(Extension),
+    Type(DbType),
+    Table(Table),
+    Index(Index),
+    View(View),
+    Function(Function),
+    Procedure(Procedure),
+    Trigger(Trigger, Table),
+}
+
+impl Object {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Extension(_) => "extension",
+            Self::Type(_) => "type",
+            Self::Table(_) => "table",
+            Self::Index(_) => "index",
+            Self::View(_) => "view",
+            Self::Function(_) => "function",
+            Self::Procedure(_) => "procedure",
+            Self::Trigger(..) => "trigger",
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Extension(extension) => extension.name().to_string(),
+            Self::Type(db_type) => db_type.name().to_string(),
+            Self::Table(table) => table.name().to_string(),
+            Self::Index(index) => index.name().to_string(),
+            Self::View(view) => view.name().to_string(),
+            Self::Function(function) => function.name().to_string(),
+            Self::Procedure(procedure) => procedure.name().to_string(),
+            Self::Trigger(trigger, table) => format!("{trigger} on {table}"),
+        }
+    }
+
+    pub fn create(&self) -> String {
+        match self {
+            Self::Extension(extension) => extension.create().to_string(),
+            Self::Type(db_type) => db_type.create().to_string(),
+            Self::Table(table) => table.create().to_string(),
+            Self::Index(index) => index.create(),
+            Self::View(view) => view.create().to_string(),
+            Self::Function(function) => function.create(),
+            Self::Procedure(procedure) => procedure.create(),
+            Self::Trigger(trigger, table) => trigger.create(*table),
+        }
+    }
+
+    /// Routines are dropped by the qualified name, see [`Function::drop_from`]
+    pub fn drop(&self, schema: &Schema) -> String {
+        match self {
+            Self::Extension(extension) => extension.drop().to_string(),
+            Self::Type(db_type) => db_type.drop().to_string(),
+            Self::Table(table) => table.drop().to_string(),
+            Self::Index(index) => index.drop(),
+            Self::View(view) => view.drop().to_string(),
+            Self::Function(function) => function.drop_from(schema),
+            Self::Procedure(procedure) => procedure.drop_from(schema),
+            Self::Trigger(trigger, table) => trigger.drop(*table),
+        }
+    }
+
+    /// Catalog query returning a row if the object exists in the current schema
+    ///
+    /// Shared by [`Object::exists`] and the guards of the printed [`Script::sql`], so both
+    /// skip the same objects
+    pub fn exists_sql(&self) -> String {
+        match self {
+            Self::Extension(extension) => format!(
+                "SELECT 1 FROM pg_catalog.pg_extension WHERE extname = '{}'",
+                extension.name()
+            ),
+            Self::Type(db_type) => format!(
+                "SELECT 1 FROM pg_catalog.pg_type WHERE typname = '{}' AND typnamespace = current_schema()::regnamespace",
+                db_type.name()
+            ),
+            Self::Table(_) | Self::Index(_) | Self::View(_) => format!(
+                "SELECT 1 FROM pg_catalog.pg_class WHERE relkind IN ({}) AND relname = '{}' AND relnamespace = current_schema()::regnamespace",
+                match self {
+                    Self::Table(_) => "'r', 'p'",
+                    Self::Index(_) => "'i'",
+                    _ => "'v', 'm'",
+                },
+                self.name()
+            ),
+            Self::Function(_) | Self::Procedure(_) => format!(
+                "SELECT 1 FROM pg_catalog.pg_proc WHERE proname = '{}' AND pronamespace = current_schema()::regnamespace",
+                self.name()
+            ),
+            Self::Trigger(trigger, table) => format!(
+                r#"SELECT 1 FROM pg_catalog.pg_trigger trg JOIN pg_catalog.pg_class cls ON trg.tgrelid = cls.oid
+WHERE trg.tgname = '{trigger}' AND cls.relname = '{table}' AND cls.relnamespace = current_schema()::regnamespace"#
+            ),
+        }
+    }
+
+    pub async fn exists<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
+        query_as::<_, (bool,)>(&format!("SELECT EXISTS ({});", self.exists_sql()))
+            .fetch_one(executor)
+            .await
+            .map(|(exists,)| exists)
+    }
+
+    /// Statement of the action skipped for existing (or already missing while dropping) object,
+    /// dropping of extensions tolerates the same errors as [`Script::execute`]
+    pub fn guarded(&self, action: Action, schema: &Schema) -> String {
+        let (condition, statement) = match action {
+            Action::Create => ("NOT EXISTS", self.create()),
+            Action::Drop => ("EXISTS", self.drop(schema)),
+        };
+        let exception = match (action, self) {
+            (Action::Drop, Self::Extension(_)) => {
+                "\nEXCEPTION\n    WHEN insufficient_privilege OR dependent_objects_still_exist THEN NULL;"
+            }
+            _ => "",
+        };
+
+        format!(
+            "DO $guard$\nBEGIN\n    IF {condition} ({}) THEN\n        EXECUTE $statement${statement}$statement$;\n    END IF;{exception}\nEND;\n$guard$;",
+            self.exists_sql()
+        )
+    }
+}
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} '{}'", self.kind(), self.name())
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Create,
+    Drop,
+}
+
+#[derive(Serialize, Clone, Copy, Debug)]
+pub enum Status {
+    Created,
+    AlreadyExists,
+    Dropped,
+    Missing,
+    /// Object was left in place for the lack of privileges (e.g. extensions owned by superuser)
+    PermissionDenied,
+    /// Object was left in place as other schemas depend on it
+    InUse,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Created => "Created",
+            Self::AlreadyExists => "Already Exists",
+            Self::Dropped => "Dropped",
+            Self::Missing => "Missing",
+            Self::PermissionDenied => "Permission Denied",
+            Self::InUse => "In Use",
+        })
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Outcome {
+    pub kind: &'static str,
+    pub name: String,
+    pub status: Status,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Summary {
+    pub action: Action,
+    pub outcomes: Vec<Outcome>,
+}
+
+/// Ordered list of objects to be created or dropped within a single transaction
+///
+/// Objects which already exist (or are already missing while dropping) are skipped,
+/// non-public schema is created before the objects and dropped after them
+#[derive(Debug)]
+pub struct Script {
+    pub action: Action,
+    pub schema: Schema,
+    pub objects: Vec<Object>,
+}
+
+impl Script {
+    /// Every application object in dependency order
+    fn objects() -> Vec<Object> {
+        let mut objects = Vec::new();
+
+        objects.extend(Extension::ALL.map(Object::Extension));
+        objects.extend(DbType::ALL.map(Object::Type));
+        objects.extend(
+            Function::ALL
+                .into_iter()
+                .filter(Function::is_table_dependency)
+                .map(Object::Function),
+        );
+        objects.extend(Table::ALL.map(Object::Table));
+        objects.extend(Index::ALL.map(Object::Index));
+        objects.extend(View::ALL.map(Object::View));
+        objects.extend(
+            Function::ALL
+                .into_iter()
+                .filter(|function| !function.is_table_dependency())
+                .map(Object::Function),
+        );
+        objects.extend(Procedure::ALL.map(Object::Procedure));
+        for trigger in Trigger::ALL {
+            objects.extend(
+                trigger
+                    .tables()
+                    .iter()
+                    .map(|table| Object::Trigger(trigger, *table)),
+            );
+        }
+
+        objects
+    }
+
+    pub fn create(schema: Schema) -> Self {
+        Self {
+            action: Action::Create,
+            schema,
+            objects: Self::objects(),
+        }
+    }
+
+    /// Extensions are shared between schemas, so they are kept unless dropping `public` one
+    pub fn drop(schema: Schema) -> Self {
+        let objects = Self::objects()
+            .into_iter()
+            .rev()
+            .filter(|object| schema.is_public() || !matches!(object, Object::Extension(_)))
+            .collect();
+
+        Self {
+            action: Action::Drop,
+            schema,
+            objects,
+        }
+    }
+
+    pub fn statement(&self, object: &Object) -> String {
+        match self.action {
+            Action::Create => object.create(),
+            Action::Drop => object.drop(&self.schema),
+        }
+    }
+
+    /// Full sql script in execution order, every statement is guarded the same way
+    /// [`Script::execute`] skips the objects, so the script can be run repeatedly
+    pub fn sql(&self) -> String {
+        let mut sql = String::from("BEGIN;\n\n");
+
+        if let (Action::Create, false) = (self.action, self.schema.is_public()) {
+            sql.push_str(&format!("{}\n", self.schema.create()));
+        }
+        sql.push_str(&format!("{}\n\n", self.schema.search_path()));
+        for object in &self.objects {
+            sql.push_str(&format!(
+                "-- {object}\n{}\n\n",
+                object.guarded(self.action, &self.schema)
+            ));
+        }
+        if let (Action::Drop, false) = (self.action, self.schema.is_public()) {
+            sql.push_str(&format!("{}\n\n", self.schema.drop()));
+        }
+        sql.push_str("COMMIT;\n");
+
+        sql
+    }
+
+    /// Executes the script, nothing is changed if any statement fails
+    ///
+    /// Pool connections may use any schema, the script sets `search_path` itself
+    /// as the schema may be missing yet
+    pub async fn execute(&self, pool: &PgPool, printer: impl Fn(&Outcome)) -> Result<Summary> {
+        let mut tx = pool.begin().await?;
+        let mut outcomes = Vec::with_capacity(self.objects.len() + 1);
+        let mut report = |kind, name: &str, status| {
+            let outcome = Outcome {
+                kind,
+                name: name.to_string(),
+                status,
+            };
+            printer(&outcome);
+            outcomes.push(outcome);
+        };
+
+        if let (Action::Create, false) = (self.action, self.schema.is_public()) {
+            let status = if self.schema.exists(&mut tx).await? {
+                Status::AlreadyExists
+            } else {
+                query(&self.schema.create())
+                    .execute(&mut tx)
+                    .await
+                    .with_context(|| format!("While creating schema '{}'", self.schema))?;
+                Status::Created
+            };
+            report("schema", self.schema.name(), status);
+        }
+
+        if let (Action::Drop, false) = (self.action, self.schema.is_public()) {
+            if !self.schema.exists(&mut tx).await? {
+                report("schema", self.schema.name(), Status::Missing);
+                tx.commit().await?;
+                return Ok(Summary {
+                    action: self.action,
+                    outcomes,
+                });
+            }
+        }
+
+        query(&self.schema.search_path()).execute(&mut tx).await?;
+        self.schema.ensure_current(&mut tx).await?;
+
+        for object in &self.objects {
+            let exists = object
+                .exists(&mut tx)
+                .await
+                .with_context(|| format!("While checking {object} existence"))?;
+
+            let status = match (self.action, exists) {
+                (Action::Create, true) => Status::AlreadyExists,
+                (Action::Drop, false) => Status::Missing,
+                (Action::Create, false) => {
+                    query(&self.statement(object))
+                        .execute(&mut tx)
+                        .await
+                        .with_context(|| format!("While creating {object}"))?;
+                    Status::Created
+                }
+                (Action::Drop, true) => {
+                    // Extensions may belong to another role, which must not abort the whole script
+                    query("SAVEPOINT drop_object;").execute(&mut tx).await?;
+                    match query(&self.statement(object)).execute(&mut tx).await {
+                        Ok(_) => {
+                            query("RELEASE SAVEPOINT drop_object;")
+                                .execute(&mut tx)
+                                .await?;
+                            Status::Dropped
+                        }
+                        Err(Error::Database(err)) if matches!(object, Object::Extension(_)) => {
+                            let status = match err.downcast_ref::<PgDatabaseError>().code() {
+                                "42501" => Some(Status::PermissionDenied),
+                                "2BP01" => Some(Status::InUse),
+                                _ => None,
+                            };
+                            let status = match status {
+                                Some(status) => status,
+                                None => {
+                                    return Err(Error::Database(err))
+                                        .with_context(|| format!("While dropping {object}"))
+                                }
+                            };
+                            query("ROLLBACK TO SAVEPOINT drop_object;")
+                                .execute(&mut tx)
+                                .await?;
+                            status
+                        }
+                        Err(err) => {
+                            return Err(err).with_context(|| format!("While dropping {object}"))
+                        }
+                    }
+                }
+            };
+
+            report(object.kind(), &object.name(), status);
+        }
+
+        if let (Action::Drop, false) = (self.action, self.schema.is_public()) {
+            // Fails if the schema still contains foreign objects
+            query(&self.schema.drop())
+                .execute(&mut tx)
+                .await
+                .with_context(|| format!("While dropping schema '{}'", self.schema))?;
+            report("schema", self.schema.name(), Status::Dropped);
+        }
+
+        tx.commit().await?;
+
+        Ok(Summary {
+            action: self.action,
+            outcomes,
+        })
+    }
+}
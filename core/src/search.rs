@@ -0,0 +1,179 @@
+//! Search of clients, phones, components and suppliers for the counter staff
+//!
+//! Names are matched by word prefixes (full-text) and by similar words (`pg_trgm`),
+//! emails by a part of them, phone numbers and IMEIs by a part of their digits.
+//! Every match is served by one of the search [`Index`](crate::indexes::Index)es
+
+use anyhow::{Context, Result};
+use sqlx::{query_as, PgPool};
+
+use crate::{
+    tables::{Person, Supplier},
+    views::{ComponentBeautified, PhoneBeautified},
+};
+
+/// Full name of the person, shared by the query and the indexes to match them
+pub(crate) const PERSON_NAME: &str =
+    "first_name || ' ' || coalesce(middle_name || ' ', '') || last_name";
+pub(crate) const PERSON_NAME_TEXT: &str =
+    "to_tsvector('simple', first_name || ' ' || coalesce(middle_name || ' ', '') || last_name)";
+/// Digits of the phone number, so `+7 (912)` and `7912` are the same
+pub(crate) const PERSON_PHONE: &str = r#"regexp_replace(phone, '\D', '', 'g')"#;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SearchGroup {
+    Clients,
+    Phones,
+    Components,
+    Suppliers,
+}
+
+impl SearchGroup {
+    pub const ALL: [Self; 4] = [
+        Self::Clients,
+        Self::Phones,
+        Self::Components,
+        Self::Suppliers,
+    ];
+}
+
+/// Found rows grouped by their kind, the best matches first
+#[derive(Default, Debug)]
+pub struct Search {
+    pub clients: Vec<Person>,
+    pub phones: Vec<PhoneBeautified>,
+    pub components: Vec<ComponentBeautified>,
+    pub suppliers: Vec<Supplier>,
+}
+
+impl Search {
+    /// Rows per group
+    pub const LIMIT: i64 = 10;
+    /// Shorter text (or number) matches nearly everything
+    pub const MIN_LENGTH: usize = 3;
+
+    /// Searches the given groups only, the others are left empty
+    pub async fn run(
+        pool: &PgPool,
+        text: &str,
+        groups: &[SearchGroup],
+        limit: i64,
+    ) -> Result<Self> {
+        let text = text.trim();
+        let mut search = Self::default();
+        if text.chars().count() < Self::MIN_LENGTH {
+            return Ok(search);
+        }
+
+        let pattern = Self::pattern(text);
+        let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+        let digits = (digits.len() >= Self::MIN_LENGTH).then(|| Self::pattern(&digits));
+        let prefixes = Self::prefixes(text);
+
+        if groups.contains(&SearchGroup::Clients) {
+            search.clients = query_as(&format!(
+                r#"SELECT * FROM "Person"
+WHERE deleted_at IS NULL AND (
+    {PERSON_NAME_TEXT} @@ to_tsquery('simple', $2)
+    OR $1 <% ({PERSON_NAME})
+    OR email ILIKE $3
+    OR {PERSON_PHONE} LIKE $4
+)
+ORDER BY ({PERSON_PHONE} LIKE $4) IS TRUE DESC,
+    greatest(word_similarity($1, {PERSON_NAME}), similarity($1, email)) DESC,
+    last_name
+LIMIT $5;"#
+            ))
+            .bind(text)
+            .bind(&prefixes)
+            .bind(&pattern)
+            .bind(&digits)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .context("While searching clients")?;
+        }
+
+        if groups.contains(&SearchGroup::Phones) {
+            search.phones = query_as(
+                // Conditions on both joined tables can't use their indexes at once
+                r#"SELECT * FROM "PhoneBeautified"
+WHERE uuid IN (
+    SELECT uuid FROM "Phone" WHERE imei::text LIKE $3
+    UNION
+    SELECT ph.uuid FROM "Phone" ph JOIN "PhoneModel" pm ON pm.uuid = ph.model
+    WHERE pm.name ILIKE $2 OR $1 <% pm.name
+)
+ORDER BY (imei::text LIKE $3) IS TRUE DESC, word_similarity($1, model) DESC, owner
+LIMIT $4;"#,
+            )
+            .bind(text)
+            .bind(&pattern)
+            .bind(&digits)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .context("While searching phones")?;
+        }
+
+        if groups.contains(&SearchGroup::Components) {
+            search.components = query_as(
+                r#"SELECT * FROM "ComponentBeautified"
+WHERE name ILIKE $2 OR $1 <% name
+ORDER BY word_similarity($1, name) DESC, name
+LIMIT $3;"#,
+            )
+            .bind(text)
+            .bind(&pattern)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .context("While searching components")?;
+        }
+
+        if groups.contains(&SearchGroup::Suppliers) {
+            search.suppliers = query_as(
+                r#"SELECT * FROM "Supplier"
+WHERE deleted_at IS NULL AND (name ILIKE $2 OR $1 <% name)
+ORDER BY word_similarity($1, name) DESC, name
+LIMIT $3;"#,
+            )
+            .bind(text)
+            .bind(&pattern)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+            .context("While searching suppliers")?;
+        }
+
+        Ok(search)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+            && self.phones.is_empty()
+            && self.components.is_empty()
+            && self.suppliers.is_empty()
+    }
+
+    /// `LIKE` pattern matching the text anywhere
+    fn pattern(text: &str) -> String {
+        let escaped = text
+            .replace('\\', r"\\")
+            .replace('%', r"\%")
+            .replace('_', r"\_");
+
+        format!("%{escaped}%")
+    }
+
+    /// Full-text query matching words starting with every word of the text
+    fn prefixes(text: &str) -> Option<String> {
+        let words: Vec<_> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| format!("{}:*", word.to_lowercase()))
+            .collect();
+
+        (!words.is_empty()).then(|| words.join(" & "))
+    }
+}
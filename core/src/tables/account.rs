@@ -80,8 +80,8 @@ impl Account {
 impl Insertable for Account {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "Account" (uuid, staff, login, password, role, status) 
-VALUES ($1, $2, $3, $4, $5, $6);"#,
+            r#"INSERT INTO "Account" (uuid, staff, login, password, role, status, meta) 
+VALUES ($1, $2, $3, $4, $5, $6, $7);"#,
         )
         .bind(self.uuid)
         .bind(self.staff)
@@ -89,5 +89,6 @@ VALUES ($1, $2, $3, $4, $5, $6);"#,
         .bind(self.password.clone())
         .bind(self.role)
         .bind(self.status)
+        .bind(self.meta.clone())
     }
 }
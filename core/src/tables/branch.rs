@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{traits::Insertable, types::metatime::MetaTime, PgQuery, PgQueryAs};
+
+/// Shop location with its own staff, stock and orders
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct Branch {
+    pub uuid: Uuid,
+    pub name: String,
+    pub address: String,
+    pub phone: String,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub meta: MetaTime,
+}
+
+impl Branch {
+    pub const NAME: &'static str = "Branch";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "Branch" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name text UNIQUE NOT NULL,
+    address text NOT NULL,
+    phone text NOT NULL CHECK (length(phone) <= 18),
+    deleted_at timestamptz,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "Branch";"#;
+
+    pub const fn new(
+        uuid: Uuid,
+        name: String,
+        address: String,
+        phone: String,
+        deleted_at: Option<DateTime<Utc>>,
+        meta: MetaTime,
+    ) -> Self {
+        Self {
+            uuid,
+            name,
+            address,
+            phone,
+            deleted_at,
+            meta,
+        }
+    }
+
+    pub fn new_auto(name: String, address: String, phone: String) -> Self {
+        Self::new(
+            Uuid::new_v4(),
+            name,
+            address,
+            phone,
+            None,
+            MetaTime::default(),
+        )
+    }
+
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Branch" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Branch" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
+    }
+
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Branch" WHERE deleted_at IS NULL"#)
+    }
+}
+
+impl Insertable for Branch {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "Branch" (uuid, name, address, phone, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.name.clone())
+        .bind(self.address.clone())
+        .bind(self.phone.clone())
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
+    }
+}
@@ -1,8 +1,9 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgArguments, query, query::Query, FromRow, Postgres};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
-use crate::{traits::Insertable, PgQuery};
+use crate::{traits::Insertable, types::metatime::MetaTime, PgQuery, PgQueryAs};
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
 pub struct Component {
@@ -14,6 +15,8 @@ pub struct Component {
     pub phone_model: Uuid,
     /// Foreign key references [`Manufacturer`](`super::manufacturer::Manufacturer`)
     pub manufacturer: Uuid,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub meta: MetaTime,
 }
 
 impl Component {
@@ -24,7 +27,9 @@ impl Component {
     name text NOT NULL,
     kind uuid NOT NULL REFERENCES "ComponentKind" ON DELETE restrict ON UPDATE cascade,
     phone_model uuid NOT NULL REFERENCES "PhoneModel" ON DELETE restrict ON UPDATE cascade,
-    manufacturer uuid NOT NULL REFERENCES "Manufacturer" ON DELETE restrict ON UPDATE cascade
+    manufacturer uuid NOT NULL REFERENCES "Manufacturer" ON DELETE restrict ON UPDATE cascade,
+    deleted_at timestamptz,
+    meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Component";"#;
@@ -158,6 +163,8 @@ impl Component {
         kind: Uuid,
         model: Uuid,
         manufacturer: Uuid,
+        deleted_at: Option<DateTime<Utc>>,
+        meta: MetaTime,
     ) -> Self {
         Self {
             uuid,
@@ -165,28 +172,49 @@ impl Component {
             kind,
             phone_model: model,
             manufacturer,
+            deleted_at,
+            meta,
         }
     }
 
     pub fn new_auto(name: String, kind: Uuid, model: Uuid, manufacturer: Uuid) -> Self {
-        Self::new(Uuid::new_v4(), name, kind, model, manufacturer)
+        Self::new(
+            Uuid::new_v4(),
+            name,
+            kind,
+            model,
+            manufacturer,
+            None,
+            MetaTime::default(),
+        )
     }
 
-    pub fn delete_by_uuid(uuid: Uuid) -> PgQuery {
-        query(r#"DELETE FROM "Component" WHERE uuid = $1"#).bind(uuid)
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Component" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Component" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
     }
 }
 
 impl Insertable for Component {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "Component" (uuid, name, kind, phone_model, manufacturer)
-VALUES ($1, $2, $3, $4, $5);"#,
+            r#"INSERT INTO "Component" (uuid, name, kind, phone_model, manufacturer, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7);"#,
         )
         .bind(self.uuid)
         .bind(self.name.clone())
         .bind(self.kind)
         .bind(self.phone_model)
         .bind(self.manufacturer)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
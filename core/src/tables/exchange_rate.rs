@@ -0,0 +1,82 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    postgres::PgArguments, query, query::Query, query_as, types::BigDecimal, FromRow, Postgres,
+};
+
+use crate::{
+    traits::Insertable,
+    types::{Currency, MetaTime},
+    PgQuery, PgQueryAs,
+};
+
+/// Price of one unit of the currency in [`Currency::BASE`], effective from the given day
+/// till the next rate of the same currency
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct ExchangeRate {
+    pub currency: Currency,
+    pub day: NaiveDate,
+    pub rate: BigDecimal,
+    pub meta: MetaTime,
+}
+
+impl ExchangeRate {
+    pub const NAME: &'static str = "ExchangeRate";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "ExchangeRate" (
+    currency text NOT NULL CHECK (currency ~ '^[A-Z]{3}$' AND currency <> base_currency()),
+    day date NOT NULL,
+    rate numeric(18, 6) NOT NULL CHECK (rate > 0),
+    meta metatime NOT NULL DEFAULT (now(), now()),
+    PRIMARY KEY(currency, day)
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "ExchangeRate";"#;
+
+    pub const fn new(currency: Currency, day: NaiveDate, rate: BigDecimal, meta: MetaTime) -> Self {
+        Self {
+            currency,
+            day,
+            rate,
+            meta,
+        }
+    }
+
+    pub fn new_auto(currency: Currency, day: NaiveDate, rate: BigDecimal) -> Self {
+        Self::new(currency, day, rate, MetaTime::default())
+    }
+
+    /// Rates of the currency, latest first
+    pub fn get_by_currency(currency: Currency) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "ExchangeRate" WHERE currency = $1 ORDER BY day DESC"#)
+            .bind(currency)
+    }
+
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "ExchangeRate" ORDER BY currency, day"#)
+    }
+
+    /// Inserts the rate or replaces the one already set for the day
+    pub fn upsert(&self) -> PgQuery {
+        query(
+            r#"INSERT INTO "ExchangeRate" (currency, day, rate) VALUES ($1, $2, $3)
+ON CONFLICT (currency, day) DO UPDATE SET rate = EXCLUDED.rate;"#,
+        )
+        .bind(self.currency)
+        .bind(self.day)
+        .bind(self.rate.clone())
+    }
+}
+
+impl Insertable for ExchangeRate {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "ExchangeRate" (currency, day, rate, meta)
+VALUES ($1, $2, $3, $4);"#,
+        )
+        .bind(self.currency)
+        .bind(self.day)
+        .bind(self.rate.clone())
+        .bind(self.meta.clone())
+    }
+}
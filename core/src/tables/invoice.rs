@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{
+    traits::Insertable,
+    types::{metatime::MetaTime, Money},
+    PgQuery, PgQueryAs,
+};
+
+/// Amount billed to the client for the order, extra work may be billed with additional invoices
+///
+/// Invoices and payments are in the base currency only, reports sum them up as is
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct Invoice {
+    pub uuid: Uuid,
+    /// Foreign key references [`Order`](`super::order::Order`)
+    pub order: Uuid,
+    pub amount: Money,
+    /// Foreign key references [`Staff`](`super::staff::Staff`)
+    pub issuer: Uuid,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub meta: MetaTime,
+}
+
+impl Invoice {
+    pub const NAME: &'static str = "Invoice";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "Invoice" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    "order" uuid NOT NULL REFERENCES "Order" ON DELETE restrict ON UPDATE cascade,
+    amount monetary NOT NULL CHECK ((amount).amount >= 0 AND (amount).currency = base_currency()),
+    issuer uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
+    deleted_at timestamptz,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "Invoice";"#;
+
+    pub const fn new(
+        uuid: Uuid,
+        order: Uuid,
+        amount: Money,
+        issuer: Uuid,
+        deleted_at: Option<DateTime<Utc>>,
+        meta: MetaTime,
+    ) -> Self {
+        Self {
+            uuid,
+            order,
+            amount,
+            issuer,
+            deleted_at,
+            meta,
+        }
+    }
+
+    pub fn new_auto(order: Uuid, amount: Money, issuer: Uuid) -> Self {
+        Self::new(
+            Uuid::new_v4(),
+            order,
+            amount,
+            issuer,
+            None,
+            MetaTime::default(),
+        )
+    }
+
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Invoice" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    pub fn get_by_order(order: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Invoice" WHERE "order" = $1 AND deleted_at IS NULL"#).bind(order)
+    }
+
+    /// Bills the order, unlike [`Insertable::insert`] nothing is inserted for the order
+    /// in the trash
+    pub fn issue(&self) -> PgQuery {
+        query(
+            r#"INSERT INTO "Invoice" (uuid, "order", amount, issuer, meta)
+SELECT $1, uuid, $3, $4, $5 FROM "Order" WHERE uuid = $2 AND deleted_at IS NULL;"#,
+        )
+        .bind(self.uuid)
+        .bind(self.order)
+        .bind(self.amount.clone())
+        .bind(self.issuer)
+        .bind(self.meta.clone())
+    }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Invoice" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
+    }
+
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Invoice" WHERE deleted_at IS NULL"#)
+    }
+}
+
+impl Insertable for Invoice {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "Invoice" (uuid, "order", amount, issuer, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.order)
+        .bind(self.amount.clone())
+        .bind(self.issuer)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
+    }
+}
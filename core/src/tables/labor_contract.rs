@@ -71,18 +71,23 @@ impl LaborContract {
     pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
         query_as(r#"SELECT * FROM "LaborContract" WHERE uuid = $1"#).bind(uuid)
     }
+
+    pub fn get_by_person(person: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "LaborContract" WHERE person = $1"#).bind(person)
+    }
 }
 
 impl Insertable for LaborContract {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "LaborContract" (uuid, person, passport, status, signed) 
-VALUES ($1, $2, $3, $4, $5);"#,
+            r#"INSERT INTO "LaborContract" (uuid, person, passport, status, signed, meta) 
+VALUES ($1, $2, $3, $4, $5, $6);"#,
         )
         .bind(self.uuid)
         .bind(self.person)
         .bind(self.passport.clone())
         .bind(self.status)
         .bind(self.signed)
+        .bind(self.meta.clone())
     }
 }
@@ -1,14 +1,21 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
-use crate::{traits::Insertable, PgQuery, PgQueryAs};
+use crate::{
+    traits::Insertable,
+    types::{metatime::MetaTime, Country},
+    PgQuery, PgQueryAs,
+};
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
 pub struct Manufacturer {
     pub uuid: Uuid,
     pub name: String,
-    pub country: String,
+    pub country: Country,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub meta: MetaTime,
 }
 
 impl Manufacturer {
@@ -17,7 +24,9 @@ impl Manufacturer {
     pub const CREATE: &'static str = r#"CREATE TABLE "Manufacturer" (
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
     name text NOT NULL,
-    country char(2) NOT NULL
+    country country_code NOT NULL,
+    deleted_at timestamptz,
+    meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Manufacturer";"#;
@@ -29,31 +38,49 @@ impl Manufacturer {
         ("FoxCon", "CN"),
     ];
 
-    pub const fn new(uuid: Uuid, name: String, country: String) -> Self {
+    pub const fn new(
+        uuid: Uuid,
+        name: String,
+        country: Country,
+        deleted_at: Option<DateTime<Utc>>,
+        meta: MetaTime,
+    ) -> Self {
         Self {
             uuid,
             name,
             country,
+            deleted_at,
+            meta,
         }
     }
 
-    pub fn new_auto(name: String, country: String) -> Self {
-        Self::new(Uuid::new_v4(), name, country)
+    pub fn new_auto(name: String, country: Country) -> Self {
+        Self::new(Uuid::new_v4(), name, country, None, MetaTime::default())
     }
 
-    pub fn delete_by_uuid(uuid: Uuid) -> PgQuery {
-        query(r#"DELETE FROM "Manufacturer" WHERE uuid = $1"#).bind(uuid)
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Manufacturer" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
     }
 
     pub fn get_all() -> PgQueryAs<Self> {
-        query_as(r#"SELECT * FROM "Manufacturer""#)
+        query_as(r#"SELECT * FROM "Manufacturer" WHERE deleted_at IS NULL"#)
     }
 }
 impl Insertable for Manufacturer {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
-        query(r#"INSERT INTO "Manufacturer" (uuid, name, country) VALUES ($1, $2, $3);"#)
-            .bind(self.uuid)
-            .bind(self.name.clone())
-            .bind(self.country.clone())
+        query(
+            r#"INSERT INTO "Manufacturer" (uuid, name, country, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.name.clone())
+        .bind(self.country)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
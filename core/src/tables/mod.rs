@@ -1,55 +1,79 @@
-use core::fmt;
+use core::{fmt, str::FromStr};
 
 use anyhow::{Context, Result};
-use sqlx::{postgres::PgQueryResult, query, query_as, Error, Executor, PgPool, Postgres};
+use sqlx::{query, query_as, Error, Executor, PgPool, Postgres};
 
 pub mod account;
 pub mod audit_log;
+pub mod branch;
 pub mod component;
 pub mod component_kind;
+pub mod exchange_rate;
+pub mod invoice;
 pub mod labor_contract;
 pub mod manufacturer;
 pub mod order;
 pub mod order_component;
 pub mod order_service;
+pub mod payment;
 pub mod person;
+pub mod person_merge;
 pub mod phone;
 pub mod phone_model;
+pub mod phone_ownership;
 pub mod position;
+pub mod price_change;
 pub mod service;
 pub mod service_phone_model;
+pub mod service_price;
 pub mod staff;
+pub mod stock_transfer;
 pub mod supplier;
 pub mod supply;
 pub mod supply_contract;
+pub mod supply_contract_item;
+pub mod supply_item;
 pub mod warehouse;
+pub mod warehouse_price;
 pub mod warehouse_supply;
 
 pub use account::Account;
 pub use audit_log::AuditLog;
+pub use branch::Branch;
 pub use component::Component;
 pub use component_kind::ComponentKind;
+pub use exchange_rate::ExchangeRate;
+pub use invoice::Invoice;
 pub use labor_contract::LaborContract;
 pub use manufacturer::Manufacturer;
 pub use order::Order;
 pub use order_component::OrderWarehouse;
 pub use order_service::OrderService;
+pub use payment::Payment;
 pub use person::Person;
+pub use person_merge::PersonMerge;
 pub use phone::Phone;
 pub use phone_model::PhoneModel;
+pub use phone_ownership::PhoneOwnership;
 pub use position::Position;
+pub use price_change::PriceChange;
 pub use service::Service;
 pub use service_phone_model::ServicePhoneModel;
+pub use service_price::ServicePrice;
 pub use staff::Staff;
-pub use supplier::Supplier;
+pub use stock_transfer::StockTransfer;
+pub use supplier::{Supplier, SupplierDetails};
 pub use supply::Supply;
 pub use supply_contract::SupplyContract;
+pub use supply_contract_item::SupplyContractItem;
+pub use supply_item::SupplyItem;
 pub use warehouse::Warehouse;
+pub use warehouse_price::WarehousePrice;
 pub use warehouse_supply::WarehouseSupply;
 
 // -------------------------------------------------------------------------------------------------
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Table {
     Person,
     Supplier,
@@ -57,6 +81,8 @@ pub enum Table {
     Position,
     Service,
     ComponentKind,
+    Branch,
+    ExchangeRate,
     LaborContract,
     Staff,
     PhoneModel,
@@ -64,27 +90,39 @@ pub enum Table {
     SupplyContract,
     Supply,
     AuditLog,
+    PhoneOwnership,
+    PersonMerge,
     Account,
     Component,
     Warehouse,
     Order,
+    StockTransfer,
+    Invoice,
+    Payment,
+    PriceChange,
+    WarehousePrice,
     ServicePhoneModel,
+    ServicePrice,
+    SupplyContractItem,
     WarehouseSupply,
+    SupplyItem,
     OrderService,
     OrderWarehouse,
 }
 
 impl Table {
-    pub const LOW: [Self; 6] = [
+    pub const LOW: [Self; 8] = [
         Self::Person,
         Self::Supplier,
         Self::Manufacturer,
         Self::Position,
         Self::Service,
         Self::ComponentKind,
+        Self::Branch,
+        Self::ExchangeRate,
     ];
 
-    pub const ALL: [Self; 21] = [
+    pub const ALL: [Self; 33] = [
         // Low-level tables
         Self::Person,
         Self::Supplier,
@@ -92,6 +130,8 @@ impl Table {
         Self::Position,
         Self::Service,
         Self::ComponentKind,
+        Self::Branch,
+        Self::ExchangeRate,
         // Mid-level tables
         Self::LaborContract,
         Self::Staff,
@@ -101,13 +141,23 @@ impl Table {
         Self::Supply,
         // High-level tables
         Self::AuditLog,
+        Self::PhoneOwnership,
+        Self::PersonMerge,
         Self::Account,
         Self::Component,
         Self::Warehouse,
         Self::Order,
+        Self::StockTransfer,
+        Self::Invoice,
+        Self::Payment,
+        Self::PriceChange,
+        Self::WarehousePrice,
         // Relations tables
         Self::ServicePhoneModel,
+        Self::ServicePrice,
+        Self::SupplyContractItem,
         Self::WarehouseSupply,
+        Self::SupplyItem,
         Self::OrderService,
         Self::OrderWarehouse,
     ];
@@ -120,6 +170,8 @@ impl Table {
             Table::Position => Position::NAME,
             Table::Service => Service::NAME,
             Table::ComponentKind => ComponentKind::NAME,
+            Table::Branch => Branch::NAME,
+            Table::ExchangeRate => ExchangeRate::NAME,
             Table::LaborContract => LaborContract::NAME,
             Table::Staff => Staff::NAME,
             Table::PhoneModel => PhoneModel::NAME,
@@ -127,12 +179,22 @@ impl Table {
             Table::SupplyContract => SupplyContract::NAME,
             Table::Supply => Supply::NAME,
             Table::AuditLog => AuditLog::NAME,
+            Table::PhoneOwnership => PhoneOwnership::NAME,
+            Table::PersonMerge => PersonMerge::NAME,
             Table::Account => Account::NAME,
             Table::Component => Component::NAME,
             Table::Warehouse => Warehouse::NAME,
             Table::Order => Order::NAME,
+            Table::StockTransfer => StockTransfer::NAME,
+            Table::Invoice => Invoice::NAME,
+            Table::Payment => Payment::NAME,
+            Table::PriceChange => PriceChange::NAME,
+            Table::WarehousePrice => WarehousePrice::NAME,
             Table::ServicePhoneModel => ServicePhoneModel::NAME,
+            Table::ServicePrice => ServicePrice::NAME,
+            Table::SupplyContractItem => SupplyContractItem::NAME,
             Table::WarehouseSupply => WarehouseSupply::NAME,
+            Table::SupplyItem => SupplyItem::NAME,
             Table::OrderService => OrderService::NAME,
             Table::OrderWarehouse => OrderWarehouse::NAME,
         }
@@ -146,6 +208,8 @@ impl Table {
             Table::Position => Position::CREATE,
             Table::Service => Service::CREATE,
             Table::ComponentKind => ComponentKind::CREATE,
+            Table::Branch => Branch::CREATE,
+            Table::ExchangeRate => ExchangeRate::CREATE,
             Table::LaborContract => LaborContract::CREATE,
             Table::Staff => Staff::CREATE,
             Table::PhoneModel => PhoneModel::CREATE,
@@ -153,12 +217,22 @@ impl Table {
             Table::SupplyContract => SupplyContract::CREATE,
             Table::Supply => Supply::CREATE,
             Table::AuditLog => AuditLog::CREATE,
+            Table::PhoneOwnership => PhoneOwnership::CREATE,
+            Table::PersonMerge => PersonMerge::CREATE,
             Table::Account => Account::CREATE,
             Table::Component => Component::CREATE,
             Table::Warehouse => Warehouse::CREATE,
             Table::Order => Order::CREATE,
+            Table::StockTransfer => StockTransfer::CREATE,
+            Table::Invoice => Invoice::CREATE,
+            Table::Payment => Payment::CREATE,
+            Table::PriceChange => PriceChange::CREATE,
+            Table::WarehousePrice => WarehousePrice::CREATE,
             Table::ServicePhoneModel => ServicePhoneModel::CREATE,
+            Table::ServicePrice => ServicePrice::CREATE,
+            Table::SupplyContractItem => SupplyContractItem::CREATE,
             Table::WarehouseSupply => WarehouseSupply::CREATE,
+            Table::SupplyItem => SupplyItem::CREATE,
             Table::OrderService => OrderService::CREATE,
             Table::OrderWarehouse => OrderWarehouse::CREATE,
         }
@@ -172,6 +246,8 @@ impl Table {
             Table::Position => Position::DROP,
             Table::Service => Service::DROP,
             Table::ComponentKind => ComponentKind::DROP,
+            Table::Branch => Branch::DROP,
+            Table::ExchangeRate => ExchangeRate::DROP,
             Table::LaborContract => LaborContract::DROP,
             Table::Staff => Staff::DROP,
             Table::PhoneModel => PhoneModel::DROP,
@@ -179,24 +255,37 @@ impl Table {
             Table::SupplyContract => SupplyContract::DROP,
             Table::Supply => Supply::DROP,
             Table::AuditLog => AuditLog::DROP,
+            Table::PhoneOwnership => PhoneOwnership::DROP,
+            Table::PersonMerge => PersonMerge::DROP,
             Table::Account => Account::DROP,
             Table::Component => Component::DROP,
             Table::Warehouse => Warehouse::DROP,
             Table::Order => Order::DROP,
+            Table::StockTransfer => StockTransfer::DROP,
+            Table::Invoice => Invoice::DROP,
+            Table::Payment => Payment::DROP,
+            Table::PriceChange => PriceChange::DROP,
+            Table::WarehousePrice => WarehousePrice::DROP,
             Table::ServicePhoneModel => ServicePhoneModel::DROP,
+            Table::ServicePrice => ServicePrice::DROP,
+            Table::SupplyContractItem => SupplyContractItem::DROP,
             Table::WarehouseSupply => WarehouseSupply::DROP,
+            Table::SupplyItem => SupplyItem::DROP,
             Table::OrderService => OrderService::DROP,
             Table::OrderWarehouse => OrderWarehouse::DROP,
         }
     }
 
-    pub async fn exists(&self, pool: &PgPool) -> Result<bool, Error> {
+    pub async fn exists<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
         match query_as::<Postgres, (bool,)>(
             r#"SELECT true FROM information_schema.tables
-WHERE table_schema = 'public' AND table_type = 'BASE TABLE' AND table_name = $1"#,
+WHERE table_schema = current_schema() AND table_type = 'BASE TABLE' AND table_name = $1"#,
         )
         .bind(self.name())
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
         {
             Ok(_) => Ok(true),
@@ -214,32 +303,6 @@ WHERE table_schema = 'public' AND table_type = 'BASE TABLE' AND table_name = $1"
         format!(r#"TRUNCATE "{}" RESTART IDENTITY cascade;"#, self.name())
     }
 
-    /// Creates all application tables
-    pub async fn create_all(
-        pool: &PgPool,
-        handler: impl Fn((Table, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for table in Self::ALL {
-            handler((table, pool.execute(query(table.create())).await))
-                .with_context(|| format!("While creating '{table}' table"))?;
-        }
-
-        Ok(())
-    }
-
-    /// Drops all application tables
-    pub async fn drop_all(
-        pool: &PgPool,
-        handler: impl Fn((Table, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for table in Self::ALL.into_iter().rev() {
-            handler((table, query(table.drop()).execute(pool).await))
-                .with_context(|| format!("While dropping '{table}' table"))?;
-        }
-
-        Ok(())
-    }
-
     /// Truncates all tables!!!
     pub async fn truncate(pool: &PgPool, handler: impl Fn((Table, bool))) -> Result<()> {
         for table in Self::LOW.into_iter() {
@@ -262,6 +325,17 @@ impl fmt::Display for Table {
     }
 }
 
+impl FromStr for Table {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|table| table.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown table '{s}'"))
+    }
+}
+
 pub trait TableObject {
     // FIX
     fn table(&self) -> Table;
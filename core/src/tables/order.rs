@@ -1,15 +1,22 @@
+use chrono::{DateTime, Utc};
+use rand::{distributions::Slice, thread_rng, Rng};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgArguments, query, query::Query, FromRow, Postgres};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
 use crate::{
     traits::Insertable,
     types::{metatime::MetaTime, order_status::OrderStatus},
+    PgQuery, PgQueryAs,
 };
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
 pub struct Order {
     pub uuid: Uuid,
+    /// Short code clients quote at the counter, generated by [`Order::gen_tracking`]
+    pub tracking: String,
+    /// Foreign key references [`Branch`](`super::branch::Branch`)
+    pub branch: Uuid,
     /// Foreign key references [`Person`](`super::person::Person`)
     pub client: Uuid,
     /// Foreign key references [`Phone`](`super::phone::Phone`)
@@ -19,6 +26,11 @@ pub struct Order {
     /// Foreign key references [`Staff`](`super::staff::Staff`)
     pub shopman: Uuid,
     pub status: OrderStatus,
+    /// Set once the order is complete, warranty period starts from this moment
+    pub completed: Option<DateTime<Utc>>,
+    /// Set for warranty claims, references the original [`Order`]
+    pub warranty_of: Option<Uuid>,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub meta: MetaTime,
 }
 
@@ -27,37 +39,60 @@ impl Order {
 
     pub const CREATE: &'static str = r#"CREATE TABLE "Order" (
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    tracking text UNIQUE NOT NULL CHECK (length(tracking) = 8),
+    branch uuid NOT NULL REFERENCES "Branch" ON DELETE restrict ON UPDATE cascade,
     client uuid NOT NULL REFERENCES "Person" ON DELETE no action ON UPDATE cascade,
     phone uuid NOT NULL REFERENCES "Phone" ON DELETE restrict ON UPDATE cascade,
     serviceman uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
     shopman uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
     status "OrderStatus" NOT NULL DEFAULT 'Processing',
+    completed timestamptz CHECK (completed IS NULL OR status = 'Complete'),
+    warranty_of uuid REFERENCES "Order" ON DELETE restrict ON UPDATE cascade CHECK (warranty_of <> uuid),
+    deleted_at timestamptz,
     meta metatime DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Order";"#;
 
+    /// Tracking code symbols, easily confused ones (`0`/`O`, `1`/`I`) are left out
+    pub const TRACKING_ALPHABET: [char; 32] = [
+        '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'J', 'K',
+        'L', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+    ];
+
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         uuid: Uuid,
+        tracking: String,
+        branch: Uuid,
         client: Uuid,
         phone: Uuid,
         serviceman: Uuid,
         shopman: Uuid,
         status: OrderStatus,
+        completed: Option<DateTime<Utc>>,
+        warranty_of: Option<Uuid>,
+        deleted_at: Option<DateTime<Utc>>,
         meta: MetaTime,
     ) -> Self {
         Self {
             uuid,
+            tracking,
+            branch,
             client,
             phone,
             serviceman,
             shopman,
             status,
+            completed,
+            warranty_of,
+            deleted_at,
             meta,
         }
     }
 
     pub fn new_auto(
+        branch: Uuid,
         client: Uuid,
         phone: Uuid,
         serviceman: Uuid,
@@ -66,27 +101,110 @@ impl Order {
     ) -> Self {
         Self::new(
             Uuid::new_v4(),
+            Self::gen_tracking(),
+            branch,
             client,
             phone,
             serviceman,
             shopman,
             status,
+            None,
+            None,
+            None,
             MetaTime::default(),
         )
     }
+
+    /// Warranty claim for the same phone of the client, its services and parts are free of charge
+    pub fn new_warranty_claim(original: &Order, serviceman: Uuid, shopman: Uuid) -> Self {
+        Self {
+            warranty_of: Some(original.uuid),
+            ..Self::new_auto(
+                original.branch,
+                original.client,
+                original.phone,
+                serviceman,
+                shopman,
+                OrderStatus::Processing,
+            )
+        }
+    }
+
+    pub fn is_warranty_claim(&self) -> bool {
+        self.warranty_of.is_some()
+    }
+
+    pub fn gen_tracking() -> String {
+        thread_rng()
+            .sample_iter(Slice::new(&Self::TRACKING_ALPHABET).unwrap())
+            .take(8)
+            .collect()
+    }
+
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Order" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    /// Tracking code is case insensitive
+    pub fn get_by_tracking(tracking: &str) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Order" WHERE tracking = upper($1) AND deleted_at IS NULL"#)
+            .bind(tracking.to_string())
+    }
+
+    /// Query of [`Self::get_by_branch`], also explained by [`Probe`](crate::explain::Probe)
+    pub const BY_BRANCH: &'static str =
+        r#"SELECT * FROM "Order" WHERE branch = $1 AND deleted_at IS NULL"#;
+
+    /// Query of [`Self::get_by_client`], also explained by [`Probe`](crate::explain::Probe)
+    pub const BY_CLIENT: &'static str =
+        r#"SELECT * FROM "Order" WHERE client = $1 AND deleted_at IS NULL ORDER BY (meta).created"#;
+
+    pub fn get_by_branch(branch: Uuid) -> PgQueryAs<Self> {
+        query_as(Self::BY_BRANCH).bind(branch)
+    }
+
+    pub fn get_by_client(client: Uuid) -> PgQueryAs<Self> {
+        query_as(Self::BY_CLIENT).bind(client)
+    }
+
+    /// Completes active order of the given version, which starts warranty period of its services
+    pub fn complete(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Order" SET status = 'Complete', completed = now(), meta.updated = $2
+WHERE uuid = $1 AND status = 'Active'"#,
+        )
+        .bind(uuid)
+        .bind(version)
+    }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Order" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
+    }
 }
+
 impl Insertable for Order {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "Order" (uuid, client, phone, serviceman, shopman, status) 
-VALUES ($1, $2, $3, $4, $5, $6);
-          "#,
+            r#"INSERT INTO "Order"
+(uuid, tracking, branch, client, phone, serviceman, shopman, status, completed, warranty_of, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12);"#,
         )
         .bind(self.uuid)
+        .bind(self.tracking.clone())
+        .bind(self.branch)
         .bind(self.client)
         .bind(self.phone)
         .bind(self.serviceman)
         .bind(self.shopman)
         .bind(self.status)
+        .bind(self.completed)
+        .bind(self.warranty_of)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
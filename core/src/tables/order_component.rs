@@ -1,13 +1,8 @@
 use serde::{Deserialize, Serialize};
-use sqlx::{
-    postgres::{types::PgMoney, PgArguments},
-    query,
-    query::Query,
-    FromRow, Postgres,
-};
+use sqlx::{postgres::PgArguments, query, query::Query, FromRow, Postgres};
 use uuid::Uuid;
 
-use crate::traits::Insertable;
+use crate::{traits::Insertable, types::Money};
 
 /// Represents relation table between [`Order`](`super::order::Order`) and [`Warehouse`](`super::warehouse::Warehouse`)
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
@@ -17,11 +12,7 @@ pub struct OrderWarehouse {
     /// Foreign key references [`Warehouse`](`super::warehouse::Warehouse`)
     pub item: Uuid,
     pub amount: i32,
-    #[serde(
-        deserialize_with = "crate::utils::deserialize_pg_money",
-        serialize_with = "crate::utils::serialize_pg_money"
-    )]
-    pub price: PgMoney,
+    pub price: Money,
 }
 
 impl OrderWarehouse {
@@ -31,12 +22,12 @@ impl OrderWarehouse {
     "order" uuid NOT NULL REFERENCES "Order" ON DELETE cascade ON UPDATE cascade,
     item uuid NOT NULL REFERENCES "Warehouse" ON DELETE restrict ON UPDATE cascade,
     amount int NOT NULL DEFAULT 1,
-    price money NOT NULL
+    price monetary NOT NULL
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "OrderWarehouse";"#;
 
-    pub fn new(order: Uuid, item: Uuid, amount: i32, price: PgMoney) -> Self {
+    pub fn new(order: Uuid, item: Uuid, amount: i32, price: Money) -> Self {
         Self {
             order,
             item,
@@ -51,6 +42,6 @@ impl Insertable for OrderWarehouse {
             .bind(self.order)
             .bind(self.item)
             .bind(self.amount)
-            .bind(self.price)
+            .bind(self.price.clone())
     }
 }
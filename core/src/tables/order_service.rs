@@ -1,13 +1,8 @@
 use serde::{Deserialize, Serialize};
-use sqlx::{
-    postgres::{types::PgMoney, PgArguments},
-    query,
-    query::Query,
-    FromRow, Postgres,
-};
+use sqlx::{postgres::PgArguments, query, query::Query, FromRow, Postgres};
 use uuid::Uuid;
 
-use crate::traits::Insertable;
+use crate::{traits::Insertable, types::Money};
 
 /// Represents relation table between [`Order`](`super::order::Order`) and [`Service`](`super::service::Service`)
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
@@ -16,11 +11,7 @@ pub struct OrderService {
     pub order: Uuid,
     /// Foreign key references [`Service`](`super::service::Service`)
     pub service: Uuid,
-    #[serde(
-        deserialize_with = "crate::utils::deserialize_pg_money",
-        serialize_with = "crate::utils::serialize_pg_money"
-    )]
-    pub price: PgMoney,
+    pub price: Money,
 }
 
 impl OrderService {
@@ -29,12 +20,12 @@ impl OrderService {
     pub const CREATE: &'static str = r#"CREATE TABLE "OrderService" (
     "order" uuid NOT NULL REFERENCES "Order" ON DELETE cascade ON UPDATE cascade,
     service uuid NOT NULL REFERENCES "Service" ON DELETE restrict ON UPDATE cascade,
-    price money NOT NULL
+    price monetary NOT NULL
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "OrderService";"#;
 
-    pub fn new(order: Uuid, service: Uuid, price: PgMoney) -> Self {
+    pub fn new(order: Uuid, service: Uuid, price: Money) -> Self {
         Self {
             order,
             service,
@@ -48,6 +39,6 @@ impl Insertable for OrderService {
         query(r#"INSERT INTO "OrderService" VALUES ($1, $2, $3);"#)
             .bind(self.order)
             .bind(self.service)
-            .bind(self.price)
+            .bind(self.price.clone())
     }
 }
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{
+    traits::Insertable,
+    types::{
+        metatime::MetaTime, payment_kind::PaymentKind, payment_method::PaymentMethod, Currency,
+        Money, MoneyError,
+    },
+    PgQuery, PgQueryAs,
+};
+
+/// Money received for (or returned on) the invoice, `meta.created` is the payment time
+///
+/// Order pending payment becomes active once its invoices are paid in full
+/// (see [`SettleOrder`](crate::triggers::SettleOrder))
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct Payment {
+    pub uuid: Uuid,
+    /// Foreign key references [`Invoice`](`super::invoice::Invoice`)
+    pub invoice: Uuid,
+    pub kind: PaymentKind,
+    pub method: PaymentMethod,
+    pub amount: Money,
+    /// Foreign key references [`Staff`](`super::staff::Staff`)
+    pub cashier: Uuid,
+    pub meta: MetaTime,
+}
+
+impl Payment {
+    pub const NAME: &'static str = "Payment";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "Payment" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    invoice uuid NOT NULL REFERENCES "Invoice" ON DELETE restrict ON UPDATE cascade,
+    kind "PaymentKind" NOT NULL DEFAULT 'Payment',
+    method "PaymentMethod" NOT NULL,
+    amount monetary NOT NULL CHECK ((amount).amount > 0 AND (amount).currency = base_currency()),
+    cashier uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "Payment";"#;
+
+    pub const fn new(
+        uuid: Uuid,
+        invoice: Uuid,
+        kind: PaymentKind,
+        method: PaymentMethod,
+        amount: Money,
+        cashier: Uuid,
+        meta: MetaTime,
+    ) -> Self {
+        Self {
+            uuid,
+            invoice,
+            kind,
+            method,
+            amount,
+            cashier,
+            meta,
+        }
+    }
+
+    pub fn new_auto(
+        invoice: Uuid,
+        kind: PaymentKind,
+        method: PaymentMethod,
+        amount: Money,
+        cashier: Uuid,
+    ) -> Self {
+        Self::new(
+            Uuid::new_v4(),
+            invoice,
+            kind,
+            method,
+            amount,
+            cashier,
+            MetaTime::default(),
+        )
+    }
+
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Payment" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    pub fn get_by_invoice(invoice: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Payment" WHERE invoice = $1"#).bind(invoice)
+    }
+
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Payment""#)
+    }
+
+    /// Records the payment through [`RecordPayment`](crate::procedures::RecordPayment),
+    /// fails on the invoice in the trash and on the refund exceeding the paid amount
+    pub fn record(&self) -> PgQuery {
+        query(r#"CALL "RecordPayment"($1, $2, $3, $4, $5, $6);"#)
+            .bind(self.uuid)
+            .bind(self.invoice)
+            .bind(self.kind)
+            .bind(self.method)
+            .bind(self.amount.clone())
+            .bind(self.cashier)
+    }
+
+    /// Amount paid with the refunds subtracted
+    pub fn net(payments: &[Self], currency: Currency) -> Result<Money, MoneyError> {
+        payments
+            .iter()
+            .try_fold(Money::zero(currency), |net, payment| match payment.kind {
+                PaymentKind::Payment => net.checked_add(&payment.amount),
+                PaymentKind::Refund => net.checked_sub(&payment.amount),
+            })
+    }
+}
+
+impl Insertable for Payment {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "Payment" (uuid, invoice, kind, method, amount, cashier, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.invoice)
+        .bind(self.kind)
+        .bind(self.method)
+        .bind(self.amount.clone())
+        .bind(self.cashier)
+        .bind(self.meta.clone())
+    }
+}
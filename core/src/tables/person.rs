@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
@@ -12,6 +13,9 @@ pub struct Person {
     pub last_name: String,
     pub email: String,
     pub phone: String,
+    /// Set once the personal data is scrubbed, see [`Person::anonymize`]
+    pub anonymized: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub meta: MetaTime,
 }
 
@@ -23,13 +27,16 @@ impl Person {
     first_name text NOT NULL,
     middle_name text,
     last_name text NOT NULL,
-    email text UNIQUE NOT NULL CHECK (length(email) <= 254),
-    phone text UNIQUE NOT NULL CHECK (length(phone) <= 18),
+    email text NOT NULL CHECK (length(email) <= 254),
+    phone text NOT NULL CHECK (length(phone) <= 18),
+    anonymized timestamptz,
+    deleted_at timestamptz,
     meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Person";"#;
 
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         uuid: Uuid,
         f_name: String,
@@ -37,6 +44,8 @@ impl Person {
         l_name: String,
         email: String,
         phone: String,
+        anonymized: Option<DateTime<Utc>>,
+        deleted_at: Option<DateTime<Utc>>,
         meta: MetaTime,
     ) -> Self {
         Self {
@@ -46,6 +55,8 @@ impl Person {
             last_name: l_name,
             email,
             phone,
+            anonymized,
+            deleted_at,
             meta,
         }
     }
@@ -64,6 +75,8 @@ impl Person {
             l_name,
             email,
             phone,
+            None,
+            None,
             MetaTime::default(),
         )
     }
@@ -72,12 +85,33 @@ impl Person {
         query_as(r#"SELECT * FROM "Person" WHERE uuid = $1"#).bind(uuid)
     }
 
-    pub fn delete_by_uuid(uuid: Uuid) -> PgQuery {
-        query(r#"DELETE FROM "Person" WHERE uuid = $1"#).bind(uuid)
+    /// Moves the person to the [`trash`](crate::trash), phones and orders keep referencing them
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Person" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
     }
 
     pub fn get_all() -> PgQueryAs<Self> {
-        query_as(r#"SELECT * FROM "Person""#)
+        query_as(r#"SELECT * FROM "Person" WHERE deleted_at IS NULL"#)
+    }
+
+    /// Moves phones, orders and labor contracts of the duplicate to the kept person
+    /// and deletes the duplicate, returns uuid of the [`PersonMerge`](super::PersonMerge)
+    pub fn merge(kept: Uuid, duplicate: Uuid) -> PgQueryAs<(Uuid,)> {
+        query_as(r#"CALL "MergePeople"($1, $2, NULL);"#)
+            .bind(kept)
+            .bind(duplicate)
+    }
+
+    /// Scrubs names and contacts in place, so orders and payments of the person are kept
+    ///
+    /// Fails while the person has orders in progress or a labor contract in force
+    pub fn anonymize(uuid: Uuid) -> PgQuery {
+        query(r#"CALL "AnonymizePerson"($1);"#).bind(uuid)
     }
 }
 
@@ -85,8 +119,8 @@ impl Insertable for Person {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
             r#"INSERT INTO "Person"
-(uuid, first_name, middle_name, last_name, email, phone) 
-VALUES ($1, $2, $3, $4, $5, $6);"#,
+(uuid, first_name, middle_name, last_name, email, phone, anonymized, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);"#,
         )
         .bind(self.uuid)
         .bind(self.first_name.clone())
@@ -94,5 +128,8 @@ VALUES ($1, $2, $3, $4, $5, $6);"#,
         .bind(self.last_name.clone())
         .bind(self.email.clone())
         .bind(self.phone.clone())
+        .bind(self.anonymized)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{traits::Insertable, types::metatime::MetaTime, PgQueryAs};
+
+/// Merge of the duplicate [`Person`](super::person::Person) into the surviving one,
+/// the duplicate is deleted, so its contacts are kept here
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct PersonMerge {
+    pub uuid: Uuid,
+    /// Foreign key references [`Person`](super::person::Person)
+    pub survivor: Uuid,
+    /// Uuid of the deleted duplicate
+    pub merged: Uuid,
+    pub first_name: String,
+    pub middle_name: Option<String>,
+    pub last_name: String,
+    pub email: String,
+    pub phone: String,
+    /// Database user who merged the records
+    pub username: String,
+    pub meta: MetaTime,
+}
+
+impl PersonMerge {
+    pub const NAME: &'static str = "PersonMerge";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "PersonMerge" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    survivor uuid NOT NULL REFERENCES "Person" ON DELETE no action ON UPDATE cascade,
+    merged uuid NOT NULL UNIQUE CHECK (merged <> survivor),
+    first_name text NOT NULL,
+    middle_name text,
+    last_name text NOT NULL,
+    email text NOT NULL,
+    phone text NOT NULL,
+    username text NOT NULL DEFAULT current_user,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "PersonMerge";"#;
+
+    pub fn get_by_survivor(survivor: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "PersonMerge" WHERE survivor = $1 ORDER BY (meta).created"#)
+            .bind(survivor)
+    }
+
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "PersonMerge" ORDER BY (meta).created"#)
+    }
+}
+
+impl Insertable for PersonMerge {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "PersonMerge"
+(uuid, survivor, merged, first_name, middle_name, last_name, email, phone, username, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.survivor)
+        .bind(self.merged)
+        .bind(self.first_name.clone())
+        .bind(self.middle_name.clone())
+        .bind(self.last_name.clone())
+        .bind(self.email.clone())
+        .bind(self.phone.clone())
+        .bind(self.username.clone())
+        .bind(self.meta.clone())
+    }
+}
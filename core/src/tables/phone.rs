@@ -1,12 +1,13 @@
+use chrono::{DateTime, Utc};
 use mac_address::MacAddress;
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgArguments, query, query::Query, FromRow, Postgres};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
 use crate::{
     traits::Insertable,
-    types::{color::Color, metatime::MetaTime},
-    PgQuery,
+    types::{color::Color, identifier::Imei, metatime::MetaTime},
+    PgQuery, PgQueryAs,
 };
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
@@ -14,12 +15,13 @@ pub struct Phone {
     pub uuid: Uuid,
     /// Foreign key references [`Person`](`super::person::Person`)
     pub person: Uuid,
-    pub imei: String,
+    pub imei: Imei,
     pub wifi: MacAddress,
     pub bluetooth: MacAddress,
     /// Foreign key references [`PhoneModel`](`super::phone_model::PhoneModel`)
     pub model: Uuid,
     pub color: Color,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub meta: MetaTime,
 }
 
@@ -29,24 +31,27 @@ impl Phone {
     pub const CREATE: &'static str = r#"CREATE TABLE "Phone" (
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
     person uuid NOT NULL REFERENCES "Person" ON DELETE no action ON UPDATE cascade,
-    imei text NOT NULL CHECK (length(imei) <= 17),
+    imei imei NOT NULL,
     wifi macaddr,
     bluetooth macaddr,
     model uuid NOT NULL REFERENCES "PhoneModel" ON DELETE restrict ON UPDATE cascade,
     color color NOT NULL,
+    deleted_at timestamptz,
     meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Phone";"#;
 
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         uuid: Uuid,
         person: Uuid,
-        imei: String,
+        imei: Imei,
         wifi: MacAddress,
         bluetooth: MacAddress,
         model: Uuid,
         color: Color,
+        deleted_at: Option<DateTime<Utc>>,
         meta: MetaTime,
     ) -> Self {
         Self {
@@ -57,13 +62,14 @@ impl Phone {
             bluetooth,
             model,
             color,
+            deleted_at,
             meta,
         }
     }
 
     pub fn new_auto(
         person: Uuid,
-        imei: String,
+        imei: Imei,
         wifi: MacAddress,
         bluetooth: MacAddress,
         model: Uuid,
@@ -77,26 +83,69 @@ impl Phone {
             bluetooth,
             model,
             color,
+            None,
             MetaTime::default(),
         )
     }
 
-    pub fn delete_by_uuid(uuid: Uuid) -> PgQuery {
-        query(r#"DELETE FROM "Service" WHERE uuid = $1"#).bind(uuid)
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Phone" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    pub fn get_by_imei(imei: Imei) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Phone" WHERE imei = $1 AND deleted_at IS NULL"#).bind(imei)
+    }
+
+    /// Query of [`Self::get_by_person`], also explained by [`Probe`](crate::explain::Probe)
+    pub const BY_PERSON: &'static str =
+        r#"SELECT * FROM "Phone" WHERE person = $1 AND deleted_at IS NULL"#;
+
+    pub fn get_by_person(person: Uuid) -> PgQueryAs<Self> {
+        query_as(Self::BY_PERSON).bind(person)
+    }
+
+    /// Hands the phone of the given version over to another person, the previous owner is kept
+    /// in [`PhoneOwnership`](`super::phone_ownership::PhoneOwnership`)
+    pub fn transfer(uuid: Uuid, person: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(r#"UPDATE "Phone" SET person = $2, meta.updated = $3 WHERE uuid = $1"#)
+            .bind(uuid)
+            .bind(person)
+            .bind(version)
+    }
+
+    /// IMEIs registered more than once with the phones (the earliest first)
+    ///
+    /// Only databases not migrated to unique IMEIs may have them, so IMEIs are read as text
+    pub fn duplicates() -> PgQueryAs<(String, Vec<Uuid>)> {
+        query_as(
+            r#"SELECT imei::text, array_agg(uuid ORDER BY (meta).created, uuid) FROM "Phone"
+GROUP BY imei HAVING count(*) > 1 ORDER BY imei"#,
+        )
+    }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Phone" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
     }
 }
 impl Insertable for Phone {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "Phone" (uuid, person, imei, wifi, bluetooth, model, color) 
-VALUES ($1, $2, $3, $4, $5, $6, $7);"#,
+            r#"INSERT INTO "Phone" (uuid, person, imei, wifi, bluetooth, model, color, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);"#,
         )
         .bind(self.uuid)
         .bind(self.person)
-        .bind(self.imei.clone())
+        .bind(self.imei)
         .bind(self.wifi)
         .bind(self.bluetooth)
         .bind(self.model)
         .bind(self.color)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
@@ -1,8 +1,9 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgArguments, query, query::Query, FromRow, Postgres};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
-use crate::traits::Insertable;
+use crate::{traits::Insertable, types::metatime::MetaTime, PgQuery, PgQueryAs};
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
 pub struct PhoneModel {
@@ -11,6 +12,8 @@ pub struct PhoneModel {
     pub description: Option<String>,
     /// Foreign key references [`Manufacturer`](super::manufacturer::Manufacturer)
     pub manufacturer: Uuid,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub meta: MetaTime,
 }
 
 impl PhoneModel {
@@ -20,7 +23,9 @@ impl PhoneModel {
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
     name text NOT NULL UNIQUE,
     description text,
-    manufacturer uuid NOT NULL REFERENCES "Manufacturer" ON DELETE restrict ON UPDATE cascade
+    manufacturer uuid NOT NULL REFERENCES "Manufacturer" ON DELETE restrict ON UPDATE cascade,
+    deleted_at timestamptz,
+    meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "PhoneModel";"#;
@@ -39,29 +44,55 @@ impl PhoneModel {
         name: String,
         description: Option<String>,
         manufacturer: Uuid,
+        deleted_at: Option<DateTime<Utc>>,
+        meta: MetaTime,
     ) -> Self {
         Self {
             uuid,
             name,
             description,
             manufacturer,
+            deleted_at,
+            meta,
         }
     }
 
     pub fn new_auto(name: String, description: Option<String>, manufacturer: Uuid) -> Self {
-        Self::new(Uuid::new_v4(), name, description, manufacturer)
+        Self::new(
+            Uuid::new_v4(),
+            name,
+            description,
+            manufacturer,
+            None,
+            MetaTime::default(),
+        )
+    }
+
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "PhoneModel" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "PhoneModel" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
     }
 }
 
 impl Insertable for PhoneModel {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "PhoneModel" (uuid, name, description, manufacturer) 
-    VALUES ($1, $2, $3, $4);"#,
+            r#"INSERT INTO "PhoneModel" (uuid, name, description, manufacturer, deleted_at, meta) 
+    VALUES ($1, $2, $3, $4, $5, $6);"#,
         )
         .bind(self.uuid)
         .bind(self.name.clone())
         .bind(self.description.clone())
         .bind(self.manufacturer)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
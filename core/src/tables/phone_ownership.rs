@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{traits::Insertable, PgQueryAs};
+
+/// Previous owner of the [`Phone`](`super::phone::Phone`), the current one is kept
+/// by the phone itself
+///
+/// Rows are appended by `phone_ownership` trigger once the phone changes hands
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct PhoneOwnership {
+    /// Foreign key references [`Phone`](`super::phone::Phone`)
+    pub phone: Uuid,
+    /// Foreign key references [`Person`](`super::person::Person`)
+    pub person: Uuid,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+impl PhoneOwnership {
+    pub const NAME: &'static str = "PhoneOwnership";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "PhoneOwnership" (
+    phone uuid NOT NULL REFERENCES "Phone" ON DELETE cascade ON UPDATE cascade,
+    person uuid NOT NULL REFERENCES "Person" ON DELETE no action ON UPDATE cascade,
+    since timestamptz NOT NULL,
+    until timestamptz NOT NULL CHECK (until >= since),
+    PRIMARY KEY (phone, since)
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "PhoneOwnership";"#;
+
+    pub const fn new(
+        phone: Uuid,
+        person: Uuid,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            phone,
+            person,
+            since,
+            until,
+        }
+    }
+
+    pub fn get_by_phone(phone: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "PhoneOwnership" WHERE phone = $1 ORDER BY since"#).bind(phone)
+    }
+
+    pub fn get_by_person(person: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "PhoneOwnership" WHERE person = $1 ORDER BY since"#).bind(person)
+    }
+}
+
+impl Insertable for PhoneOwnership {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(r#"INSERT INTO "PhoneOwnership" (phone, person, since, until) VALUES ($1, $2, $3, $4);"#)
+            .bind(self.phone)
+            .bind(self.person)
+            .bind(self.since)
+            .bind(self.until)
+    }
+}
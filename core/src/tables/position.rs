@@ -1,17 +1,11 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{
-    postgres::{types::PgMoney, PgArguments},
-    query,
-    query::Query,
-    query_as,
-    types::BigDecimal,
-    FromRow, Postgres,
-};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
 use crate::{
     traits::Insertable,
-    types::{metatime::MetaTime, AccountRole},
+    types::{metatime::MetaTime, AccountRole, Money},
     PgQuery, PgQueryAs,
 };
 
@@ -20,11 +14,8 @@ pub struct Position {
     pub uuid: Uuid,
     pub name: String,
     pub details: Option<String>,
-    #[serde(
-        deserialize_with = "crate::utils::deserialize_pg_money",
-        serialize_with = "crate::utils::serialize_pg_money"
-    )]
-    pub salary: PgMoney,
+    pub salary: Money,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub meta: MetaTime,
 }
 
@@ -35,7 +26,8 @@ impl Position {
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
     name text NOT NULL,
     details text,
-    salary money NOT NULL,
+    salary monetary NOT NULL,
+    deleted_at timestamptz,
     meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
@@ -66,7 +58,8 @@ impl Position {
         uuid: Uuid,
         name: String,
         details: Option<String>,
-        salary: PgMoney,
+        salary: Money,
+        deleted_at: Option<DateTime<Utc>>,
         meta: MetaTime,
     ) -> Self {
         Self {
@@ -74,35 +67,47 @@ impl Position {
             name,
             details,
             salary,
+            deleted_at,
             meta,
         }
     }
 
-    pub fn new_auto(name: String, details: Option<String>, salary: BigDecimal) -> Self {
+    pub fn new_auto(name: String, details: Option<String>, salary: Money) -> Self {
         Self::new(
             Uuid::new_v4(),
             name,
             details,
-            PgMoney::from_bigdecimal(salary, 2).unwrap(),
+            salary,
+            None,
             MetaTime::default(),
         )
     }
 
-    pub fn delete_by_uuid(uuid: Uuid) -> PgQuery {
-        query(r#"DELETE FROM "Position" WHERE uuid = $1"#).bind(uuid)
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Position" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
     }
 
     pub fn get_all() -> PgQueryAs<Self> {
-        query_as(r#"SELECT * FROM "Position""#)
+        query_as(r#"SELECT * FROM "Position" WHERE deleted_at IS NULL"#)
     }
 }
 
 impl Insertable for Position {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
-        query(r#"INSERT INTO "Position" (uuid, name, details, salary) VALUES ($1, $2, $3, $4);"#)
-            .bind(self.uuid)
-            .bind(self.name.clone())
-            .bind(self.details.clone())
-            .bind(self.salary)
+        query(
+            r#"INSERT INTO "Position" (uuid, name, details, salary, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.name.clone())
+        .bind(self.details.clone())
+        .bind(self.salary.clone())
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
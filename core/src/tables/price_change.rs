@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    postgres::PgArguments, query, query::Query, query_as, types::BigDecimal, FromRow, Postgres,
+};
+use uuid::Uuid;
+
+use crate::{
+    traits::Insertable,
+    types::{metatime::MetaTime, price_change_status::PriceChangeStatus, price_scope::PriceScope},
+    PgQueryAs,
+};
+
+/// Single price indexation, new prices are kept in [`WarehousePrice`](super::WarehousePrice)
+/// and [`ServicePrice`](super::ServicePrice) history
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct PriceChange {
+    pub uuid: Uuid,
+    pub coef: BigDecimal,
+    pub scope: PriceScope,
+    /// Manufacturer, component kind, service or phone model uuid depending on `scope`
+    pub target: Option<Uuid>,
+    pub status: PriceChangeStatus,
+    /// Set if the change restores prices of a rolled back [`PriceChange`]
+    pub reverts: Option<Uuid>,
+    pub meta: MetaTime,
+}
+
+impl PriceChange {
+    pub const NAME: &'static str = "PriceChange";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "PriceChange" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    coef numeric NOT NULL CHECK (coef > 0),
+    scope "PriceScope" NOT NULL DEFAULT 'All',
+    target uuid CHECK ((scope = 'All') = (target IS NULL)),
+    status "PriceChangeStatus" NOT NULL DEFAULT 'Applied',
+    reverts uuid REFERENCES "PriceChange" ON DELETE restrict ON UPDATE cascade,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "PriceChange";"#;
+
+    pub const fn new(
+        uuid: Uuid,
+        coef: BigDecimal,
+        scope: PriceScope,
+        target: Option<Uuid>,
+        status: PriceChangeStatus,
+        reverts: Option<Uuid>,
+        meta: MetaTime,
+    ) -> Self {
+        Self {
+            uuid,
+            coef,
+            scope,
+            target,
+            status,
+            reverts,
+            meta,
+        }
+    }
+
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "PriceChange" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "PriceChange" ORDER BY (meta).created"#)
+    }
+
+    /// Multiplies prices in scope by `coef`, returns uuid of the new change
+    pub fn index(coef: BigDecimal, scope: PriceScope, target: Option<Uuid>) -> PgQueryAs<(Uuid,)> {
+        query_as(r#"CALL "IndexPrices"($1, $2, $3, NULL);"#)
+            .bind(coef)
+            .bind(scope)
+            .bind(target)
+    }
+
+    /// Restores prices preceding the change, returns uuid of the reverting change
+    ///
+    /// Fails if some of the prices were changed again since then
+    pub fn rollback(uuid: Uuid) -> PgQueryAs<(Uuid,)> {
+        query_as(r#"CALL "RollbackPrices"($1, NULL);"#).bind(uuid)
+    }
+}
+
+impl Insertable for PriceChange {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "PriceChange" (uuid, coef, scope, target, status, reverts, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.coef.clone())
+        .bind(self.scope)
+        .bind(self.target)
+        .bind(self.status)
+        .bind(self.reverts)
+        .bind(self.meta.clone())
+    }
+}
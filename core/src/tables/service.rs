@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
@@ -9,6 +10,9 @@ pub struct Service {
     pub uuid: Uuid,
     pub name: String,
     pub description: Option<String>,
+    /// Warranty period granted on the repair, no warranty if zero
+    pub warranty_days: i32,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub meta: MetaTime,
 }
 
@@ -19,69 +23,99 @@ impl Service {
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
     name text NOT NULL UNIQUE,
     description text,
+    warranty_days int NOT NULL DEFAULT 0 CHECK (warranty_days >= 0),
+    deleted_at timestamptz,
     meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Service";"#;
 
-    pub const SAMPLES: [(&'static str, Option<&'static str>, f64, &'static str); 5] = [
-        ("Battery replacement", None, 10000.0, "Battery"),
+    pub const SAMPLES: [(&'static str, Option<&'static str>, f64, &'static str, i32); 5] = [
+        ("Battery replacement", None, 10000.0, "Battery", 180),
         (
             "Screen display replacement",
             None,
             15000.0,
             "Screen Display",
+            90,
         ),
         (
             "RAM Fix",
             Some("Replace malfunctioning RAM bank"),
             25000.0,
             "RAM",
+            365,
         ),
         (
             "Memory Fix",
             Some("Replace malfunctioning memory bank"),
             20000.0,
             "Memory",
+            365,
+        ),
+        (
+            "Screen glass replacement",
+            None,
+            12500.0,
+            "Screen Glass",
+            30,
         ),
-        ("Screen glass replacement", None, 12500.0, "Screen Glass"),
     ];
 
     pub const fn new(
         uuid: Uuid,
         name: String,
         description: Option<String>,
+        warranty_days: i32,
+        deleted_at: Option<DateTime<Utc>>,
         meta: MetaTime,
     ) -> Self {
         Self {
             uuid,
             name,
             description,
+            warranty_days,
+            deleted_at,
             meta,
         }
     }
 
-    pub fn new_auto(name: String, description: Option<String>) -> Self {
-        Self::new(Uuid::new_v4(), name, description, MetaTime::default())
+    pub fn new_auto(name: String, description: Option<String>, warranty_days: i32) -> Self {
+        Self::new(
+            Uuid::new_v4(),
+            name,
+            description,
+            warranty_days,
+            None,
+            MetaTime::default(),
+        )
     }
 
-    pub fn delete_by_uuid(uuid: Uuid) -> PgQuery {
-        query(r#"DELETE FROM "Service" WHERE uuid = $1"#).bind(uuid)
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Service" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
     }
 
     pub fn get_all() -> PgQueryAs<Self> {
-        query_as(r#"SELECT * FROM "Service""#)
+        query_as(r#"SELECT * FROM "Service" WHERE deleted_at IS NULL"#)
     }
 }
 
 impl Insertable for Service {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "Service" (uuid, name, description)
-    VALUES ($1, $2, $3);"#,
+            r#"INSERT INTO "Service" (uuid, name, description, warranty_days, deleted_at, meta)
+    VALUES ($1, $2, $3, $4, $5, $6);"#,
         )
         .bind(self.uuid)
         .bind(self.name.clone())
         .bind(self.description.clone())
+        .bind(self.warranty_days)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
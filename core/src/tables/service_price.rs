@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{traits::Insertable, types::Money, PgQueryAs};
+
+/// Price of the service for the phone model ([`ServicePhoneModel`](super::ServicePhoneModel))
+/// effective from the given moment
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct ServicePrice {
+    pub uuid: Uuid,
+    /// Foreign key references [`Service`](`super::service::Service`)
+    pub service: Uuid,
+    /// Foreign key references [`PhoneModel`](`super::phone_model::PhoneModel`)
+    pub phone_model: Uuid,
+    pub price: Money,
+    pub effective: DateTime<Utc>,
+    /// Foreign key references [`PriceChange`](`super::price_change::PriceChange`),
+    /// not set for the initial price
+    pub change: Option<Uuid>,
+}
+
+impl ServicePrice {
+    pub const NAME: &'static str = "ServicePrice";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "ServicePrice" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    service uuid NOT NULL,
+    phone_model uuid NOT NULL,
+    price monetary NOT NULL,
+    effective timestamptz NOT NULL DEFAULT now(),
+    change uuid REFERENCES "PriceChange" ON DELETE restrict ON UPDATE cascade,
+    FOREIGN KEY (service, phone_model) REFERENCES "ServicePhoneModel" ON DELETE cascade ON UPDATE cascade
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "ServicePrice";"#;
+
+    pub const fn new(
+        uuid: Uuid,
+        service: Uuid,
+        phone_model: Uuid,
+        price: Money,
+        effective: DateTime<Utc>,
+        change: Option<Uuid>,
+    ) -> Self {
+        Self {
+            uuid,
+            service,
+            phone_model,
+            price,
+            effective,
+            change,
+        }
+    }
+
+    /// Price history of the service for the phone model, latest first
+    pub fn get_by_service(service: Uuid, phone_model: Uuid) -> PgQueryAs<Self> {
+        query_as(
+            r#"SELECT * FROM "ServicePrice" WHERE service = $1 AND phone_model = $2
+ORDER BY effective DESC"#,
+        )
+        .bind(service)
+        .bind(phone_model)
+    }
+}
+
+impl Insertable for ServicePrice {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "ServicePrice" (uuid, service, phone_model, price, effective, change)
+VALUES ($1, $2, $3, $4, $5, $6);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.service)
+        .bind(self.phone_model)
+        .bind(self.price.clone())
+        .bind(self.effective)
+        .bind(self.change)
+    }
+}
@@ -1,8 +1,13 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
-use crate::{traits::Insertable, types::staff_status::StaffStatus, PgQueryAs};
+use crate::{
+    traits::Insertable,
+    types::{metatime::MetaTime, staff_status::StaffStatus},
+    PgQuery, PgQueryAs,
+};
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
 pub struct Staff {
@@ -11,7 +16,11 @@ pub struct Staff {
     pub contract: Uuid,
     /// Foreign key references [`Position`](`super::position::Position`)
     pub position: Uuid,
+    /// Foreign key references [`Branch`](`super::branch::Branch`)
+    pub branch: Uuid,
     pub status: StaffStatus,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub meta: MetaTime,
 }
 
 impl Staff {
@@ -21,38 +30,72 @@ impl Staff {
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
     contract uuid NOT NULL REFERENCES "LaborContract" ON DELETE restrict ON UPDATE cascade,
     position uuid NOT NULL REFERENCES "Position" ON DELETE restrict ON UPDATE cascade,
-    status "StaffStatus" NOT NULL DEFAULT 'Suspended'
+    branch uuid NOT NULL REFERENCES "Branch" ON DELETE restrict ON UPDATE cascade,
+    status "StaffStatus" NOT NULL DEFAULT 'Suspended',
+    deleted_at timestamptz,
+    meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Staff";"#;
 
-    pub const fn new(uuid: Uuid, contract: Uuid, position: Uuid, status: StaffStatus) -> Self {
+    pub const fn new(
+        uuid: Uuid,
+        contract: Uuid,
+        position: Uuid,
+        branch: Uuid,
+        status: StaffStatus,
+        deleted_at: Option<DateTime<Utc>>,
+        meta: MetaTime,
+    ) -> Self {
         Self {
             uuid,
             contract,
             position,
+            branch,
             status,
+            deleted_at,
+            meta,
         }
     }
 
-    pub fn new_auto(contract: Uuid, position: Uuid, status: StaffStatus) -> Self {
-        Self::new(Uuid::new_v4(), contract, position, status)
+    pub fn new_auto(contract: Uuid, position: Uuid, branch: Uuid, status: StaffStatus) -> Self {
+        Self::new(
+            Uuid::new_v4(),
+            contract,
+            position,
+            branch,
+            status,
+            None,
+            MetaTime::default(),
+        )
     }
 
     pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
         query_as(r#"SELECT * FROM "Staff" WHERE uuid = $1"#).bind(uuid)
     }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Staff" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
+    }
 }
 
 impl Insertable for Staff {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "Staff" (uuid, contract, position, status) 
-    VALUES ($1, $2, $3, $4);"#,
+            r#"INSERT INTO "Staff" (uuid, contract, position, branch, status, deleted_at, meta) 
+    VALUES ($1, $2, $3, $4, $5, $6, $7);"#,
         )
         .bind(self.uuid)
         .bind(self.contract)
         .bind(self.position)
+        .bind(self.branch)
         .bind(self.status)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{
+    traits::Insertable,
+    types::{metatime::MetaTime, transfer_status::TransferStatus},
+    PgQuery, PgQueryAs,
+};
+
+/// Movement of warehouse stock between branches
+///
+/// Stock leaves the source item while shipping and is added to the destination branch
+/// on receiving (see [`TransferStock`](crate::procedures::TransferStock))
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct StockTransfer {
+    pub uuid: Uuid,
+    /// Foreign key references [`Warehouse`](`super::warehouse::Warehouse`)
+    pub item: Uuid,
+    /// Foreign key references [`Branch`](`super::branch::Branch`)
+    pub destination: Uuid,
+    pub amount: i32,
+    pub status: TransferStatus,
+    /// Foreign key references [`Staff`](`super::staff::Staff`)
+    pub sender: Uuid,
+    /// Foreign key references [`Staff`](`super::staff::Staff`)
+    pub receiver: Option<Uuid>,
+    pub meta: MetaTime,
+}
+
+impl StockTransfer {
+    pub const NAME: &'static str = "StockTransfer";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "StockTransfer" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    item uuid NOT NULL REFERENCES "Warehouse" ON DELETE restrict ON UPDATE cascade,
+    destination uuid NOT NULL REFERENCES "Branch" ON DELETE restrict ON UPDATE cascade,
+    amount int NOT NULL CHECK (amount > 0),
+    status "TransferStatus" NOT NULL DEFAULT 'InTransit',
+    sender uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
+    receiver uuid REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
+    meta metatime NOT NULL DEFAULT (now(), now())
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "StockTransfer";"#;
+
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        uuid: Uuid,
+        item: Uuid,
+        destination: Uuid,
+        amount: i32,
+        status: TransferStatus,
+        sender: Uuid,
+        receiver: Option<Uuid>,
+        meta: MetaTime,
+    ) -> Self {
+        Self {
+            uuid,
+            item,
+            destination,
+            amount,
+            status,
+            sender,
+            receiver,
+            meta,
+        }
+    }
+
+    pub fn new_auto(item: Uuid, destination: Uuid, amount: i32, sender: Uuid) -> Self {
+        Self::new(
+            Uuid::new_v4(),
+            item,
+            destination,
+            amount,
+            TransferStatus::InTransit,
+            sender,
+            None,
+            MetaTime::default(),
+        )
+    }
+
+    /// Ships `amount` of the warehouse item of the given version to the destination branch
+    ///
+    /// Like the transfer status changes below, fails with [`Conflict`](crate::types::Conflict)
+    /// if the row is no longer of the given version
+    pub fn ship(
+        item: Uuid,
+        destination: Uuid,
+        amount: i32,
+        sender: Uuid,
+        version: DateTime<Utc>,
+    ) -> PgQuery {
+        query(r#"CALL "TransferStock"($1, $2, $3, $4, $5);"#)
+            .bind(item)
+            .bind(destination)
+            .bind(amount)
+            .bind(sender)
+            .bind(version)
+    }
+
+    /// Adds transferred stock to the destination branch
+    pub fn receive(uuid: Uuid, receiver: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(r#"CALL "ReceiveTransfer"($1, $2, $3);"#)
+            .bind(uuid)
+            .bind(receiver)
+            .bind(version)
+    }
+
+    /// Returns transferred stock to the source item
+    pub fn cancel(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(r#"CALL "CancelTransfer"($1, $2);"#)
+            .bind(uuid)
+            .bind(version)
+    }
+
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "StockTransfer" WHERE uuid = $1"#).bind(uuid)
+    }
+}
+
+impl Insertable for StockTransfer {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "StockTransfer"
+(uuid, item, destination, amount, status, sender, receiver, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.item)
+        .bind(self.destination)
+        .bind(self.amount)
+        .bind(self.status)
+        .bind(self.sender)
+        .bind(self.receiver)
+        .bind(self.meta.clone())
+    }
+}
@@ -1,17 +1,38 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, types::Json, FromRow, Postgres};
 use uuid::Uuid;
 
-use crate::{traits::Insertable, PgQuery, PgQueryAs};
+use crate::{
+    traits::Insertable,
+    types::{metatime::MetaTime, Bic, Country, Iban},
+    PgQuery, PgQueryAs,
+};
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
 pub struct Supplier {
     pub uuid: Uuid,
     pub name: String,
-    pub iban: String,
-    pub swift: String,
+    pub iban: Iban,
+    pub swift: Bic,
     pub address: String,
-    pub country: String,
+    pub country: Country,
+    pub details: Option<Json<SupplierDetails>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub meta: MetaTime,
+}
+
+/// Contacts and payment terms of the supplier, stored as `details` json
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct SupplierDetails {
+    pub contact: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub website: Option<String>,
+    /// Days given to pay for the delivered supply
+    pub payment_days: Option<i32>,
+    pub notes: Option<String>,
 }
 
 impl Supplier {
@@ -20,23 +41,28 @@ impl Supplier {
     pub const CREATE: &'static str = r#"CREATE TABLE "Supplier" (
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
     name text NOT NULL,
-    iban text NOT NULL UNIQUE CHECK (length(iban) <= 32),
-    swift text NOT NULL CHECK (length(swift) <= 11),
+    iban iban NOT NULL UNIQUE,
+    swift bic NOT NULL,
     address text NOT NULL,
-    country char(2) NOT NULL,
+    country country_code NOT NULL,
     details json,
+    deleted_at timestamptz,
     meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Supplier";"#;
 
-    pub const fn new(
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
         uuid: Uuid,
         name: String,
-        iban: String,
-        swift: String,
+        iban: Iban,
+        swift: Bic,
         address: String,
-        country: String,
+        country: Country,
+        details: Option<SupplierDetails>,
+        deleted_at: Option<DateTime<Utc>>,
+        meta: MetaTime,
     ) -> Self {
         Self {
             uuid,
@@ -45,39 +71,69 @@ impl Supplier {
             swift,
             address,
             country,
+            details: details.map(Json),
+            deleted_at,
+            meta,
         }
     }
 
     pub fn new_auto(
         name: String,
-        iban: String,
-        swift: String,
+        iban: Iban,
+        swift: Bic,
         address: String,
-        country: String,
+        country: Country,
+        details: Option<SupplierDetails>,
     ) -> Self {
-        Self::new(Uuid::new_v4(), name, iban, swift, address, country)
+        Self::new(
+            Uuid::new_v4(),
+            name,
+            iban,
+            swift,
+            address,
+            country,
+            details,
+            None,
+            MetaTime::default(),
+        )
+    }
+
+    /// Replaces the details read at the given version, see [`Conflict`](crate::types::Conflict)
+    pub fn update_details(uuid: Uuid, details: SupplierDetails, version: DateTime<Utc>) -> PgQuery {
+        query(r#"UPDATE "Supplier" SET details = $2, meta.updated = $3 WHERE uuid = $1"#)
+            .bind(uuid)
+            .bind(Json(details))
+            .bind(version)
     }
 
-    pub fn delete_by_uuid(uuid: Uuid) -> PgQuery {
-        query(r#"DELETE FROM "Supplier" WHERE uuid = $1"#).bind(uuid)
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Supplier" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
     }
 
     pub fn get_all() -> PgQueryAs<Self> {
-        query_as(r#"SELECT * FROM "Supplier""#)
+        query_as(r#"SELECT * FROM "Supplier" WHERE deleted_at IS NULL"#)
     }
 }
 
 impl Insertable for Supplier {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "Supplier" (uuid, name, iban, swift, address, country) 
-VALUES ($1, $2, $3, $4, $5, $6);"#,
+            r#"INSERT INTO "Supplier" (uuid, name, iban, swift, address, country, details, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);"#,
         )
         .bind(self.uuid)
         .bind(self.name.clone())
         .bind(self.iban.clone())
         .bind(self.swift.clone())
         .bind(self.address.clone())
-        .bind(self.country.clone())
+        .bind(self.country)
+        .bind(self.details.clone())
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
@@ -1,11 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgArguments, query, query::Query, FromRow, Postgres};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
 use crate::{
     traits::Insertable,
     types::{metatime::MetaTime, supply_status::SupplyStatus},
+    PgQuery, PgQueryAs,
 };
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
@@ -15,8 +16,15 @@ pub struct Supply {
     pub contract: Uuid,
     /// Foreign key references [`Staff`](`super::staff::Staff`)
     pub staff: Uuid,
+    /// Manager who approved the supply, foreign key references [`Staff`](`super::staff::Staff`)
+    pub approver: Option<Uuid>,
     pub status: SupplyStatus,
     pub signed: Option<DateTime<Utc>>,
+    /// Expected delivery, set on signing from the longest lead time of the contract items
+    pub due: Option<DateTime<Utc>>,
+    /// Set when the supply becomes [`SupplyStatus::Delivered`]
+    pub delivered: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub meta: MetaTime,
 }
 
@@ -27,27 +35,40 @@ impl Supply {
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
     contract uuid NOT NULL REFERENCES "SupplyContract" ON DELETE restrict ON UPDATE cascade,
     staff uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
+    approver uuid REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
     status "SupplyStatus" NOT NULL DEFAULT 'Review',
     signed timestamptz,
+    due timestamptz,
+    delivered timestamptz,
+    deleted_at timestamptz,
     meta metatime NOT NULL DEFAULT (now(), now())
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Supply";"#;
 
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         uuid: Uuid,
         contract: Uuid,
         staff: Uuid,
+        approver: Option<Uuid>,
         status: SupplyStatus,
         signed: Option<DateTime<Utc>>,
+        due: Option<DateTime<Utc>>,
+        delivered: Option<DateTime<Utc>>,
+        deleted_at: Option<DateTime<Utc>>,
         meta: MetaTime,
     ) -> Self {
         Self {
             uuid,
             contract,
             staff,
+            approver,
             status,
             signed,
+            due,
+            delivered,
+            deleted_at,
             meta,
         }
     }
@@ -62,23 +83,92 @@ impl Supply {
             Uuid::new_v4(),
             contract,
             staff,
+            None,
             status,
             signed,
+            None,
+            None,
+            None,
             MetaTime::default(),
         )
     }
+
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Supply" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    /// Signs the supply under review, see [`ApproveSupply`](crate::procedures::ApproveSupply)
+    ///
+    /// Like other status changes below, fails with [`Conflict`](crate::types::Conflict)
+    /// if the supply is no longer of the given version
+    pub fn approve(uuid: Uuid, manager: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(r#"CALL "ApproveSupply"($1, $2, $3);"#)
+            .bind(uuid)
+            .bind(manager)
+            .bind(version)
+    }
+
+    /// Declines the supply under review
+    pub fn reject(uuid: Uuid, manager: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Supply" SET status = 'Rejected', approver = $2, meta.updated = $3
+WHERE uuid = $1 AND status IN ('Review', 'Negotiation');"#,
+        )
+        .bind(uuid)
+        .bind(manager)
+        .bind(version)
+    }
+
+    /// Marks the signed supply as shipped, expected delivery time is kept if not given
+    pub fn dispatch(uuid: Uuid, due: Option<DateTime<Utc>>, version: DateTime<Utc>) -> PgQuery {
+        query(r#"CALL "DispatchSupply"($1, $2, $3);"#)
+            .bind(uuid)
+            .bind(due)
+            .bind(version)
+    }
+
+    /// Adds received items to the warehouse, see [`ReceiveSupply`](crate::procedures::ReceiveSupply)
+    pub fn receive(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(r#"CALL "ReceiveSupply"($1, $2);"#)
+            .bind(uuid)
+            .bind(version)
+    }
+
+    /// Marks the supply awaiting delivery as failed
+    pub fn fail(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Supply" SET status = 'Failed', meta.updated = $2
+WHERE uuid = $1 AND status IN ('Signed', 'Paid', 'Dispatched');"#,
+        )
+        .bind(uuid)
+        .bind(version)
+    }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Supply" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
+    }
 }
 
 impl Insertable for Supply {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "Supply" (uuid, contract, staff, status, signed)
-VALUES ($1, $2, $3, $4, $5);"#,
+            r#"INSERT INTO "Supply" (uuid, contract, staff, approver, status, signed, due, delivered, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);"#,
         )
         .bind(self.uuid)
         .bind(self.contract)
         .bind(self.staff)
+        .bind(self.approver)
         .bind(self.status)
         .bind(self.signed)
+        .bind(self.due)
+        .bind(self.delivered)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
@@ -1,11 +1,12 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgArguments, query, query::Query, FromRow, Postgres};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
 use crate::{
     traits::Insertable,
-    types::{contract_status::ContractStatus, metatime::MetaTime},
+    types::{contract_status::ContractStatus, metatime::MetaTime, Currency},
+    PgQuery, PgQueryAs,
 };
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
@@ -17,6 +18,12 @@ pub struct SupplyContract {
     pub manager: Uuid,
     pub status: ContractStatus,
     pub signed: Option<DateTime<Utc>>,
+    pub valid_from: NaiveDate,
+    /// Last day of validity, active contract becomes expired after it, open-ended if not set
+    pub valid_to: Option<NaiveDate>,
+    /// Currency of the supply prices under the contract
+    pub currency: Currency,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub meta: MetaTime,
 }
 
@@ -29,17 +36,27 @@ impl SupplyContract {
     manager uuid NOT NULL REFERENCES "Staff" ON DELETE restrict ON UPDATE cascade,
     status "ContractStatus" NOT NULL DEFAULT 'Review',
     signed timestamptz,
-    meta metatime NOT NULL DEFAULT (now(), now())
+    valid_from date NOT NULL DEFAULT current_date,
+    valid_to date,
+    currency text NOT NULL DEFAULT base_currency() CHECK (currency ~ '^[A-Z]{3}$'),
+    deleted_at timestamptz,
+    meta metatime NOT NULL DEFAULT (now(), now()),
+    CHECK (valid_to IS NULL OR valid_to >= valid_from)
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "SupplyContract";"#;
 
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         uuid: Uuid,
         supplier: Uuid,
         manager: Uuid,
         status: ContractStatus,
         signed: Option<DateTime<Utc>>,
+        valid_from: NaiveDate,
+        valid_to: Option<NaiveDate>,
+        currency: Currency,
+        deleted_at: Option<DateTime<Utc>>,
         meta: MetaTime,
     ) -> Self {
         Self {
@@ -48,6 +65,10 @@ impl SupplyContract {
             manager,
             status,
             signed,
+            valid_from,
+            valid_to,
+            currency,
+            deleted_at,
             meta,
         }
     }
@@ -57,6 +78,9 @@ impl SupplyContract {
         manager: Uuid,
         status: ContractStatus,
         signed: Option<DateTime<Utc>>,
+        valid_from: NaiveDate,
+        valid_to: Option<NaiveDate>,
+        currency: Currency,
     ) -> Self {
         Self::new(
             Uuid::new_v4(),
@@ -64,21 +88,52 @@ impl SupplyContract {
             manager,
             status,
             signed,
+            valid_from,
+            valid_to,
+            currency,
+            None,
             MetaTime::default(),
         )
     }
+
+    /// Contracts with the supplier, latest first
+    pub fn get_by_supplier(supplier: Uuid) -> PgQueryAs<Self> {
+        query_as(
+            r#"SELECT * FROM "SupplyContract" WHERE supplier = $1 AND deleted_at IS NULL ORDER BY valid_from DESC"#,
+        )
+        .bind(supplier)
+    }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "SupplyContract" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
+    }
+
+    /// Marks active contracts past their validity as expired, returns count of them
+    pub fn expire() -> PgQueryAs<(i32,)> {
+        query_as(r#"CALL "ExpireContracts"(NULL)"#)
+    }
 }
 
 impl Insertable for SupplyContract {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "SupplyContract" (uuid, supplier, manager, status, signed) 
-VALUES ($1, $2, $3, $4, $5);"#,
+            r#"INSERT INTO "SupplyContract" (uuid, supplier, manager, status, signed, valid_from, valid_to, currency, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);"#,
         )
         .bind(self.uuid)
         .bind(self.supplier)
         .bind(self.manager)
         .bind(self.status)
         .bind(self.signed)
+        .bind(self.valid_from)
+        .bind(self.valid_to)
+        .bind(self.currency)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
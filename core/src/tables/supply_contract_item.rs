@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{
+    traits::Insertable,
+    types::{MetaTime, Money},
+    PgQuery, PgQueryAs,
+};
+
+/// Terms of the [`Component`](`super::component::Component`) supply agreed by the
+/// [`SupplyContract`](`super::supply_contract::SupplyContract`)
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct SupplyContractItem {
+    /// Foreign key references [`SupplyContract`](`super::supply_contract::SupplyContract`)
+    pub contract: Uuid,
+    /// Foreign key references [`Component`](`super::component::Component`)
+    pub component: Uuid,
+    /// Agreed price of one item in the contract currency
+    pub price: Money,
+    /// Days from signing the supply till the delivery
+    pub lead_days: i32,
+    /// Minimal amount of items in a single supply
+    pub min_order: i32,
+    pub meta: MetaTime,
+}
+
+impl SupplyContractItem {
+    pub const NAME: &'static str = "SupplyContractItem";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "SupplyContractItem" (
+    contract uuid NOT NULL REFERENCES "SupplyContract" ON DELETE cascade ON UPDATE cascade,
+    component uuid NOT NULL REFERENCES "Component" ON DELETE restrict ON UPDATE cascade,
+    price monetary NOT NULL CHECK ((price).amount >= 0),
+    lead_days int NOT NULL CHECK (lead_days >= 0),
+    min_order int NOT NULL DEFAULT 1 CHECK (min_order > 0),
+    meta metatime NOT NULL DEFAULT (now(), now()),
+    PRIMARY KEY (contract, component)
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "SupplyContractItem";"#;
+
+    pub fn new(
+        contract: Uuid,
+        component: Uuid,
+        price: Money,
+        lead_days: i32,
+        min_order: i32,
+        meta: MetaTime,
+    ) -> Self {
+        Self {
+            contract,
+            component,
+            price,
+            lead_days,
+            min_order,
+            meta,
+        }
+    }
+
+    pub fn new_auto(
+        contract: Uuid,
+        component: Uuid,
+        price: Money,
+        lead_days: i32,
+        min_order: i32,
+    ) -> Self {
+        Self::new(
+            contract,
+            component,
+            price,
+            lead_days,
+            min_order,
+            MetaTime::default(),
+        )
+    }
+
+    pub fn get_by_contract(contract: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "SupplyContractItem" WHERE contract = $1"#).bind(contract)
+    }
+
+    pub fn delete(contract: Uuid, component: Uuid) -> PgQuery {
+        query(r#"DELETE FROM "SupplyContractItem" WHERE contract = $1 AND component = $2"#)
+            .bind(contract)
+            .bind(component)
+    }
+}
+
+impl Insertable for SupplyContractItem {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "SupplyContractItem" (contract, component, price, lead_days, min_order, meta)
+VALUES ($1, $2, $3, $4, $5, $6);"#,
+        )
+        .bind(self.contract)
+        .bind(self.component)
+        .bind(self.price.clone())
+        .bind(self.lead_days)
+        .bind(self.min_order)
+        .bind(self.meta.clone())
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{
+    traits::Insertable,
+    types::{MetaTime, Money},
+    PgQuery, PgQueryAs,
+};
+
+/// Line of the purchase order ([`Supply`](`super::supply::Supply`)), received amount is set
+/// on delivery and may differ from the ordered one
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct SupplyItem {
+    /// Foreign key references [`Supply`](`super::supply::Supply`)
+    pub supply: Uuid,
+    /// Foreign key references [`Component`](`super::component::Component`)
+    pub component: Uuid,
+    pub ordered: i32,
+    pub received: Option<i32>,
+    /// Price of one item agreed by the [`SupplyContractItem`](`super::supply_contract_item::SupplyContractItem`)
+    pub price: Money,
+    pub meta: MetaTime,
+}
+
+impl SupplyItem {
+    pub const NAME: &'static str = "SupplyItem";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "SupplyItem" (
+    supply uuid NOT NULL REFERENCES "Supply" ON DELETE cascade ON UPDATE cascade,
+    component uuid NOT NULL REFERENCES "Component" ON DELETE restrict ON UPDATE cascade,
+    ordered int NOT NULL CHECK (ordered > 0),
+    received int CHECK (received >= 0),
+    price monetary NOT NULL CHECK ((price).amount >= 0),
+    meta metatime NOT NULL DEFAULT (now(), now()),
+    PRIMARY KEY (supply, component)
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "SupplyItem";"#;
+
+    pub fn new(
+        supply: Uuid,
+        component: Uuid,
+        ordered: i32,
+        received: Option<i32>,
+        price: Money,
+        meta: MetaTime,
+    ) -> Self {
+        Self {
+            supply,
+            component,
+            ordered,
+            received,
+            price,
+            meta,
+        }
+    }
+
+    pub fn new_auto(supply: Uuid, component: Uuid, ordered: i32, price: Money) -> Self {
+        Self::new(supply, component, ordered, None, price, MetaTime::default())
+    }
+
+    pub fn get_by_supply(supply: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "SupplyItem" WHERE supply = $1"#).bind(supply)
+    }
+
+    /// Adds the component to the supply at the price agreed by its contract,
+    /// nothing is inserted if the contract has no terms for the component
+    pub fn order(supply: Uuid, component: Uuid, ordered: i32) -> PgQuery {
+        query(
+            r#"INSERT INTO "SupplyItem" (supply, component, ordered, price)
+SELECT s.uuid, ci.component, $3, ci.price
+FROM "Supply" s
+    JOIN "SupplyContractItem" ci ON ci.contract = s.contract
+WHERE s.uuid = $1 AND ci.component = $2;"#,
+        )
+        .bind(supply)
+        .bind(component)
+        .bind(ordered)
+    }
+
+    /// Sets amount actually received to the line of the given version,
+    /// lines left unset are received as ordered
+    pub fn set_received(
+        supply: Uuid,
+        component: Uuid,
+        received: i32,
+        version: DateTime<Utc>,
+    ) -> PgQuery {
+        query(
+            r#"UPDATE "SupplyItem" SET received = $3, meta.updated = $4
+WHERE supply = $1 AND component = $2"#,
+        )
+        .bind(supply)
+        .bind(component)
+        .bind(received)
+        .bind(version)
+    }
+}
+
+impl Insertable for SupplyItem {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "SupplyItem" (supply, component, ordered, received, price, meta)
+VALUES ($1, $2, $3, $4, $5, $6);"#,
+        )
+        .bind(self.supply)
+        .bind(self.component)
+        .bind(self.ordered)
+        .bind(self.received)
+        .bind(self.price.clone())
+        .bind(self.meta.clone())
+    }
+}
@@ -1,28 +1,26 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{
-    postgres::{types::PgMoney, PgArguments},
-    query,
-    query::Query,
-    types::BigDecimal,
-    FromRow, Postgres,
-};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
 use uuid::Uuid;
 
-use crate::{traits::Insertable, types::MetaTime};
+use crate::{
+    traits::Insertable,
+    types::{MetaTime, Money},
+    PgQuery, PgQueryAs,
+};
 
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
 pub struct Warehouse {
     pub uuid: Uuid,
+    /// Foreign key references [`Branch`](`super::branch::Branch`)
+    pub branch: Uuid,
     /// Foreign key references [`Component`](`super::component::Component`)
     pub component: Uuid,
     /// Foreign key references [`Supplier`](`super::supplier::Supplier`)
     pub supplier: Uuid,
-    #[serde(
-        deserialize_with = "crate::utils::deserialize_pg_money",
-        serialize_with = "crate::utils::serialize_pg_money"
-    )]
-    pub price: PgMoney,
+    pub price: Money,
     pub amount: i32,
+    pub deleted_at: Option<DateTime<Utc>>,
     pub meta: MetaTime,
 }
 
@@ -31,56 +29,87 @@ impl Warehouse {
 
     pub const CREATE: &'static str = r#"CREATE TABLE "Warehouse" (
     uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    branch uuid NOT NULL REFERENCES "Branch" ON DELETE restrict ON UPDATE cascade,
     component uuid NOT NULL REFERENCES "Component" ON DELETE restrict ON UPDATE cascade,
     supplier uuid NOT NULL REFERENCES "Supplier" ON DELETE restrict ON UPDATE cascade,
-    price money NOT NULL,
+    price monetary NOT NULL,
     amount int NOT NULL DEFAULT 0,
+    deleted_at timestamptz,
     meta metatime NOT NULL DEFAULT (now(), now()),
-    UNIQUE(component, supplier)
+    UNIQUE(branch, component, supplier)
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "Warehouse";"#;
 
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         uuid: Uuid,
+        branch: Uuid,
         component: Uuid,
         supplier: Uuid,
-        price: PgMoney,
+        price: Money,
         amount: i32,
+        deleted_at: Option<DateTime<Utc>>,
         meta: MetaTime,
     ) -> Self {
         Self {
             uuid,
+            branch,
             component,
             supplier,
             price,
             amount,
+            deleted_at,
             meta,
         }
     }
 
-    pub fn new_auto(component: Uuid, supplier: Uuid, price: BigDecimal, amount: i32) -> Self {
+    pub fn new_auto(
+        branch: Uuid,
+        component: Uuid,
+        supplier: Uuid,
+        price: Money,
+        amount: i32,
+    ) -> Self {
         Self::new(
             Uuid::new_v4(),
+            branch,
             component,
             supplier,
-            PgMoney::from_bigdecimal(price, 2).unwrap(),
+            price,
             amount,
+            None,
             MetaTime::default(),
         )
     }
+
+    pub fn get_by_uuid(uuid: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "Warehouse" WHERE uuid = $1"#).bind(uuid)
+    }
+
+    pub fn delete_by_uuid(uuid: Uuid, version: DateTime<Utc>) -> PgQuery {
+        query(
+            r#"UPDATE "Warehouse" SET deleted_at = now(), meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NULL;"#,
+        )
+        .bind(uuid)
+        .bind(version)
+    }
 }
 
 impl Insertable for Warehouse {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
         query(
-            r#"INSERT INTO "Warehouse" (uuid, component, supplier, price, amount)
-VALUES ($1, $2, $3, $4, $5);"#,
+            r#"INSERT INTO "Warehouse" (uuid, branch, component, supplier, price, amount, deleted_at, meta)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8);"#,
         )
         .bind(self.uuid)
+        .bind(self.branch)
         .bind(self.component)
         .bind(self.supplier)
-        .bind(self.price)
+        .bind(self.price.clone())
         .bind(self.amount)
+        .bind(self.deleted_at)
+        .bind(self.meta.clone())
     }
 }
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{postgres::PgArguments, query, query::Query, query_as, FromRow, Postgres};
+use uuid::Uuid;
+
+use crate::{traits::Insertable, types::Money, PgQueryAs};
+
+/// Price of the [`Warehouse`](super::Warehouse) item effective from the given moment
+#[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
+pub struct WarehousePrice {
+    pub uuid: Uuid,
+    /// Foreign key references [`Warehouse`](`super::warehouse::Warehouse`)
+    pub item: Uuid,
+    pub price: Money,
+    pub effective: DateTime<Utc>,
+    /// Foreign key references [`PriceChange`](`super::price_change::PriceChange`),
+    /// not set for the price the item was stocked with
+    pub change: Option<Uuid>,
+}
+
+impl WarehousePrice {
+    pub const NAME: &'static str = "WarehousePrice";
+
+    pub const CREATE: &'static str = r#"CREATE TABLE "WarehousePrice" (
+    uuid uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    item uuid NOT NULL REFERENCES "Warehouse" ON DELETE cascade ON UPDATE cascade,
+    price monetary NOT NULL,
+    effective timestamptz NOT NULL DEFAULT now(),
+    change uuid REFERENCES "PriceChange" ON DELETE restrict ON UPDATE cascade
+);"#;
+
+    pub const DROP: &'static str = r#"DROP TABLE "WarehousePrice";"#;
+
+    pub const fn new(
+        uuid: Uuid,
+        item: Uuid,
+        price: Money,
+        effective: DateTime<Utc>,
+        change: Option<Uuid>,
+    ) -> Self {
+        Self {
+            uuid,
+            item,
+            price,
+            effective,
+            change,
+        }
+    }
+
+    /// Price history of the item, latest first
+    pub fn get_by_item(item: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "WarehousePrice" WHERE item = $1 ORDER BY effective DESC"#)
+            .bind(item)
+    }
+}
+
+impl Insertable for WarehousePrice {
+    fn insert(&self) -> Query<'static, Postgres, PgArguments> {
+        query(
+            r#"INSERT INTO "WarehousePrice" (uuid, item, price, effective, change)
+VALUES ($1, $2, $3, $4, $5);"#,
+        )
+        .bind(self.uuid)
+        .bind(self.item)
+        .bind(self.price.clone())
+        .bind(self.effective)
+        .bind(self.change)
+    }
+}
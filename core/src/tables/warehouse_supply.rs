@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgArguments, query, query::Query, FromRow, Postgres};
 use uuid::Uuid;
 
-use crate::traits::Insertable;
+use crate::{traits::Insertable, types::Money};
 
 /// Represents relation table between [`Warehouse`](`super::warehouse::Warehouse`) and [`Supply`](`super::supply::Supply`)
 #[derive(FromRow, Serialize, Deserialize, Clone, Debug)]
@@ -13,6 +13,8 @@ pub struct WarehouseSupply {
     /// Foreign key references [`Supply`](`super::supply::Supply`)
     pub supply: Uuid,
     pub amount: i32,
+    /// Purchase price of one item in the [`SupplyContract`](`super::supply_contract::SupplyContract`) currency
+    pub price: Money,
     pub created: DateTime<Utc>,
 }
 
@@ -23,27 +25,39 @@ impl WarehouseSupply {
     item uuid NOT NULL REFERENCES "Warehouse" ON DELETE restrict ON UPDATE cascade,
     supply uuid NOT NULL REFERENCES "Supply" ON DELETE restrict ON UPDATE cascade,
     amount int NOT NULL,
+    price monetary NOT NULL CHECK ((price).amount >= 0),
     created timestamptz NOT NULL DEFAULT now(),
     PRIMARY KEY(item, supply)
 );"#;
 
     pub const DROP: &'static str = r#"DROP TABLE "WarehouseSupply";"#;
 
-    pub const fn new(item: Uuid, supply: Uuid, amount: i32, created: DateTime<Utc>) -> Self {
+    pub const fn new(
+        item: Uuid,
+        supply: Uuid,
+        amount: i32,
+        price: Money,
+        created: DateTime<Utc>,
+    ) -> Self {
         Self {
             item,
             supply,
             amount,
+            price,
             created,
         }
     }
 }
 impl Insertable for WarehouseSupply {
     fn insert(&self) -> Query<'static, Postgres, PgArguments> {
-        query(r#"INSERT INTO "WarehouseSupply" VALUES ($1, $2, $3, $4);"#)
-            .bind(self.item)
-            .bind(self.supply)
-            .bind(self.amount)
-            .bind(self.created)
+        query(
+            r#"INSERT INTO "WarehouseSupply" (item, supply, amount, price, created)
+VALUES ($1, $2, $3, $4, $5);"#,
+        )
+        .bind(self.item)
+        .bind(self.supply)
+        .bind(self.amount)
+        .bind(self.price.clone())
+        .bind(self.created)
     }
 }
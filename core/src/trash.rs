@@ -0,0 +1,196 @@
+//! Soft-deleted rows of business entities
+//!
+//! `delete_by_uuid` of the [`TABLES`] only sets `deleted_at`, so the rows are hidden from
+//! `get_all` and the beautified views while orders and payments keep referencing them.
+//! Rows are restored by [`restore`] and hard-deleted by [`purge`].
+//! Deleting and restoring are versioned updates, see [`Conflict`]
+
+use anyhow::{ensure, Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{query, query_as, Acquire, Error, Executor, PgPool, Postgres};
+use uuid::Uuid;
+
+use crate::{tables::Table, types::Conflict};
+
+/// Tables with the `deleted_at` column, referencing tables come before the referenced ones
+/// so a purged phone doesn't keep its owner
+pub const TABLES: [Table; 15] = [
+    Table::Invoice,
+    Table::Order,
+    Table::Phone,
+    Table::Warehouse,
+    Table::Component,
+    Table::PhoneModel,
+    Table::Supply,
+    Table::SupplyContract,
+    Table::Staff,
+    Table::Person,
+    Table::Branch,
+    Table::Position,
+    Table::Service,
+    Table::Supplier,
+    Table::Manufacturer,
+];
+
+/// Postgres error code of `foreign_key_violation`
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+
+/// Table name, uuid, label, deletion and update time of [`Trashed`]
+type TrashedRow = (String, Uuid, String, DateTime<Utc>, DateTime<Utc>);
+
+/// Row in the trash
+#[derive(Clone, Debug)]
+pub struct Trashed {
+    pub table: Table,
+    pub uuid: Uuid,
+    /// Name of the row to tell it apart (full name, IMEI, tracking code, invoice amount...)
+    pub label: String,
+    pub deleted_at: DateTime<Utc>,
+    /// Version of the row [`restore`] is based on
+    pub updated: DateTime<Utc>,
+}
+
+impl Trashed {
+    fn label(table: Table) -> &'static str {
+        match table {
+            Table::Person => "CONCAT_WS(' ', first_name, middle_name, last_name)",
+            Table::Phone => "imei::text",
+            Table::Invoice => "(amount).amount || ' ' || (amount).currency",
+            Table::Order => "tracking",
+            Table::Warehouse => {
+                r#"(SELECT c.name FROM "Component" c WHERE c.uuid = "Warehouse".component) || ' x' || amount"#
+            }
+            Table::Staff => {
+                r#"(SELECT CONCAT_WS(' ', p.first_name, p.middle_name, p.last_name) FROM "LaborContract" l
+    JOIN "Person" p ON p.uuid = l.person WHERE l.uuid = "Staff".contract)"#
+            }
+            Table::SupplyContract => {
+                r#"(SELECT s.name FROM "Supplier" s WHERE s.uuid = "SupplyContract".supplier) || ' ' || valid_from"#
+            }
+            Table::Supply => {
+                r#"(SELECT s.name FROM "SupplyContract" c JOIN "Supplier" s ON s.uuid = c.supplier
+    WHERE c.uuid = "Supply".contract) || ' ' || (meta).created::date"#
+            }
+            _ => "name",
+        }
+    }
+
+    /// Rows deleted before the given time, the whole trash if not set, recently deleted first
+    pub async fn fetch<'e>(
+        executor: impl Executor<'e, Database = Postgres>,
+        before: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Self>> {
+        let sql = TABLES
+            .iter()
+            .map(|table| {
+                format!(
+                    r#"SELECT '{table}', uuid, {}, deleted_at, (meta).updated FROM "{table}"
+WHERE deleted_at IS NOT NULL AND ($1::timestamptz IS NULL OR deleted_at < $1)"#,
+                    Self::label(*table)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\nUNION ALL\n")
+            + "\nORDER BY 4 DESC;";
+
+        let rows: Vec<TrashedRow> = query_as(&sql)
+            .bind(before)
+            .fetch_all(executor)
+            .await
+            .context("While reading the trash")?;
+
+        rows.into_iter()
+            .map(|(table, uuid, label, deleted_at, updated)| {
+                Ok(Self {
+                    table: table.parse().map_err(anyhow::Error::msg)?,
+                    uuid,
+                    label,
+                    deleted_at,
+                    updated,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Takes the row of the given version out of the trash
+pub async fn restore<'e>(
+    executor: impl Executor<'e, Database = Postgres>,
+    table: Table,
+    uuid: Uuid,
+    version: DateTime<Utc>,
+) -> Result<()> {
+    ensure!(
+        TABLES.contains(&table),
+        "Rows of '{table}' table aren't trashed"
+    );
+
+    let sql = format!(
+        r#"UPDATE "{table}" SET deleted_at = NULL, meta.updated = $2
+WHERE uuid = $1 AND deleted_at IS NOT NULL;"#
+    );
+    let result = query(&sql)
+        .bind(uuid)
+        .bind(version)
+        .execute(executor)
+        .await
+        .map_err(Conflict::map)
+        .with_context(|| format!("While restoring '{uuid}' of '{table}' table"))?;
+    ensure!(
+        result.rows_affected() == 1,
+        "No row '{uuid}' of '{table}' table in the trash"
+    );
+
+    Ok(())
+}
+
+/// Outcome of [`purge`]
+#[derive(Default, Debug)]
+pub struct Purge {
+    pub purged: Vec<Trashed>,
+    /// Rows still referenced by other ones, left in the trash
+    pub kept: Vec<Trashed>,
+}
+
+/// Hard-deletes rows trashed before the given time unless something still references them
+///
+/// Every row is deleted within its own savepoint, a dry run rolls back all of them
+pub async fn purge(pool: &PgPool, before: DateTime<Utc>, dry_run: bool) -> Result<Purge> {
+    let mut tx = pool.begin().await?;
+
+    let mut rows = Trashed::fetch(&mut tx, Some(before)).await?;
+    rows.sort_by_key(|row| TABLES.iter().position(|table| *table == row.table));
+
+    let mut outcome = Purge::default();
+    for row in rows {
+        let mut savepoint = tx.begin().await?;
+        let sql = format!(
+            r#"DELETE FROM "{}" WHERE uuid = $1 AND deleted_at IS NOT NULL;"#,
+            row.table
+        );
+
+        match query(&sql).bind(row.uuid).execute(&mut savepoint).await {
+            Ok(_) => {
+                savepoint.commit().await?;
+                outcome.purged.push(row);
+            }
+            Err(Error::Database(err)) if err.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                savepoint.rollback().await?;
+                outcome.kept.push(row);
+            }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("While purging '{}' of '{}' table", row.uuid, row.table)
+                })
+            }
+        }
+    }
+
+    if dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+
+    Ok(outcome)
+}
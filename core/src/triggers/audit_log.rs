@@ -13,6 +13,6 @@ WHEN (pg_trigger_depth() = 0) EXECUTE FUNCTION audit_log_func();"#;
     const DROP: &'static str = r#"DROP TRIGGER audit_log ON "$1";"#;
 }
 
-impl MultipleTables<21> for AuditLog {
-    const TABLES: [Table; 21] = Table::ALL;
+impl MultipleTables<33> for AuditLog {
+    const TABLES: [Table; 33] = Table::ALL;
 }
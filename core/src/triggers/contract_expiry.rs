@@ -0,0 +1,17 @@
+use crate::{
+    tables::Table,
+    traits::{MultipleTables, Recreatable},
+};
+
+pub struct ContractExpiry;
+
+impl Recreatable for ContractExpiry {
+    const NAME: &'static str = "contract_expiry";
+    const CREATE: &'static str = r#"CREATE TRIGGER contract_expiry BEFORE
+INSERT OR UPDATE ON "$1" FOR EACH ROW EXECUTE FUNCTION contract_expiry_func();"#;
+    const DROP: &'static str = r#"DROP TRIGGER contract_expiry ON "$1";"#;
+}
+
+impl MultipleTables<1> for ContractExpiry {
+    const TABLES: [Table; 1] = [Table::SupplyContract];
+}
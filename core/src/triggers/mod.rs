@@ -1,7 +1,6 @@
 use core::fmt;
 
-use anyhow::{Context, Result};
-use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool, Postgres};
+use sqlx::{query_as, Error, Executor, PgPool, Postgres};
 
 use crate::{
     tables::Table,
@@ -9,24 +8,57 @@ use crate::{
 };
 
 pub mod audit_log;
+pub mod contract_expiry;
+pub mod notify_change;
+pub mod phone_ownership;
+pub mod settle_order;
+pub mod supply_delivery;
 pub mod update_time;
+pub mod warranty_price;
 
 pub use audit_log::AuditLog;
+pub use contract_expiry::ContractExpiry;
+pub use notify_change::NotifyChange;
+pub use phone_ownership::PhoneOwnership;
+pub use settle_order::SettleOrder;
+pub use supply_delivery::SupplyDelivery;
 pub use update_time::UpdateTime;
+pub use warranty_price::WarrantyPrice;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Trigger {
     UpdateTime,
     AuditLog,
+    SettleOrder,
+    WarrantyPrice,
+    ContractExpiry,
+    SupplyDelivery,
+    PhoneOwnership,
+    NotifyChange,
 }
 
 impl Trigger {
-    pub const ALL: [Self; 2] = [Self::UpdateTime, Self::AuditLog];
+    pub const ALL: [Self; 8] = [
+        Self::UpdateTime,
+        Self::AuditLog,
+        Self::SettleOrder,
+        Self::WarrantyPrice,
+        Self::ContractExpiry,
+        Self::SupplyDelivery,
+        Self::PhoneOwnership,
+        Self::NotifyChange,
+    ];
 
     pub fn name(&self) -> &str {
         match self {
             Self::UpdateTime => UpdateTime::NAME,
             Self::AuditLog => AuditLog::NAME,
+            Self::SettleOrder => SettleOrder::NAME,
+            Self::WarrantyPrice => WarrantyPrice::NAME,
+            Self::ContractExpiry => ContractExpiry::NAME,
+            Self::SupplyDelivery => SupplyDelivery::NAME,
+            Self::PhoneOwnership => PhoneOwnership::NAME,
+            Self::NotifyChange => NotifyChange::NAME,
         }
     }
 
@@ -34,6 +66,20 @@ impl Trigger {
         match self {
             Self::UpdateTime => UpdateTime::CREATE.to_string().replace("$1", table.name()),
             Self::AuditLog => AuditLog::CREATE.to_string().replace("$1", table.name()),
+            Self::SettleOrder => SettleOrder::CREATE.to_string().replace("$1", table.name()),
+            Self::ContractExpiry => ContractExpiry::CREATE
+                .to_string()
+                .replace("$1", table.name()),
+            Self::SupplyDelivery => SupplyDelivery::CREATE
+                .to_string()
+                .replace("$1", table.name()),
+            Self::PhoneOwnership => PhoneOwnership::CREATE
+                .to_string()
+                .replace("$1", table.name()),
+            Self::NotifyChange => NotifyChange::CREATE.to_string().replace("$1", table.name()),
+            Self::WarrantyPrice => WarrantyPrice::CREATE
+                .to_string()
+                .replace("$1", table.name()),
         }
     }
 
@@ -41,6 +87,12 @@ impl Trigger {
         match self {
             Self::UpdateTime => UpdateTime::DROP.to_string().replace("$1", table.name()),
             Self::AuditLog => AuditLog::DROP.to_string().replace("$1", table.name()),
+            Self::SettleOrder => SettleOrder::DROP.to_string().replace("$1", table.name()),
+            Self::ContractExpiry => ContractExpiry::DROP.to_string().replace("$1", table.name()),
+            Self::SupplyDelivery => SupplyDelivery::DROP.to_string().replace("$1", table.name()),
+            Self::PhoneOwnership => PhoneOwnership::DROP.to_string().replace("$1", table.name()),
+            Self::NotifyChange => NotifyChange::DROP.to_string().replace("$1", table.name()),
+            Self::WarrantyPrice => WarrantyPrice::DROP.to_string().replace("$1", table.name()),
         }
     }
 
@@ -48,6 +100,12 @@ impl Trigger {
         match self {
             Self::UpdateTime => &UpdateTime::TABLES,
             Self::AuditLog => &AuditLog::TABLES,
+            Self::SettleOrder => &SettleOrder::TABLES,
+            Self::WarrantyPrice => &WarrantyPrice::TABLES,
+            Self::ContractExpiry => &ContractExpiry::TABLES,
+            Self::SupplyDelivery => &SupplyDelivery::TABLES,
+            Self::PhoneOwnership => &PhoneOwnership::TABLES,
+            Self::NotifyChange => &NotifyChange::TABLES,
         }
     }
 
@@ -55,72 +113,37 @@ impl Trigger {
         let mut result = Vec::with_capacity(self.tables().len());
 
         for table in self.tables() {
-            result.push((
-                *table,
-                match query_as::<Postgres, (bool,)>(
-                    r#"SELECT true FROM pg_catalog.pg_trigger trg JOIN pg_catalog.pg_class cls
-ON trg.tgrelid = cls."oid" WHERE trg.tgname = $1 AND cls.relname = $2;"#,
-                )
-                .bind(self.name())
-                .bind(table.name())
-                .fetch_one(pool)
-                .await
-                {
-                    Ok(_) => Ok(true),
-                    Err(err) => {
-                        if let Error::RowNotFound = err {
-                            Ok(false)
-                        } else {
-                            Err(err)
-                        }
-                    }
-                },
-            ))
+            result.push((*table, self.exists_on(*table, pool).await))
         }
 
         result
     }
 
-    /// Create all procedures necessary for application
-    pub async fn create_all(
-        pool: &PgPool,
-        handler: impl Fn((Trigger, Table, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for trigger in Self::ALL {
-            println!("> Creating '{trigger}' trigger");
-            for table in trigger.tables() {
-                handler((
-                    trigger,
-                    *table,
-                    query(&trigger.create(*table)).execute(pool).await,
-                ))
-                .with_context(|| {
-                    format!("While creating '{trigger}' trigger for '{table}' table")
-                })?;
+    /// Checks trigger existence for the single table
+    pub async fn exists_on<'e>(
+        &self,
+        table: Table,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
+        match query_as::<Postgres, (bool,)>(
+            r#"SELECT true FROM pg_catalog.pg_trigger trg JOIN pg_catalog.pg_class cls
+ON trg.tgrelid = cls."oid" WHERE trg.tgname = $1 AND cls.relname = $2
+    AND cls.relnamespace = current_schema()::regnamespace;"#,
+        )
+        .bind(self.name())
+        .bind(table.name())
+        .fetch_one(executor)
+        .await
+        {
+            Ok(_) => Ok(true),
+            Err(err) => {
+                if let Error::RowNotFound = err {
+                    Ok(false)
+                } else {
+                    Err(err)
+                }
             }
         }
-
-        Ok(())
-    }
-
-    /// Drop all application procedures
-    pub async fn drop_all(
-        pool: &PgPool,
-        handler: impl Fn((Trigger, Table, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for trigger in Self::ALL {
-            println!("> Dropping '{trigger}' trigger");
-            for table in trigger.tables() {
-                handler((
-                    trigger,
-                    *table,
-                    query(&trigger.drop(*table)).execute(pool).await,
-                ))
-                .with_context(|| format!("While dropping trigger for '{table}' table"))?;
-            }
-        }
-
-        Ok(())
     }
 }
 
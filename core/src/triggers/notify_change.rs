@@ -0,0 +1,18 @@
+use crate::{
+    tables::Table,
+    traits::{MultipleTables, Recreatable},
+};
+
+pub struct NotifyChange;
+
+impl Recreatable for NotifyChange {
+    const NAME: &'static str = "notify_change";
+    const CREATE: &'static str = r#"CREATE TRIGGER notify_change AFTER
+INSERT OR UPDATE OR DELETE OR TRUNCATE ON "$1" FOR EACH STATEMENT
+EXECUTE FUNCTION notify_change_func();"#;
+    const DROP: &'static str = r#"DROP TRIGGER notify_change ON "$1";"#;
+}
+
+impl MultipleTables<33> for NotifyChange {
+    const TABLES: [Table; 33] = Table::ALL;
+}
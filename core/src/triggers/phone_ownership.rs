@@ -0,0 +1,18 @@
+use crate::{
+    tables::Table,
+    traits::{MultipleTables, Recreatable},
+};
+
+pub struct PhoneOwnership;
+
+impl Recreatable for PhoneOwnership {
+    const NAME: &'static str = "phone_ownership";
+    const CREATE: &'static str = r#"CREATE TRIGGER phone_ownership AFTER
+UPDATE OF person ON "$1" FOR EACH ROW
+WHEN (OLD.person IS DISTINCT FROM NEW.person) EXECUTE FUNCTION phone_ownership_func();"#;
+    const DROP: &'static str = r#"DROP TRIGGER phone_ownership ON "$1";"#;
+}
+
+impl MultipleTables<1> for PhoneOwnership {
+    const TABLES: [Table; 1] = [Table::Phone];
+}
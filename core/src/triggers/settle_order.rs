@@ -0,0 +1,17 @@
+use crate::{
+    tables::Table,
+    traits::{MultipleTables, Recreatable},
+};
+
+pub struct SettleOrder;
+
+impl Recreatable for SettleOrder {
+    const NAME: &'static str = "settle_order";
+    const CREATE: &'static str = r#"CREATE TRIGGER settle_order AFTER
+INSERT ON "$1" FOR EACH ROW EXECUTE FUNCTION settle_order_func();"#;
+    const DROP: &'static str = r#"DROP TRIGGER settle_order ON "$1";"#;
+}
+
+impl MultipleTables<1> for SettleOrder {
+    const TABLES: [Table; 1] = [Table::Payment];
+}
@@ -0,0 +1,17 @@
+use crate::{
+    tables::Table,
+    traits::{MultipleTables, Recreatable},
+};
+
+pub struct SupplyDelivery;
+
+impl Recreatable for SupplyDelivery {
+    const NAME: &'static str = "supply_delivery";
+    const CREATE: &'static str = r#"CREATE TRIGGER supply_delivery BEFORE
+INSERT OR UPDATE ON "$1" FOR EACH ROW EXECUTE FUNCTION supply_delivery_func();"#;
+    const DROP: &'static str = r#"DROP TRIGGER supply_delivery ON "$1";"#;
+}
+
+impl MultipleTables<1> for SupplyDelivery {
+    const TABLES: [Table; 1] = [Table::Supply];
+}
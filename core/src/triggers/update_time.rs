@@ -7,25 +7,37 @@ pub struct UpdateTime;
 
 impl Recreatable for UpdateTime {
     const NAME: &'static str = "update_time";
-    const CREATE: &'static str = r#"CREATE TRIGGER update_time AFTER
-UPDATE ON "$1" FOR EACH ROW
-    WHEN (pg_trigger_depth() = 0) EXECUTE FUNCTION update_time_func();"#;
+    const CREATE: &'static str = r#"CREATE TRIGGER update_time BEFORE
+UPDATE ON "$1" FOR EACH ROW EXECUTE FUNCTION update_time_func();"#;
     const DROP: &'static str = r#"DROP TRIGGER update_time ON "$1";"#;
 }
 
-impl MultipleTables<12> for UpdateTime {
-    const TABLES: [Table; 12] = [
+impl MultipleTables<25> for UpdateTime {
+    const TABLES: [Table; 25] = [
         Table::Person,
         Table::Supplier,
         Table::Position,
         Table::Service,
+        Table::Branch,
+        Table::Manufacturer,
+        Table::Staff,
+        Table::PhoneModel,
+        Table::Component,
+        Table::ExchangeRate,
         Table::LaborContract,
         Table::Phone,
         Table::SupplyContract,
         Table::Supply,
+        Table::PersonMerge,
         Table::Account,
         Table::Warehouse,
         Table::Order,
+        Table::StockTransfer,
+        Table::Invoice,
+        Table::Payment,
+        Table::PriceChange,
         Table::ServicePhoneModel,
+        Table::SupplyContractItem,
+        Table::SupplyItem,
     ];
 }
@@ -0,0 +1,17 @@
+use crate::{
+    tables::Table,
+    traits::{MultipleTables, Recreatable},
+};
+
+pub struct WarrantyPrice;
+
+impl Recreatable for WarrantyPrice {
+    const NAME: &'static str = "warranty_price";
+    const CREATE: &'static str = r#"CREATE TRIGGER warranty_price BEFORE
+INSERT OR UPDATE ON "$1" FOR EACH ROW EXECUTE FUNCTION warranty_price_func();"#;
+    const DROP: &'static str = r#"DROP TRIGGER warranty_price ON "$1";"#;
+}
+
+impl MultipleTables<2> for WarrantyPrice {
+    const TABLES: [Table; 2] = [Table::OrderService, Table::OrderWarehouse];
+}
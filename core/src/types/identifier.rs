@@ -0,0 +1,506 @@
+use core::{fmt, str::FromStr};
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+    types::Type,
+    Decode, Encode, Postgres,
+};
+
+use crate::traits::Recreatable;
+
+/// ISO 3166-1 alpha-2 country code
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Country([u8; 2]);
+
+impl Country {
+    /// Officially assigned codes
+    ///
+    /// Must match `country_code` SQL domain
+    pub const CODES: [&'static str; 249] = [
+        "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX",
+        "AZ", "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ",
+        "BR", "BS", "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK",
+        "CL", "CM", "CN", "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM",
+        "DO", "DZ", "EC", "EE", "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR",
+        "GA", "GB", "GD", "GE", "GF", "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS",
+        "GT", "GU", "GW", "GY", "HK", "HM", "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN",
+        "IO", "IQ", "IR", "IS", "IT", "JE", "JM", "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN",
+        "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC", "LI", "LK", "LR", "LS", "LT", "LU", "LV",
+        "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK", "ML", "MM", "MN", "MO", "MP", "MQ",
+        "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA", "NC", "NE", "NF", "NG", "NI",
+        "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG", "PH", "PK", "PL", "PM",
+        "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW", "SA", "SB", "SC",
+        "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS", "ST", "SV",
+        "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO", "TR",
+        "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+        "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+    ];
+
+    pub fn as_str(&self) -> &str {
+        // Only codes from `CODES` get through `FromStr`
+        core::str::from_utf8(&self.0).expect("country code is ascii")
+    }
+}
+
+impl FromStr for Country {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            &[a, b] if Self::CODES.binary_search(&s).is_ok() => Ok(Self([a, b])),
+            _ => Err(IdentifierError::Country(s.to_string())),
+        }
+    }
+}
+
+impl Recreatable for Country {
+    const NAME: &'static str = "country_code";
+
+    const CREATE: &'static str = "CREATE DOMAIN country_code AS char(2) CHECK (VALUE::text = ANY (string_to_array(
+    'AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG BH BI BJ BL BM BN BO BQ BR BS BT BV BW BY BZ '
+    'CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW CX CY CZ DE DJ DK DM DO DZ EC EE EG EH ER ES ET FI FJ FK FM FO FR '
+    'GA GB GD GE GF GG GH GI GL GM GN GP GQ GR GS GT GU GW GY HK HM HN HR HT HU ID IE IL IM IN IO IQ IR IS IT JE JM JO JP '
+    'KE KG KH KI KM KN KP KR KW KY KZ LA LB LC LI LK LR LS LT LU LV LY MA MC MD ME MF MG MH MK ML MM MN MO MP MQ MR MS MT '
+    'MU MV MW MX MY MZ NA NC NE NF NG NI NL NO NP NR NU NZ OM PA PE PF PG PH PK PL PM PN PR PS PT PW PY QA RE RO RS RU RW '
+    'SA SB SC SD SE SG SH SI SJ SK SL SM SN SO SR SS ST SV SX SY SZ TC TD TF TG TH TJ TK TL TM TN TO TR TT TV TW TZ '
+    'UA UG UM US UY UZ VA VC VE VG VI VN VU WF WS YE YT ZA ZM ZW',
+    ' '
+)));";
+
+    const DROP: &'static str = "DROP DOMAIN country_code;";
+}
+
+/// International bank account number, kept in the electronic format (no spaces)
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct Iban(String);
+
+impl Iban {
+    /// Builds the number from the country and the domestic account number (BBAN),
+    /// computing the check digits
+    pub fn from_bban(country: Country, bban: &str) -> Result<Self, IdentifierError> {
+        let bban = bban.to_ascii_uppercase();
+        let remainder = mod_97(&format!("{bban}{country}00"))
+            .ok_or_else(|| IdentifierError::Iban(bban.clone()))?;
+
+        format!("{country}{:02}{bban}", 98 - remainder).parse()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Country code, not necessarily an ISO 3166 one (e.g. `XK`)
+    pub fn country(&self) -> &str {
+        &self.0[..2]
+    }
+
+    pub fn bban(&self) -> &str {
+        &self.0[4..]
+    }
+}
+
+impl FromStr for Iban {
+    type Err = IdentifierError;
+
+    /// Accepts the print format as well: lowercase letters and groups separated by spaces
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let iban: String = s
+            .chars()
+            .filter(|c| *c != ' ')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        let bytes = iban.as_bytes();
+
+        let valid = (15..=34).contains(&bytes.len())
+            && bytes[..2].iter().all(u8::is_ascii_uppercase)
+            && bytes[2..4].iter().all(u8::is_ascii_digit)
+            && bytes[4..]
+                .iter()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+            && mod_97(&format!("{}{}", &iban[4..], &iban[..4])) == Some(1);
+
+        if valid {
+            Ok(Self(iban))
+        } else {
+            Err(IdentifierError::Iban(s.to_string()))
+        }
+    }
+}
+
+impl Recreatable for Iban {
+    const NAME: &'static str = "iban";
+
+    /// Letters become numbers from 10 (`A`) to 35 (`Z`), the number of the rearranged
+    /// code must give 1 modulo 97 (ISO 13616)
+    const CREATE: &'static str = r#"CREATE DOMAIN iban AS text CHECK (CASE
+    WHEN VALUE ~ '^[A-Z]{2}[0-9]{2}[A-Z0-9]{11,30}$' THEN replace(replace(replace(replace(replace(replace(replace(
+        replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(
+        replace(replace(replace(replace(replace(replace(substr(VALUE, 5) || left(VALUE, 4),
+        'A', '10'), 'B', '11'), 'C', '12'), 'D', '13'), 'E', '14'), 'F', '15'), 'G', '16'), 'H', '17'), 'I', '18'),
+        'J', '19'), 'K', '20'), 'L', '21'), 'M', '22'), 'N', '23'), 'O', '24'), 'P', '25'), 'Q', '26'), 'R', '27'),
+        'S', '28'), 'T', '29'), 'U', '30'), 'V', '31'), 'W', '32'), 'X', '33'), 'Y', '34'), 'Z', '35')::numeric % 97 = 1
+    ELSE false
+END);"#;
+
+    const DROP: &'static str = "DROP DOMAIN iban;";
+}
+
+/// Remainder of the number made of digits and letters (`A` is 10, ..., `Z` is 35) divided by 97
+fn mod_97(code: &str) -> Option<u32> {
+    code.chars().try_fold(0, |remainder, c| {
+        let value = c.to_digit(36)?;
+        let shift = if value < 10 { 10 } else { 100 };
+        Some((remainder * shift + value) % 97)
+    })
+}
+
+/// Business identifier code (SWIFT code) of a bank: 8 or 11 characters
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct Bic(String);
+
+impl Bic {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn institution(&self) -> &str {
+        &self.0[..4]
+    }
+
+    /// Country code, not checked against ISO 3166 as codes of gone countries still occur
+    pub fn country(&self) -> &str {
+        &self.0[4..6]
+    }
+
+    /// Branch code, `XXX` for the primary office
+    pub fn branch(&self) -> &str {
+        self.0.get(8..).unwrap_or("XXX")
+    }
+}
+
+impl FromStr for Bic {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bic = s.to_ascii_uppercase();
+        let bytes = bic.as_bytes();
+
+        let valid = matches!(bytes.len(), 8 | 11)
+            && bytes[..6].iter().all(u8::is_ascii_uppercase)
+            && bytes[6..]
+                .iter()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit());
+
+        if valid {
+            Ok(Self(bic))
+        } else {
+            Err(IdentifierError::Bic(s.to_string()))
+        }
+    }
+}
+
+impl Recreatable for Bic {
+    const NAME: &'static str = "bic";
+
+    const CREATE: &'static str =
+        "CREATE DOMAIN bic AS text CHECK (VALUE ~ '^[A-Z]{6}[A-Z0-9]{2}([A-Z0-9]{3})?$');";
+
+    const DROP: &'static str = "DROP DOMAIN bic;";
+}
+
+/// International mobile equipment identity: 14 digits and the Luhn check digit
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Imei([u8; 15]);
+
+impl Imei {
+    /// Appends the check digit to the 14 digits of the type allocation code and serial number
+    pub fn with_check_digit(body: &str) -> Result<Self, IdentifierError> {
+        match body.as_bytes() {
+            bytes if bytes.len() == 14 && bytes.iter().all(u8::is_ascii_digit) => {
+                let check = (10 - luhn_sum(bytes) % 10) % 10;
+                format!("{body}{check}").parse()
+            }
+            _ => Err(IdentifierError::Imei(body.to_string())),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only digits get through `FromStr`
+        core::str::from_utf8(&self.0).expect("imei is ascii")
+    }
+
+    /// Type allocation code, identifies the model of the device
+    pub fn tac(&self) -> &str {
+        &self.as_str()[..8]
+    }
+}
+
+impl FromStr for Imei {
+    type Err = IdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match <[u8; 15]>::try_from(s.as_bytes()) {
+            Ok(bytes)
+                if bytes.iter().all(u8::is_ascii_digit) && luhn_sum(&bytes).is_multiple_of(10) =>
+            {
+                Ok(Self(bytes))
+            }
+            _ => Err(IdentifierError::Imei(s.to_string())),
+        }
+    }
+}
+
+impl Recreatable for Imei {
+    const NAME: &'static str = "imei";
+
+    /// Every second digit is doubled (its digits summed), the sum must be a multiple of 10
+    const CREATE: &'static str = "CREATE DOMAIN imei AS text CHECK (CASE
+    WHEN VALUE ~ '^[0-9]{15}$' THEN (
+        substr(VALUE, 1, 1)::int + translate(substr(VALUE, 2, 1), '0123456789', '0246813579')::int
+        + substr(VALUE, 3, 1)::int + translate(substr(VALUE, 4, 1), '0123456789', '0246813579')::int
+        + substr(VALUE, 5, 1)::int + translate(substr(VALUE, 6, 1), '0123456789', '0246813579')::int
+        + substr(VALUE, 7, 1)::int + translate(substr(VALUE, 8, 1), '0123456789', '0246813579')::int
+        + substr(VALUE, 9, 1)::int + translate(substr(VALUE, 10, 1), '0123456789', '0246813579')::int
+        + substr(VALUE, 11, 1)::int + translate(substr(VALUE, 12, 1), '0123456789', '0246813579')::int
+        + substr(VALUE, 13, 1)::int + translate(substr(VALUE, 14, 1), '0123456789', '0246813579')::int
+        + substr(VALUE, 15, 1)::int
+    ) % 10 = 0
+    ELSE false
+END);";
+
+    const DROP: &'static str = "DROP DOMAIN imei;";
+}
+
+/// Luhn sum of ASCII digits, doubling the second, fourth, ... digit from the left
+///
+/// With the check digit at an odd position this doubles every second digit left of it
+fn luhn_sum(digits: &[u8]) -> u32 {
+    digits
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let digit = u32::from(b - b'0');
+            if i % 2 == 1 {
+                let doubled = digit * 2;
+                doubled / 10 + doubled % 10
+            } else {
+                digit
+            }
+        })
+        .sum()
+}
+
+/// Display, debug, serde and sqlx `text` implementations of the code types
+macro_rules! text_type {
+    ($($name:ident),*) => {$(
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}({})", stringify!($name), self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer)?
+                    .parse()
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+
+        // Domains are sent to clients as their base types
+        impl Type<Postgres> for $name {
+            fn type_info() -> PgTypeInfo {
+                <&str as Type<Postgres>>::type_info()
+            }
+
+            fn compatible(ty: &PgTypeInfo) -> bool {
+                <&str as Type<Postgres>>::compatible(ty)
+            }
+        }
+
+        impl Encode<'_, Postgres> for $name {
+            fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+                <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+            }
+        }
+
+        impl<'r> Decode<'r, Postgres> for $name {
+            fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+                // `char(2)` may come padded
+                Ok(<&str as Decode<Postgres>>::decode(value)?.trim_end().parse()?)
+            }
+        }
+    )*};
+}
+
+text_type!(Country, Iban, Bic, Imei);
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum IdentifierError {
+    /// Not an ISO 3166-1 alpha-2 code
+    Country(String),
+    /// Malformed or wrong check digits
+    Iban(String),
+    /// Malformed
+    Bic(String),
+    /// Not 15 digits or wrong check digit
+    Imei(String),
+}
+
+impl fmt::Display for IdentifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Country(code) => write!(f, "Invalid country code '{code}'"),
+            Self::Iban(iban) => write!(f, "Invalid IBAN '{iban}'"),
+            Self::Bic(bic) => write!(f, "Invalid BIC '{bic}'"),
+            Self::Imei(imei) => write!(f, "Invalid IMEI '{imei}'"),
+        }
+    }
+}
+
+impl Error for IdentifierError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn country(code: &str) -> Country {
+        code.parse().unwrap()
+    }
+
+    #[test]
+    fn mod_97_of_digits_and_letters() {
+        assert_eq!(mod_97("97"), Some(0));
+        assert_eq!(mod_97("98"), Some(1));
+        assert_eq!(mod_97("A"), Some(10));
+        assert_eq!(mod_97("Z"), Some(35));
+        assert_eq!(mod_97("WEST12345698765432GB82"), Some(1));
+        assert_eq!(mod_97("WEST12345698765432GB00"), Some(16));
+        assert_eq!(mod_97("12-34"), None);
+    }
+
+    #[test]
+    fn iban_from_str() {
+        for iban in [
+            "GB82WEST12345698765432",
+            "DE89370400440532013000",
+            "FR1420041010050500013M02606",
+            "NL91ABNA0417164300",
+            "BE68539007547034",
+        ] {
+            assert_eq!(iban.parse::<Iban>().unwrap().as_str(), iban);
+        }
+        assert_eq!(
+            "gb82 west 1234 5698 7654 32".parse::<Iban>().unwrap(),
+            "GB82WEST12345698765432".parse().unwrap()
+        );
+
+        for iban in [
+            // Wrong check digits
+            "GB82WEST12345698765433",
+            "GB28WEST12345698765432",
+            "DE00370400440532013000",
+            // Malformed
+            "GB82",
+            "GB82WEST1234",
+            "8282WEST12345698765432",
+            "GBXXWEST12345698765432",
+            "GB82WEST-12345698765432",
+            "",
+        ] {
+            assert_eq!(
+                iban.parse::<Iban>(),
+                Err(IdentifierError::Iban(iban.to_string())),
+                "{iban}"
+            );
+        }
+    }
+
+    #[test]
+    fn iban_from_bban() {
+        let iban = Iban::from_bban(country("GB"), "west12345698765432").unwrap();
+        assert_eq!(iban.as_str(), "GB82WEST12345698765432");
+        assert_eq!(iban.country(), "GB");
+        assert_eq!(iban.bban(), "WEST12345698765432");
+
+        assert_eq!(
+            Iban::from_bban(country("DE"), "370400440532013000")
+                .unwrap()
+                .as_str(),
+            "DE89370400440532013000"
+        );
+        assert!(Iban::from_bban(country("DE"), "3704-0044").is_err());
+        assert!(Iban::from_bban(country("DE"), "1234").is_err());
+    }
+
+    #[test]
+    fn luhn_sum_doubles_every_second_digit() {
+        assert_eq!(luhn_sum(b"05"), 1);
+        assert_eq!(luhn_sum(b"09"), 9);
+        assert_eq!(luhn_sum(b"49015420323751"), 52);
+        assert_eq!(luhn_sum(b"490154203237518"), 60);
+    }
+
+    #[test]
+    fn imei_from_str() {
+        for imei in ["490154203237518", "352099001761481"] {
+            let parsed = imei.parse::<Imei>().unwrap();
+            assert_eq!(parsed.as_str(), imei);
+        }
+        assert_eq!("490154203237518".parse::<Imei>().unwrap().tac(), "49015420");
+
+        for imei in [
+            // Wrong check digit
+            "490154203237517",
+            "352099001761480",
+            // Malformed
+            "49015420323751",
+            "4901542032375180",
+            "35-209900-176148-1",
+            "49015420323751a",
+            "",
+        ] {
+            assert_eq!(
+                imei.parse::<Imei>(),
+                Err(IdentifierError::Imei(imei.to_string())),
+                "{imei}"
+            );
+        }
+    }
+
+    #[test]
+    fn imei_with_check_digit() {
+        assert_eq!(
+            Imei::with_check_digit("49015420323751").unwrap().as_str(),
+            "490154203237518"
+        );
+        assert_eq!(
+            Imei::with_check_digit("35209900176148").unwrap().as_str(),
+            "352099001761481"
+        );
+
+        for body in ["4901542032375", "490154203237518", "4901542032375a", ""] {
+            assert_eq!(
+                Imei::with_check_digit(body),
+                Err(IdentifierError::Imei(body.to_string())),
+                "{body}"
+            );
+        }
+    }
+}
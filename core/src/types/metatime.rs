@@ -1,10 +1,14 @@
-use chrono::{DateTime, Utc};
+use core::fmt;
+use std::error::Error;
+
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::types::Type;
+use sqlx::{postgres::PgDatabaseError, types::Type};
 
-use crate::traits::Recreatable;
+use crate::{tables::Table, traits::Recreatable};
 
 #[derive(Type, Serialize, Deserialize, Clone, Debug)]
+#[sqlx(type_name = "metatime")]
 pub struct MetaTime {
     pub updated: DateTime<Utc>,
     pub created: DateTime<Utc>,
@@ -14,6 +18,14 @@ impl MetaTime {
     pub fn now() -> DateTime<Utc> {
         Utc::now()
     }
+
+    /// Meta for the row created (and last updated) at the given time
+    pub fn at(created: DateTime<Utc>) -> Self {
+        Self {
+            updated: created,
+            created,
+        }
+    }
 }
 
 impl Recreatable for MetaTime {
@@ -34,3 +46,53 @@ impl Default for MetaTime {
         }
     }
 }
+
+/// Versioned update failed as the row was changed by someone else since it was read
+///
+/// Raised by `update_time_func` when `updated` set by the update differs from the row's one
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    pub table: Table,
+    /// Version of the row the update ran into
+    pub updated: DateTime<Utc>,
+}
+
+impl Conflict {
+    /// `SQLSTATE` of the conflict
+    pub const CODE: &'static str = "CW409";
+
+    /// Extracts the conflict from the database error, if it's one
+    pub fn from_error(err: &sqlx::Error) -> Option<Self> {
+        let err = err
+            .as_database_error()?
+            .try_downcast_ref::<PgDatabaseError>()?;
+        if err.code() != Self::CODE {
+            return None;
+        }
+
+        Some(Self {
+            table: err.table()?.parse().ok()?,
+            updated: Utc.timestamp_nanos(err.detail()?.parse::<i64>().ok()? * 1000),
+        })
+    }
+
+    /// Turns conflicts into [`Conflict`] errors, other errors are kept as is
+    pub fn map(err: sqlx::Error) -> anyhow::Error {
+        match Self::from_error(&err) {
+            Some(conflict) => conflict.into(),
+            None => err.into(),
+        }
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Row of '{}' table was changed by someone else at {}, reload it and try again",
+            self.table, self.updated
+        )
+    }
+}
+
+impl Error for Conflict {}
@@ -1,25 +1,38 @@
 use core::fmt;
 
-use anyhow::{Context, Result};
-use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool, Postgres};
+use sqlx::{query_as, Error, Executor, Postgres};
 
 pub mod account_role;
 pub mod account_status;
 pub mod color;
 pub mod contract_status;
+pub mod identifier;
 pub mod metatime;
+pub mod money;
 pub mod order_status;
+pub mod payment_kind;
+pub mod payment_method;
+pub mod price_change_status;
+pub mod price_scope;
 pub mod staff_status;
 pub mod supply_status;
+pub mod transfer_status;
 
 pub use account_role::AccountRole;
 pub use account_status::AccountStatus;
 pub use color::Color;
 pub use contract_status::ContractStatus;
-pub use metatime::MetaTime;
+pub use identifier::{Bic, Country, Iban, IdentifierError, Imei};
+pub use metatime::{Conflict, MetaTime};
+pub use money::{Currency, Money, MoneyError};
 pub use order_status::OrderStatus;
+pub use payment_kind::PaymentKind;
+pub use payment_method::PaymentMethod;
+pub use price_change_status::PriceChangeStatus;
+pub use price_scope::PriceScope;
 pub use staff_status::StaffStatus;
 pub use supply_status::SupplyStatus;
+pub use transfer_status::TransferStatus;
 
 use crate::traits::Recreatable;
 
@@ -27,45 +40,78 @@ use crate::traits::Recreatable;
 pub enum DbType {
     AccountRole,
     AccountStatus,
+    Bic,
     Color,
     ContractStatus,
+    Country,
+    Iban,
+    Imei,
     MetaTime,
+    Money,
     OrderStatus,
+    PaymentKind,
+    PaymentMethod,
+    PriceChangeStatus,
+    PriceScope,
     StaffStatus,
     SupplyStatus,
+    TransferStatus,
 }
 
 impl DbType {
-    pub const ALL: [Self; 8] = [
+    pub const ALL: [Self; 18] = [
         Self::AccountRole,
         Self::AccountStatus,
+        Self::Bic,
         Self::Color,
         Self::ContractStatus,
+        Self::Country,
+        Self::Iban,
+        Self::Imei,
         Self::MetaTime,
+        Self::Money,
         Self::OrderStatus,
+        Self::PaymentKind,
+        Self::PaymentMethod,
+        Self::PriceChangeStatus,
+        Self::PriceScope,
         Self::StaffStatus,
         Self::SupplyStatus,
+        Self::TransferStatus,
     ];
 
     pub fn name(&self) -> &str {
         match self {
             Self::AccountRole => AccountRole::NAME,
             Self::AccountStatus => AccountStatus::NAME,
+            Self::Bic => Bic::NAME,
             Self::Color => Color::NAME,
             Self::ContractStatus => ContractStatus::NAME,
+            Self::Country => Country::NAME,
+            Self::Iban => Iban::NAME,
+            Self::Imei => Imei::NAME,
             Self::MetaTime => MetaTime::NAME,
+            Self::Money => Money::NAME,
             Self::OrderStatus => OrderStatus::NAME,
+            Self::PaymentKind => PaymentKind::NAME,
+            Self::PaymentMethod => PaymentMethod::NAME,
+            Self::PriceChangeStatus => PriceChangeStatus::NAME,
+            Self::PriceScope => PriceScope::NAME,
             Self::StaffStatus => StaffStatus::NAME,
             Self::SupplyStatus => SupplyStatus::NAME,
+            Self::TransferStatus => TransferStatus::NAME,
         }
     }
 
-    pub async fn exists(&self, pool: &PgPool) -> Result<bool, Error> {
+    pub async fn exists<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
         match query_as::<Postgres, (bool,)>(
-            "SELECT true FROM pg_catalog.pg_type WHERE typname = $1",
+            "SELECT true FROM pg_catalog.pg_type WHERE typname = $1 AND typnamespace = current_schema()::regnamespace",
         )
         .bind(self.name())
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
         {
             Ok(_) => Ok(true),
@@ -83,12 +129,22 @@ impl DbType {
         match self {
             Self::AccountRole => AccountRole::CREATE,
             Self::AccountStatus => AccountStatus::CREATE,
+            Self::Bic => Bic::CREATE,
             Self::Color => Color::CREATE,
             Self::ContractStatus => ContractStatus::CREATE,
+            Self::Country => Country::CREATE,
+            Self::Iban => Iban::CREATE,
+            Self::Imei => Imei::CREATE,
             Self::MetaTime => MetaTime::CREATE,
+            Self::Money => Money::CREATE,
             Self::OrderStatus => OrderStatus::CREATE,
+            Self::PaymentKind => PaymentKind::CREATE,
+            Self::PaymentMethod => PaymentMethod::CREATE,
+            Self::PriceChangeStatus => PriceChangeStatus::CREATE,
+            Self::PriceScope => PriceScope::CREATE,
             Self::StaffStatus => StaffStatus::CREATE,
             Self::SupplyStatus => SupplyStatus::CREATE,
+            Self::TransferStatus => TransferStatus::CREATE,
         }
     }
 
@@ -96,40 +152,24 @@ impl DbType {
         match self {
             Self::AccountRole => AccountRole::DROP,
             Self::AccountStatus => AccountStatus::DROP,
+            Self::Bic => Bic::DROP,
             Self::Color => Color::DROP,
             Self::ContractStatus => ContractStatus::DROP,
+            Self::Country => Country::DROP,
+            Self::Iban => Iban::DROP,
+            Self::Imei => Imei::DROP,
             Self::MetaTime => MetaTime::DROP,
+            Self::Money => Money::DROP,
             Self::OrderStatus => OrderStatus::DROP,
+            Self::PaymentKind => PaymentKind::DROP,
+            Self::PaymentMethod => PaymentMethod::DROP,
+            Self::PriceChangeStatus => PriceChangeStatus::DROP,
+            Self::PriceScope => PriceScope::DROP,
             Self::StaffStatus => StaffStatus::DROP,
             Self::SupplyStatus => SupplyStatus::DROP,
+            Self::TransferStatus => TransferStatus::DROP,
         }
     }
-
-    /// Create all types necessary for application
-    pub async fn create_all(
-        pool: &PgPool,
-        handler: impl Fn((DbType, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for db_type in Self::ALL {
-            handler((db_type, query(db_type.create()).execute(pool).await))
-                .with_context(|| format!("While creating '{db_type}' type"))?;
-        }
-
-        Ok(())
-    }
-
-    /// Drop all types necessary for application
-    pub async fn drop_all(
-        pool: &PgPool,
-        handler: impl Fn((DbType, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for db_type in Self::ALL {
-            handler((db_type, query(db_type.drop()).execute(pool).await))
-                .with_context(|| format!("While dropping '{db_type}' type"))?;
-        }
-
-        Ok(())
-    }
 }
 
 impl fmt::Display for DbType {
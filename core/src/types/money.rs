@@ -0,0 +1,437 @@
+use core::{fmt, str::FromStr};
+use std::error::Error;
+
+use bigdecimal::{BigDecimal, Signed, ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+    types::Type,
+    Decode, Encode, Postgres,
+};
+
+use crate::traits::Recreatable;
+
+/// ISO 4217 currency code
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    /// Currency of the shop, every price without explicit currency is in it
+    ///
+    /// Must match `base_currency()` SQL function
+    pub const BASE: Self = Self(*b"RUB");
+
+    pub fn as_str(&self) -> &str {
+        // Only ASCII letters get through `FromStr`
+        core::str::from_utf8(&self.0).expect("currency code is ascii")
+    }
+
+    /// Sign put after the amount
+    pub fn symbol(&self) -> &str {
+        match &self.0 {
+            b"RUB" => "₽",
+            b"USD" => "$",
+            b"EUR" => "€",
+            b"CNY" => "¥",
+            _ => self.as_str(),
+        }
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self::BASE
+    }
+}
+
+impl FromStr for Currency {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            &[a, b, c] if [a, b, c].iter().all(u8::is_ascii_uppercase) => Ok(Self([a, b, c])),
+            _ => Err(MoneyError::Currency(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Currency({})", self.as_str())
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl Type<Postgres> for Currency {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for Currency {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Currency {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as Decode<Postgres>>::decode(value)?.parse()?)
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum MoneyError {
+    /// Not a three letter uppercase code
+    Currency(String),
+    /// Operands are in different currencies
+    CurrencyMismatch(Currency, Currency),
+    /// Amount doesn't fit into `numeric(14, 2)`
+    Overflow(BigDecimal),
+    /// Amount is not a number
+    Amount(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Currency(code) => write!(f, "Invalid currency code '{code}'"),
+            Self::CurrencyMismatch(a, b) => write!(f, "Currency mismatch: {a} and {b}"),
+            Self::Overflow(amount) => write!(f, "Amount {amount} is out of range"),
+            Self::Amount(amount) => write!(f, "Invalid amount '{amount}'"),
+        }
+    }
+}
+
+impl Error for MoneyError {}
+
+/// Amount of money in the given currency, stored as `monetary` composite
+/// with `numeric(14, 2)` amount
+///
+/// Amounts are always rounded to cents, half away from zero (as `round(numeric, 2)` does),
+/// arithmetic fails instead of mixing currencies or going out of range
+#[derive(Type, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[sqlx(type_name = "monetary")]
+#[serde(try_from = "RawMoney")]
+pub struct Money {
+    amount: BigDecimal,
+    currency: Currency,
+}
+
+impl Money {
+    /// Digits after the decimal point
+    pub const SCALE: i64 = 2;
+    /// Digits before the decimal point
+    pub const INTEGER_DIGITS: u64 = 12;
+
+    /// Rounds the amount to cents
+    pub fn new(amount: BigDecimal, currency: Currency) -> Result<Self, MoneyError> {
+        let amount = round(&amount);
+
+        if amount.abs().with_scale(0).digits() > Self::INTEGER_DIGITS {
+            Err(MoneyError::Overflow(amount))
+        } else {
+            Ok(Self { amount, currency })
+        }
+    }
+
+    /// Amount in the [`Currency::BASE`]
+    pub fn base(amount: BigDecimal) -> Result<Self, MoneyError> {
+        Self::new(amount, Currency::BASE)
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self {
+            amount: BigDecimal::zero().with_scale(Self::SCALE),
+            currency,
+        }
+    }
+
+    /// Amount in minor units (e.g. kopecks)
+    pub fn from_minor(minor: i64, currency: Currency) -> Result<Self, MoneyError> {
+        Self::new(BigDecimal::new(minor.into(), Self::SCALE), currency)
+    }
+
+    /// Amount in minor units, always fits as amounts are limited to 14 digits
+    pub fn to_minor(&self) -> i64 {
+        (&self.amount * BigDecimal::from(100))
+            .to_i64()
+            .expect("amount fits into i64")
+    }
+
+    pub fn amount(&self) -> &BigDecimal {
+        &self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.amount.is_negative()
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Result<Self, MoneyError> {
+        self.same_currency(other)?;
+        Self::new(&self.amount + &other.amount, self.currency)
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, MoneyError> {
+        self.same_currency(other)?;
+        Self::new(&self.amount - &other.amount, self.currency)
+    }
+
+    /// Multiplies by quantity or coefficient, result is rounded to cents
+    pub fn checked_mul(&self, factor: &BigDecimal) -> Result<Self, MoneyError> {
+        Self::new(&self.amount * factor, self.currency)
+    }
+
+    /// Sum of the amounts, zero in `currency` if there are none
+    pub fn checked_sum<'a>(
+        items: impl IntoIterator<Item = &'a Self>,
+        currency: Currency,
+    ) -> Result<Self, MoneyError> {
+        items
+            .into_iter()
+            .try_fold(Self::zero(currency), |sum, item| sum.checked_add(item))
+    }
+
+    fn same_currency(&self, other: &Self) -> Result<(), MoneyError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(MoneyError::CurrencyMismatch(self.currency, other.currency))
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let amount = self.amount.with_scale(Self::SCALE);
+        let symbol = self.currency.symbol();
+
+        // Codes of currencies without a sign are separated
+        if symbol == self.currency.as_str() {
+            write!(f, "{amount} {symbol}")
+        } else {
+            write!(f, "{amount}{symbol}")
+        }
+    }
+}
+
+impl FromStr for Money {
+    type Err = MoneyError;
+
+    /// Parses amount with optional currency code (e.g. `12.50` or `12.50 USD`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let amount = parts.next().unwrap_or_default();
+        let currency = match parts.next() {
+            Some(code) => code.parse()?,
+            None => Currency::BASE,
+        };
+
+        if parts.next().is_some() {
+            return Err(MoneyError::Amount(s.to_string()));
+        }
+
+        Self::new(
+            amount
+                .parse()
+                .map_err(|_| MoneyError::Amount(amount.to_string()))?,
+            currency,
+        )
+    }
+}
+
+/// Serialized form of [`Money`], amount is kept as string to preserve precision
+#[derive(Deserialize)]
+struct RawMoney {
+    amount: BigDecimal,
+    currency: Currency,
+}
+
+impl TryFrom<RawMoney> for Money {
+    type Error = MoneyError;
+
+    fn try_from(raw: RawMoney) -> Result<Self, Self::Error> {
+        Self::new(raw.amount, raw.currency)
+    }
+}
+
+impl Recreatable for Money {
+    const NAME: &'static str = "monetary";
+
+    const CREATE: &'static str = "CREATE TYPE monetary AS (amount numeric(14, 2), currency text);";
+
+    const DROP: &'static str = r#"DROP TYPE monetary;"#;
+}
+
+/// Rounds to [`Money::SCALE`] digits, half away from zero
+fn round(value: &BigDecimal) -> BigDecimal {
+    // Decreasing the scale truncates towards zero
+    let truncated = value.with_scale(Money::SCALE);
+    let rest = value - &truncated;
+    let half = BigDecimal::new(5.into(), Money::SCALE + 1);
+
+    if rest.abs() < half {
+        truncated
+    } else {
+        let cent = BigDecimal::new(1.into(), Money::SCALE);
+        if value.is_negative() {
+            truncated - cent
+        } else {
+            truncated + cent
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(s: &str) -> BigDecimal {
+        s.parse().unwrap()
+    }
+
+    fn usd() -> Currency {
+        "USD".parse().unwrap()
+    }
+
+    #[test]
+    fn round_half_away_from_zero() {
+        for (value, rounded) in [
+            ("1.005", "1.01"),
+            ("1.0049", "1.00"),
+            ("0.125", "0.13"),
+            ("2.675", "2.68"),
+            ("-1.005", "-1.01"),
+            ("-1.0049", "-1.00"),
+            ("-0.005", "-0.01"),
+            ("-0.0049", "0.00"),
+            ("-2.675", "-2.68"),
+            ("7", "7.00"),
+        ] {
+            assert_eq!(round(&decimal(value)), decimal(rounded), "{value}");
+        }
+    }
+
+    #[test]
+    fn new_rounds_to_cents() {
+        let money = Money::new(decimal("-10.125"), usd()).unwrap();
+
+        assert_eq!(money.amount(), &decimal("-10.13"));
+        assert_eq!(money.to_minor(), -1013);
+        assert_eq!(money.currency(), usd());
+    }
+
+    #[test]
+    fn new_overflows_past_integer_digits() {
+        for value in ["999999999999.99", "-999999999999.99", "999999999999.994"] {
+            assert!(Money::new(decimal(value), usd()).is_ok(), "{value}");
+        }
+        // Rounding itself may carry into the 13th digit
+        for value in [
+            "1000000000000",
+            "-1000000000000",
+            "999999999999.995",
+            "-999999999999.995",
+        ] {
+            assert!(
+                matches!(
+                    Money::new(decimal(value), usd()),
+                    Err(MoneyError::Overflow(_))
+                ),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let a = Money::new(decimal("0.10"), usd()).unwrap();
+        let b = Money::new(decimal("0.20"), usd()).unwrap();
+
+        assert_eq!(a.checked_add(&b).unwrap().amount(), &decimal("0.30"));
+        assert_eq!(a.checked_sub(&b).unwrap().amount(), &decimal("-0.10"));
+        assert_eq!(
+            a.checked_mul(&decimal("0.05")).unwrap().amount(),
+            &decimal("0.01")
+        );
+        assert_eq!(
+            a.checked_add(&Money::zero(Currency::BASE)),
+            Err(MoneyError::CurrencyMismatch(usd(), Currency::BASE))
+        );
+        assert!(matches!(
+            Money::new(decimal("999999999999.99"), usd())
+                .unwrap()
+                .checked_add(&a),
+            Err(MoneyError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "12.50".parse::<Money>().unwrap(),
+            Money::base(decimal("12.5")).unwrap()
+        );
+        assert_eq!(
+            " -0.005   USD ".parse::<Money>().unwrap(),
+            Money::new(decimal("-0.01"), usd()).unwrap()
+        );
+        assert_eq!(
+            "12.50 usd".parse::<Money>(),
+            Err(MoneyError::Currency("usd".to_string()))
+        );
+        assert_eq!(
+            "12,50".parse::<Money>(),
+            Err(MoneyError::Amount("12,50".to_string()))
+        );
+        assert_eq!("".parse::<Money>(), Err(MoneyError::Amount(String::new())));
+        assert_eq!(
+            "1 USD EUR".parse::<Money>(),
+            Err(MoneyError::Amount("1 USD EUR".to_string()))
+        );
+        assert!(matches!(
+            "1000000000000".parse::<Money>(),
+            Err(MoneyError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Money::base(decimal("5")).unwrap().to_string(), "5.00₽");
+        assert_eq!(
+            Money::new(decimal("-5.5"), "CHF".parse().unwrap())
+                .unwrap()
+                .to_string(),
+            "-5.50 CHF"
+        );
+    }
+}
@@ -20,6 +20,16 @@ impl OrderStatus {
         Self::Complete,
         Self::Rejected,
     ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Processing => "Processing",
+            Self::PendingPayment => "Pending Payment",
+            Self::Active => "Active",
+            Self::Complete => "Complete",
+            Self::Rejected => "Rejected",
+        }
+    }
 }
 
 impl Recreatable for OrderStatus {
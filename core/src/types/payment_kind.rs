@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::traits::Recreatable;
+
+/// Direction of the money flow, refunds are stored with positive amount as well
+#[derive(Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy, Debug)]
+#[sqlx(type_name = "PaymentKind", rename_all = "PascalCase")]
+pub enum PaymentKind {
+    Payment,
+    Refund,
+}
+
+impl PaymentKind {
+    pub const ALL: [Self; 2] = [Self::Payment, Self::Refund];
+}
+
+impl Recreatable for PaymentKind {
+    const NAME: &'static str = "PaymentKind";
+
+    const CREATE: &'static str = r#"CREATE TYPE "PaymentKind" AS ENUM (
+    'Payment',
+    'Refund'
+);"#;
+
+    const DROP: &'static str = r#"DROP TYPE "PaymentKind";"#;
+}
@@ -0,0 +1,51 @@
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::traits::Recreatable;
+
+#[derive(Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy, Debug)]
+#[sqlx(type_name = "PaymentMethod", rename_all = "PascalCase")]
+pub enum PaymentMethod {
+    Cash,
+    Card,
+    BankTransfer,
+}
+
+impl PaymentMethod {
+    pub const ALL: [Self; 3] = [Self::Cash, Self::Card, Self::BankTransfer];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Cash => "Cash",
+            Self::Card => "Card",
+            Self::BankTransfer => "Bank Transfer",
+        }
+    }
+}
+
+impl FromStr for PaymentMethod {
+    type Err = String;
+
+    /// Accepts the database names (e.g. `BankTransfer`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Cash" => Ok(Self::Cash),
+            "Card" => Ok(Self::Card),
+            "BankTransfer" => Ok(Self::BankTransfer),
+            _ => Err(format!("Unknown payment method '{s}'")),
+        }
+    }
+}
+
+impl Recreatable for PaymentMethod {
+    const NAME: &'static str = "PaymentMethod";
+
+    const CREATE: &'static str = r#"CREATE TYPE "PaymentMethod" AS ENUM (
+    'Cash',
+    'Card',
+    'BankTransfer'
+);"#;
+
+    const DROP: &'static str = r#"DROP TYPE "PaymentMethod";"#;
+}
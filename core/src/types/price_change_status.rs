@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::traits::Recreatable;
+
+#[derive(Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy, Debug)]
+#[sqlx(type_name = "PriceChangeStatus", rename_all = "PascalCase")]
+pub enum PriceChangeStatus {
+    Applied,
+    RolledBack,
+}
+
+impl PriceChangeStatus {
+    pub const ALL: [Self; 2] = [Self::Applied, Self::RolledBack];
+}
+
+impl Recreatable for PriceChangeStatus {
+    const NAME: &'static str = "PriceChangeStatus";
+
+    const CREATE: &'static str = r#"CREATE TYPE "PriceChangeStatus" AS ENUM (
+    'Applied',
+    'RolledBack'
+);"#;
+
+    const DROP: &'static str = r#"DROP TYPE "PriceChangeStatus";"#;
+}
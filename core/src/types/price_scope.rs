@@ -0,0 +1,72 @@
+use core::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::traits::Recreatable;
+
+/// Subset of prices affected by indexation, every scope except `All` needs a target uuid
+#[derive(Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy, Debug)]
+#[sqlx(type_name = "PriceScope", rename_all = "PascalCase")]
+pub enum PriceScope {
+    /// Every warehouse item and service price
+    All,
+    /// Components of the manufacturer and services for its phone models
+    Manufacturer,
+    /// Components of the kind
+    ComponentKind,
+    /// Service prices for every phone model
+    Service,
+    /// Components and services of the phone model
+    PhoneModel,
+}
+
+impl PriceScope {
+    pub const ALL: [Self; 5] = [
+        Self::All,
+        Self::Manufacturer,
+        Self::ComponentKind,
+        Self::Service,
+        Self::PhoneModel,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Manufacturer => "manufacturer",
+            Self::ComponentKind => "component-kind",
+            Self::Service => "service",
+            Self::PhoneModel => "phone-model",
+        }
+    }
+}
+
+impl fmt::Display for PriceScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PriceScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == s)
+            .ok_or_else(|| format!("Unknown price scope '{s}'"))
+    }
+}
+
+impl Recreatable for PriceScope {
+    const NAME: &'static str = "PriceScope";
+
+    const CREATE: &'static str = r#"CREATE TYPE "PriceScope" AS ENUM (
+    'All',
+    'Manufacturer',
+    'ComponentKind',
+    'Service',
+    'PhoneModel'
+);"#;
+
+    const DROP: &'static str = r#"DROP TYPE "PriceScope";"#;
+}
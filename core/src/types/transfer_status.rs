@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use crate::traits::Recreatable;
+
+#[derive(Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Clone, Copy, Debug)]
+#[sqlx(type_name = "TransferStatus", rename_all = "PascalCase")]
+pub enum TransferStatus {
+    InTransit,
+    Received,
+    Cancelled,
+}
+
+impl TransferStatus {
+    pub const ALL: [Self; 3] = [Self::InTransit, Self::Received, Self::Cancelled];
+}
+
+impl Recreatable for TransferStatus {
+    const NAME: &'static str = "TransferStatus";
+
+    const CREATE: &'static str = r#"CREATE TYPE "TransferStatus" AS ENUM (
+    'InTransit',
+    'Received',
+    'Cancelled'
+);"#;
+
+    const DROP: &'static str = r#"DROP TYPE "TransferStatus";"#;
+}
@@ -1,7 +1,7 @@
 use sqlx::{query_as, FromRow};
 use uuid::Uuid;
 
-use crate::{traits::Recreatable, PgQueryAs};
+use crate::{traits::Recreatable, types::MetaTime, PgQueryAs};
 
 #[derive(FromRow, Clone, Debug)]
 pub struct ComponentBeautified {
@@ -10,6 +10,7 @@ pub struct ComponentBeautified {
     pub kind: String,
     pub model: String,
     pub manufacturer: String,
+    pub meta: MetaTime,
 }
 
 impl Recreatable for ComponentBeautified {
@@ -19,14 +20,16 @@ SELECT c.uuid as uuid,
     c.name as name,
     k.name as kind,
     pm.name as model,
-    m.name as manufacturer
+    m.name as manufacturer,
+    c.meta as meta
 FROM "Component" c,
     "ComponentKind" k,
     "PhoneModel" pm,
     "Manufacturer" m
 WHERE c.kind = k.uuid
     AND c.manufacturer = m.uuid
-    AND c.phone_model = pm.uuid;"#;
+    AND c.phone_model = pm.uuid
+    AND c.deleted_at IS NULL;"#;
     const DROP: &'static str = r#"DROP VIEW "ComponentBeautified";"#;
 }
 
@@ -0,0 +1,53 @@
+use chrono::NaiveDate;
+use sqlx::{query_as, types::BigDecimal, FromRow};
+use uuid::Uuid;
+
+use crate::{traits::Recreatable, types::Currency, PgQueryAs};
+
+/// Payments and refunds per day, branch and currency, as counted by `revenue_for_period`
+#[derive(FromRow, Clone, Debug)]
+pub struct DailyRevenue {
+    pub day: NaiveDate,
+    pub branch: Uuid,
+    pub currency: Currency,
+    pub payments: i64,
+    pub refunds: i64,
+    pub received: BigDecimal,
+    pub refunded: BigDecimal,
+}
+
+impl Recreatable for DailyRevenue {
+    const NAME: &'static str = "DailyRevenue";
+    const CREATE: &'static str = r#"DO $$
+BEGIN
+    CREATE MATERIALIZED VIEW "DailyRevenue" AS
+    SELECT ((p.meta).created)::date as day,
+        o.branch as branch,
+        (p.amount).currency as currency,
+        count(*) FILTER (WHERE p.kind = 'Payment') as payments,
+        count(*) FILTER (WHERE p.kind = 'Refund') as refunds,
+        coalesce(sum((p.amount).amount) FILTER (WHERE p.kind = 'Payment'), 0) as received,
+        coalesce(sum((p.amount).amount) FILTER (WHERE p.kind = 'Refund'), 0) as refunded
+    FROM "Payment" p
+        JOIN "Invoice" i ON p.invoice = i.uuid
+        JOIN "Order" o ON i."order" = o.uuid
+    GROUP BY 1, 2, 3;
+
+    CREATE UNIQUE INDEX "DailyRevenue_key" ON "DailyRevenue" (day, branch, currency);
+    EXECUTE format('COMMENT ON MATERIALIZED VIEW "DailyRevenue" IS %L', now());
+END;
+$$;"#;
+    const DROP: &'static str = r#"DROP MATERIALIZED VIEW "DailyRevenue";"#;
+}
+
+impl DailyRevenue {
+    pub fn get_for_period(from: NaiveDate, to: NaiveDate) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "DailyRevenue" WHERE day BETWEEN $1 AND $2 ORDER BY day, branch"#)
+            .bind(from)
+            .bind(to)
+    }
+
+    pub fn summary(&self) -> BigDecimal {
+        &self.received - &self.refunded
+    }
+}
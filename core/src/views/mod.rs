@@ -1,25 +1,42 @@
-use core::fmt;
+use core::{fmt, str::FromStr};
 
-use anyhow::{Context, Result};
-use sqlx::{postgres::PgQueryResult, query, query_as, Error, PgPool, Postgres};
+use anyhow::{ensure, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{query, query_as, Error, Executor, PgPool, Postgres};
 
 use crate::traits::Recreatable;
 
 pub mod component_beautified;
+pub mod daily_revenue;
 pub mod order_service_beautified;
+pub mod order_warranty;
 pub mod phone_beautified;
+pub mod phone_history;
 pub mod service_phone_model_beautified;
+pub mod serviceman_workload;
 pub mod staff_beautified;
+pub mod stock_transfer_beautified;
+pub mod stock_valuation;
+pub mod supply_beautified;
+pub mod supply_item_beautified;
 pub mod warehouse_beautified;
 
 pub use component_beautified::ComponentBeautified;
+pub use daily_revenue::DailyRevenue;
 pub use order_service_beautified::OrderServiceBeautified;
+pub use order_warranty::OrderWarranty;
 pub use phone_beautified::PhoneBeautified;
+pub use phone_history::PhoneHistory;
 pub use service_phone_model_beautified::ServicePhoneModelBeautified;
+pub use serviceman_workload::ServicemanWorkload;
 pub use staff_beautified::StaffBeautified;
+pub use stock_transfer_beautified::StockTransferBeautified;
+pub use stock_valuation::StockValuation;
+pub use supply_beautified::SupplyBeautified;
+pub use supply_item_beautified::SupplyItemBeautified;
 pub use warehouse_beautified::WarehouseBeautified;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum View {
     StaffBeautified,
     ComponentBeautified,
@@ -27,16 +44,39 @@ pub enum View {
     WarehouseBeautified,
     ServicePhoneModelBeautified,
     OrderServiceBeautified,
+    StockTransferBeautified,
+    OrderWarranty,
+    SupplyBeautified,
+    SupplyItemBeautified,
+    PhoneHistory,
+    /// Materialized, refreshed by [`View::refresh`]
+    DailyRevenue,
+    StockValuation,
+    ServicemanWorkload,
 }
 
 impl View {
-    pub const ALL: [Self; 6] = [
+    pub const ALL: [Self; 14] = [
         Self::StaffBeautified,
         Self::ComponentBeautified,
         Self::PhoneBeautified,
         Self::WarehouseBeautified,
         Self::ServicePhoneModelBeautified,
         Self::OrderServiceBeautified,
+        Self::StockTransferBeautified,
+        Self::OrderWarranty,
+        Self::SupplyBeautified,
+        Self::SupplyItemBeautified,
+        Self::PhoneHistory,
+        Self::DailyRevenue,
+        Self::StockValuation,
+        Self::ServicemanWorkload,
+    ];
+
+    pub const MATERIALIZED: [Self; 3] = [
+        Self::DailyRevenue,
+        Self::StockValuation,
+        Self::ServicemanWorkload,
     ];
 
     pub fn name(&self) -> &str {
@@ -47,6 +87,14 @@ impl View {
             Self::WarehouseBeautified => WarehouseBeautified::NAME,
             Self::ServicePhoneModelBeautified => ServicePhoneModelBeautified::NAME,
             Self::OrderServiceBeautified => OrderServiceBeautified::NAME,
+            Self::StockTransferBeautified => StockTransferBeautified::NAME,
+            Self::OrderWarranty => OrderWarranty::NAME,
+            Self::SupplyBeautified => SupplyBeautified::NAME,
+            Self::SupplyItemBeautified => SupplyItemBeautified::NAME,
+            Self::PhoneHistory => PhoneHistory::NAME,
+            Self::DailyRevenue => DailyRevenue::NAME,
+            Self::StockValuation => StockValuation::NAME,
+            Self::ServicemanWorkload => ServicemanWorkload::NAME,
         }
     }
 
@@ -58,6 +106,14 @@ impl View {
             Self::WarehouseBeautified => WarehouseBeautified::CREATE,
             Self::ServicePhoneModelBeautified => ServicePhoneModelBeautified::CREATE,
             Self::OrderServiceBeautified => OrderServiceBeautified::CREATE,
+            Self::StockTransferBeautified => StockTransferBeautified::CREATE,
+            Self::OrderWarranty => OrderWarranty::CREATE,
+            Self::SupplyBeautified => SupplyBeautified::CREATE,
+            Self::SupplyItemBeautified => SupplyItemBeautified::CREATE,
+            Self::PhoneHistory => PhoneHistory::CREATE,
+            Self::DailyRevenue => DailyRevenue::CREATE,
+            Self::StockValuation => StockValuation::CREATE,
+            Self::ServicemanWorkload => ServicemanWorkload::CREATE,
         }
     }
 
@@ -69,16 +125,49 @@ impl View {
             Self::WarehouseBeautified => WarehouseBeautified::DROP,
             Self::ServicePhoneModelBeautified => ServicePhoneModelBeautified::DROP,
             Self::OrderServiceBeautified => OrderServiceBeautified::DROP,
+            Self::StockTransferBeautified => StockTransferBeautified::DROP,
+            Self::OrderWarranty => OrderWarranty::DROP,
+            Self::SupplyBeautified => SupplyBeautified::DROP,
+            Self::SupplyItemBeautified => SupplyItemBeautified::DROP,
+            Self::PhoneHistory => PhoneHistory::DROP,
+            Self::DailyRevenue => DailyRevenue::DROP,
+            Self::StockValuation => StockValuation::DROP,
+            Self::ServicemanWorkload => ServicemanWorkload::DROP,
         }
     }
 
-    pub async fn exists(&self, pool: &PgPool) -> Result<bool, Error> {
+    pub fn is_materialized(&self) -> bool {
+        Self::MATERIALIZED.contains(self)
+    }
+
+    /// Object type in the DDL statements
+    pub fn kind(&self) -> &str {
+        if self.is_materialized() {
+            "MATERIALIZED VIEW"
+        } else {
+            "VIEW"
+        }
+    }
+
+    /// Age after which a materialized view is considered stale
+    pub fn max_age(&self) -> Option<Duration> {
+        match self {
+            Self::DailyRevenue => Some(Duration::hours(1)),
+            Self::StockValuation | Self::ServicemanWorkload => Some(Duration::minutes(15)),
+            _ => None,
+        }
+    }
+
+    pub async fn exists<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<bool, Error> {
         match query_as::<Postgres, (bool,)>(
-            r#"SELECT true FROM information_schema.tables 
-WHERE table_schema = 'public' and table_type = 'VIEW' and table_name = $1;"#,
+            r#"SELECT true FROM pg_catalog.pg_class
+WHERE relkind IN ('v', 'm') AND relname = $1 AND relnamespace = current_schema()::regnamespace;"#,
         )
         .bind(self.name())
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await
         {
             Ok(_) => Ok(true),
@@ -92,30 +181,92 @@ WHERE table_schema = 'public' and table_type = 'VIEW' and table_name = $1;"#,
         }
     }
 
-    /// Create all views necessary for application
-    pub async fn create_all(
-        pool: &PgPool,
-        handler: impl Fn((View, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for procedure in Self::ALL {
-            handler((procedure, query(procedure.create()).execute(pool).await))
-                .with_context(|| format!("While creating '{procedure}' procedure"))?;
-        }
+    /// Recomputes the materialized view without locking out the readers, the refresh time
+    /// is kept in the comment of the view
+    ///
+    /// Unpopulated views can't be refreshed concurrently, so they are refreshed with a lock
+    pub async fn refresh(&self, pool: &PgPool) -> Result<()> {
+        ensure!(self.is_materialized(), "View '{self}' is not materialized");
+
+        let mut tx = pool.begin().await?;
+        let status = self.status(&mut tx).await?;
+        let concurrently = if status.populated {
+            " CONCURRENTLY"
+        } else {
+            ""
+        };
+        query(&format!(
+            r#"REFRESH MATERIALIZED VIEW{concurrently} "{self}";"#
+        ))
+        .execute(&mut tx)
+        .await
+        .with_context(|| format!("While refreshing '{self}' view"))?;
+        query(&format!(
+            r#"DO $$ BEGIN EXECUTE format('COMMENT ON MATERIALIZED VIEW "{self}" IS %L', now()); END; $$;"#
+        ))
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
 
         Ok(())
     }
 
-    /// Drop all application views
-    pub async fn drop_all(
-        pool: &PgPool,
-        handler: impl Fn((View, Result<PgQueryResult, Error>)) -> Result<PgQueryResult, Error>,
-    ) -> Result<()> {
-        for procedure in Self::ALL {
-            handler((procedure, query(procedure.drop()).execute(pool).await))
-                .with_context(|| format!("While dropping '{procedure}' procedure"))?;
+    pub async fn status<'e>(
+        &self,
+        executor: impl Executor<'e, Database = Postgres>,
+    ) -> Result<Freshness> {
+        // Views refreshed by other tools have no refresh time and are taken as stale
+        let (populated, refreshed) = query_as::<_, (bool, Option<DateTime<Utc>>)>(
+            r#"SELECT m.ispopulated,
+    CASE WHEN d.description ~ '^\d{4}-\d{2}-\d{2} ' THEN d.description::timestamptz END
+FROM pg_catalog.pg_matviews m
+    JOIN pg_catalog.pg_class c ON c.relname = m.matviewname
+        AND c.relnamespace = current_schema()::regnamespace
+    LEFT JOIN pg_catalog.pg_description d ON d.objoid = c.oid
+        AND d.classoid = 'pg_catalog.pg_class'::regclass AND d.objsubid = 0
+WHERE m.schemaname = current_schema() AND m.matviewname = $1;"#,
+        )
+        .bind(self.name())
+        .fetch_one(executor)
+        .await
+        .with_context(|| format!("While checking '{self}' materialized view"))?;
+
+        Ok(Freshness {
+            view: *self,
+            populated,
+            refreshed,
+        })
+    }
+
+    /// Statuses of the materialized views
+    pub async fn statuses(pool: &PgPool) -> Result<Vec<Freshness>> {
+        let mut statuses = Vec::with_capacity(Self::MATERIALIZED.len());
+        for view in Self::MATERIALIZED {
+            statuses.push(view.status(pool).await?);
         }
 
-        Ok(())
+        Ok(statuses)
+    }
+}
+
+/// Refresh status of a materialized view
+#[derive(Debug)]
+pub struct Freshness {
+    pub view: View,
+    pub populated: bool,
+    pub refreshed: Option<DateTime<Utc>>,
+}
+
+impl Freshness {
+    pub fn age(&self) -> Option<Duration> {
+        self.refreshed.map(|at| Utc::now() - at)
+    }
+
+    pub fn is_stale(&self) -> bool {
+        match (self.age(), self.view.max_age()) {
+            (Some(age), Some(max_age)) => !self.populated || age > max_age,
+            _ => true,
+        }
     }
 }
 
@@ -124,3 +275,14 @@ impl fmt::Display for View {
         f.write_str(self.name())
     }
 }
+
+impl FromStr for View {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|view| view.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown view '{s}'"))
+    }
+}
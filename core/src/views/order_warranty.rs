@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
+
+use crate::{traits::Recreatable, PgQueryAs};
+
+/// Warranty on every service of completed orders, claims themselves are not covered
+#[derive(FromRow, Clone, Debug)]
+pub struct OrderWarranty {
+    pub order: Uuid,
+    pub tracking: String,
+    pub service: String,
+    pub completed: DateTime<Utc>,
+    pub expires: DateTime<Utc>,
+}
+
+impl Recreatable for OrderWarranty {
+    const NAME: &'static str = "OrderWarranty";
+    const CREATE: &'static str = r#"CREATE VIEW "OrderWarranty" AS
+SELECT o.uuid as order,
+    o.tracking as tracking,
+    s.name as service,
+    o.completed as completed,
+    o.completed + s.warranty_days * interval '1 day' as expires
+FROM "Order" o,
+    "OrderService" os,
+    "Service" s
+WHERE os.order = o.uuid
+    AND os.service = s.uuid
+    AND o.status = 'Complete'
+    AND o.completed IS NOT NULL
+    AND o.warranty_of IS NULL
+    AND s.warranty_days > 0;"#;
+    const DROP: &'static str = r#"DROP VIEW "OrderWarranty";"#;
+}
+
+impl OrderWarranty {
+    pub fn get_by_order(order: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "OrderWarranty" WHERE "order" = $1"#).bind(order)
+    }
+
+    /// Warranties which have not expired yet
+    pub fn get_active() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "OrderWarranty" WHERE expires > now()"#)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.expires > Utc::now()
+    }
+}
@@ -4,7 +4,7 @@ use uuid::Uuid;
 
 use crate::{
     traits::Recreatable,
-    types::{Color, MetaTime},
+    types::{Color, Imei, MetaTime},
     PgQueryAs,
 };
 
@@ -12,7 +12,7 @@ use crate::{
 pub struct PhoneBeautified {
     pub uuid: Uuid,
     pub owner: String,
-    pub imei: String,
+    pub imei: Imei,
     pub wifi: MacAddress,
     pub bluetooth: MacAddress,
     pub model: String,
@@ -36,7 +36,8 @@ FROM "Phone" ph,
     "Person" p,
     "PhoneModel" pm
 WHERE ph.person = p.uuid
-    AND ph.model = pm.uuid;"#;
+    AND ph.model = pm.uuid
+    AND ph.deleted_at IS NULL;"#;
 
     const DROP: &'static str = r#"DROP VIEW "PhoneBeautified";"#;
 }
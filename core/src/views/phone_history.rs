@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
+
+use crate::{
+    traits::Recreatable,
+    types::{Imei, Money},
+    PgQueryAs,
+};
+
+/// Event in the life of the device: owner change (`Owner`), repair order (`Order`),
+/// service done (`Service`) or part installed (`Part`) within the order
+#[derive(FromRow, Clone, Debug)]
+pub struct PhoneHistory {
+    pub phone: Uuid,
+    pub imei: Imei,
+    pub at: DateTime<Utc>,
+    pub kind: String,
+    pub order: Option<Uuid>,
+    pub tracking: Option<String>,
+    /// Owner for ownership, client for orders and serviceman for services and parts
+    pub person: String,
+    /// Order status, service or component name
+    pub description: String,
+    /// Number of installed parts
+    pub amount: Option<i32>,
+    pub price: Option<Money>,
+}
+
+impl Recreatable for PhoneHistory {
+    const NAME: &'static str = "PhoneHistory";
+    const CREATE: &'static str = r#"CREATE VIEW "PhoneHistory" AS
+SELECT ph.uuid as phone,
+    ph.imei as imei,
+    po.since as at,
+    'Owner' as kind,
+    NULL::uuid as "order",
+    NULL::text as tracking,
+    CONCAT_WS(' ', p.first_name, p.middle_name, p.last_name) as person,
+    'Previous owner' as description,
+    NULL::int as amount,
+    NULL::monetary as price
+FROM "PhoneOwnership" po
+    JOIN "Phone" ph ON po.phone = ph.uuid
+    JOIN "Person" p ON po.person = p.uuid
+UNION ALL
+SELECT ph.uuid,
+    ph.imei,
+    coalesce(
+        (SELECT max(po.until) FROM "PhoneOwnership" po WHERE po.phone = ph.uuid),
+        (ph.meta).created
+    ),
+    'Owner',
+    NULL,
+    NULL,
+    CONCAT_WS(' ', p.first_name, p.middle_name, p.last_name),
+    'Current owner',
+    NULL,
+    NULL
+FROM "Phone" ph
+    JOIN "Person" p ON ph.person = p.uuid
+UNION ALL
+SELECT ph.uuid,
+    ph.imei,
+    (o.meta).created,
+    'Order',
+    o.uuid,
+    o.tracking,
+    CONCAT_WS(' ', p.first_name, p.middle_name, p.last_name),
+    o.status::text || CASE WHEN o.warranty_of IS NOT NULL THEN ' (warranty claim)' ELSE '' END,
+    NULL,
+    NULL
+FROM "Order" o
+    JOIN "Phone" ph ON o.phone = ph.uuid
+    JOIN "Person" p ON o.client = p.uuid
+UNION ALL
+SELECT ph.uuid,
+    ph.imei,
+    (o.meta).created,
+    'Service',
+    o.uuid,
+    o.tracking,
+    CONCAT_WS(' ', p.first_name, p.middle_name, p.last_name),
+    s.name,
+    NULL,
+    os.price
+FROM "OrderService" os
+    JOIN "Order" o ON os."order" = o.uuid
+    JOIN "Phone" ph ON o.phone = ph.uuid
+    JOIN "Service" s ON os.service = s.uuid
+    JOIN "Staff" st ON o.serviceman = st.uuid
+    JOIN "LaborContract" lc ON st.contract = lc.uuid
+    JOIN "Person" p ON lc.person = p.uuid
+UNION ALL
+SELECT ph.uuid,
+    ph.imei,
+    (o.meta).created,
+    'Part',
+    o.uuid,
+    o.tracking,
+    CONCAT_WS(' ', p.first_name, p.middle_name, p.last_name),
+    c.name,
+    ow.amount,
+    ow.price
+FROM "OrderWarehouse" ow
+    JOIN "Order" o ON ow."order" = o.uuid
+    JOIN "Phone" ph ON o.phone = ph.uuid
+    JOIN "Warehouse" w ON ow.item = w.uuid
+    JOIN "Component" c ON w.component = c.uuid
+    JOIN "Staff" st ON o.serviceman = st.uuid
+    JOIN "LaborContract" lc ON st.contract = lc.uuid
+    JOIN "Person" p ON lc.person = p.uuid;"#;
+    const DROP: &'static str = r#"DROP VIEW "PhoneHistory";"#;
+}
+
+impl PhoneHistory {
+    /// Orders come before their services and parts
+    /// Query of [`Self::get_by_imei`], also explained by [`Probe`](crate::explain::Probe)
+    pub const BY_IMEI: &'static str =
+        r#"SELECT * FROM "PhoneHistory" WHERE imei = $1 ORDER BY at, "order", kind"#;
+
+    pub fn get_by_imei(imei: Imei) -> PgQueryAs<Self> {
+        query_as(Self::BY_IMEI).bind(imei)
+    }
+}
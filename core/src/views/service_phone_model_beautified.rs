@@ -13,6 +13,8 @@ FROM "ServicePhoneModel" spm,
     "Service" s,
     "PhoneModel" pm
 WHERE spm.service = s.uuid
-    AND spm.phone_model = pm.uuid;"#;
+    AND spm.phone_model = pm.uuid
+    AND s.deleted_at IS NULL
+    AND pm.deleted_at IS NULL;"#;
     const DROP: &'static str = r#"DROP VIEW "ServicePhoneModelBeautified";"#;
 }
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_as, types::BigDecimal, FromRow};
+use uuid::Uuid;
+
+use crate::{traits::Recreatable, PgQueryAs};
+
+/// Open orders of every serviceman and the ones completed within the last 30 days
+#[derive(FromRow, Clone, Debug)]
+pub struct ServicemanWorkload {
+    pub serviceman: Uuid,
+    pub branch: Uuid,
+    pub open: i64,
+    pub oldest_open: Option<DateTime<Utc>>,
+    pub completed: i64,
+    /// Average hours from creation to completion of the completed orders
+    pub average_hours: Option<BigDecimal>,
+}
+
+impl Recreatable for ServicemanWorkload {
+    const NAME: &'static str = "ServicemanWorkload";
+    const CREATE: &'static str = r#"DO $$
+BEGIN
+    CREATE MATERIALIZED VIEW "ServicemanWorkload" AS
+    SELECT s.uuid as serviceman,
+        s.branch as branch,
+        count(o.uuid) FILTER (WHERE o.status IN ('Processing', 'PendingPayment', 'Active')) as open,
+        min((o.meta).created) FILTER (WHERE o.status IN ('Processing', 'PendingPayment', 'Active')) as oldest_open,
+        count(o.uuid) FILTER (WHERE o.completed >= now() - interval '30 days') as completed,
+        round(
+            extract(epoch FROM avg(o.completed - (o.meta).created) FILTER (WHERE o.completed >= now() - interval '30 days'))::numeric / 3600,
+            1
+        ) as average_hours
+    FROM "Staff" s
+        JOIN "Order" o ON o.serviceman = s.uuid
+    GROUP BY s.uuid, s.branch;
+
+    CREATE UNIQUE INDEX "ServicemanWorkload_key" ON "ServicemanWorkload" (serviceman);
+    EXECUTE format('COMMENT ON MATERIALIZED VIEW "ServicemanWorkload" IS %L', now());
+END;
+$$;"#;
+    const DROP: &'static str = r#"DROP MATERIALIZED VIEW "ServicemanWorkload";"#;
+}
+
+impl ServicemanWorkload {
+    pub fn get_by_branch(branch: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "ServicemanWorkload" WHERE branch = $1 ORDER BY open DESC"#)
+            .bind(branch)
+    }
+
+    pub fn get_by_serviceman(serviceman: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "ServicemanWorkload" WHERE serviceman = $1"#).bind(serviceman)
+    }
+}
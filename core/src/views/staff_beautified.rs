@@ -8,9 +8,13 @@ impl Recreatable for StaffBeautified {
 SELECT s.uuid,
     s.contract,
     p.name,
+    b.name as branch,
     s.status
 FROM "Staff" s,
-    "Position" p
-WHERE s.position = p.uuid;"#;
+    "Position" p,
+    "Branch" b
+WHERE s.position = p.uuid
+    AND s.branch = b.uuid
+    AND s.deleted_at IS NULL;"#;
     const DROP: &'static str = r#"DROP VIEW "StaffBeautified";"#;
 }
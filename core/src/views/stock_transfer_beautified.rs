@@ -0,0 +1,63 @@
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
+
+use crate::{
+    traits::Recreatable,
+    types::{MetaTime, TransferStatus},
+    PgQueryAs,
+};
+
+#[derive(FromRow, Clone, Debug)]
+pub struct StockTransferBeautified {
+    pub uuid: Uuid,
+    pub component: String,
+    pub supplier: String,
+    pub source: String,
+    pub destination: String,
+    pub amount: i32,
+    pub status: TransferStatus,
+    pub meta: MetaTime,
+}
+
+impl Recreatable for StockTransferBeautified {
+    const NAME: &'static str = "StockTransferBeautified";
+    const CREATE: &'static str = r#"CREATE VIEW "StockTransferBeautified" AS
+SELECT t.uuid as uuid,
+    c.name as component,
+    s.name as supplier,
+    src.name as source,
+    dst.name as destination,
+    t.amount as amount,
+    t.status as status,
+    t.meta as meta
+FROM "StockTransfer" t,
+    "Warehouse" w,
+    "Component" c,
+    "Supplier" s,
+    "Branch" src,
+    "Branch" dst
+WHERE t.item = w.uuid
+    AND w.component = c.uuid
+    AND w.supplier = s.uuid
+    AND w.branch = src.uuid
+    AND t.destination = dst.uuid
+    AND w.deleted_at IS NULL;"#;
+    const DROP: &'static str = r#"DROP VIEW "StockTransferBeautified";"#;
+}
+
+impl StockTransferBeautified {
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "StockTransferBeautified""#)
+    }
+
+    /// Transfers shipped from or to the branch
+    /// Query of [`Self::get_by_branch`], also explained by [`Probe`](crate::explain::Probe)
+    pub const BY_BRANCH: &'static str = r#"SELECT tb.* FROM "StockTransferBeautified" tb
+    JOIN "StockTransfer" t ON tb.uuid = t.uuid
+    JOIN "Warehouse" w ON t.item = w.uuid
+WHERE w.branch = $1 OR t.destination = $1"#;
+
+    pub fn get_by_branch(branch: Uuid) -> PgQueryAs<Self> {
+        query_as(Self::BY_BRANCH).bind(branch)
+    }
+}
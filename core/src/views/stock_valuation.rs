@@ -0,0 +1,47 @@
+use sqlx::{query_as, types::BigDecimal, FromRow};
+use uuid::Uuid;
+
+use crate::{traits::Recreatable, types::Currency, PgQueryAs};
+
+/// Value of the components in stock per branch and currency of their prices
+#[derive(FromRow, Clone, Debug)]
+pub struct StockValuation {
+    pub branch: Uuid,
+    pub currency: Currency,
+    /// Warehouse rows out of the trash with anything in stock
+    pub items: i64,
+    pub units: i64,
+    pub value: BigDecimal,
+}
+
+impl Recreatable for StockValuation {
+    const NAME: &'static str = "StockValuation";
+    const CREATE: &'static str = r#"DO $$
+BEGIN
+    CREATE MATERIALIZED VIEW "StockValuation" AS
+    SELECT w.branch as branch,
+        (w.price).currency as currency,
+        count(*) as items,
+        sum(w.amount)::bigint as units,
+        sum((w.price).amount * w.amount) as value
+    FROM "Warehouse" w
+    WHERE w.amount > 0 AND w.deleted_at IS NULL
+    GROUP BY 1, 2;
+
+    CREATE UNIQUE INDEX "StockValuation_key" ON "StockValuation" (branch, currency);
+    EXECUTE format('COMMENT ON MATERIALIZED VIEW "StockValuation" IS %L', now());
+END;
+$$;"#;
+    const DROP: &'static str = r#"DROP MATERIALIZED VIEW "StockValuation";"#;
+}
+
+impl StockValuation {
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "StockValuation" ORDER BY branch, currency"#)
+    }
+
+    pub fn get_by_branch(branch: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "StockValuation" WHERE branch = $1 ORDER BY currency"#)
+            .bind(branch)
+    }
+}
@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
+
+use crate::{
+    traits::Recreatable,
+    types::{MetaTime, SupplyStatus},
+    PgQueryAs,
+};
+
+#[derive(FromRow, Clone, Debug)]
+pub struct SupplyBeautified {
+    pub uuid: Uuid,
+    pub supplier: String,
+    pub status: SupplyStatus,
+    pub signed: Option<DateTime<Utc>>,
+    pub due: Option<DateTime<Utc>>,
+    pub delivered: Option<DateTime<Utc>>,
+    pub lines: i64,
+    pub ordered: i64,
+    pub received: Option<i64>,
+    /// Count of lines received in amount other than ordered
+    pub discrepancies: i64,
+    pub meta: MetaTime,
+}
+
+impl Recreatable for SupplyBeautified {
+    const NAME: &'static str = "SupplyBeautified";
+    const CREATE: &'static str = r#"CREATE VIEW "SupplyBeautified" AS
+SELECT sp.uuid as uuid,
+    s.name as supplier,
+    sp.status as status,
+    sp.signed as signed,
+    sp.due as due,
+    sp.delivered as delivered,
+    count(i.component) as lines,
+    coalesce(sum(i.ordered), 0) as ordered,
+    sum(i.received) as received,
+    count(*) FILTER (WHERE i.received <> i.ordered) as discrepancies,
+    sp.meta as meta
+FROM "Supply" sp
+    JOIN "SupplyContract" c ON sp.contract = c.uuid
+    JOIN "Supplier" s ON c.supplier = s.uuid
+    LEFT JOIN "SupplyItem" i ON i.supply = sp.uuid
+WHERE sp.deleted_at IS NULL
+GROUP BY sp.uuid, s.name;"#;
+    const DROP: &'static str = r#"DROP VIEW "SupplyBeautified";"#;
+}
+
+impl SupplyBeautified {
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "SupplyBeautified""#)
+    }
+
+    /// Supplies placed by the branch staff, they are delivered to the branch
+    /// Query of [`Self::get_by_branch`], also explained by [`Probe`](crate::explain::Probe)
+    pub const BY_BRANCH: &'static str = r#"SELECT sb.* FROM "SupplyBeautified" sb
+    JOIN "Supply" sp ON sb.uuid = sp.uuid
+    JOIN "Staff" st ON sp.staff = st.uuid
+WHERE st.branch = $1
+ORDER BY (sb.meta).created DESC"#;
+
+    pub fn get_by_branch(branch: Uuid) -> PgQueryAs<Self> {
+        query_as(Self::BY_BRANCH).bind(branch)
+    }
+}
@@ -0,0 +1,37 @@
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
+
+use crate::{traits::Recreatable, types::Money, PgQueryAs};
+
+#[derive(FromRow, Clone, Debug)]
+pub struct SupplyItemBeautified {
+    pub supply: Uuid,
+    pub component: String,
+    pub ordered: i32,
+    pub received: Option<i32>,
+    /// Received minus ordered, not set until delivery
+    pub difference: Option<i32>,
+    pub price: Money,
+}
+
+impl Recreatable for SupplyItemBeautified {
+    const NAME: &'static str = "SupplyItemBeautified";
+    const CREATE: &'static str = r#"CREATE VIEW "SupplyItemBeautified" AS
+SELECT i.supply as supply,
+    c.name as component,
+    i.ordered as ordered,
+    i.received as received,
+    i.received - i.ordered as difference,
+    i.price as price
+FROM "SupplyItem" i,
+    "Component" c
+WHERE i.component = c.uuid;"#;
+    const DROP: &'static str = r#"DROP VIEW "SupplyItemBeautified";"#;
+}
+
+impl SupplyItemBeautified {
+    pub fn get_by_supply(supply: Uuid) -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "SupplyItemBeautified" WHERE supply = $1 ORDER BY component"#)
+            .bind(supply)
+    }
+}
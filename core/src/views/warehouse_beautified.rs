@@ -1,20 +1,56 @@
-use crate::traits::Recreatable;
+use sqlx::{query_as, FromRow};
+use uuid::Uuid;
 
-pub struct WarehouseBeautified;
+use crate::{
+    traits::Recreatable,
+    types::{MetaTime, Money},
+    PgQueryAs,
+};
+
+#[derive(FromRow, Clone, Debug)]
+pub struct WarehouseBeautified {
+    pub uuid: Uuid,
+    pub branch: String,
+    pub component: String,
+    pub supplier: String,
+    pub price: Money,
+    pub amount: i32,
+    pub meta: MetaTime,
+}
 
 impl Recreatable for WarehouseBeautified {
     const NAME: &'static str = "WarehouseBeautified";
     const CREATE: &'static str = r#"CREATE VIEW "WarehouseBeautified" AS
 SELECT w.uuid as uuid,
+    b.name as branch,
     c.name as component,
     s.name as supplier,
     w.price as price,
     w.amount as amount,
     w.meta as meta
 FROM "Warehouse" w,
+    "Branch" b,
     "Supplier" s,
     "Component" c
-WHERE w.supplier = s.uuid
-    AND w.component = c.uuid;"#;
+WHERE w.branch = b.uuid
+    AND w.supplier = s.uuid
+    AND w.component = c.uuid
+    AND w.deleted_at IS NULL;"#;
     const DROP: &'static str = r#"DROP VIEW "WarehouseBeautified";"#;
 }
+
+impl WarehouseBeautified {
+    pub fn get_all() -> PgQueryAs<Self> {
+        query_as(r#"SELECT * FROM "WarehouseBeautified""#)
+    }
+
+    /// Stock of the single branch
+    /// Query of [`Self::get_by_branch`], also explained by [`Probe`](crate::explain::Probe)
+    pub const BY_BRANCH: &'static str = r#"SELECT wb.* FROM "WarehouseBeautified" wb
+    JOIN "Warehouse" w ON wb.uuid = w.uuid
+WHERE w.branch = $1"#;
+
+    pub fn get_by_branch(branch: Uuid) -> PgQueryAs<Self> {
+        query_as(Self::BY_BRANCH).bind(branch)
+    }
+}